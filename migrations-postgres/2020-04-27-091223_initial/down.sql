@@ -0,0 +1,49 @@
+DROP VIEW search;
+ALTER TABLE ipaddrs DROP CONSTRAINT ipaddrs_netblock_id_fkey;
+DROP TABLE trash_rows;
+DROP TABLE trash;
+DROP TABLE notes;
+DROP TABLE tags;
+DROP TABLE account_locations;
+DROP TABLE phonenumber_locations;
+DROP TABLE domain_locations;
+DROP TABLE locations;
+DROP TABLE url_documents;
+DROP TABLE documents;
+DROP TABLE autonomous_systems;
+DROP TABLE person_netblocks;
+DROP TABLE person_accounts;
+DROP TABLE person_phonenumbers;
+DROP TABLE person_emails;
+DROP TABLE person_domains;
+DROP TABLE organization_netblocks;
+DROP TABLE organization_accounts;
+DROP TABLE organization_phonenumbers;
+DROP TABLE organization_emails;
+DROP TABLE organization_domains;
+DROP TABLE persons;
+DROP TABLE organizations;
+DROP TABLE port_certificates;
+DROP TABLE subdomain_certificates;
+DROP TABLE certificates;
+DROP TABLE dns_records;
+DROP TABLE ports;
+DROP TABLE activity;
+DROP TABLE cryptoaddrs;
+DROP TABLE netblocks;
+DROP TABLE autonoscope;
+DROP TABLE images;
+DROP TABLE breach_emails;
+DROP TABLE breaches;
+DROP TABLE accounts;
+DROP TABLE ttls;
+DROP TABLE network_devices;
+DROP TABLE devices;
+DROP TABLE networks;
+DROP TABLE phonenumbers;
+DROP TABLE urls;
+DROP TABLE subdomain_ipaddrs;
+DROP TABLE ipaddrs;
+DROP TABLE emails;
+DROP TABLE subdomains;
+DROP TABLE domains;
@@ -0,0 +1,675 @@
+CREATE TABLE domains (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT domain_unique UNIQUE (value)
+);
+CREATE TABLE subdomains (
+    id SERIAL PRIMARY KEY,
+    domain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    resolvable BOOLEAN,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    CONSTRAINT subdomain_unique UNIQUE (value)
+);
+CREATE TABLE emails (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    valid BOOLEAN,
+    displayname VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT email_unique UNIQUE (value)
+);
+CREATE TABLE ipaddrs (
+    id SERIAL PRIMARY KEY,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    continent VARCHAR,
+    continent_code VARCHAR,
+    country VARCHAR,
+    country_code VARCHAR,
+    city VARCHAR,
+    latitude REAL,
+    longitude REAL,
+    asn INTEGER,
+    as_org VARCHAR,
+    description VARCHAR,
+    reverse_dns VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    netblock_id INTEGER,
+    range_key VARCHAR,
+    CONSTRAINT ipaddr_unique UNIQUE (value)
+);
+CREATE TABLE subdomain_ipaddrs (
+    id SERIAL PRIMARY KEY,
+    subdomain_id INTEGER NOT NULL,
+    ip_addr_id INTEGER NOT NULL,
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    FOREIGN KEY(ip_addr_id) REFERENCES ipaddrs(id) ON DELETE CASCADE,
+    CONSTRAINT subdomain_ipaddr_unique UNIQUE (subdomain_id, ip_addr_id)
+);
+CREATE TABLE urls (
+    id SERIAL PRIMARY KEY,
+    subdomain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    path VARCHAR NOT NULL,
+    status INTEGER,
+    body BYTEA,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    online BOOLEAN,
+    title VARCHAR,
+    redirect VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    CONSTRAINT url_unique UNIQUE (value)
+);
+CREATE TABLE phonenumbers (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    name VARCHAR,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    valid BOOLEAN,
+    last_online TIMESTAMP,
+    country VARCHAR,
+    carrier VARCHAR,
+    line VARCHAR,
+    is_ported BOOLEAN,
+    last_ported TIMESTAMP,
+    caller_name VARCHAR,
+    caller_type VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT phonenumber_unique UNIQUE (value)
+);
+CREATE TABLE networks (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    latitude REAL,
+    longitude REAL,
+    description VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT network_unique UNIQUE (value)
+);
+CREATE TABLE devices (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    name VARCHAR,
+    hostname VARCHAR,
+    vendor VARCHAR,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    last_seen TIMESTAMP,
+    first_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT device_unique UNIQUE (value)
+);
+CREATE TABLE network_devices (
+    id SERIAL PRIMARY KEY,
+    network_id INTEGER NOT NULL,
+    device_id INTEGER NOT NULL,
+    ipaddr VARCHAR,
+    last_seen TIMESTAMP,
+    FOREIGN KEY(network_id) REFERENCES networks(id) ON DELETE CASCADE,
+    FOREIGN KEY(device_id) REFERENCES devices(id) ON DELETE CASCADE,
+    CONSTRAINT network_device_unique UNIQUE (network_id, device_id)
+);
+CREATE TABLE ttls (
+    id SERIAL PRIMARY KEY,
+    family VARCHAR NOT NULL,
+    key INTEGER NOT NULL,
+    expire TIMESTAMP NOT NULL,
+    CONSTRAINT ttl_unique UNIQUE (family, key)
+);
+CREATE TABLE accounts (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    service VARCHAR NOT NULL,
+    username VARCHAR NOT NULL,
+    displayname VARCHAR,
+    email VARCHAR,
+    url VARCHAR,
+    last_seen TIMESTAMP,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    phonenumber VARCHAR,
+    profile_pic VARCHAR,
+    birthday VARCHAR,
+    first_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT account_unique UNIQUE (value)
+);
+CREATE TABLE breaches (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT breach_unique UNIQUE (value)
+);
+CREATE TABLE breach_emails (
+    id SERIAL PRIMARY KEY,
+    breach_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    password VARCHAR,
+    FOREIGN KEY(breach_id) REFERENCES breaches(id) ON DELETE CASCADE,
+    FOREIGN KEY(email_id) REFERENCES emails(id) ON DELETE CASCADE,
+    CONSTRAINT breach_emails_unique UNIQUE (breach_id, email_id, password)
+);
+CREATE TABLE images (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    filename VARCHAR,
+    mime VARCHAR,
+    width INTEGER,
+    height INTEGER,
+    created TIMESTAMP,
+    latitude REAL,
+    longitude REAL,
+    nudity REAL,
+    ahash VARCHAR,
+    dhash VARCHAR,
+    phash VARCHAR,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT image_unique UNIQUE (value)
+);
+CREATE TABLE autonoscope (
+    id SERIAL PRIMARY KEY,
+    object VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    scoped BOOLEAN NOT NULL,
+    CONSTRAINT autonoscope_unique UNIQUE (object, value)
+);
+CREATE TABLE netblocks (
+    id SERIAL PRIMARY KEY,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    asn INTEGER,
+    as_org VARCHAR,
+    description VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    range_start VARCHAR,
+    range_end VARCHAR,
+    CONSTRAINT netblock_unique UNIQUE (value)
+);
+CREATE TABLE cryptoaddrs (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    currency VARCHAR,
+    denominator INTEGER,
+    balance BIGINT,
+    received BIGINT,
+    first_seen TIMESTAMP,
+    last_withdrawal TIMESTAMP,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    description VARCHAR,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT cryptoaddrs_unique UNIQUE (value)
+);
+CREATE TABLE activity (
+    id SERIAL PRIMARY KEY,
+    topic VARCHAR NOT NULL,
+    time TIMESTAMP NOT NULL,
+    uniq VARCHAR,
+    latitude REAL,
+    longitude REAL,
+    radius INTEGER,
+    content VARCHAR NOT NULL
+);
+CREATE UNIQUE INDEX activity_uniq ON activity(topic, uniq);
+CREATE INDEX activity_topic ON activity(topic);
+CREATE INDEX activity_time ON activity(time);
+CREATE INDEX activity_topic_time ON activity(topic, time);
+CREATE TABLE ports (
+    id SERIAL PRIMARY KEY,
+    ip_addr_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    ip_addr VARCHAR NOT NULL,
+    port INTEGER NOT NULL,
+    protocol VARCHAR NOT NULL,
+    status VARCHAR,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    banner VARCHAR,
+    service VARCHAR,
+    version VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    FOREIGN KEY(ip_addr_id) REFERENCES ipaddrs(id) ON DELETE CASCADE,
+    CONSTRAINT port_unique UNIQUE (value)
+);
+CREATE TABLE dns_records (
+    id SERIAL PRIMARY KEY,
+    subdomain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    name VARCHAR NOT NULL,
+    record_type VARCHAR NOT NULL,
+    data VARCHAR NOT NULL,
+    ttl INTEGER,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    CONSTRAINT dns_record_unique UNIQUE (value)
+);
+CREATE TABLE certificates (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    serial VARCHAR NOT NULL,
+    subject VARCHAR NOT NULL,
+    issuer VARCHAR NOT NULL,
+    not_before TIMESTAMP NOT NULL,
+    not_after TIMESTAMP NOT NULL,
+    names VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT certificate_unique UNIQUE (value)
+);
+CREATE TABLE subdomain_certificates (
+    id SERIAL PRIMARY KEY,
+    subdomain_id INTEGER NOT NULL,
+    certificate_id INTEGER NOT NULL,
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    FOREIGN KEY(certificate_id) REFERENCES certificates(id) ON DELETE CASCADE,
+    CONSTRAINT subdomain_certificate_unique UNIQUE (subdomain_id, certificate_id)
+);
+CREATE TABLE port_certificates (
+    id SERIAL PRIMARY KEY,
+    port_id INTEGER NOT NULL,
+    certificate_id INTEGER NOT NULL,
+    FOREIGN KEY(port_id) REFERENCES ports(id) ON DELETE CASCADE,
+    FOREIGN KEY(certificate_id) REFERENCES certificates(id) ON DELETE CASCADE,
+    CONSTRAINT port_certificate_unique UNIQUE (port_id, certificate_id)
+);
+CREATE TABLE organizations (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    description VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT organization_unique UNIQUE (value)
+);
+CREATE TABLE persons (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    description VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT person_unique UNIQUE (value)
+);
+CREATE TABLE organization_domains (
+    id SERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL,
+    domain_id INTEGER NOT NULL,
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    CONSTRAINT organization_domain_unique UNIQUE (organization_id, domain_id)
+);
+CREATE TABLE organization_emails (
+    id SERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(email_id) REFERENCES emails(id) ON DELETE CASCADE,
+    CONSTRAINT organization_email_unique UNIQUE (organization_id, email_id)
+);
+CREATE TABLE organization_phonenumbers (
+    id SERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL,
+    phone_number_id INTEGER NOT NULL,
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(phone_number_id) REFERENCES phonenumbers(id) ON DELETE CASCADE,
+    CONSTRAINT organization_phonenumber_unique UNIQUE (organization_id, phone_number_id)
+);
+CREATE TABLE organization_accounts (
+    id SERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    CONSTRAINT organization_account_unique UNIQUE (organization_id, account_id)
+);
+CREATE TABLE organization_netblocks (
+    id SERIAL PRIMARY KEY,
+    organization_id INTEGER NOT NULL,
+    netblock_id INTEGER NOT NULL,
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(netblock_id) REFERENCES netblocks(id) ON DELETE CASCADE,
+    CONSTRAINT organization_netblock_unique UNIQUE (organization_id, netblock_id)
+);
+CREATE TABLE person_domains (
+    id SERIAL PRIMARY KEY,
+    person_id INTEGER NOT NULL,
+    domain_id INTEGER NOT NULL,
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    CONSTRAINT person_domain_unique UNIQUE (person_id, domain_id)
+);
+CREATE TABLE person_emails (
+    id SERIAL PRIMARY KEY,
+    person_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(email_id) REFERENCES emails(id) ON DELETE CASCADE,
+    CONSTRAINT person_email_unique UNIQUE (person_id, email_id)
+);
+CREATE TABLE person_phonenumbers (
+    id SERIAL PRIMARY KEY,
+    person_id INTEGER NOT NULL,
+    phone_number_id INTEGER NOT NULL,
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(phone_number_id) REFERENCES phonenumbers(id) ON DELETE CASCADE,
+    CONSTRAINT person_phonenumber_unique UNIQUE (person_id, phone_number_id)
+);
+CREATE TABLE person_accounts (
+    id SERIAL PRIMARY KEY,
+    person_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    CONSTRAINT person_account_unique UNIQUE (person_id, account_id)
+);
+CREATE TABLE person_netblocks (
+    id SERIAL PRIMARY KEY,
+    person_id INTEGER NOT NULL,
+    netblock_id INTEGER NOT NULL,
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(netblock_id) REFERENCES netblocks(id) ON DELETE CASCADE,
+    CONSTRAINT person_netblock_unique UNIQUE (person_id, netblock_id)
+);
+CREATE TABLE autonomous_systems (
+    id SERIAL PRIMARY KEY,
+    value INTEGER NOT NULL,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    name VARCHAR,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT autonomous_system_unique UNIQUE (value)
+);
+CREATE TABLE documents (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    filename VARCHAR,
+    mime VARCHAR,
+    size INTEGER,
+    title VARCHAR,
+    author VARCHAR,
+    creator VARCHAR,
+    created TIMESTAMP,
+    modified TIMESTAMP,
+    text VARCHAR,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT document_unique UNIQUE (value)
+);
+CREATE TABLE url_documents (
+    id SERIAL PRIMARY KEY,
+    url_id INTEGER NOT NULL,
+    document_id INTEGER NOT NULL,
+    FOREIGN KEY(url_id) REFERENCES urls(id) ON DELETE CASCADE,
+    FOREIGN KEY(document_id) REFERENCES documents(id) ON DELETE CASCADE,
+    CONSTRAINT url_document_unique UNIQUE (url_id, document_id)
+);
+CREATE TABLE locations (
+    id SERIAL PRIMARY KEY,
+    value VARCHAR NOT NULL,
+    street VARCHAR,
+    city VARCHAR,
+    postcode VARCHAR,
+    region VARCHAR,
+    country VARCHAR,
+    latitude REAL,
+    longitude REAL,
+    radius REAL,
+    source VARCHAR,
+    unscoped BOOLEAN DEFAULT FALSE NOT NULL,
+    first_seen TIMESTAMP,
+    last_seen TIMESTAMP,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT FALSE NOT NULL,
+    extra TEXT,
+    CONSTRAINT location_unique UNIQUE (value)
+);
+CREATE TABLE domain_locations (
+    id SERIAL PRIMARY KEY,
+    domain_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    CONSTRAINT domain_location_unique UNIQUE (domain_id, location_id)
+);
+CREATE TABLE phonenumber_locations (
+    id SERIAL PRIMARY KEY,
+    phone_number_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+    FOREIGN KEY(phone_number_id) REFERENCES phonenumbers(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    CONSTRAINT phonenumber_location_unique UNIQUE (phone_number_id, location_id)
+);
+CREATE TABLE account_locations (
+    id SERIAL PRIMARY KEY,
+    account_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    CONSTRAINT account_location_unique UNIQUE (account_id, location_id)
+);
+CREATE TABLE tags (
+    id SERIAL PRIMARY KEY,
+    family VARCHAR NOT NULL,
+    entity_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    CONSTRAINT tag_unique UNIQUE (family, entity_id, value)
+);
+CREATE INDEX tags_family_value ON tags(family, value);
+CREATE TABLE notes (
+    id SERIAL PRIMARY KEY,
+    family VARCHAR NOT NULL,
+    entity_id INTEGER NOT NULL,
+    author VARCHAR NOT NULL,
+    time TIMESTAMP NOT NULL,
+    content TEXT NOT NULL
+);
+CREATE INDEX notes_entity ON notes(family, entity_id);
+CREATE TABLE trash (
+    id SERIAL PRIMARY KEY,
+    time TIMESTAMP NOT NULL,
+    description VARCHAR NOT NULL
+);
+CREATE TABLE trash_rows (
+    id SERIAL PRIMARY KEY,
+    trash_id INTEGER NOT NULL,
+    family VARCHAR NOT NULL,
+    key INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    reference BOOLEAN DEFAULT FALSE NOT NULL,
+    FOREIGN KEY(trash_id) REFERENCES trash(id) ON DELETE CASCADE
+);
+CREATE INDEX trash_rows_trash ON trash_rows(trash_id);
+CREATE INDEX ipaddrs_netblock ON ipaddrs(netblock_id);
+CREATE INDEX ipaddrs_range_key ON ipaddrs(range_key);
+CREATE INDEX netblocks_range ON netblocks(range_start, range_end);
+ALTER TABLE ipaddrs ADD FOREIGN KEY (netblock_id) REFERENCES netblocks(id) ON DELETE SET NULL;
+
+-- the textual fields of all entities that are searched by the search command,
+-- punctuation is a word boundary so domains and emails are split into words
+ALTER TABLE domains ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX domains_search ON domains USING GIN (search_vector);
+ALTER TABLE subdomains ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX subdomains_search ON subdomains USING GIN (search_vector);
+ALTER TABLE ipaddrs ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(continent, '') || ' ' || COALESCE(country, '') || ' ' || COALESCE(city, '') || ' ' || COALESCE(as_org, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(reverse_dns, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX ipaddrs_search ON ipaddrs USING GIN (search_vector);
+ALTER TABLE urls ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(title, '') || ' ' || COALESCE(redirect, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX urls_search ON urls USING GIN (search_vector);
+ALTER TABLE emails ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(displayname, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX emails_search ON emails USING GIN (search_vector);
+ALTER TABLE phonenumbers ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(country, '') || ' ' || COALESCE(carrier, '') || ' ' || COALESCE(caller_name, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX phonenumbers_search ON phonenumbers USING GIN (search_vector);
+ALTER TABLE devices ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(hostname, '') || ' ' || COALESCE(vendor, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX devices_search ON devices USING GIN (search_vector);
+ALTER TABLE networks ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX networks_search ON networks USING GIN (search_vector);
+ALTER TABLE accounts ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(service, '') || ' ' || COALESCE(username, '') || ' ' || COALESCE(displayname, '') || ' ' || COALESCE(email, '') || ' ' || COALESCE(url, '') || ' ' || COALESCE(phonenumber, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX accounts_search ON accounts USING GIN (search_vector);
+ALTER TABLE breaches ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX breaches_search ON breaches USING GIN (search_vector);
+ALTER TABLE images ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(filename, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX images_search ON images USING GIN (search_vector);
+ALTER TABLE ports ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(protocol, '') || ' ' || COALESCE(status, '') || ' ' || COALESCE(banner, '') || ' ' || COALESCE(service, '') || ' ' || COALESCE(version, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX ports_search ON ports USING GIN (search_vector);
+ALTER TABLE netblocks ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(as_org, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX netblocks_search ON netblocks USING GIN (search_vector);
+ALTER TABLE cryptoaddrs ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(currency, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX cryptoaddrs_search ON cryptoaddrs USING GIN (search_vector);
+ALTER TABLE dns_records ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(record_type, '') || ' ' || COALESCE(data, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX dns_records_search ON dns_records USING GIN (search_vector);
+ALTER TABLE certificates ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(subject, '') || ' ' || COALESCE(issuer, '') || ' ' || COALESCE(names, '') || ' ' || COALESCE(serial, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX certificates_search ON certificates USING GIN (search_vector);
+ALTER TABLE organizations ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX organizations_search ON organizations USING GIN (search_vector);
+ALTER TABLE persons ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX persons_search ON persons USING GIN (search_vector);
+ALTER TABLE autonomous_systems ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(CAST(value AS TEXT) || ' ' || COALESCE(name, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX autonomous_systems_search ON autonomous_systems USING GIN (search_vector);
+ALTER TABLE documents ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(filename, '') || ' ' || COALESCE(title, '') || ' ' || COALESCE(author, '') || ' ' || COALESCE(creator, '') || ' ' || COALESCE(text, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX documents_search ON documents USING GIN (search_vector);
+ALTER TABLE locations ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS
+    (to_tsvector('simple', regexp_replace(COALESCE(value, '') || ' ' || COALESCE(street, '') || ' ' || COALESCE(city, '') || ' ' || COALESCE(postcode, '') || ' ' || COALESCE(region, '') || ' ' || COALESCE(country, '') || ' ' || COALESCE(extra, ''), '[^[:alnum:]]+', ' ', 'g'))) STORED;
+CREATE INDEX locations_search ON locations USING GIN (search_vector);
+
+CREATE VIEW search AS
+    SELECT 'domains' AS family, id AS entity_id, search_vector FROM domains
+    UNION ALL
+    SELECT 'subdomains' AS family, id AS entity_id, search_vector FROM subdomains
+    UNION ALL
+    SELECT 'ipaddrs' AS family, id AS entity_id, search_vector FROM ipaddrs
+    UNION ALL
+    SELECT 'urls' AS family, id AS entity_id, search_vector FROM urls
+    UNION ALL
+    SELECT 'emails' AS family, id AS entity_id, search_vector FROM emails
+    UNION ALL
+    SELECT 'phonenumbers' AS family, id AS entity_id, search_vector FROM phonenumbers
+    UNION ALL
+    SELECT 'devices' AS family, id AS entity_id, search_vector FROM devices
+    UNION ALL
+    SELECT 'networks' AS family, id AS entity_id, search_vector FROM networks
+    UNION ALL
+    SELECT 'accounts' AS family, id AS entity_id, search_vector FROM accounts
+    UNION ALL
+    SELECT 'breaches' AS family, id AS entity_id, search_vector FROM breaches
+    UNION ALL
+    SELECT 'images' AS family, id AS entity_id, search_vector FROM images
+    UNION ALL
+    SELECT 'ports' AS family, id AS entity_id, search_vector FROM ports
+    UNION ALL
+    SELECT 'netblocks' AS family, id AS entity_id, search_vector FROM netblocks
+    UNION ALL
+    SELECT 'cryptoaddrs' AS family, id AS entity_id, search_vector FROM cryptoaddrs
+    UNION ALL
+    SELECT 'dns-records' AS family, id AS entity_id, search_vector FROM dns_records
+    UNION ALL
+    SELECT 'certificates' AS family, id AS entity_id, search_vector FROM certificates
+    UNION ALL
+    SELECT 'organizations' AS family, id AS entity_id, search_vector FROM organizations
+    UNION ALL
+    SELECT 'persons' AS family, id AS entity_id, search_vector FROM persons
+    UNION ALL
+    SELECT 'autonomous-systems' AS family, id AS entity_id, search_vector FROM autonomous_systems
+    UNION ALL
+    SELECT 'documents' AS family, id AS entity_id, search_vector FROM documents
+    UNION ALL
+    SELECT 'locations' AS family, id AS entity_id, search_vector FROM locations;
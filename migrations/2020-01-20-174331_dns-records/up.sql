@@ -0,0 +1,13 @@
+CREATE TABLE dns_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    subdomain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    name VARCHAR NOT NULL,
+    record_type VARCHAR NOT NULL,
+    data VARCHAR NOT NULL,
+    ttl INTEGER,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    CONSTRAINT dns_record_unique UNIQUE (value)
+);
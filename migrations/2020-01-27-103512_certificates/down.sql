@@ -0,0 +1,3 @@
+DROP TABLE port_certificates;
+DROP TABLE subdomain_certificates;
+DROP TABLE certificates;
@@ -0,0 +1,33 @@
+CREATE TABLE certificates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    serial VARCHAR NOT NULL,
+    subject VARCHAR NOT NULL,
+    issuer VARCHAR NOT NULL,
+    not_before DATETIME NOT NULL,
+    not_after DATETIME NOT NULL,
+    names VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT certificate_unique UNIQUE (value)
+);
+
+CREATE TABLE subdomain_certificates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    subdomain_id INTEGER NOT NULL,
+    certificate_id INTEGER NOT NULL,
+
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    FOREIGN KEY(certificate_id) REFERENCES certificates(id) ON DELETE CASCADE,
+    CONSTRAINT subdomain_certificate_unique UNIQUE (subdomain_id, certificate_id)
+);
+
+CREATE TABLE port_certificates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    port_id INTEGER NOT NULL,
+    certificate_id INTEGER NOT NULL,
+
+    FOREIGN KEY(port_id) REFERENCES ports(id) ON DELETE CASCADE,
+    FOREIGN KEY(certificate_id) REFERENCES certificates(id) ON DELETE CASCADE,
+    CONSTRAINT port_certificate_unique UNIQUE (port_id, certificate_id)
+);
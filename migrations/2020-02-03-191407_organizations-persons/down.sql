@@ -0,0 +1,12 @@
+DROP TABLE organization_domains;
+DROP TABLE organization_emails;
+DROP TABLE organization_phonenumbers;
+DROP TABLE organization_accounts;
+DROP TABLE organization_netblocks;
+DROP TABLE person_domains;
+DROP TABLE person_emails;
+DROP TABLE person_phonenumbers;
+DROP TABLE person_accounts;
+DROP TABLE person_netblocks;
+DROP TABLE persons;
+DROP TABLE organizations;
@@ -0,0 +1,117 @@
+CREATE TABLE organizations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    description VARCHAR,
+
+    CONSTRAINT organization_unique UNIQUE (value)
+);
+
+CREATE TABLE persons (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    description VARCHAR,
+
+    CONSTRAINT person_unique UNIQUE (value)
+);
+
+CREATE TABLE organization_domains (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    organization_id INTEGER NOT NULL,
+    domain_id INTEGER NOT NULL,
+
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    CONSTRAINT organization_domain_unique UNIQUE (organization_id, domain_id)
+);
+
+CREATE TABLE organization_emails (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    organization_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(email_id) REFERENCES emails(id) ON DELETE CASCADE,
+    CONSTRAINT organization_email_unique UNIQUE (organization_id, email_id)
+);
+
+CREATE TABLE organization_phonenumbers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    organization_id INTEGER NOT NULL,
+    phone_number_id INTEGER NOT NULL,
+
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(phone_number_id) REFERENCES phonenumbers(id) ON DELETE CASCADE,
+    CONSTRAINT organization_phonenumber_unique UNIQUE (organization_id, phone_number_id)
+);
+
+CREATE TABLE organization_accounts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    organization_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    CONSTRAINT organization_account_unique UNIQUE (organization_id, account_id)
+);
+
+CREATE TABLE organization_netblocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    organization_id INTEGER NOT NULL,
+    netblock_id INTEGER NOT NULL,
+
+    FOREIGN KEY(organization_id) REFERENCES organizations(id) ON DELETE CASCADE,
+    FOREIGN KEY(netblock_id) REFERENCES netblocks(id) ON DELETE CASCADE,
+    CONSTRAINT organization_netblock_unique UNIQUE (organization_id, netblock_id)
+);
+
+CREATE TABLE person_domains (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    person_id INTEGER NOT NULL,
+    domain_id INTEGER NOT NULL,
+
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    CONSTRAINT person_domain_unique UNIQUE (person_id, domain_id)
+);
+
+CREATE TABLE person_emails (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    person_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(email_id) REFERENCES emails(id) ON DELETE CASCADE,
+    CONSTRAINT person_email_unique UNIQUE (person_id, email_id)
+);
+
+CREATE TABLE person_phonenumbers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    person_id INTEGER NOT NULL,
+    phone_number_id INTEGER NOT NULL,
+
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(phone_number_id) REFERENCES phonenumbers(id) ON DELETE CASCADE,
+    CONSTRAINT person_phonenumber_unique UNIQUE (person_id, phone_number_id)
+);
+
+CREATE TABLE person_accounts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    person_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    CONSTRAINT person_account_unique UNIQUE (person_id, account_id)
+);
+
+CREATE TABLE person_netblocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    person_id INTEGER NOT NULL,
+    netblock_id INTEGER NOT NULL,
+
+    FOREIGN KEY(person_id) REFERENCES persons(id) ON DELETE CASCADE,
+    FOREIGN KEY(netblock_id) REFERENCES netblocks(id) ON DELETE CASCADE,
+    CONSTRAINT person_netblock_unique UNIQUE (person_id, netblock_id)
+);
@@ -0,0 +1 @@
+DROP TABLE autonomous_systems;
@@ -0,0 +1,8 @@
+CREATE TABLE autonomous_systems (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value INTEGER NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    name VARCHAR,
+
+    CONSTRAINT autonomous_system_unique UNIQUE (value)
+);
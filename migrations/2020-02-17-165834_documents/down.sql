@@ -0,0 +1,2 @@
+DROP TABLE url_documents;
+DROP TABLE documents;
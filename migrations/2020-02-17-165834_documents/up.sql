@@ -0,0 +1,26 @@
+CREATE TABLE documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    filename VARCHAR,
+    mime VARCHAR,
+    size INTEGER,
+    title VARCHAR,
+    author VARCHAR,
+    creator VARCHAR,
+    created DATETIME,
+    modified DATETIME,
+    text VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT document_unique UNIQUE (value)
+);
+
+CREATE TABLE url_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    url_id INTEGER NOT NULL,
+    document_id INTEGER NOT NULL,
+
+    FOREIGN KEY(url_id) REFERENCES urls(id) ON DELETE CASCADE,
+    FOREIGN KEY(document_id) REFERENCES documents(id) ON DELETE CASCADE,
+    CONSTRAINT url_document_unique UNIQUE (url_id, document_id)
+);
@@ -0,0 +1,4 @@
+DROP TABLE account_locations;
+DROP TABLE phonenumber_locations;
+DROP TABLE domain_locations;
+DROP TABLE locations;
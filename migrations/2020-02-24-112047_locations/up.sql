@@ -0,0 +1,46 @@
+CREATE TABLE locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    street VARCHAR,
+    city VARCHAR,
+    postcode VARCHAR,
+    region VARCHAR,
+    country VARCHAR,
+    latitude FLOAT,
+    longitude FLOAT,
+    radius FLOAT,
+    source VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT location_unique UNIQUE (value)
+);
+
+CREATE TABLE domain_locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    domain_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    CONSTRAINT domain_location_unique UNIQUE (domain_id, location_id)
+);
+
+CREATE TABLE phonenumber_locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    phone_number_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+
+    FOREIGN KEY(phone_number_id) REFERENCES phonenumbers(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    CONSTRAINT phonenumber_location_unique UNIQUE (phone_number_id, location_id)
+);
+
+CREATE TABLE account_locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    account_id INTEGER NOT NULL,
+    location_id INTEGER NOT NULL,
+
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE,
+    FOREIGN KEY(location_id) REFERENCES locations(id) ON DELETE CASCADE,
+    CONSTRAINT account_location_unique UNIQUE (account_id, location_id)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    entity_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+
+    CONSTRAINT tag_unique UNIQUE (family, entity_id, value)
+);
+CREATE INDEX tags_family_value ON tags(family, value);
@@ -0,0 +1,9 @@
+CREATE TABLE notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    entity_id INTEGER NOT NULL,
+    author VARCHAR NOT NULL,
+    time DATETIME NOT NULL,
+    content TEXT NOT NULL
+);
+CREATE INDEX notes_entity ON notes(family, entity_id);
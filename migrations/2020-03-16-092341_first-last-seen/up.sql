@@ -0,0 +1,39 @@
+ALTER TABLE accounts ADD COLUMN first_seen DATETIME;
+ALTER TABLE autonomous_systems ADD COLUMN first_seen DATETIME;
+ALTER TABLE autonomous_systems ADD COLUMN last_seen DATETIME;
+ALTER TABLE breaches ADD COLUMN first_seen DATETIME;
+ALTER TABLE breaches ADD COLUMN last_seen DATETIME;
+ALTER TABLE certificates ADD COLUMN first_seen DATETIME;
+ALTER TABLE certificates ADD COLUMN last_seen DATETIME;
+ALTER TABLE cryptoaddrs ADD COLUMN last_seen DATETIME;
+ALTER TABLE devices ADD COLUMN first_seen DATETIME;
+ALTER TABLE dns_records ADD COLUMN first_seen DATETIME;
+ALTER TABLE dns_records ADD COLUMN last_seen DATETIME;
+ALTER TABLE documents ADD COLUMN first_seen DATETIME;
+ALTER TABLE documents ADD COLUMN last_seen DATETIME;
+ALTER TABLE domains ADD COLUMN first_seen DATETIME;
+ALTER TABLE domains ADD COLUMN last_seen DATETIME;
+ALTER TABLE emails ADD COLUMN first_seen DATETIME;
+ALTER TABLE emails ADD COLUMN last_seen DATETIME;
+ALTER TABLE images ADD COLUMN first_seen DATETIME;
+ALTER TABLE images ADD COLUMN last_seen DATETIME;
+ALTER TABLE ipaddrs ADD COLUMN first_seen DATETIME;
+ALTER TABLE ipaddrs ADD COLUMN last_seen DATETIME;
+ALTER TABLE locations ADD COLUMN first_seen DATETIME;
+ALTER TABLE locations ADD COLUMN last_seen DATETIME;
+ALTER TABLE netblocks ADD COLUMN first_seen DATETIME;
+ALTER TABLE netblocks ADD COLUMN last_seen DATETIME;
+ALTER TABLE networks ADD COLUMN first_seen DATETIME;
+ALTER TABLE networks ADD COLUMN last_seen DATETIME;
+ALTER TABLE organizations ADD COLUMN first_seen DATETIME;
+ALTER TABLE organizations ADD COLUMN last_seen DATETIME;
+ALTER TABLE persons ADD COLUMN first_seen DATETIME;
+ALTER TABLE persons ADD COLUMN last_seen DATETIME;
+ALTER TABLE phonenumbers ADD COLUMN first_seen DATETIME;
+ALTER TABLE phonenumbers ADD COLUMN last_seen DATETIME;
+ALTER TABLE ports ADD COLUMN first_seen DATETIME;
+ALTER TABLE ports ADD COLUMN last_seen DATETIME;
+ALTER TABLE subdomains ADD COLUMN first_seen DATETIME;
+ALTER TABLE subdomains ADD COLUMN last_seen DATETIME;
+ALTER TABLE urls ADD COLUMN first_seen DATETIME;
+ALTER TABLE urls ADD COLUMN last_seen DATETIME;
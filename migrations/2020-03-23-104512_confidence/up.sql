@@ -0,0 +1,42 @@
+ALTER TABLE accounts ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE accounts ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE autonomous_systems ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE autonomous_systems ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE breaches ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE breaches ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE certificates ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE certificates ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE cryptoaddrs ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE cryptoaddrs ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE devices ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE devices ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE dns_records ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE dns_records ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE documents ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE documents ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE domains ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE domains ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE emails ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE emails ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE images ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE images ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE ipaddrs ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE ipaddrs ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE locations ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE locations ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE netblocks ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE netblocks ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE networks ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE networks ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE organizations ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE organizations ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE persons ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE persons ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE phonenumbers ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE phonenumbers ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE ports ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE ports ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE subdomains ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE subdomains ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
+ALTER TABLE urls ADD COLUMN confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100);
+ALTER TABLE urls ADD COLUMN verified BOOLEAN DEFAULT 0 NOT NULL;
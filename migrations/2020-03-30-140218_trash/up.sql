@@ -0,0 +1,15 @@
+CREATE TABLE trash (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    time DATETIME NOT NULL,
+    description VARCHAR NOT NULL
+);
+
+CREATE TABLE trash_rows (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    trash_id INTEGER NOT NULL,
+    family VARCHAR NOT NULL,
+    key INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    FOREIGN KEY(trash_id) REFERENCES trash(id) ON DELETE CASCADE
+);
+CREATE INDEX trash_rows_trash ON trash_rows(trash_id);
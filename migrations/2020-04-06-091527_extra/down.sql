@@ -0,0 +1,527 @@
+PRAGMA foreign_keys=off;
+
+-- accounts
+CREATE TABLE _accounts_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    service VARCHAR NOT NULL,
+    username VARCHAR NOT NULL,
+    displayname VARCHAR,
+    email VARCHAR,
+    url VARCHAR,
+    last_seen DATETIME,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    phonenumber VARCHAR,
+    profile_pic VARCHAR,
+    birthday VARCHAR,
+    first_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT account_unique UNIQUE (value)
+);
+
+INSERT INTO _accounts_new (id, value, service, username, displayname, email, url, last_seen, unscoped, phonenumber, profile_pic, birthday, first_seen, confidence, verified)
+    SELECT id, value, service, username, displayname, email, url, last_seen, unscoped, phonenumber, profile_pic, birthday, first_seen, confidence, verified
+    FROM accounts;
+
+DROP TABLE accounts;
+ALTER TABLE _accounts_new RENAME TO accounts;
+
+-- autonomous_systems
+CREATE TABLE _autonomous_systems_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value INTEGER NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    name VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT autonomous_system_unique UNIQUE (value)
+);
+
+INSERT INTO _autonomous_systems_new (id, value, unscoped, name, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, name, first_seen, last_seen, confidence, verified
+    FROM autonomous_systems;
+
+DROP TABLE autonomous_systems;
+ALTER TABLE _autonomous_systems_new RENAME TO autonomous_systems;
+
+-- breaches
+CREATE TABLE _breaches_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT breach_unique UNIQUE (value)
+);
+
+INSERT INTO _breaches_new (id, value, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, first_seen, last_seen, confidence, verified
+    FROM breaches;
+
+DROP TABLE breaches;
+ALTER TABLE _breaches_new RENAME TO breaches;
+
+-- certificates
+CREATE TABLE _certificates_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    serial VARCHAR NOT NULL,
+    subject VARCHAR NOT NULL,
+    issuer VARCHAR NOT NULL,
+    not_before DATETIME NOT NULL,
+    not_after DATETIME NOT NULL,
+    names VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT certificate_unique UNIQUE (value)
+);
+
+INSERT INTO _certificates_new (id, value, serial, subject, issuer, not_before, not_after, names, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, value, serial, subject, issuer, not_before, not_after, names, unscoped, first_seen, last_seen, confidence, verified
+    FROM certificates;
+
+DROP TABLE certificates;
+ALTER TABLE _certificates_new RENAME TO certificates;
+
+-- cryptoaddrs
+CREATE TABLE _cryptoaddrs_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    currency VARCHAR,
+    denominator INTEGER,
+    balance BIGINT,
+    received BIGINT,
+    first_seen DATETIME,
+    last_withdrawal DATETIME,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    description VARCHAR,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT netblock_unique UNIQUE (value)
+);
+
+INSERT INTO _cryptoaddrs_new (id, value, currency, denominator, balance, received, first_seen, last_withdrawal, unscoped, description, last_seen, confidence, verified)
+    SELECT id, value, currency, denominator, balance, received, first_seen, last_withdrawal, unscoped, description, last_seen, confidence, verified
+    FROM cryptoaddrs;
+
+DROP TABLE cryptoaddrs;
+ALTER TABLE _cryptoaddrs_new RENAME TO cryptoaddrs;
+
+-- devices
+CREATE TABLE _devices_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    name VARCHAR,
+    hostname VARCHAR,
+    vendor VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    last_seen DATETIME,
+    first_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT device_unique UNIQUE (value)
+);
+
+INSERT INTO _devices_new (id, value, name, hostname, vendor, unscoped, last_seen, first_seen, confidence, verified)
+    SELECT id, value, name, hostname, vendor, unscoped, last_seen, first_seen, confidence, verified
+    FROM devices;
+
+DROP TABLE devices;
+ALTER TABLE _devices_new RENAME TO devices;
+
+-- dns_records
+CREATE TABLE _dns_records_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    subdomain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    name VARCHAR NOT NULL,
+    record_type VARCHAR NOT NULL,
+    data VARCHAR NOT NULL,
+    ttl INTEGER,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    CONSTRAINT dns_record_unique UNIQUE (value)
+);
+
+INSERT INTO _dns_records_new (id, subdomain_id, value, name, record_type, data, ttl, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, subdomain_id, value, name, record_type, data, ttl, unscoped, first_seen, last_seen, confidence, verified
+    FROM dns_records;
+
+DROP TABLE dns_records;
+ALTER TABLE _dns_records_new RENAME TO dns_records;
+
+-- documents
+CREATE TABLE _documents_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    filename VARCHAR,
+    mime VARCHAR,
+    size INTEGER,
+    title VARCHAR,
+    author VARCHAR,
+    creator VARCHAR,
+    created DATETIME,
+    modified DATETIME,
+    text VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT document_unique UNIQUE (value)
+);
+
+INSERT INTO _documents_new (id, value, filename, mime, size, title, author, creator, created, modified, text, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, value, filename, mime, size, title, author, creator, created, modified, text, unscoped, first_seen, last_seen, confidence, verified
+    FROM documents;
+
+DROP TABLE documents;
+ALTER TABLE _documents_new RENAME TO documents;
+
+-- domains
+CREATE TABLE _domains_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT domain_unique UNIQUE (value)
+);
+
+INSERT INTO _domains_new (id, value, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, first_seen, last_seen, confidence, verified
+    FROM domains;
+
+DROP TABLE domains;
+ALTER TABLE _domains_new RENAME TO domains;
+
+-- emails
+CREATE TABLE _emails_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    valid BOOLEAN,
+    displayname VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT email_unique UNIQUE (value)
+);
+
+INSERT INTO _emails_new (id, value, unscoped, valid, displayname, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, valid, displayname, first_seen, last_seen, confidence, verified
+    FROM emails;
+
+DROP TABLE emails;
+ALTER TABLE _emails_new RENAME TO emails;
+
+-- images
+CREATE TABLE _images_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+
+    filename VARCHAR,
+    mime VARCHAR,
+    width INT,
+    height INT,
+    created DATETIME,
+
+    latitude FLOAT,
+    longitude FLOAT,
+
+    nudity FLOAT,
+    ahash VARCHAR,
+    dhash VARCHAR,
+    phash VARCHAR,
+
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT image_unique UNIQUE (value)
+);
+
+INSERT INTO _images_new (id, value, filename, mime, width, height, created, latitude, longitude, nudity, ahash, dhash, phash, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, value, filename, mime, width, height, created, latitude, longitude, nudity, ahash, dhash, phash, unscoped, first_seen, last_seen, confidence, verified
+    FROM images;
+
+DROP TABLE images;
+ALTER TABLE _images_new RENAME TO images;
+
+-- ipaddrs
+CREATE TABLE _ipaddrs_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    continent VARCHAR,
+    continent_code VARCHAR,
+    country VARCHAR,
+    country_code VARCHAR,
+    city VARCHAR,
+    latitude FLOAT,
+    longitude FLOAT,
+    asn INTEGER,
+    as_org VARCHAR,
+    description VARCHAR,
+    reverse_dns VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT ipaddr_unique UNIQUE (value)
+);
+
+INSERT INTO _ipaddrs_new (id, family, value, unscoped, continent, continent_code, country, country_code, city, latitude, longitude, asn, as_org, description, reverse_dns, first_seen, last_seen, confidence, verified)
+    SELECT id, family, value, unscoped, continent, continent_code, country, country_code, city, latitude, longitude, asn, as_org, description, reverse_dns, first_seen, last_seen, confidence, verified
+    FROM ipaddrs;
+
+DROP TABLE ipaddrs;
+ALTER TABLE _ipaddrs_new RENAME TO ipaddrs;
+
+-- locations
+CREATE TABLE _locations_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    street VARCHAR,
+    city VARCHAR,
+    postcode VARCHAR,
+    region VARCHAR,
+    country VARCHAR,
+    latitude FLOAT,
+    longitude FLOAT,
+    radius FLOAT,
+    source VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT location_unique UNIQUE (value)
+);
+
+INSERT INTO _locations_new (id, value, street, city, postcode, region, country, latitude, longitude, radius, source, unscoped, first_seen, last_seen, confidence, verified)
+    SELECT id, value, street, city, postcode, region, country, latitude, longitude, radius, source, unscoped, first_seen, last_seen, confidence, verified
+    FROM locations;
+
+DROP TABLE locations;
+ALTER TABLE _locations_new RENAME TO locations;
+
+-- netblocks
+CREATE TABLE _netblocks_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    asn INTEGER,
+    as_org VARCHAR,
+    description VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT netblock_unique UNIQUE (value)
+);
+
+INSERT INTO _netblocks_new (id, family, value, unscoped, asn, as_org, description, first_seen, last_seen, confidence, verified)
+    SELECT id, family, value, unscoped, asn, as_org, description, first_seen, last_seen, confidence, verified
+    FROM netblocks;
+
+DROP TABLE netblocks;
+ALTER TABLE _netblocks_new RENAME TO netblocks;
+
+-- networks
+CREATE TABLE _networks_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    latitude FLOAT,
+    longitude FLOAT,
+    description VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT network_unique UNIQUE (value)
+);
+
+INSERT INTO _networks_new (id, value, unscoped, latitude, longitude, description, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, latitude, longitude, description, first_seen, last_seen, confidence, verified
+    FROM networks;
+
+DROP TABLE networks;
+ALTER TABLE _networks_new RENAME TO networks;
+
+-- organizations
+CREATE TABLE _organizations_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    description VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT organization_unique UNIQUE (value)
+);
+
+INSERT INTO _organizations_new (id, value, unscoped, description, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, description, first_seen, last_seen, confidence, verified
+    FROM organizations;
+
+DROP TABLE organizations;
+ALTER TABLE _organizations_new RENAME TO organizations;
+
+-- persons
+CREATE TABLE _persons_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    description VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    CONSTRAINT person_unique UNIQUE (value)
+);
+
+INSERT INTO _persons_new (id, value, unscoped, description, first_seen, last_seen, confidence, verified)
+    SELECT id, value, unscoped, description, first_seen, last_seen, confidence, verified
+    FROM persons;
+
+DROP TABLE persons;
+ALTER TABLE _persons_new RENAME TO persons;
+
+-- phonenumbers
+CREATE TABLE _phonenumbers_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    value VARCHAR NOT NULL,
+    name VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    valid BOOLEAN,
+    last_online DATETIME,
+    country VARCHAR,
+    carrier VARCHAR,
+    line VARCHAR,
+    is_ported BOOLEAN,
+    last_ported DATETIME,
+    caller_name VARCHAR,
+    caller_type VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    CONSTRAINT phonenumber_unique UNIQUE (value)
+);
+
+INSERT INTO _phonenumbers_new (id, value, name, unscoped, valid, last_online, country, carrier, line, is_ported, last_ported, caller_name, caller_type, first_seen, last_seen, confidence, verified)
+    SELECT id, value, name, unscoped, valid, last_online, country, carrier, line, is_ported, last_ported, caller_name, caller_type, first_seen, last_seen, confidence, verified
+    FROM phonenumbers;
+
+DROP TABLE phonenumbers;
+ALTER TABLE _phonenumbers_new RENAME TO phonenumbers;
+
+-- ports
+CREATE TABLE _ports_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    ip_addr_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    ip_addr VARCHAR NOT NULL,
+    port INTEGER NOT NULL,
+    protocol VARCHAR NOT NULL,
+    status VARCHAR,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+
+    banner VARCHAR,
+    service VARCHAR,
+    version VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+
+    FOREIGN KEY(ip_addr_id) REFERENCES ipaddrs(id) ON DELETE CASCADE,
+    CONSTRAINT port_unique UNIQUE (value)
+);
+
+INSERT INTO _ports_new (id, ip_addr_id, value, ip_addr, port, protocol, status, unscoped, banner, service, version, first_seen, last_seen, confidence, verified)
+    SELECT id, ip_addr_id, value, ip_addr, port, protocol, status, unscoped, banner, service, version, first_seen, last_seen, confidence, verified
+    FROM ports;
+
+DROP TABLE ports;
+ALTER TABLE _ports_new RENAME TO ports;
+
+-- subdomains
+CREATE TABLE _subdomains_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    domain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    resolvable BOOLEAN,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    FOREIGN KEY(domain_id) REFERENCES domains(id) ON DELETE CASCADE,
+    CONSTRAINT subdomain_unique UNIQUE (value)
+);
+
+INSERT INTO _subdomains_new (id, domain_id, value, unscoped, resolvable, first_seen, last_seen, confidence, verified)
+    SELECT id, domain_id, value, unscoped, resolvable, first_seen, last_seen, confidence, verified
+    FROM subdomains;
+
+DROP TABLE subdomains;
+ALTER TABLE _subdomains_new RENAME TO subdomains;
+
+-- urls
+CREATE TABLE _urls_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    subdomain_id INTEGER NOT NULL,
+    value VARCHAR NOT NULL,
+    path VARCHAR NOT NULL,
+    status INTEGER,
+    body BLOB,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    online BOOLEAN,
+    title VARCHAR,
+    redirect VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    FOREIGN KEY(subdomain_id) REFERENCES subdomains(id) ON DELETE CASCADE,
+    CONSTRAINT url_unique UNIQUE (value)
+);
+
+INSERT INTO _urls_new (id, subdomain_id, value, path, status, body, unscoped, online, title, redirect, first_seen, last_seen, confidence, verified)
+    SELECT id, subdomain_id, value, path, status, body, unscoped, online, title, redirect, first_seen, last_seen, confidence, verified
+    FROM urls;
+
+DROP TABLE urls;
+ALTER TABLE _urls_new RENAME TO urls;
+
+PRAGMA foreign_keys=on;
@@ -0,0 +1,21 @@
+ALTER TABLE accounts ADD COLUMN extra TEXT;
+ALTER TABLE autonomous_systems ADD COLUMN extra TEXT;
+ALTER TABLE breaches ADD COLUMN extra TEXT;
+ALTER TABLE certificates ADD COLUMN extra TEXT;
+ALTER TABLE cryptoaddrs ADD COLUMN extra TEXT;
+ALTER TABLE devices ADD COLUMN extra TEXT;
+ALTER TABLE dns_records ADD COLUMN extra TEXT;
+ALTER TABLE documents ADD COLUMN extra TEXT;
+ALTER TABLE domains ADD COLUMN extra TEXT;
+ALTER TABLE emails ADD COLUMN extra TEXT;
+ALTER TABLE images ADD COLUMN extra TEXT;
+ALTER TABLE ipaddrs ADD COLUMN extra TEXT;
+ALTER TABLE locations ADD COLUMN extra TEXT;
+ALTER TABLE netblocks ADD COLUMN extra TEXT;
+ALTER TABLE networks ADD COLUMN extra TEXT;
+ALTER TABLE organizations ADD COLUMN extra TEXT;
+ALTER TABLE persons ADD COLUMN extra TEXT;
+ALTER TABLE phonenumbers ADD COLUMN extra TEXT;
+ALTER TABLE ports ADD COLUMN extra TEXT;
+ALTER TABLE subdomains ADD COLUMN extra TEXT;
+ALTER TABLE urls ADD COLUMN extra TEXT;
@@ -0,0 +1,34 @@
+PRAGMA foreign_keys=off;
+
+CREATE TABLE _ipaddrs_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    continent VARCHAR,
+    continent_code VARCHAR,
+    country VARCHAR,
+    country_code VARCHAR,
+    city VARCHAR,
+    latitude FLOAT,
+    longitude FLOAT,
+    asn INTEGER,
+    as_org VARCHAR,
+    description VARCHAR,
+    reverse_dns VARCHAR,
+    first_seen DATETIME,
+    last_seen DATETIME,
+    confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100),
+    verified BOOLEAN DEFAULT 0 NOT NULL,
+    extra TEXT,
+    CONSTRAINT ipaddr_unique UNIQUE (value)
+);
+
+INSERT INTO _ipaddrs_new (id, family, value, unscoped, continent, continent_code, country, country_code, city, latitude, longitude, asn, as_org, description, reverse_dns, first_seen, last_seen, confidence, verified, extra)
+    SELECT id, family, value, unscoped, continent, continent_code, country, country_code, city, latitude, longitude, asn, as_org, description, reverse_dns, first_seen, last_seen, confidence, verified, extra
+    FROM ipaddrs;
+
+DROP TABLE ipaddrs;
+ALTER TABLE _ipaddrs_new RENAME TO ipaddrs;
+
+PRAGMA foreign_keys=on;
@@ -0,0 +1,2 @@
+ALTER TABLE ipaddrs ADD COLUMN netblock_id INTEGER REFERENCES netblocks(id) ON DELETE SET NULL;
+CREATE INDEX ipaddrs_netblock ON ipaddrs(netblock_id);
@@ -0,0 +1,64 @@
+DROP TRIGGER domains_search_insert;
+DROP TRIGGER domains_search_update;
+DROP TRIGGER domains_search_delete;
+DROP TRIGGER subdomains_search_insert;
+DROP TRIGGER subdomains_search_update;
+DROP TRIGGER subdomains_search_delete;
+DROP TRIGGER ipaddrs_search_insert;
+DROP TRIGGER ipaddrs_search_update;
+DROP TRIGGER ipaddrs_search_delete;
+DROP TRIGGER urls_search_insert;
+DROP TRIGGER urls_search_update;
+DROP TRIGGER urls_search_delete;
+DROP TRIGGER emails_search_insert;
+DROP TRIGGER emails_search_update;
+DROP TRIGGER emails_search_delete;
+DROP TRIGGER phonenumbers_search_insert;
+DROP TRIGGER phonenumbers_search_update;
+DROP TRIGGER phonenumbers_search_delete;
+DROP TRIGGER devices_search_insert;
+DROP TRIGGER devices_search_update;
+DROP TRIGGER devices_search_delete;
+DROP TRIGGER networks_search_insert;
+DROP TRIGGER networks_search_update;
+DROP TRIGGER networks_search_delete;
+DROP TRIGGER accounts_search_insert;
+DROP TRIGGER accounts_search_update;
+DROP TRIGGER accounts_search_delete;
+DROP TRIGGER breaches_search_insert;
+DROP TRIGGER breaches_search_update;
+DROP TRIGGER breaches_search_delete;
+DROP TRIGGER images_search_insert;
+DROP TRIGGER images_search_update;
+DROP TRIGGER images_search_delete;
+DROP TRIGGER ports_search_insert;
+DROP TRIGGER ports_search_update;
+DROP TRIGGER ports_search_delete;
+DROP TRIGGER netblocks_search_insert;
+DROP TRIGGER netblocks_search_update;
+DROP TRIGGER netblocks_search_delete;
+DROP TRIGGER cryptoaddrs_search_insert;
+DROP TRIGGER cryptoaddrs_search_update;
+DROP TRIGGER cryptoaddrs_search_delete;
+DROP TRIGGER dns_records_search_insert;
+DROP TRIGGER dns_records_search_update;
+DROP TRIGGER dns_records_search_delete;
+DROP TRIGGER certificates_search_insert;
+DROP TRIGGER certificates_search_update;
+DROP TRIGGER certificates_search_delete;
+DROP TRIGGER organizations_search_insert;
+DROP TRIGGER organizations_search_update;
+DROP TRIGGER organizations_search_delete;
+DROP TRIGGER persons_search_insert;
+DROP TRIGGER persons_search_update;
+DROP TRIGGER persons_search_delete;
+DROP TRIGGER autonomous_systems_search_insert;
+DROP TRIGGER autonomous_systems_search_update;
+DROP TRIGGER autonomous_systems_search_delete;
+DROP TRIGGER documents_search_insert;
+DROP TRIGGER documents_search_update;
+DROP TRIGGER documents_search_delete;
+DROP TRIGGER locations_search_insert;
+DROP TRIGGER locations_search_update;
+DROP TRIGGER locations_search_delete;
+DROP TABLE search;
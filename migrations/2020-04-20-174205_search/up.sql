@@ -0,0 +1,276 @@
+-- the rowid is derived from the family and the entity id so the index can be
+-- updated without scanning it
+CREATE VIRTUAL TABLE search USING fts5(family UNINDEXED, entity_id UNINDEXED, content);
+
+CREATE TRIGGER domains_search_insert AFTER INSERT ON domains BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (1 * 4294967296 + new.id, 'domains', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER domains_search_update AFTER UPDATE OF value, extra ON domains BEGIN
+    DELETE FROM search WHERE rowid = 1 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (1 * 4294967296 + new.id, 'domains', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER domains_search_delete AFTER DELETE ON domains BEGIN
+    DELETE FROM search WHERE rowid = 1 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 1 * 4294967296 + id, 'domains', id, COALESCE(value, '') || ' ' || COALESCE(extra, '') FROM domains;
+
+CREATE TRIGGER subdomains_search_insert AFTER INSERT ON subdomains BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (2 * 4294967296 + new.id, 'subdomains', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER subdomains_search_update AFTER UPDATE OF value, extra ON subdomains BEGIN
+    DELETE FROM search WHERE rowid = 2 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (2 * 4294967296 + new.id, 'subdomains', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER subdomains_search_delete AFTER DELETE ON subdomains BEGIN
+    DELETE FROM search WHERE rowid = 2 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 2 * 4294967296 + id, 'subdomains', id, COALESCE(value, '') || ' ' || COALESCE(extra, '') FROM subdomains;
+
+CREATE TRIGGER ipaddrs_search_insert AFTER INSERT ON ipaddrs BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (3 * 4294967296 + new.id, 'ipaddrs', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.continent, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.city, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.reverse_dns, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER ipaddrs_search_update AFTER UPDATE OF value, continent, country, city, as_org, description, reverse_dns, extra ON ipaddrs BEGIN
+    DELETE FROM search WHERE rowid = 3 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (3 * 4294967296 + new.id, 'ipaddrs', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.continent, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.city, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.reverse_dns, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER ipaddrs_search_delete AFTER DELETE ON ipaddrs BEGIN
+    DELETE FROM search WHERE rowid = 3 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 3 * 4294967296 + id, 'ipaddrs', id, COALESCE(value, '') || ' ' || COALESCE(continent, '') || ' ' || COALESCE(country, '') || ' ' || COALESCE(city, '') || ' ' || COALESCE(as_org, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(reverse_dns, '') || ' ' || COALESCE(extra, '') FROM ipaddrs;
+
+CREATE TRIGGER urls_search_insert AFTER INSERT ON urls BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (4 * 4294967296 + new.id, 'urls', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.title, '') || ' ' || COALESCE(new.redirect, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER urls_search_update AFTER UPDATE OF value, title, redirect, extra ON urls BEGIN
+    DELETE FROM search WHERE rowid = 4 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (4 * 4294967296 + new.id, 'urls', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.title, '') || ' ' || COALESCE(new.redirect, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER urls_search_delete AFTER DELETE ON urls BEGIN
+    DELETE FROM search WHERE rowid = 4 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 4 * 4294967296 + id, 'urls', id, COALESCE(value, '') || ' ' || COALESCE(title, '') || ' ' || COALESCE(redirect, '') || ' ' || COALESCE(extra, '') FROM urls;
+
+CREATE TRIGGER emails_search_insert AFTER INSERT ON emails BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (5 * 4294967296 + new.id, 'emails', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.displayname, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER emails_search_update AFTER UPDATE OF value, displayname, extra ON emails BEGIN
+    DELETE FROM search WHERE rowid = 5 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (5 * 4294967296 + new.id, 'emails', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.displayname, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER emails_search_delete AFTER DELETE ON emails BEGIN
+    DELETE FROM search WHERE rowid = 5 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 5 * 4294967296 + id, 'emails', id, COALESCE(value, '') || ' ' || COALESCE(displayname, '') || ' ' || COALESCE(extra, '') FROM emails;
+
+CREATE TRIGGER phonenumbers_search_insert AFTER INSERT ON phonenumbers BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (6 * 4294967296 + new.id, 'phonenumbers', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.carrier, '') || ' ' || COALESCE(new.caller_name, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER phonenumbers_search_update AFTER UPDATE OF value, name, country, carrier, caller_name, extra ON phonenumbers BEGIN
+    DELETE FROM search WHERE rowid = 6 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (6 * 4294967296 + new.id, 'phonenumbers', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.carrier, '') || ' ' || COALESCE(new.caller_name, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER phonenumbers_search_delete AFTER DELETE ON phonenumbers BEGIN
+    DELETE FROM search WHERE rowid = 6 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 6 * 4294967296 + id, 'phonenumbers', id, COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(country, '') || ' ' || COALESCE(carrier, '') || ' ' || COALESCE(caller_name, '') || ' ' || COALESCE(extra, '') FROM phonenumbers;
+
+CREATE TRIGGER devices_search_insert AFTER INSERT ON devices BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (7 * 4294967296 + new.id, 'devices', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.hostname, '') || ' ' || COALESCE(new.vendor, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER devices_search_update AFTER UPDATE OF value, name, hostname, vendor, extra ON devices BEGIN
+    DELETE FROM search WHERE rowid = 7 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (7 * 4294967296 + new.id, 'devices', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.hostname, '') || ' ' || COALESCE(new.vendor, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER devices_search_delete AFTER DELETE ON devices BEGIN
+    DELETE FROM search WHERE rowid = 7 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 7 * 4294967296 + id, 'devices', id, COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(hostname, '') || ' ' || COALESCE(vendor, '') || ' ' || COALESCE(extra, '') FROM devices;
+
+CREATE TRIGGER networks_search_insert AFTER INSERT ON networks BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (8 * 4294967296 + new.id, 'networks', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER networks_search_update AFTER UPDATE OF value, description, extra ON networks BEGIN
+    DELETE FROM search WHERE rowid = 8 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (8 * 4294967296 + new.id, 'networks', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER networks_search_delete AFTER DELETE ON networks BEGIN
+    DELETE FROM search WHERE rowid = 8 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 8 * 4294967296 + id, 'networks', id, COALESCE(value, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, '') FROM networks;
+
+CREATE TRIGGER accounts_search_insert AFTER INSERT ON accounts BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (9 * 4294967296 + new.id, 'accounts', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.service, '') || ' ' || COALESCE(new.username, '') || ' ' || COALESCE(new.displayname, '') || ' ' || COALESCE(new.email, '') || ' ' || COALESCE(new.url, '') || ' ' || COALESCE(new.phonenumber, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER accounts_search_update AFTER UPDATE OF value, service, username, displayname, email, url, phonenumber, extra ON accounts BEGIN
+    DELETE FROM search WHERE rowid = 9 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (9 * 4294967296 + new.id, 'accounts', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.service, '') || ' ' || COALESCE(new.username, '') || ' ' || COALESCE(new.displayname, '') || ' ' || COALESCE(new.email, '') || ' ' || COALESCE(new.url, '') || ' ' || COALESCE(new.phonenumber, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER accounts_search_delete AFTER DELETE ON accounts BEGIN
+    DELETE FROM search WHERE rowid = 9 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 9 * 4294967296 + id, 'accounts', id, COALESCE(value, '') || ' ' || COALESCE(service, '') || ' ' || COALESCE(username, '') || ' ' || COALESCE(displayname, '') || ' ' || COALESCE(email, '') || ' ' || COALESCE(url, '') || ' ' || COALESCE(phonenumber, '') || ' ' || COALESCE(extra, '') FROM accounts;
+
+CREATE TRIGGER breaches_search_insert AFTER INSERT ON breaches BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (10 * 4294967296 + new.id, 'breaches', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER breaches_search_update AFTER UPDATE OF value, extra ON breaches BEGIN
+    DELETE FROM search WHERE rowid = 10 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (10 * 4294967296 + new.id, 'breaches', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER breaches_search_delete AFTER DELETE ON breaches BEGIN
+    DELETE FROM search WHERE rowid = 10 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 10 * 4294967296 + id, 'breaches', id, COALESCE(value, '') || ' ' || COALESCE(extra, '') FROM breaches;
+
+CREATE TRIGGER images_search_insert AFTER INSERT ON images BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (11 * 4294967296 + new.id, 'images', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.filename, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER images_search_update AFTER UPDATE OF value, filename, extra ON images BEGIN
+    DELETE FROM search WHERE rowid = 11 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (11 * 4294967296 + new.id, 'images', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.filename, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER images_search_delete AFTER DELETE ON images BEGIN
+    DELETE FROM search WHERE rowid = 11 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 11 * 4294967296 + id, 'images', id, COALESCE(value, '') || ' ' || COALESCE(filename, '') || ' ' || COALESCE(extra, '') FROM images;
+
+CREATE TRIGGER ports_search_insert AFTER INSERT ON ports BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (12 * 4294967296 + new.id, 'ports', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.protocol, '') || ' ' || COALESCE(new.status, '') || ' ' || COALESCE(new.banner, '') || ' ' || COALESCE(new.service, '') || ' ' || COALESCE(new.version, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER ports_search_update AFTER UPDATE OF value, protocol, status, banner, service, version, extra ON ports BEGIN
+    DELETE FROM search WHERE rowid = 12 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (12 * 4294967296 + new.id, 'ports', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.protocol, '') || ' ' || COALESCE(new.status, '') || ' ' || COALESCE(new.banner, '') || ' ' || COALESCE(new.service, '') || ' ' || COALESCE(new.version, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER ports_search_delete AFTER DELETE ON ports BEGIN
+    DELETE FROM search WHERE rowid = 12 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 12 * 4294967296 + id, 'ports', id, COALESCE(value, '') || ' ' || COALESCE(protocol, '') || ' ' || COALESCE(status, '') || ' ' || COALESCE(banner, '') || ' ' || COALESCE(service, '') || ' ' || COALESCE(version, '') || ' ' || COALESCE(extra, '') FROM ports;
+
+CREATE TRIGGER netblocks_search_insert AFTER INSERT ON netblocks BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (13 * 4294967296 + new.id, 'netblocks', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER netblocks_search_update AFTER UPDATE OF value, as_org, description, extra ON netblocks BEGIN
+    DELETE FROM search WHERE rowid = 13 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (13 * 4294967296 + new.id, 'netblocks', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER netblocks_search_delete AFTER DELETE ON netblocks BEGIN
+    DELETE FROM search WHERE rowid = 13 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 13 * 4294967296 + id, 'netblocks', id, COALESCE(value, '') || ' ' || COALESCE(as_org, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, '') FROM netblocks;
+
+CREATE TRIGGER cryptoaddrs_search_insert AFTER INSERT ON cryptoaddrs BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (14 * 4294967296 + new.id, 'cryptoaddrs', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.currency, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER cryptoaddrs_search_update AFTER UPDATE OF value, currency, description, extra ON cryptoaddrs BEGIN
+    DELETE FROM search WHERE rowid = 14 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (14 * 4294967296 + new.id, 'cryptoaddrs', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.currency, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER cryptoaddrs_search_delete AFTER DELETE ON cryptoaddrs BEGIN
+    DELETE FROM search WHERE rowid = 14 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 14 * 4294967296 + id, 'cryptoaddrs', id, COALESCE(value, '') || ' ' || COALESCE(currency, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, '') FROM cryptoaddrs;
+
+CREATE TRIGGER dns_records_search_insert AFTER INSERT ON dns_records BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (15 * 4294967296 + new.id, 'dns-records', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.record_type, '') || ' ' || COALESCE(new.data, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER dns_records_search_update AFTER UPDATE OF value, name, record_type, data, extra ON dns_records BEGIN
+    DELETE FROM search WHERE rowid = 15 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (15 * 4294967296 + new.id, 'dns-records', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.record_type, '') || ' ' || COALESCE(new.data, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER dns_records_search_delete AFTER DELETE ON dns_records BEGIN
+    DELETE FROM search WHERE rowid = 15 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 15 * 4294967296 + id, 'dns-records', id, COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(record_type, '') || ' ' || COALESCE(data, '') || ' ' || COALESCE(extra, '') FROM dns_records;
+
+CREATE TRIGGER certificates_search_insert AFTER INSERT ON certificates BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (16 * 4294967296 + new.id, 'certificates', new.id, COALESCE(new.subject, '') || ' ' || COALESCE(new.issuer, '') || ' ' || COALESCE(new.names, '') || ' ' || COALESCE(new.serial, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER certificates_search_update AFTER UPDATE OF subject, issuer, names, serial, extra ON certificates BEGIN
+    DELETE FROM search WHERE rowid = 16 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (16 * 4294967296 + new.id, 'certificates', new.id, COALESCE(new.subject, '') || ' ' || COALESCE(new.issuer, '') || ' ' || COALESCE(new.names, '') || ' ' || COALESCE(new.serial, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER certificates_search_delete AFTER DELETE ON certificates BEGIN
+    DELETE FROM search WHERE rowid = 16 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 16 * 4294967296 + id, 'certificates', id, COALESCE(subject, '') || ' ' || COALESCE(issuer, '') || ' ' || COALESCE(names, '') || ' ' || COALESCE(serial, '') || ' ' || COALESCE(extra, '') FROM certificates;
+
+CREATE TRIGGER organizations_search_insert AFTER INSERT ON organizations BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (17 * 4294967296 + new.id, 'organizations', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER organizations_search_update AFTER UPDATE OF value, description, extra ON organizations BEGIN
+    DELETE FROM search WHERE rowid = 17 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (17 * 4294967296 + new.id, 'organizations', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER organizations_search_delete AFTER DELETE ON organizations BEGIN
+    DELETE FROM search WHERE rowid = 17 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 17 * 4294967296 + id, 'organizations', id, COALESCE(value, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, '') FROM organizations;
+
+CREATE TRIGGER persons_search_insert AFTER INSERT ON persons BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (18 * 4294967296 + new.id, 'persons', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER persons_search_update AFTER UPDATE OF value, description, extra ON persons BEGIN
+    DELETE FROM search WHERE rowid = 18 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (18 * 4294967296 + new.id, 'persons', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER persons_search_delete AFTER DELETE ON persons BEGIN
+    DELETE FROM search WHERE rowid = 18 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 18 * 4294967296 + id, 'persons', id, COALESCE(value, '') || ' ' || COALESCE(description, '') || ' ' || COALESCE(extra, '') FROM persons;
+
+CREATE TRIGGER autonomous_systems_search_insert AFTER INSERT ON autonomous_systems BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (19 * 4294967296 + new.id, 'autonomous-systems', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER autonomous_systems_search_update AFTER UPDATE OF value, name, extra ON autonomous_systems BEGIN
+    DELETE FROM search WHERE rowid = 19 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (19 * 4294967296 + new.id, 'autonomous-systems', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.name, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER autonomous_systems_search_delete AFTER DELETE ON autonomous_systems BEGIN
+    DELETE FROM search WHERE rowid = 19 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 19 * 4294967296 + id, 'autonomous-systems', id, COALESCE(value, '') || ' ' || COALESCE(name, '') || ' ' || COALESCE(extra, '') FROM autonomous_systems;
+
+CREATE TRIGGER documents_search_insert AFTER INSERT ON documents BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (20 * 4294967296 + new.id, 'documents', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.filename, '') || ' ' || COALESCE(new.title, '') || ' ' || COALESCE(new.author, '') || ' ' || COALESCE(new.creator, '') || ' ' || COALESCE(new.text, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER documents_search_update AFTER UPDATE OF value, filename, title, author, creator, text, extra ON documents BEGIN
+    DELETE FROM search WHERE rowid = 20 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (20 * 4294967296 + new.id, 'documents', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.filename, '') || ' ' || COALESCE(new.title, '') || ' ' || COALESCE(new.author, '') || ' ' || COALESCE(new.creator, '') || ' ' || COALESCE(new.text, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER documents_search_delete AFTER DELETE ON documents BEGIN
+    DELETE FROM search WHERE rowid = 20 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 20 * 4294967296 + id, 'documents', id, COALESCE(value, '') || ' ' || COALESCE(filename, '') || ' ' || COALESCE(title, '') || ' ' || COALESCE(author, '') || ' ' || COALESCE(creator, '') || ' ' || COALESCE(text, '') || ' ' || COALESCE(extra, '') FROM documents;
+
+CREATE TRIGGER locations_search_insert AFTER INSERT ON locations BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (21 * 4294967296 + new.id, 'locations', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.street, '') || ' ' || COALESCE(new.city, '') || ' ' || COALESCE(new.postcode, '') || ' ' || COALESCE(new.region, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER locations_search_update AFTER UPDATE OF value, street, city, postcode, region, country, extra ON locations BEGIN
+    DELETE FROM search WHERE rowid = 21 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (21 * 4294967296 + new.id, 'locations', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.street, '') || ' ' || COALESCE(new.city, '') || ' ' || COALESCE(new.postcode, '') || ' ' || COALESCE(new.region, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER locations_search_delete AFTER DELETE ON locations BEGIN
+    DELETE FROM search WHERE rowid = 21 * 4294967296 + old.id;
+END;
+INSERT INTO search (rowid, family, entity_id, content)
+    SELECT 21 * 4294967296 + id, 'locations', id, COALESCE(value, '') || ' ' || COALESCE(street, '') || ' ' || COALESCE(city, '') || ' ' || COALESCE(postcode, '') || ' ' || COALESCE(region, '') || ' ' || COALESCE(country, '') || ' ' || COALESCE(extra, '') FROM locations;
@@ -0,0 +1,20 @@
+PRAGMA foreign_keys=off;
+
+CREATE TABLE _trash_rows_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    trash_id INTEGER NOT NULL,
+    family VARCHAR NOT NULL,
+    key INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    FOREIGN KEY(trash_id) REFERENCES trash(id) ON DELETE CASCADE
+);
+
+INSERT INTO _trash_rows_new (id, trash_id, family, key, data)
+    SELECT id, trash_id, family, key, data
+    FROM trash_rows;
+
+DROP TABLE trash_rows;
+ALTER TABLE _trash_rows_new RENAME TO trash_rows;
+CREATE INDEX trash_rows_trash ON trash_rows(trash_id);
+
+PRAGMA foreign_keys=on;
@@ -0,0 +1 @@
+ALTER TABLE trash_rows ADD COLUMN reference BOOLEAN DEFAULT 0 NOT NULL;
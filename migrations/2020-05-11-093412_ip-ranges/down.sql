@@ -0,0 +1,66 @@
+PRAGMA foreign_keys=off;
+
+CREATE TABLE _netblocks_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    asn INTEGER,
+    as_org VARCHAR,
+    description VARCHAR, first_seen DATETIME, last_seen DATETIME, confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100), verified BOOLEAN DEFAULT 0 NOT NULL, extra TEXT,
+    CONSTRAINT netblock_unique UNIQUE (value)
+);
+
+INSERT INTO _netblocks_new (id, family, value, unscoped, asn, as_org, description, first_seen, last_seen, confidence, verified, extra)
+    SELECT id, family, value, unscoped, asn, as_org, description, first_seen, last_seen, confidence, verified, extra
+    FROM netblocks;
+
+DROP TABLE netblocks;
+ALTER TABLE _netblocks_new RENAME TO netblocks;
+CREATE TRIGGER netblocks_search_delete AFTER DELETE ON netblocks BEGIN
+    DELETE FROM search WHERE rowid = 13 * 4294967296 + old.id;
+END;
+CREATE TRIGGER netblocks_search_insert AFTER INSERT ON netblocks BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (13 * 4294967296 + new.id, 'netblocks', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER netblocks_search_update AFTER UPDATE OF value, as_org, description, extra ON netblocks BEGIN
+    DELETE FROM search WHERE rowid = 13 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (13 * 4294967296 + new.id, 'netblocks', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.extra, ''));
+END;
+
+CREATE TABLE _ipaddrs_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    family VARCHAR NOT NULL,
+    value VARCHAR NOT NULL,
+    unscoped BOOLEAN DEFAULT 0 NOT NULL,
+    continent VARCHAR,
+    continent_code VARCHAR,
+    country VARCHAR,
+    country_code VARCHAR,
+    city VARCHAR,
+    latitude FLOAT,
+    longitude FLOAT,
+    asn INTEGER,
+    as_org VARCHAR, description VARCHAR, reverse_dns VARCHAR, first_seen DATETIME, last_seen DATETIME, confidence INTEGER DEFAULT 100 NOT NULL CHECK (confidence BETWEEN 0 AND 100), verified BOOLEAN DEFAULT 0 NOT NULL, extra TEXT, netblock_id INTEGER REFERENCES netblocks(id) ON DELETE SET NULL,
+    CONSTRAINT ipaddr_unique UNIQUE (value)
+);
+
+INSERT INTO _ipaddrs_new (id, family, value, unscoped, continent, continent_code, country, country_code, city, latitude, longitude, asn, as_org, description, reverse_dns, first_seen, last_seen, confidence, verified, extra, netblock_id)
+    SELECT id, family, value, unscoped, continent, continent_code, country, country_code, city, latitude, longitude, asn, as_org, description, reverse_dns, first_seen, last_seen, confidence, verified, extra, netblock_id
+    FROM ipaddrs;
+
+DROP TABLE ipaddrs;
+ALTER TABLE _ipaddrs_new RENAME TO ipaddrs;
+CREATE INDEX ipaddrs_netblock ON ipaddrs(netblock_id);
+CREATE TRIGGER ipaddrs_search_delete AFTER DELETE ON ipaddrs BEGIN
+    DELETE FROM search WHERE rowid = 3 * 4294967296 + old.id;
+END;
+CREATE TRIGGER ipaddrs_search_insert AFTER INSERT ON ipaddrs BEGIN
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (3 * 4294967296 + new.id, 'ipaddrs', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.continent, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.city, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.reverse_dns, '') || ' ' || COALESCE(new.extra, ''));
+END;
+CREATE TRIGGER ipaddrs_search_update AFTER UPDATE OF value, continent, country, city, as_org, description, reverse_dns, extra ON ipaddrs BEGIN
+    DELETE FROM search WHERE rowid = 3 * 4294967296 + old.id;
+    INSERT INTO search (rowid, family, entity_id, content) VALUES (3 * 4294967296 + new.id, 'ipaddrs', new.id, COALESCE(new.value, '') || ' ' || COALESCE(new.continent, '') || ' ' || COALESCE(new.country, '') || ' ' || COALESCE(new.city, '') || ' ' || COALESCE(new.as_org, '') || ' ' || COALESCE(new.description, '') || ' ' || COALESCE(new.reverse_dns, '') || ' ' || COALESCE(new.extra, ''));
+END;
+
+PRAGMA foreign_keys=on;
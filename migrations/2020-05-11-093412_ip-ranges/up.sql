@@ -0,0 +1,5 @@
+ALTER TABLE ipaddrs ADD COLUMN range_key VARCHAR;
+CREATE INDEX ipaddrs_range_key ON ipaddrs(range_key);
+ALTER TABLE netblocks ADD COLUMN range_start VARCHAR;
+ALTER TABLE netblocks ADD COLUMN range_end VARCHAR;
+CREATE INDEX netblocks_range ON netblocks(range_start, range_end);
@@ -43,6 +43,13 @@ pub enum Source {
     Ports,
     Netblocks,
     CryptoAddrs(Option<String>),
+    DnsRecords(Option<String>),
+    Certificates,
+    Organizations,
+    Persons,
+    AutonomousSystems,
+    Documents,
+    Locations,
     KeyRing(String),
 }
 
@@ -63,6 +70,13 @@ impl Source {
             Source::Ports => "ports",
             Source::Netblocks => "netblocks",
             Source::CryptoAddrs(_) => "cryptoaddrs",
+            Source::DnsRecords(_) => "dns-records",
+            Source::Certificates => "certificates",
+            Source::Organizations => "organizations",
+            Source::Persons => "persons",
+            Source::AutonomousSystems => "autonomous-systems",
+            Source::Documents => "documents",
+            Source::Locations => "locations",
             Source::KeyRing(_) => "keyring",
         }
     }
@@ -94,6 +108,13 @@ impl FromStr for Source {
             ("ports", None) => Ok(Source::Ports),
             ("netblocks", None) => Ok(Source::Netblocks),
             ("cryptoaddrs", param) => Ok(Source::CryptoAddrs(param.map(String::from))),
+            ("dns-records", param) => Ok(Source::DnsRecords(param.map(String::from))),
+            ("certificates", None) => Ok(Source::Certificates),
+            ("organizations", None) => Ok(Source::Organizations),
+            ("persons", None) => Ok(Source::Persons),
+            ("autonomous-systems", None) => Ok(Source::AutonomousSystems),
+            ("documents", None) => Ok(Source::Documents),
+            ("locations", None) => Ok(Source::Locations),
             ("keyring", Some(param)) => Ok(Source::KeyRing(param.to_string())),
             (x, Some(param)) => bail!("Unknown Source: {:?} ({:?})", x, param),
             (x, None) => bail!("Unknown Source: {:?}", x),
@@ -291,4 +312,46 @@ mod tests {
         let x = Source::from_str("accounts:github.com").unwrap();
         assert_eq!(x, Source::Accounts(Some("github.com".into())));
     }
+
+    #[test]
+    fn verify_dns_record_source() {
+        let x = Source::from_str("dns-records").unwrap();
+        assert_eq!(x, Source::DnsRecords(None));
+
+        let x = Source::from_str("dns-records:TXT").unwrap();
+        assert_eq!(x, Source::DnsRecords(Some("TXT".into())));
+    }
+
+    #[test]
+    fn verify_certificate_source() {
+        let x = Source::from_str("certificates").unwrap();
+        assert_eq!(x, Source::Certificates);
+    }
+
+    #[test]
+    fn verify_organization_person_source() {
+        let x = Source::from_str("organizations").unwrap();
+        assert_eq!(x, Source::Organizations);
+
+        let x = Source::from_str("persons").unwrap();
+        assert_eq!(x, Source::Persons);
+    }
+
+    #[test]
+    fn verify_autonomous_system_source() {
+        let x = Source::from_str("autonomous-systems").unwrap();
+        assert_eq!(x, Source::AutonomousSystems);
+    }
+
+    #[test]
+    fn verify_document_source() {
+        let x = Source::from_str("documents").unwrap();
+        assert_eq!(x, Source::Documents);
+    }
+
+    #[test]
+    fn verify_location_source() {
+        let x = Source::from_str("locations").unwrap();
+        assert_eq!(x, Source::Locations);
+    }
 }
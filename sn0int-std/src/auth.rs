@@ -0,0 +1,357 @@
+use crate::errors::*;
+
+use digest::Digest;
+use md5::Md5;
+use rand::{Rng, thread_rng};
+use rand::distributions::Alphanumeric;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+
+/// Authentication that is applied to an http request
+///
+/// Credentials for bearer tokens and oauth2 can be loaded from the keyring
+/// with `keyring="namespace:access_key"` instead of setting them explicitly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Auth {
+    Basic {
+        user: String,
+        password: String,
+    },
+    Digest {
+        user: String,
+        password: String,
+    },
+    Bearer {
+        token: Option<String>,
+        keyring: Option<String>,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        token_url: String,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+        keyring: Option<String>,
+        scope: Option<String>,
+    },
+}
+
+impl Auth {
+    /// Load credentials that reference the keyring
+    ///
+    /// The lookup function returns the access key and the secret key of a
+    /// keyring entry the module has access to.
+    pub fn resolve_keyring<F>(&mut self, lookup: F) -> Result<()>
+        where F: Fn(&str) -> Option<(String, Option<String>)>
+    {
+        match self {
+            Auth::Bearer { token, keyring: Some(query) } => {
+                let (access_key, secret_key) = lookup(query)
+                    .ok_or_else(|| format_err!("Keyring entry not found: {:?}", query))?;
+                *token = Some(secret_key.unwrap_or(access_key));
+            },
+            Auth::OAuth2 { client_id, client_secret, keyring: Some(query), .. } => {
+                let (access_key, secret_key) = lookup(query)
+                    .ok_or_else(|| format_err!("Keyring entry not found: {:?}", query))?;
+                *client_id = Some(access_key);
+                *client_secret = secret_key;
+            },
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+pub fn basic_auth_header(user: &str, password: &str) -> String {
+    let auth = base64::encode(&format!("{}:{}", user, password));
+    format!("Basic {}", auth)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    fn parse(x: &str) -> Result<DigestAlgorithm> {
+        match x.to_uppercase().as_str() {
+            "MD5" => Ok(DigestAlgorithm::Md5),
+            "MD5-SESS" => Ok(DigestAlgorithm::Md5Sess),
+            "SHA-256" => Ok(DigestAlgorithm::Sha256),
+            "SHA-256-SESS" => Ok(DigestAlgorithm::Sha256Sess),
+            _ => bail!("Unsupported digest algorithm: {:?}", x),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Md5 => "MD5",
+            DigestAlgorithm::Md5Sess => "MD5-sess",
+            DigestAlgorithm::Sha256 => "SHA-256",
+            DigestAlgorithm::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_sess(self) -> bool {
+        match self {
+            DigestAlgorithm::Md5Sess | DigestAlgorithm::Sha256Sess => true,
+            _ => false,
+        }
+    }
+
+    fn hash(self, data: &str) -> String {
+        let hash = match self {
+            DigestAlgorithm::Md5 | DigestAlgorithm::Md5Sess => Md5::digest(data.as_bytes()).to_vec(),
+            DigestAlgorithm::Sha256 | DigestAlgorithm::Sha256Sess => Sha256::digest(data.as_bytes()).to_vec(),
+        };
+        hash.iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+/// The `WWW-Authenticate` challenge of a server that requires digest auth
+#[derive(Debug, PartialEq)]
+pub struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: DigestAlgorithm,
+    pub qop_auth: bool,
+}
+
+impl DigestChallenge {
+    pub fn parse(header: &str) -> Result<DigestChallenge> {
+        let header = header.trim();
+        if header.len() < 7 || !header[..7].eq_ignore_ascii_case("digest ") {
+            bail!("Server didn't send a digest challenge");
+        }
+        let params = parse_auth_params(&header[7..]);
+
+        let realm = params.get("realm")
+            .ok_or_else(|| format_err!("Digest challenge is missing realm"))?
+            .to_string();
+        let nonce = params.get("nonce")
+            .ok_or_else(|| format_err!("Digest challenge is missing nonce"))?
+            .to_string();
+        let opaque = params.get("opaque").cloned();
+        let algorithm = match params.get("algorithm") {
+            Some(algorithm) => DigestAlgorithm::parse(algorithm)?,
+            None => DigestAlgorithm::Md5,
+        };
+
+        let qop_auth = match params.get("qop") {
+            Some(qop) if qop.split(',').any(|x| x.trim() == "auth") => true,
+            Some(qop) => bail!("Unsupported digest qop: {:?}", qop),
+            None => false,
+        };
+
+        Ok(DigestChallenge {
+            realm,
+            nonce,
+            opaque,
+            algorithm,
+            qop_auth,
+        })
+    }
+
+    /// Generate the value of the `Authorization` header
+    pub fn authorization(&self, user: &str, password: &str, method: &str, uri: &str) -> String {
+        let cnonce: String = thread_rng().sample_iter(&Alphanumeric).take(16).collect();
+        self.authorization_with_cnonce(user, password, method, uri, &cnonce)
+    }
+
+    fn authorization_with_cnonce(&self, user: &str, password: &str, method: &str, uri: &str, cnonce: &str) -> String {
+        let algo = self.algorithm;
+        let nc = "00000001";
+
+        let mut ha1 = algo.hash(&format!("{}:{}:{}", user, self.realm, password));
+        if algo.is_sess() {
+            ha1 = algo.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+        let ha2 = algo.hash(&format!("{}:{}", method, uri));
+
+        let response = if self.qop_auth {
+            algo.hash(&format!("{}:{}:{}:{}:auth:{}", ha1, self.nonce, nc, cnonce, ha2))
+        } else {
+            algo.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2))
+        };
+
+        let mut header = format!("Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}",
+            user, self.realm, self.nonce, uri, algo.as_str());
+        if self.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce));
+        }
+        header.push_str(&format!(", response=\"{}\"", response));
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+        header
+    }
+}
+
+fn parse_auth_params(x: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut chars = x.chars().peekable();
+
+    loop {
+        // skip separators
+        while let Some(&c) = chars.peek() {
+            if c == ',' || c.is_whitespace() {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let mut key = String::new();
+        while let Some(&c) = chars.peek() {
+            if c == '=' {
+                break;
+            }
+            key.push(c);
+            chars.next();
+        }
+        if chars.next().is_none() {
+            break;
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => if let Some(c) = chars.next() {
+                        value.push(c);
+                    },
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c == ',' {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+
+        params.insert(key.trim().to_lowercase(), value.trim().to_string());
+    }
+
+    params
+}
+
+/// The reply of an oauth2 token endpoint
+#[derive(Debug, Deserialize)]
+pub struct OAuth2TokenResponse {
+    pub access_token: String,
+    pub expires_in: Option<u64>,
+}
+
+/// An oauth2 access token that is cached in an `HttpSession`
+#[derive(Debug, Clone)]
+pub struct OAuth2Token {
+    pub token: String,
+    expires: Option<Instant>,
+}
+
+impl OAuth2Token {
+    pub fn new(token: String, expires_in: Option<u64>) -> OAuth2Token {
+        // refresh the token a little earlier than necessary
+        let expires = expires_in
+            .map(|secs| Instant::now() + Duration::from_secs(secs.saturating_sub(10)));
+        OAuth2Token {
+            token,
+            expires,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self.expires {
+            Some(expires) => Instant::now() < expires,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_digest_challenge() {
+        let challenge = DigestChallenge::parse(r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#).unwrap();
+        assert_eq!(challenge, DigestChallenge {
+            realm: "testrealm@host.com".to_string(),
+            nonce: "dcd98b7102dd2f0e8b11d0f600bfb0c093".to_string(),
+            opaque: Some("5ccc069c403ebaf9f0171e9517f40e41".to_string()),
+            algorithm: DigestAlgorithm::Md5,
+            qop_auth: true,
+        });
+    }
+
+    #[test]
+    fn test_parse_digest_challenge_sha256() {
+        let challenge = DigestChallenge::parse(r#"Digest realm="a, b", nonce="abc", algorithm=SHA-256"#).unwrap();
+        assert_eq!(challenge, DigestChallenge {
+            realm: "a, b".to_string(),
+            nonce: "abc".to_string(),
+            opaque: None,
+            algorithm: DigestAlgorithm::Sha256,
+            qop_auth: false,
+        });
+    }
+
+    #[test]
+    fn test_parse_basic_challenge() {
+        let r = DigestChallenge::parse(r#"Basic realm="foo""#);
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_digest_rfc2617() {
+        let challenge = DigestChallenge::parse(r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#).unwrap();
+        let header = challenge.authorization_with_cnonce("Mufasa", "Circle Of Life", "GET", "/dir/index.html", "0a4f113b");
+        assert_eq!(header, r#"Digest username="Mufasa", realm="testrealm@host.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", uri="/dir/index.html", algorithm=MD5, qop=auth, nc=00000001, cnonce="0a4f113b", response="6629fae49393a05397450978507c4ef1", opaque="5ccc069c403ebaf9f0171e9517f40e41""#);
+    }
+
+    #[test]
+    fn test_basic_auth_header() {
+        let header = basic_auth_header("user", "pass");
+        assert_eq!(header, "Basic dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn test_resolve_bearer_keyring() {
+        let mut auth = Auth::Bearer {
+            token: None,
+            keyring: Some("github:foo".to_string()),
+        };
+        auth.resolve_keyring(|query| {
+            assert_eq!(query, "github:foo");
+            Some(("foo".to_string(), Some("secret".to_string())))
+        }).unwrap();
+        assert_eq!(auth, Auth::Bearer {
+            token: Some("secret".to_string()),
+            keyring: Some("github:foo".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_resolve_missing_keyring() {
+        let mut auth = Auth::Bearer {
+            token: None,
+            keyring: Some("github:foo".to_string()),
+        };
+        let r = auth.resolve_keyring(|_| None);
+        assert!(r.is_err());
+    }
+}
@@ -1,9 +1,11 @@
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use blake2::VarBlake2b;
 use digest::{Input, VariableOutput};
 use serde::ser::{Serialize, Serializer};
 use serde::de::{self, Deserialize, Deserializer};
+use crate::errors::*;
 use std::result;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Blob {
@@ -21,9 +23,9 @@ impl Blob {
     }
 
     pub fn hash(bytes: &[u8]) -> String {
-        let mut h = VarBlake2b::new(32).unwrap();
-        h.input(bytes);
-        Self::encode_hash(&h.vec_result())
+        let mut h = BlobHasher::new();
+        h.update(bytes);
+        h.finish()
     }
 
     #[inline]
@@ -57,8 +59,68 @@ impl<'de> Deserialize<'de> for Blob {
     }
 }
 
+/// Calculates the id of a blob that is received in chunks
+pub struct BlobHasher(VarBlake2b);
+
+impl BlobHasher {
+    pub fn new() -> BlobHasher {
+        BlobHasher(VarBlake2b::new(32).unwrap())
+    }
+
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.input(bytes);
+    }
+
+    pub fn finish(self) -> String {
+        Blob::encode_hash(&self.0.vec_result())
+    }
+}
+
+impl Default for BlobHasher {
+    fn default() -> BlobHasher {
+        BlobHasher::new()
+    }
+}
+
+/// Writes a blob in chunks, the blob id is known after the last chunk
+pub trait BlobWriter {
+    fn write(&mut self, bytes: &[u8]) -> Result<()>;
+
+    fn finish(self: Box<Self>) -> Result<String>;
+}
+
 pub trait BlobState {
     fn register_blob(&self, blob: Blob) -> String;
+
+    fn get_blob(&self, id: &str) -> Result<Arc<Blob>>;
+
+    /// Create a blob in chunks, by default the chunks are collected in memory
+    fn blob_writer<'a>(&'a self) -> Box<dyn BlobWriter + 'a>
+        where Self: Sized
+    {
+        Box::new(MemoryBlobWriter {
+            state: self,
+            buf: BytesMut::new(),
+        })
+    }
+}
+
+struct MemoryBlobWriter<'a, S: BlobState> {
+    state: &'a S,
+    buf: BytesMut,
+}
+
+impl<'a, S: BlobState> BlobWriter for MemoryBlobWriter<'a, S> {
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<String> {
+        let blob = Blob::create(self.buf.freeze());
+        Ok(self.state.register_blob(blob))
+    }
 }
 
 
@@ -96,6 +158,15 @@ mod tests {
         assert_eq!(blob1, blob2);
     }
 
+    #[test]
+    fn test_hasher_chunks() {
+        let mut h = BlobHasher::new();
+        h.update(b"as");
+        h.update(b"");
+        h.update(b"df");
+        assert_eq!(h.finish(), "DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz");
+    }
+
     #[test]
     fn test_hash_encoding() {
         let x = bs58::decode("22es54J4FbFtpb5D1MtBazVuum4TcqCQ7M9JkmYdmJ8W")
@@ -1,5 +1,8 @@
 use crate::errors::*;
 
+use chrono::NaiveDateTime;
+use digest::Digest;
+use sha2::Sha256;
 use x509_parser;
 use der_parser::der::DerObject;
 use der_parser::ber::{BerObjectContent, BerTag};
@@ -81,21 +84,25 @@ pub struct Certificate {
     pub valid_ipaddrs: Vec<IpAddr>,
 }
 
+fn pem_to_der(crt: &str) -> Result<Vec<u8>> {
+    match x509_parser::pem::pem_to_der(crt.as_bytes()) {
+        Ok((remaining, pem)) => {
+            if !remaining.is_empty() {
+                bail!("input cert has trailing garbage");
+            }
+            if pem.label != "CERTIFICATE" {
+                bail!("input is not a certificate");
+            }
+            Ok(pem.contents)
+        },
+        Err(_) => bail!("Failed to parse pem"),
+    }
+}
+
 impl Certificate {
     pub fn parse_pem(crt: &str) -> Result<Certificate> {
-        let pem = match x509_parser::pem::pem_to_der(crt.as_bytes()) {
-            Ok((remaining, pem)) => {
-                if !remaining.is_empty() {
-                    bail!("input cert has trailing garbage");
-                }
-                if pem.label != "CERTIFICATE" {
-                    bail!("input is not a certificate");
-                }
-                pem
-            },
-            Err(_) => bail!("Failed to parse pem"),
-        };
-        Certificate::from_bytes(&pem.contents)
+        let der = pem_to_der(crt)?;
+        Certificate::from_bytes(&der)
     }
 
     pub fn from_bytes(crt: &[u8]) -> Result<Certificate> {
@@ -159,14 +166,61 @@ impl Certificate {
     }
 }
 
+/// The identity and validity of a certificate, in addition to the names it's valid for
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CertificateDetails {
+    /// The sha256 of the der encoded certificate
+    pub fingerprint: String,
+    pub serial: String,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: NaiveDateTime,
+    pub not_after: NaiveDateTime,
+    pub names: Certificate,
+}
+
+impl CertificateDetails {
+    pub fn parse_pem(crt: &str) -> Result<CertificateDetails> {
+        let der = pem_to_der(crt)?;
+        CertificateDetails::from_bytes(&der)
+    }
+
+    pub fn from_bytes(crt: &[u8]) -> Result<CertificateDetails> {
+        let der = match x509_parser::parse_x509_der(&crt) {
+            Ok((remaining, der)) => {
+                if !remaining.is_empty() {
+                    bail!("input cert has trailing garbage");
+                }
+                der
+            },
+            Err(_) => bail!("Failed to parse der"),
+        };
+        let tbs = &der.tbs_certificate;
+
+        let fingerprint = Sha256::digest(crt).iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let not_before = NaiveDateTime::from_timestamp(tbs.validity.not_before.to_timespec().sec, 0);
+        let not_after = NaiveDateTime::from_timestamp(tbs.validity.not_after.to_timespec().sec, 0);
+
+        Ok(CertificateDetails {
+            fingerprint,
+            serial: tbs.serial.to_str_radix(16),
+            subject: tbs.subject.to_string(),
+            issuer: tbs.issuer.to_string(),
+            not_before,
+            not_after,
+            names: Certificate::from_bytes(crt)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use der_parser::parse_der;
 
-    #[test]
-    fn test_parse_pem_github() {
-        let mut x = Certificate::parse_pem(r#"-----BEGIN CERTIFICATE-----
+    const GITHUB_PEM: &str = r#"-----BEGIN CERTIFICATE-----
 MIIHQjCCBiqgAwIBAgIQCgYwQn9bvO1pVzllk7ZFHzANBgkqhkiG9w0BAQsFADB1
 MQswCQYDVQQGEwJVUzEVMBMGA1UEChMMRGlnaUNlcnQgSW5jMRkwFwYDVQQLExB3
 d3cuZGlnaWNlcnQuY29tMTQwMgYDVQQDEytEaWdpQ2VydCBTSEEyIEV4dGVuZGVk
@@ -207,7 +261,22 @@ Kqg6LK0Hcq4K0sZnxE8HFxiZ92WpV2AVWjRMEc/2z2shNoDvxvFUYyY1Oe67xINk
 myQKc+ygSBZzyLnXSFVWmHr3u5dcaaQGGAR42v6Ydr4iL38Hd4dOiBma+FXsXBIq
 WUjbST4VXmdaol7uzFMojA4zkxQDZAvF5XgJlAFadfySna/teik=
 -----END CERTIFICATE-----
-"#).expect("Failed to parse cert");
+"#;
+
+    #[test]
+    fn test_parse_details_github() {
+        let x = CertificateDetails::parse_pem(GITHUB_PEM).expect("Failed to parse cert");
+        assert_eq!(x.fingerprint, "3111500c4a66012cdae333ec3fca1c9dde45c954440e7ee413716bff3663c074");
+        assert_eq!(x.serial, "a0630427f5bbced6957396593b6451f");
+        assert!(x.subject.ends_with("CN=github.com"));
+        assert!(x.issuer.ends_with("CN=DigiCert SHA2 Extended Validation Server CA"));
+        assert_eq!(x.not_before, NaiveDateTime::from_timestamp(1525737600, 0));
+        assert_eq!(x.not_after, NaiveDateTime::from_timestamp(1591185600, 0));
+    }
+
+    #[test]
+    fn test_parse_pem_github() {
+        let mut x = Certificate::parse_pem(GITHUB_PEM).expect("Failed to parse cert");
         x.valid_names.sort();
         assert_eq!(x, Certificate {
             valid_names: vec!["github.com".into(), "www.github.com".into()],
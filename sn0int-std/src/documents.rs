@@ -0,0 +1,804 @@
+use crate::errors::*;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+use std::io::Read;
+use xml::reader::{EventReader, XmlEvent};
+
+/// Number of characters we keep from the extracted text
+const TEXT_EXCERPT_LEN: usize = 2048;
+/// Upper limit for decompressed streams and zip entries
+const MAX_INFLATE_SIZE: u64 = 16 * 1024 * 1024;
+/// Upper limit for the combined size of all decompressed streams of a pdf
+const MAX_PDF_INFLATE_TOTAL: u64 = 64 * 1024 * 1024;
+/// Upper limit for the number of streams that are decompressed in a pdf
+const MAX_PDF_STREAMS: usize = 4096;
+
+const MIME_PDF: &str = "application/pdf";
+const MIME_DOCX: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+const MIME_XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+const MIME_PPTX: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+const MIME_ZIP: &str = "application/zip";
+
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DocumentMetadata {
+    pub mime: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// The application that created the document
+    pub creator: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub modified: Option<NaiveDateTime>,
+    /// The beginning of the text content
+    pub text: Option<String>,
+}
+
+/// Detect the document type and extract its metadata
+pub fn parse(bytes: &[u8]) -> Result<DocumentMetadata> {
+    if is_pdf(bytes) {
+        pdf(bytes)
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        office(bytes)
+    } else {
+        bail!("Unsupported document format")
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8], start: usize) -> Option<usize> {
+    if start >= haystack.len() {
+        return None;
+    }
+    haystack[start..].windows(needle.len())
+        .position(|w| w == needle)
+        .map(|idx| idx + start)
+}
+
+fn excerpt(text: &str) -> Option<String> {
+    let text = text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.chars().take(TEXT_EXCERPT_LEN).collect())
+    }
+}
+
+fn inflate<R: Read>(r: R, limit: u64) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    r.take(limit).read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+// pdf
+
+fn is_pdf(bytes: &[u8]) -> bool {
+    let header = &bytes[..bytes.len().min(1024)];
+    find(header, b"%PDF-", 0).is_some()
+}
+
+/// Extract the info dictionary and a text excerpt from a pdf
+pub fn pdf(bytes: &[u8]) -> Result<DocumentMetadata> {
+    if !is_pdf(bytes) {
+        bail!("Not a pdf document");
+    }
+
+    // the info dictionary and the page contents may be inside compressed streams
+    let streams = pdf_streams(bytes);
+    let mut sources = vec![bytes];
+    sources.extend(streams.iter().map(|x| x.as_slice()));
+
+    let lookup = |key: &[u8]| {
+        sources.iter()
+            .filter_map(|src| pdf_dict_value(src, key))
+            .next()
+    };
+
+    let creator = lookup(b"/Creator").or_else(|| lookup(b"/Producer"));
+    let created = lookup(b"/CreationDate").and_then(|x| pdf_date(&x));
+    let modified = lookup(b"/ModDate").and_then(|x| pdf_date(&x));
+
+    let mut text = String::new();
+    for stream in &streams {
+        pdf_text(stream, &mut text);
+        if text.len() > TEXT_EXCERPT_LEN * 4 {
+            break;
+        }
+    }
+
+    Ok(DocumentMetadata {
+        mime: MIME_PDF.to_string(),
+        title: lookup(b"/Title"),
+        author: lookup(b"/Author"),
+        creator,
+        created,
+        modified,
+        text: excerpt(&text),
+    })
+}
+
+/// Decompress all zlib encoded streams, other filters are skipped
+fn pdf_streams(bytes: &[u8]) -> Vec<Vec<u8>> {
+    pdf_streams_limited(bytes, MAX_PDF_STREAMS, MAX_PDF_INFLATE_TOTAL)
+}
+
+/// Decompress streams until either `max_streams` or `max_total` bytes are reached
+fn pdf_streams_limited(bytes: &[u8], max_streams: usize, max_total: u64) -> Vec<Vec<u8>> {
+    let mut streams = Vec::new();
+    let mut cursor = 0;
+    let mut attempts = 0;
+    let mut total = 0;
+
+    while attempts < max_streams && total < max_total {
+        let idx = match find(bytes, b"stream", cursor) {
+            Some(idx) => idx,
+            None => break,
+        };
+        cursor = idx + 6;
+        if idx >= 3 && &bytes[idx-3..idx] == b"end" {
+            continue;
+        }
+
+        let mut start = cursor;
+        if bytes[start..].starts_with(b"\r\n") {
+            start += 2;
+        } else if bytes[start..].starts_with(b"\n") {
+            start += 1;
+        } else {
+            continue;
+        }
+
+        let end = match find(bytes, b"endstream", start) {
+            Some(end) => end,
+            None => break,
+        };
+        cursor = end + 9;
+
+        attempts += 1;
+        let limit = MAX_INFLATE_SIZE.min(max_total - total);
+        if let Ok(data) = inflate(ZlibDecoder::new(&bytes[start..end]), limit) {
+            total += data.len() as u64;
+            streams.push(data);
+        }
+    }
+
+    streams
+}
+
+fn pdf_dict_value(bytes: &[u8], key: &[u8]) -> Option<String> {
+    let mut cursor = 0;
+    while let Some(idx) = find(bytes, key, cursor) {
+        cursor = idx + key.len();
+
+        // make sure we didn't match a prefix of a longer key
+        match bytes.get(cursor) {
+            Some(b) if b.is_ascii_alphanumeric() => continue,
+            _ => (),
+        }
+
+        let mut pos = cursor;
+        while bytes.get(pos).map(|b| b.is_ascii_whitespace()).unwrap_or(false) {
+            pos += 1;
+        }
+
+        let value = match bytes.get(pos) {
+            Some(b'(') => pdf_literal_string(&bytes[pos..]).map(|(x, _)| x),
+            Some(b'<') if bytes.get(pos+1) != Some(&b'<') => pdf_hex_string(&bytes[pos..]),
+            _ => None,
+        };
+
+        if let Some(value) = value {
+            let value = pdf_decode_text(&value);
+            let value = value.trim();
+            if !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Parse a literal string starting at the opening paren, returns the string
+/// and the number of bytes that have been consumed
+fn pdf_literal_string(bytes: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let mut out = Vec::new();
+    let mut depth = 0;
+    let mut i = 1;
+
+    while i < bytes.len() {
+        let chr = bytes[i];
+        match chr {
+            b'\\' => {
+                i += 1;
+                let esc = *bytes.get(i)?;
+                match esc {
+                    b'n' => out.push(b'\n'),
+                    b'r' => out.push(b'\r'),
+                    b't' => out.push(b'\t'),
+                    b'b' => out.push(8),
+                    b'f' => out.push(12),
+                    b'\r' => {
+                        if bytes.get(i+1) == Some(&b'\n') {
+                            i += 1;
+                        }
+                    },
+                    b'\n' => (),
+                    b'0'..=b'7' => {
+                        let mut n = u32::from(esc - b'0');
+                        let mut len = 1;
+                        while len < 3 {
+                            match bytes.get(i+1) {
+                                Some(digit @ b'0'..=b'7') => n = n * 8 + u32::from(digit - b'0'),
+                                _ => break,
+                            }
+                            i += 1;
+                            len += 1;
+                        }
+                        out.push(n as u8);
+                    },
+                    _ => out.push(esc),
+                }
+            },
+            b'(' => {
+                depth += 1;
+                out.push(chr);
+            },
+            b')' => {
+                if depth == 0 {
+                    return Some((out, i + 1));
+                }
+                depth -= 1;
+                out.push(chr);
+            },
+            _ => out.push(chr),
+        }
+        i += 1;
+    }
+
+    None
+}
+
+fn pdf_hex_string(bytes: &[u8]) -> Option<Vec<u8>> {
+    let end = find(bytes, b">", 0)?;
+    let mut digits = bytes[1..end].iter()
+        .filter(|b| !b.is_ascii_whitespace())
+        .map(|b| (*b as char).to_digit(16).map(|x| x as u8))
+        .collect::<Option<Vec<_>>>()?;
+    if digits.len() % 2 == 1 {
+        digits.push(0);
+    }
+    Some(digits.chunks(2)
+        .map(|x| x[0] << 4 | x[1])
+        .collect())
+}
+
+/// Text strings are either utf-16 with a byte order mark or PDFDocEncoding,
+/// which is close enough to latin1 for our purpose
+fn pdf_decode_text(bytes: &[u8]) -> String {
+    if bytes.starts_with(b"\xfe\xff") {
+        let units = bytes[2..].chunks(2)
+            .filter(|x| x.len() == 2)
+            .map(|x| u16::from(x[0]) << 8 | u16::from(x[1]))
+            .collect::<Vec<_>>();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter()
+            .map(|b| *b as char)
+            .collect()
+    }
+}
+
+/// Parse dates like `D:20190523041600+02'00'`
+fn pdf_date(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim_start_matches("D:");
+    let digits = s.chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    if digits.len() < 4 {
+        return None;
+    }
+
+    let field = |start: usize, len: usize, default: u32| -> Option<u32> {
+        match digits.get(start..start+len) {
+            Some(x) => x.parse().ok(),
+            None => Some(default),
+        }
+    };
+
+    let year = digits[..4].parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, field(4, 2, 1)?, field(6, 2, 1)?)?;
+    let datetime = date.and_hms_opt(field(8, 2, 0)?, field(10, 2, 0)?, field(12, 2, 0)?)?;
+
+    let tz = &s[digits.len()..];
+    let offset = match tz.chars().next() {
+        Some(sign @ '+') | Some(sign @ '-') => {
+            let tz = tz[1..].replace('\'', "");
+            let hours = tz.get(..2).and_then(|x| x.parse::<i64>().ok()).unwrap_or(0);
+            let minutes = tz.get(2..4).and_then(|x| x.parse::<i64>().ok()).unwrap_or(0);
+            let offset = Duration::minutes(hours * 60 + minutes);
+            if sign == '-' { -offset } else { offset }
+        },
+        _ => Duration::zero(),
+    };
+
+    Some(datetime - offset)
+}
+
+/// Collect the strings that are shown inside of text objects
+fn pdf_text(bytes: &[u8], out: &mut String) {
+    let mut cursor = 0;
+    while let Some(start) = find(bytes, b"BT", cursor) {
+        let end = find(bytes, b"ET", start + 2).unwrap_or_else(|| bytes.len());
+        let block = &bytes[start+2..end];
+
+        let mut i = 0;
+        while i < block.len() {
+            if block[i] == b'(' {
+                if let Some((s, len)) = pdf_literal_string(&block[i..]) {
+                    out.extend(s.iter()
+                        .map(|b| *b as char)
+                        .filter(|c| !c.is_control()));
+                    i += len;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        out.push(' ');
+
+        cursor = end + 2;
+    }
+}
+
+// zip based office formats
+
+struct ZipEntry {
+    name: String,
+    method: u16,
+    compressed_size: usize,
+    header_offset: usize,
+}
+
+struct ZipArchive<'a> {
+    bytes: &'a [u8],
+    entries: Vec<ZipEntry>,
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16> {
+    let x = bytes.get(offset..offset+2)
+        .ok_or_else(|| format_err!("Unexpected end of zip file"))?;
+    Ok(u16::from(x[0]) | u16::from(x[1]) << 8)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+    let x = bytes.get(offset..offset+4)
+        .ok_or_else(|| format_err!("Unexpected end of zip file"))?;
+    Ok(u32::from(x[0]) | u32::from(x[1]) << 8 | u32::from(x[2]) << 16 | u32::from(x[3]) << 24)
+}
+
+impl<'a> ZipArchive<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<ZipArchive<'a>> {
+        // the end of central directory record is followed by a comment of up to 64k
+        if bytes.len() < 22 {
+            bail!("Zip file is too short");
+        }
+        let lower = bytes.len().saturating_sub(22 + 0xffff);
+        let eocd = (lower..=bytes.len() - 22).rev()
+            .find(|i| &bytes[*i..*i+4] == b"PK\x05\x06")
+            .ok_or_else(|| format_err!("Missing end of central directory"))?;
+
+        let count = read_u16(bytes, eocd + 10)?;
+        let mut offset = read_u32(bytes, eocd + 16)? as usize;
+
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            if bytes.get(offset..offset+4) != Some(b"PK\x01\x02") {
+                bail!("Invalid central directory entry");
+            }
+
+            let method = read_u16(bytes, offset + 10)?;
+            let compressed_size = read_u32(bytes, offset + 20)? as usize;
+            let name_len = read_u16(bytes, offset + 28)? as usize;
+            let extra_len = read_u16(bytes, offset + 30)? as usize;
+            let comment_len = read_u16(bytes, offset + 32)? as usize;
+            let header_offset = read_u32(bytes, offset + 42)? as usize;
+            let name = bytes.get(offset+46..offset+46+name_len)
+                .ok_or_else(|| format_err!("Unexpected end of zip file"))?;
+
+            entries.push(ZipEntry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                method,
+                compressed_size,
+                header_offset,
+            });
+
+            offset += 46 + name_len + extra_len + comment_len;
+        }
+
+        Ok(ZipArchive {
+            bytes,
+            entries,
+        })
+    }
+
+    fn names(&self) -> impl Iterator<Item=&str> {
+        self.entries.iter()
+            .map(|x| x.name.as_str())
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.names().any(|x| x == name)
+    }
+
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let entry = match self.entries.iter().find(|x| x.name == name) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let offset = entry.header_offset;
+        if self.bytes.get(offset..offset+4) != Some(b"PK\x03\x04") {
+            bail!("Invalid local file header");
+        }
+        let name_len = read_u16(self.bytes, offset + 26)? as usize;
+        let extra_len = read_u16(self.bytes, offset + 28)? as usize;
+        let start = offset + 30 + name_len + extra_len;
+        let data = self.bytes.get(start..start+entry.compressed_size)
+            .ok_or_else(|| format_err!("Unexpected end of zip file"))?;
+
+        let data = match entry.method {
+            0 => data.to_vec(),
+            8 => inflate(DeflateDecoder::new(data), MAX_INFLATE_SIZE)?,
+            x => bail!("Unsupported zip compression method: {}", x),
+        };
+        Ok(Some(data))
+    }
+}
+
+/// Read the text of the given elements, identified by their local name
+fn xml_fields(bytes: &[u8], fields: &[&str]) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut current = None;
+
+    for event in EventReader::new(bytes) {
+        match event {
+            Ok(XmlEvent::StartElement { name, .. }) => {
+                if fields.contains(&name.local_name.as_str()) {
+                    current = Some((name.local_name, String::new()));
+                }
+            },
+            Ok(XmlEvent::Characters(text)) => {
+                if let Some((_, value)) = &mut current {
+                    value.push_str(&text);
+                }
+            },
+            Ok(XmlEvent::EndElement { .. }) => {
+                if let Some((name, value)) = current.take() {
+                    out.push((name, value.trim().to_string()));
+                }
+            },
+            Err(_) => break,
+            _ => (),
+        }
+    }
+
+    out
+}
+
+fn xml_field(fields: &[(String, String)], name: &str) -> Option<String> {
+    fields.iter()
+        .find(|(k, v)| k == name && !v.is_empty())
+        .map(|(_, v)| v.to_string())
+}
+
+/// Collect the text inside of `text` elements, `breaks` are separated by a space
+fn xml_text(bytes: &[u8], text: &[&str], breaks: &[&str], out: &mut String) {
+    let mut depth = 0;
+
+    for event in EventReader::new(bytes) {
+        match event {
+            Ok(XmlEvent::StartElement { name, .. }) => {
+                if text.contains(&name.local_name.as_str()) {
+                    depth += 1;
+                }
+            },
+            Ok(XmlEvent::Characters(x)) => {
+                if depth > 0 {
+                    out.push_str(&x);
+                }
+            },
+            Ok(XmlEvent::EndElement { name }) => {
+                if text.contains(&name.local_name.as_str()) && depth > 0 {
+                    depth -= 1;
+                }
+                if breaks.contains(&name.local_name.as_str()) {
+                    out.push(' ');
+                }
+            },
+            Err(_) => break,
+            _ => (),
+        }
+
+        if out.len() > TEXT_EXCERPT_LEN * 4 {
+            break;
+        }
+    }
+}
+
+fn xml_date(s: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|x| x.naive_utc())
+        .or_else(|_| s.parse::<NaiveDateTime>())
+        .ok()
+}
+
+/// Extract metadata from OOXML (docx, xlsx, pptx) and ODF documents
+pub fn office(bytes: &[u8]) -> Result<DocumentMetadata> {
+    let zip = ZipArchive::parse(bytes)?;
+
+    if zip.contains("mimetype") {
+        odf(&zip)
+    } else if zip.contains("[Content_Types].xml") {
+        ooxml(&zip)
+    } else {
+        bail!("Unsupported office document format")
+    }
+}
+
+fn ooxml(zip: &ZipArchive) -> Result<DocumentMetadata> {
+    let mut meta = DocumentMetadata::default();
+
+    if let Some(core) = zip.read("docProps/core.xml")? {
+        let fields = xml_fields(&core, &["title", "creator", "created", "modified"]);
+        meta.title = xml_field(&fields, "title");
+        meta.author = xml_field(&fields, "creator");
+        meta.created = xml_field(&fields, "created").and_then(|x| xml_date(&x));
+        meta.modified = xml_field(&fields, "modified").and_then(|x| xml_date(&x));
+    }
+
+    if let Some(app) = zip.read("docProps/app.xml")? {
+        let fields = xml_fields(&app, &["Application"]);
+        meta.creator = xml_field(&fields, "Application");
+    }
+
+    let mut text = String::new();
+    if zip.contains("word/document.xml") {
+        meta.mime = MIME_DOCX.to_string();
+        if let Some(doc) = zip.read("word/document.xml")? {
+            xml_text(&doc, &["t"], &["p"], &mut text);
+        }
+    } else if zip.contains("xl/workbook.xml") {
+        meta.mime = MIME_XLSX.to_string();
+        if let Some(strings) = zip.read("xl/sharedStrings.xml")? {
+            xml_text(&strings, &["t"], &["si"], &mut text);
+        }
+    } else if zip.contains("ppt/presentation.xml") {
+        meta.mime = MIME_PPTX.to_string();
+        let mut slides = zip.names()
+            .filter(|x| x.starts_with("ppt/slides/slide") && x.ends_with(".xml"))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        slides.sort_by_key(|x| (x.len(), x.clone()));
+        for slide in slides {
+            if let Some(slide) = zip.read(&slide)? {
+                xml_text(&slide, &["t"], &["p"], &mut text);
+            }
+        }
+    } else {
+        meta.mime = MIME_ZIP.to_string();
+    }
+    meta.text = excerpt(&text);
+
+    Ok(meta)
+}
+
+fn odf(zip: &ZipArchive) -> Result<DocumentMetadata> {
+    let mut meta = DocumentMetadata::default();
+
+    meta.mime = zip.read("mimetype")?
+        .map(|x| String::from_utf8_lossy(&x).trim().to_string())
+        .unwrap_or_else(|| MIME_ZIP.to_string());
+
+    if let Some(xml) = zip.read("meta.xml")? {
+        let fields = xml_fields(&xml, &["title", "initial-creator", "creator", "generator", "creation-date", "date"]);
+        meta.title = xml_field(&fields, "title");
+        meta.author = xml_field(&fields, "initial-creator")
+            .or_else(|| xml_field(&fields, "creator"));
+        meta.creator = xml_field(&fields, "generator");
+        meta.created = xml_field(&fields, "creation-date").and_then(|x| xml_date(&x));
+        meta.modified = xml_field(&fields, "date").and_then(|x| xml_date(&x));
+    }
+
+    if let Some(content) = zip.read("content.xml")? {
+        let mut text = String::new();
+        xml_text(&content, &["p", "h"], &["p", "h"], &mut text);
+        meta.text = excerpt(&text);
+    }
+
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::{DeflateEncoder, ZlibEncoder};
+    use std::io::Write;
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+        e.write_all(data).unwrap();
+        e.finish().unwrap()
+    }
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut cd = Vec::new();
+
+        for (i, (name, data)) in files.iter().enumerate() {
+            // store the first file, deflate the rest
+            let (method, data) = if i == 0 {
+                (0u16, data.to_vec())
+            } else {
+                let mut e = DeflateEncoder::new(Vec::new(), Compression::default());
+                e.write_all(data).unwrap();
+                (8u16, e.finish().unwrap())
+            };
+            let offset = out.len() as u32;
+
+            out.extend_from_slice(b"PK\x03\x04");
+            out.extend_from_slice(&[20, 0, 0, 0]);
+            out.extend_from_slice(&method.to_le_bytes());
+            out.extend_from_slice(&[0; 8]);
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            out.extend_from_slice(&[0; 4]);
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&[0; 2]);
+            out.extend(name.as_bytes());
+            out.extend_from_slice(&data);
+
+            cd.extend_from_slice(b"PK\x01\x02");
+            cd.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+            cd.extend_from_slice(&method.to_le_bytes());
+            cd.extend_from_slice(&[0; 8]);
+            cd.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            cd.extend_from_slice(&[0; 4]);
+            cd.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            cd.extend_from_slice(&[0; 12]);
+            cd.extend_from_slice(&offset.to_le_bytes());
+            cd.extend(name.as_bytes());
+        }
+
+        let cd_offset = out.len() as u32;
+        out.extend_from_slice(&cd);
+        out.extend_from_slice(b"PK\x05\x06");
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(cd.len() as u32).to_le_bytes());
+        out.extend_from_slice(&cd_offset.to_le_bytes());
+        out.extend_from_slice(&[0; 2]);
+        out
+    }
+
+    #[test]
+    fn test_pdf_date() {
+        let dt = pdf_date("D:20190523041600+02'00'").unwrap();
+        assert_eq!(dt, NaiveDate::from_ymd(2019, 5, 23).and_hms(2, 16, 0));
+        let dt = pdf_date("D:2019").unwrap();
+        assert_eq!(dt, NaiveDate::from_ymd(2019, 1, 1).and_hms(0, 0, 0));
+        assert!(pdf_date("D:").is_none());
+    }
+
+    #[test]
+    fn test_pdf_strings() {
+        let (s, len) = pdf_literal_string(b"(a \\(b\\) (c)\\101\\n) Tj").unwrap();
+        assert_eq!(s, b"a (b) (c)A\n");
+        assert_eq!(len, 19);
+        let s = pdf_hex_string(b"<FEFF00680069>").unwrap();
+        assert_eq!(pdf_decode_text(&s), "hi");
+    }
+
+    fn pdf_with_streams(streams: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = b"%PDF-1.4\n".to_vec();
+        for (i, data) in streams.iter().enumerate() {
+            bytes.extend(format!("{} 0 obj\n<< /Filter /FlateDecode >>\nstream\n", i + 1).as_bytes());
+            bytes.extend_from_slice(&zlib(data));
+            bytes.extend_from_slice(b"\nendstream\nendobj\n");
+        }
+        bytes.extend_from_slice(b"%%EOF\n");
+        bytes
+    }
+
+    #[test]
+    fn test_pdf_streams_count_limit() {
+        let bytes = pdf_with_streams(&[b"a", b"b", b"c", b"d"]);
+        assert_eq!(pdf_streams_limited(&bytes, 10, 1024).len(), 4);
+        let streams = pdf_streams_limited(&bytes, 2, 1024);
+        assert_eq!(streams, vec![b"a".to_vec(), b"b".to_vec()]);
+    }
+
+    #[test]
+    fn test_pdf_streams_total_limit() {
+        let data = [0u8; 100];
+        let bytes = pdf_with_streams(&[&data, &data, &data]);
+        let streams = pdf_streams_limited(&bytes, 10, 250);
+        let sizes = streams.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![100, 100, 50]);
+    }
+
+    #[test]
+    fn test_pdf_metadata() {
+        let content = zlib(b"BT /F1 12 Tf 72 712 Td (Hello) Tj [(Wor) -20 (ld)] TJ ET");
+        let mut bytes = b"%PDF-1.4\n1 0 obj\n<< /Length 0 /Filter /FlateDecode >>\nstream\n".to_vec();
+        bytes.extend_from_slice(&content);
+        bytes.extend_from_slice(b"\nendstream\nendobj\n2 0 obj\n<< /Title (Quarterly report) /Author <FEFF0061006C006900630065> /Producer (LibreOffice 6.3) /CreationDate (D:20190523041600Z) >>\nendobj\ntrailer\n<< /Info 2 0 R >>\n%%EOF\n");
+
+        let meta = pdf(&bytes).unwrap();
+        assert_eq!(meta, DocumentMetadata {
+            mime: MIME_PDF.to_string(),
+            title: Some("Quarterly report".to_string()),
+            author: Some("alice".to_string()),
+            creator: Some("LibreOffice 6.3".to_string()),
+            created: Some(NaiveDate::from_ymd(2019, 5, 23).and_hms(4, 16, 0)),
+            modified: None,
+            text: Some("HelloWorld".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_docx_metadata() {
+        let docx = zip(&[
+            ("[Content_Types].xml", b"<Types/>"),
+            ("docProps/core.xml", br#"<?xml version="1.0"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/">
+<dc:title>Budget</dc:title><dc:creator>bob</dc:creator>
+<dcterms:created>2019-05-01T10:00:00Z</dcterms:created>
+<dcterms:modified>2019-05-02T11:30:00Z</dcterms:modified>
+</cp:coreProperties>"#),
+            ("docProps/app.xml", br#"<Properties><Application>Microsoft Office Word</Application></Properties>"#),
+            ("word/document.xml", br#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body><w:p><w:r><w:t>Hello</w:t></w:r></w:p><w:p><w:r><w:t>World</w:t></w:r></w:p></w:body></w:document>"#),
+        ]);
+
+        let meta = parse(&docx).unwrap();
+        assert_eq!(meta, DocumentMetadata {
+            mime: MIME_DOCX.to_string(),
+            title: Some("Budget".to_string()),
+            author: Some("bob".to_string()),
+            creator: Some("Microsoft Office Word".to_string()),
+            created: Some(NaiveDate::from_ymd(2019, 5, 1).and_hms(10, 0, 0)),
+            modified: Some(NaiveDate::from_ymd(2019, 5, 2).and_hms(11, 30, 0)),
+            text: Some("Hello World".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_odt_metadata() {
+        let odt = zip(&[
+            ("mimetype", b"application/vnd.oasis.opendocument.text"),
+            ("meta.xml", br#"<office:document-meta xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/"><office:meta>
+<meta:generator>LibreOffice/6.3</meta:generator><meta:initial-creator>carol</meta:initial-creator>
+<meta:creation-date>2019-06-01T08:00:00</meta:creation-date><dc:date>2019-06-03T09:00:00</dc:date>
+</office:meta></office:document-meta>"#),
+            ("content.xml", br#"<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><office:body><office:text><text:h>Title</text:h><text:p>Some <text:span>text</text:span></text:p></office:text></office:body></office:document-content>"#),
+        ]);
+
+        let meta = office(&odt).unwrap();
+        assert_eq!(meta, DocumentMetadata {
+            mime: "application/vnd.oasis.opendocument.text".to_string(),
+            title: None,
+            author: Some("carol".to_string()),
+            creator: Some("LibreOffice/6.3".to_string()),
+            created: Some(NaiveDate::from_ymd(2019, 6, 1).and_hms(8, 0, 0)),
+            modified: Some(NaiveDate::from_ymd(2019, 6, 3).and_hms(9, 0, 0)),
+            text: Some("Title Some text".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_unsupported_document() {
+        assert!(parse(b"GIF89a").is_err());
+        assert!(office(b"PK\x03\x04").is_err());
+    }
+}
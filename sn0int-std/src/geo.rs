@@ -2,17 +2,28 @@ use crate::errors::*;
 
 use crate::hlua::AnyLuaValue;
 use crate::json::LuaJsonValue;
-use geo::{LineString, Polygon, Coordinate};
+use geo::{LineString, Polygon as GeoPolygon, Coordinate};
 use geo::prelude::*;
+use std::str::FromStr;
 
 
 #[derive(Debug, Deserialize)]
 pub struct Point {
+    #[serde(alias="longitude")]
     lon: f64,
+    #[serde(alias="latitude")]
     lat: f64,
 }
 
 impl Point {
+    #[inline]
+    pub fn new(lat: f64, lon: f64) -> Point {
+        Point {
+            lon,
+            lat,
+        }
+    }
+
     pub fn try_from(x: AnyLuaValue) -> Result<Point> {
         let x = LuaJsonValue::from(x);
         let x = serde_json::from_value(x.into())?;
@@ -20,12 +31,56 @@ impl Point {
     }
 }
 
+impl FromStr for Point {
+    type Err = Error;
+
+    /// Parse a point from `lat,lon`
+    fn from_str(s: &str) -> Result<Point> {
+        let mut parts = s.splitn(2, ',');
+        let (lat, lon) = match (parts.next(), parts.next()) {
+            (Some(lat), Some(lon)) => (lat, lon),
+            _ => bail!("Point is missing a longitude: {:?}", s),
+        };
+        let lat = lat.trim().parse::<f64>()
+            .map_err(|_| format_err!("Invalid latitude: {:?}", lat))?;
+        let lon = lon.trim().parse::<f64>()
+            .map_err(|_| format_err!("Invalid longitude: {:?}", lon))?;
+        Ok(Point::new(lat, lon))
+    }
+}
+
+/// A ring of points that describes an area
+#[derive(Debug)]
+pub struct Polygon(Vec<Point>);
+
+impl Polygon {
+    #[inline]
+    pub fn contains(&self, p: &Point) -> bool {
+        polygon_contains(&self.0, p)
+    }
+}
+
+impl FromStr for Polygon {
+    type Err = Error;
+
+    /// Parse a polygon from whitespace separated `lat,lon` points
+    fn from_str(s: &str) -> Result<Polygon> {
+        let points = s.split_whitespace()
+            .map(Point::from_str)
+            .collect::<Result<Vec<_>>>()?;
+        if points.len() < 3 {
+            bail!("A polygon needs at least 3 points");
+        }
+        Ok(Polygon(points))
+    }
+}
+
 pub fn polygon_contains(ring: &[Point], p: &Point) -> bool {
     let ring = ring.iter()
         .map(|p| Coordinate { x: p.lon, y: p.lat })
         .collect::<Vec<_>>();
 
-    let polygon = Polygon::new(LineString::from(ring), vec![]);
+    let polygon = GeoPolygon::new(LineString::from(ring), vec![]);
     let point = geo::Point::new(p.lon, p.lat);
 
     polygon.contains(&point)
@@ -108,4 +163,28 @@ mod tests {
         });
         assert!(!contains);
     }
+
+    #[test]
+    fn test_parse_polygon() {
+        let polygon = "53.63,9.73 53.63,10.3\n53.4,10.3 53.4,9.73".parse::<Polygon>().unwrap();
+        assert!(polygon.contains(&Point::new(53.55, 9.99)));
+        assert!(!polygon.contains(&Point::new(52.52, 13.41)));
+    }
+
+    #[test]
+    fn test_parse_polygon_invalid() {
+        assert!("53.63,9.73 53.63,10.3".parse::<Polygon>().is_err());
+        assert!("53.63,9.73 53.63 53.4,10.3".parse::<Polygon>().is_err());
+        assert!("53.63,9.73 53.63,x 53.4,10.3".parse::<Polygon>().is_err());
+    }
+
+    #[test]
+    fn test_point_from_struct_fields() {
+        let point = Point::try_from(AnyLuaValue::LuaArray(vec![
+            (AnyLuaValue::LuaString("value".into()), AnyLuaValue::LuaString("Rathaus".into())),
+            (AnyLuaValue::LuaString("latitude".into()), AnyLuaValue::LuaNumber(53.550556)),
+            (AnyLuaValue::LuaString("longitude".into()), AnyLuaValue::LuaNumber(9.993333)),
+        ])).unwrap();
+        assert!(polygon_contains(hamburg_polygon(), &point));
+    }
 }
@@ -1,8 +1,10 @@
 #[macro_use] extern crate serde_derive;
 use hlua_badtouch as hlua;
 
+pub mod auth;
 pub mod blobs;
 pub mod crt;
+pub mod documents;
 mod errors;
 pub mod engine;
 pub mod geo;
@@ -11,9 +13,11 @@ pub mod gfx;
 pub mod html;
 pub mod json;
 pub mod lazy;
+pub mod proxy;
 pub mod psl;
 pub mod ratelimits;
 pub mod sockets;
+pub mod transport;
 pub mod web;
 pub mod websockets;
 pub mod xml;
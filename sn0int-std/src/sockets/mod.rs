@@ -4,9 +4,8 @@ use bufstream::BufStream;
 use crate::hlua::AnyLuaValue;
 use crate::json::LuaJsonValue;
 use chrootable_https::dns::{DnsResolver, RecordType};
-use chrootable_https::socks5::{self, ProxyDest};
+use crate::proxy::Proxy;
 use regex::Regex;
-use tokio::runtime::Runtime;
 
 use std::fmt;
 use std::str;
@@ -15,35 +14,25 @@ use std::io::prelude::*;
 use std::io::BufRead;
 use std::net::SocketAddr;
 use std::net::TcpStream;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::IpAddr;
 use std::time::Duration;
 
 mod tls;
-pub use self::tls::TlsData;
+pub use self::tls::{TlsData, ClientCert};
 
 
-#[cfg(unix)]
-fn unwrap_socket(socket: tokio::net::TcpStream) -> Result<TcpStream> {
-    use std::os::unix::io::AsRawFd;
-    use std::os::unix::io::FromRawFd;
-    let socket2 = unsafe { TcpStream::from_raw_fd(socket.as_raw_fd()) };
-    let socket = socket2.try_clone()?;
-    Ok(socket)
-}
-
-#[cfg(windows)]
-fn unwrap_socket(socket: tokio::net::TcpStream) -> Result<TcpStream> {
-    bail!("Unwrapping tokio sockets into std sockets isn't supported on windows")
-}
-
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, Deserialize)]
 pub struct SocketOptions {
     #[serde(default)]
     pub tls: bool,
     pub sni_value: Option<String>,
     #[serde(default)]
     pub disable_tls_verify: bool,
-    pub proxy: Option<SocketAddr>,
+    pub proxy: Option<Proxy>,
+    /// A keyring entry that contains a client certificate
+    pub client_cert: Option<String>,
+    #[serde(skip)]
+    pub tls_identity: Option<ClientCert>,
 
     // TODO: enable_sni (default to true)
     // TODO: sni_name
@@ -63,6 +52,16 @@ impl SocketOptions {
         let x = serde_json::from_value(x.into())?;
         Ok(x)
     }
+
+    /// Load the client certificate from the keyring
+    pub fn resolve_keyring<F>(&mut self, lookup: F) -> Result<()>
+        where F: Fn(&str) -> Option<(String, Option<String>)>
+    {
+        if let Some(query) = &self.client_cert {
+            self.tls_identity = Some(ClientCert::from_keyring(query, lookup)?);
+        }
+        Ok(())
+    }
 }
 
 impl SocketOptions {
@@ -137,20 +136,14 @@ impl Stream {
         tls::wrap_if_enabled(socket, host, options)
     }
 
-    pub fn connect_socks5_stream(proxy: &SocketAddr, host: &str, port: u16, options: &SocketOptions) -> Result<Stream> {
-        debug!("connecting to {:?}:{:?} with socks5 on {:?}", host, port, proxy);
-
-        let addr = match host.parse::<Ipv4Addr>() {
-            Ok(ipaddr) => ProxyDest::Ipv4Addr(ipaddr),
-            _ => ProxyDest::Domain(host.to_string()),
+    pub fn connect_proxy_stream(proxy: &Proxy, host: &str, port: u16, options: &SocketOptions) -> Result<Stream> {
+        let connect_timeout = match options.connect_timeout {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
         };
+        let socket = proxy.connect(host, port, connect_timeout)?;
 
-        let fut = socks5::connect(proxy, addr, port);
-
-        let mut rt = Runtime::new()?;
-        let socket = rt.block_on(fut)?;
-
-        let socket = unwrap_socket(socket)?;
+        options.apply_tcp(&socket)?;
 
         tls::wrap_if_enabled(socket, host, options)
     }
@@ -210,8 +203,8 @@ impl Socket {
         Ok(Socket::new(stream))
     }
 
-    pub fn connect_socks5(proxy: &SocketAddr, host: &str, port: u16, options: &SocketOptions) -> Result<Socket> {
-        let stream = Stream::connect_socks5_stream(proxy, host, port, options)?;
+    pub fn connect_proxy(proxy: &Proxy, host: &str, port: u16, options: &SocketOptions) -> Result<Socket> {
+        let stream = Stream::connect_proxy_stream(proxy, host, port, options)?;
         Ok(Socket::new(stream))
     }
 
@@ -2,7 +2,8 @@ use crate::errors::*;
 
 use crate::hlua::AnyLuaValue;
 use crate::json::LuaJsonValue;
-use rustls::{self, ClientConfig, Session, ClientSession};
+use rustls::{self, ClientConfig, Session, ClientSession, Certificate, PrivateKey};
+use rustls::internal::pemfile;
 
 use std::str;
 use std::result;
@@ -26,6 +27,66 @@ impl TlsData {
     }
 }
 
+/// A client certificate and its private key for mutual tls
+///
+/// This is loaded from a pem bundle that contains the certificate chain and
+/// the private key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClientCert {
+    pem: String,
+}
+
+impl ClientCert {
+    pub fn from_pem(pem: String) -> Result<ClientCert> {
+        let cert = ClientCert {
+            pem,
+        };
+        // make sure the bundle can be used
+        cert.parse()?;
+        Ok(cert)
+    }
+
+    /// Load the pem bundle from the secret key of a keyring entry
+    pub fn from_keyring<F>(query: &str, lookup: F) -> Result<ClientCert>
+        where F: Fn(&str) -> Option<(String, Option<String>)>
+    {
+        let (_, secret_key) = lookup(query)
+            .ok_or_else(|| format_err!("Keyring entry not found: {:?}", query))?;
+        let pem = secret_key
+            .ok_or_else(|| format_err!("Keyring entry has no client certificate: {:?}", query))?;
+        ClientCert::from_pem(pem)
+    }
+
+    fn parse(&self) -> Result<(Vec<Certificate>, PrivateKey)> {
+        let certs = pemfile::certs(&mut self.pem.as_bytes())
+            .map_err(|_| format_err!("Failed to parse client certificate"))?;
+        if certs.is_empty() {
+            bail!("No client certificate found");
+        }
+
+        let mut keys = pemfile::pkcs8_private_keys(&mut self.pem.as_bytes())
+            .map_err(|_| format_err!("Failed to parse client key"))?;
+        if keys.is_empty() {
+            keys = pemfile::rsa_private_keys(&mut self.pem.as_bytes())
+                .map_err(|_| format_err!("Failed to parse client key"))?;
+        }
+        let key = keys.into_iter().next()
+            .ok_or_else(|| format_err!("No private key found for client certificate"))?;
+
+        if rustls::sign::any_supported_type(&key).is_err() {
+            bail!("Unsupported private key for client certificate");
+        }
+
+        Ok((certs, key))
+    }
+
+    pub fn apply(&self, config: &mut ClientConfig) -> Result<()> {
+        let (certs, key) = self.parse()?;
+        config.set_single_client_cert(certs, key);
+        Ok(())
+    }
+}
+
 pub fn wrap_if_enabled(stream: TcpStream, host: &str, options: &SocketOptions) -> Result<Stream> {
     if !options.tls {
         let stream = Stream::Tcp(stream);
@@ -49,6 +110,11 @@ pub fn wrap(stream: TcpStream, host: &str, options: &SocketOptions) -> Result<(S
             .set_certificate_verifier(Arc::new(NoCertificateVerification {}));
     }
 
+    if let Some(cert) = &options.tls_identity {
+        debug!("adding client certificate");
+        cert.apply(&mut config)?;
+    }
+
     let dns_name = if let Some(v) = &options.sni_value {
         get_dns_name(&mut config, &v)
     } else {
@@ -122,3 +188,20 @@ impl rustls::ServerCertVerifier for NoCertificateVerification {
         Ok(rustls::ServerCertVerified::assertion())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_cert_invalid_pem() {
+        let r = ClientCert::from_pem("foo".to_string());
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_client_cert_missing_secret_key() {
+        let r = ClientCert::from_keyring("tls:foo", |_| Some(("foo".to_string(), None)));
+        assert!(r.is_err());
+    }
+}
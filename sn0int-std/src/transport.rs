@@ -0,0 +1,373 @@
+use crate::errors::*;
+
+use bytes::BytesMut;
+use chrootable_https::{DnsResolver, RecordType, Resolver, Request, Body, Response};
+use chrootable_https::http::response::Parts;
+use futures::{future, Future, Stream as _};
+use hyper::client::connect::{Connect, Connected, Destination};
+use hyper_rustls::HttpsConnector;
+use rustls::ClientConfig;
+use tokio::net::TcpStream;
+use tokio::prelude::FutureExt;
+use tokio::reactor::Handle;
+use tokio::runtime::Runtime;
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use crate::proxy::Proxy;
+use crate::sockets::ClientCert;
+use crate::web::{BodySink, HttpTransport};
+
+
+/// The address we've connected to, attached to the response by hyper
+#[derive(Debug, Clone)]
+struct RemoteAddr(Option<IpAddr>);
+
+/// Connector for hyper that either resolves and connects directly, or
+/// tunnels every connection through a proxy
+#[derive(Debug, Clone)]
+pub enum Connector {
+    Direct(Resolver),
+    Proxy(Proxy, Option<Duration>),
+}
+
+impl Connector {
+    fn connect_direct(resolver: &Resolver, host: String, port: u16) -> Box<dyn Future<Item=(TcpStream, Option<IpAddr>), Error=io::Error> + Send> {
+        let addrs: Box<dyn Future<Item=Vec<IpAddr>, Error=io::Error> + Send> = match host.parse::<IpAddr>() {
+            Ok(addr) => Box::new(future::ok(vec![addr])),
+            Err(_) => Box::new(Self::resolve(resolver, &host)),
+        };
+
+        let fut = addrs.and_then(move |addrs| {
+            let mut attempt: Box<dyn Future<Item=(TcpStream, Option<IpAddr>), Error=io::Error> + Send> =
+                Box::new(future::err(io::Error::new(io::ErrorKind::NotFound, "no dns records found")));
+
+            for addr in addrs {
+                attempt = Box::new(attempt.or_else(move |_| {
+                    debug!("connecting to {}", addr);
+                    TcpStream::connect(&SocketAddr::new(addr, port))
+                        .map(move |stream| (stream, Some(addr)))
+                }));
+            }
+
+            attempt
+        });
+        Box::new(fut)
+    }
+
+    /// Resolve both A and AAAA records, fails only if neither query succeeded
+    fn resolve(resolver: &Resolver, host: &str) -> impl Future<Item=Vec<IpAddr>, Error=io::Error> + Send {
+        let lookup = |record| resolver.resolve(host, record)
+            .and_then(|reply| reply.success())
+            .then(Ok::<_, io::Error>);
+
+        lookup(RecordType::A)
+            .join(lookup(RecordType::AAAA))
+            .and_then(|(a, aaaa)| {
+                match (a, aaaa) {
+                    (Err(err), Err(_)) => Err(io::Error::new(io::ErrorKind::Other, err.to_string())),
+                    (a, aaaa) => Ok(a.unwrap_or_default()
+                        .into_iter()
+                        .chain(aaaa.unwrap_or_default())
+                        .collect()),
+                }
+            })
+    }
+
+    /// The handshake is blocking, the timeout makes sure a proxy that stopped
+    /// responding can't block the runtime forever
+    fn connect_proxy(proxy: Proxy, connect_timeout: Option<Duration>, host: String, port: u16) -> Box<dyn Future<Item=(TcpStream, Option<IpAddr>), Error=io::Error> + Send> {
+        let fut = future::lazy(move || {
+            let stream = proxy.connect(&host, port, connect_timeout)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+            let stream = TcpStream::from_std(stream, &Handle::default())?;
+            Ok((stream, None))
+        });
+        Box::new(fut)
+    }
+}
+
+impl Connect for Connector {
+    type Transport = TcpStream;
+    type Error = io::Error;
+    type Future = Box<dyn Future<Item=(Self::Transport, Connected), Error=io::Error> + Send>;
+
+    fn connect(&self, dest: Destination) -> Self::Future {
+        let host = dest.host()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = match (dest.scheme(), dest.port()) {
+            (_, Some(port)) => port,
+            ("http", None) => 80,
+            (_, None) => 443,
+        };
+
+        let fut = match self {
+            Connector::Direct(resolver) => Self::connect_direct(resolver, host, port),
+            Connector::Proxy(proxy, connect_timeout) => Self::connect_proxy(proxy.clone(), *connect_timeout, host, port),
+        };
+
+        Box::new(fut.map(|(stream, addr)| {
+            (stream, Connected::new().extra(RemoteAddr(addr)))
+        }))
+    }
+}
+
+/// Connection and tls settings of an http client
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ClientOptions {
+    /// Offer http/2 with alpn
+    pub http2: bool,
+    pub client_cert: Option<ClientCert>,
+    /// Timeout for connecting through a proxy, including the handshake
+    pub connect_timeout: Option<Duration>,
+}
+
+impl ClientOptions {
+    fn tls_config(&self) -> Result<ClientConfig> {
+        let mut config = ClientConfig::new();
+        config
+            .root_store
+            .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+        config.ct_logs = Some(&ct_logs::LOGS);
+
+        if self.http2 {
+            config.set_protocols(&[b"h2".to_vec(), b"http/1.1".to_vec()]);
+        }
+
+        if let Some(cert) = &self.client_cert {
+            cert.apply(&mut config)?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Http client that streams the response body and enforces a size limit
+pub struct HttpsClient {
+    client: hyper::Client<HttpsConnector<Connector>>,
+}
+
+impl HttpsClient {
+    pub fn new(connector: Connector, options: &ClientOptions) -> Result<HttpsClient> {
+        let config = options.tls_config()?;
+
+        let https = HttpsConnector::from((connector, config));
+        let client = hyper::Client::builder()
+            .keep_alive(false)
+            .build::<_, Body>(https);
+
+        Ok(HttpsClient {
+            client,
+        })
+    }
+
+    #[inline]
+    pub fn direct(resolver: Resolver, options: &ClientOptions) -> Result<HttpsClient> {
+        HttpsClient::new(Connector::Direct(resolver), options)
+    }
+
+    #[inline]
+    pub fn proxy(proxy: Proxy, options: &ClientOptions) -> Result<HttpsClient> {
+        HttpsClient::new(Connector::Proxy(proxy, options.connect_timeout), options)
+    }
+}
+
+/// Sink that never accepts a body, the response is buffered instead
+struct Buffered;
+
+impl BodySink for Buffered {
+    fn accept(&mut self, _status: u16) -> bool {
+        false
+    }
+
+    fn write(&mut self, _chunk: &[u8]) -> Result<()> {
+        unreachable!()
+    }
+}
+
+impl HttpTransport for HttpsClient {
+    fn send(&self, req: Request<Body>, timeout: Option<Duration>, max_size: Option<u64>) -> Result<Response> {
+        self.stream(req, timeout, max_size, &mut Buffered)
+    }
+
+    fn stream(&self, req: Request<Body>, timeout: Option<Duration>, max_size: Option<u64>, sink: &mut dyn BodySink) -> Result<Response> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut rt = Runtime::new()?;
+
+        let res = block_on(&mut rt, deadline, self.client.request(req)
+            .map_err(Error::from))?;
+        let (parts, mut body) = res.into_parts();
+
+        if let (Some(max_size), Some(len)) = (max_size, content_length(&parts)) {
+            if len > max_size {
+                bail!("Response exceeds max size of {} bytes: {}", max_size, len);
+            }
+        }
+
+        let accepted = sink.accept(parts.status.as_u16());
+        let mut buf = BytesMut::new();
+        let mut size = 0;
+
+        // poll one chunk at a time so the sink can process it before the next one is received
+        loop {
+            let (chunk, rest) = block_on(&mut rt, deadline, body.into_future()
+                .map_err(|(err, _)| Error::from(err)))?;
+            let chunk = match chunk {
+                Some(chunk) => chunk,
+                None => break,
+            };
+
+            size += chunk.len() as u64;
+            if let Some(max_size) = max_size {
+                if size > max_size {
+                    bail!("Response exceeds max size of {} bytes", max_size);
+                }
+            }
+
+            if accepted {
+                sink.write(&chunk)?;
+            } else {
+                buf.extend_from_slice(&chunk);
+            }
+            body = rest;
+        }
+
+        Ok(build_response(parts, buf.freeze()))
+    }
+}
+
+fn block_on<F>(rt: &mut Runtime, deadline: Option<Instant>, fut: F) -> Result<F::Item>
+    where F: Future<Error=Error> + Send + 'static,
+          F::Item: Send + 'static,
+{
+    match deadline {
+        Some(deadline) => {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            rt.block_on(fut.timeout(timeout)
+                .map_err(|err| match err.into_inner() {
+                    Some(err) => err,
+                    _ => format_err!("Request timed out"),
+                }))
+        },
+        None => rt.block_on(fut),
+    }
+}
+
+fn content_length(parts: &Parts) -> Option<u64> {
+    parts.headers.get("content-length")
+        .and_then(|x| x.to_str().ok())
+        .and_then(|x| x.parse().ok())
+}
+
+fn build_response(parts: Parts, body: bytes::Bytes) -> Response {
+    let cookies = parts.headers
+        .get_all("set-cookie")
+        .into_iter()
+        .flat_map(|x| x.to_str().map(|x| x.to_owned()).ok())
+        .collect();
+
+    let mut headers = HashMap::new();
+    for (k, v) in &parts.headers {
+        if let Ok(v) = v.to_str() {
+            headers.insert(k.as_str().to_string(), v.to_string());
+        }
+    }
+
+    let ipaddr = parts.extensions.get::<RemoteAddr>()
+        .and_then(|x| x.0);
+
+    Response {
+        status: parts.status.as_u16(),
+        headers,
+        cookies,
+        ipaddr,
+        body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn verify_direct_request() {
+        let client = HttpsClient::direct(Resolver::cloudflare(), &ClientOptions::default()).unwrap();
+        let req = Request::get("https://httpbin.org/anything")
+            .body(Body::empty())
+            .unwrap();
+        let res = client.send(req, None, None).unwrap();
+        assert_eq!(res.status, 200);
+        assert!(res.ipaddr.is_some());
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_max_size() {
+        let client = HttpsClient::direct(Resolver::cloudflare(), &ClientOptions::default()).unwrap();
+        let req = Request::get("https://httpbin.org/bytes/1024")
+            .body(Body::empty())
+            .unwrap();
+        let res = client.send(req, None, Some(512));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_resolve_ipv6_only() {
+        let client = HttpsClient::direct(Resolver::cloudflare(), &ClientOptions::default()).unwrap();
+        let req = Request::get("https://ipv6.google.com/")
+            .body(Body::empty())
+            .unwrap();
+        let res = client.send(req, None, None).unwrap();
+        assert!(res.ipaddr.unwrap().is_ipv6());
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_stream() {
+        struct Collect(Vec<u8>);
+
+        impl BodySink for Collect {
+            fn accept(&mut self, status: u16) -> bool {
+                status == 200
+            }
+
+            fn write(&mut self, chunk: &[u8]) -> Result<()> {
+                self.0.extend(chunk);
+                Ok(())
+            }
+        }
+
+        let client = HttpsClient::direct(Resolver::cloudflare(), &ClientOptions::default()).unwrap();
+        let req = Request::get("https://httpbin.org/stream-bytes/4096?chunk_size=512")
+            .body(Body::empty())
+            .unwrap();
+        let mut sink = Collect(Vec::new());
+        let res = client.stream(req, None, None, &mut sink).unwrap();
+        assert!(res.body.is_empty());
+        assert_eq!(sink.0.len(), 4096);
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_http2() {
+        let options = ClientOptions {
+            http2: true,
+            client_cert: None,
+            connect_timeout: None,
+        };
+        let client = HttpsClient::direct(Resolver::cloudflare(), &options).unwrap();
+        let req = Request::get("https://http2.golang.org/reqinfo")
+            .body(Body::empty())
+            .unwrap();
+        let res = client.send(req, None, None).unwrap();
+        assert_eq!(res.status, 200);
+        assert!(String::from_utf8_lossy(&res.body).contains("Protocol: HTTP/2.0"));
+    }
+}
@@ -1,6 +1,8 @@
 pub use chrootable_https::{Client, HttpClient, Resolver, Response};
 
-use crate::blobs::{Blob, BlobState};
+use crate::auth::{Auth, DigestChallenge, OAuth2Token, OAuth2TokenResponse, basic_auth_header};
+use crate::blobs::{BlobState, BlobWriter};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 use std::ops::Deref;
@@ -10,15 +12,16 @@ use serde_json;
 use rand::{Rng, thread_rng};
 use rand::distributions::Alphanumeric;
 use std::fmt;
-use std::net::SocketAddr;
 use std::sync::Arc;
 use serde::Serialize;
 use crate::engine::structs::LuaMap;
 use crate::json::LuaJsonValue;
+use crate::proxy::Proxy;
+use crate::sockets::ClientCert;
+use crate::transport::ClientOptions;
 use chrootable_https::http::uri::Parts;
 use chrootable_https::{Request, Body, Uri};
 use serde_urlencoded;
-use base64;
 
 
 pub fn url_set_qs<S: Serialize + fmt::Debug>(url: Uri, query: &S) -> Result<Uri> {
@@ -37,16 +40,64 @@ pub fn url_set_qs<S: Serialize + fmt::Debug>(url: Uri, query: &S) -> Result<Uri>
         .map_err(Error::from)
 }
 
+/// Receives the response body while it's downloaded instead of buffering it
+pub trait BodySink {
+    /// Called once the status is known, if this returns false the body is buffered as usual
+    fn accept(&mut self, status: u16) -> bool;
+
+    fn write(&mut self, chunk: &[u8]) -> Result<()>;
+}
+
+/// Something that can send an http request and wait for the response
+pub trait HttpTransport: Send + Sync {
+    /// Send the request, the response is rejected if the body is larger than `max_size`
+    fn send(&self, req: Request<Body>, timeout: Option<Duration>, max_size: Option<u64>) -> Result<Response>;
+
+    /// Send the request and write the body into `sink` if it accepts the response
+    ///
+    /// The body of the returned response is empty if it was written into the sink.
+    fn stream(&self, req: Request<Body>, timeout: Option<Duration>, max_size: Option<u64>, sink: &mut dyn BodySink) -> Result<Response> {
+        let mut res = self.send(req, timeout, max_size)?;
+        if sink.accept(res.status) {
+            sink.write(&res.body)?;
+            res.body = Default::default();
+        }
+        Ok(res)
+    }
+}
+
+impl HttpTransport for chrootable_https::Client<Resolver> {
+    fn send(&self, req: Request<Body>, timeout: Option<Duration>, max_size: Option<u64>) -> Result<Response> {
+        let res = self.request(req)
+            .with_timeout(timeout)
+            .wait_for_response()?;
+
+        // this client buffers the whole response, we can only check afterwards
+        if let Some(max_size) = max_size {
+            if res.body.len() as u64 > max_size {
+                bail!("Response exceeds max size of {} bytes", max_size);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
 pub trait WebState {
-    fn http(&self, proxy: &Option<SocketAddr>) -> Result<Arc<chrootable_https::Client<Resolver>>>;
+    fn http(&self, proxy: &Option<Proxy>, options: &ClientOptions) -> Result<Arc<dyn HttpTransport>>;
+
+    fn register_in_jar(&self, session: &str, key: String, value: String, expires: Option<DateTime<Utc>>);
+
+    fn oauth2_token(&self, session: &str, key: &str) -> Option<String>;
 
-    fn register_in_jar(&self, session: &str, key: String, value: String);
+    fn register_oauth2_token(&self, session: &str, key: String, token: OAuth2Token);
 }
 
 #[derive(Debug)]
 pub struct HttpSession {
     id: String,
     pub cookies: CookieJar,
+    pub oauth2_tokens: HashMap<String, OAuth2Token>,
 }
 
 impl HttpSession {
@@ -55,8 +106,15 @@ impl HttpSession {
         (id.clone(), HttpSession {
             id,
             cookies: CookieJar::default(),
+            oauth2_tokens: HashMap::new(),
         })
     }
+
+    pub fn oauth2_token(&self, key: &str) -> Option<String> {
+        self.oauth2_tokens.get(key)
+            .filter(|token| token.is_valid())
+            .map(|token| token.token.clone())
+    }
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -64,14 +122,22 @@ pub struct RequestOptions {
     query: Option<HashMap<String, String>>,
     headers: Option<HashMap<String, String>>,
     basic_auth: Option<(String, String)>,
+    auth: Option<Auth>,
     user_agent: Option<String>,
     json: Option<serde_json::Value>,
     form: Option<serde_json::Value>,
     body: Option<String>,
+    multipart: Option<Vec<MultipartField>>,
     timeout: Option<u64>,
     #[serde(default)]
     into_blob: bool,
-    proxy: Option<SocketAddr>,
+    max_size: Option<u64>,
+    proxy: Option<Proxy>,
+    #[serde(default)]
+    http2: bool,
+    client_cert: Option<String>,
+    #[serde(skip)]
+    tls_identity: Option<ClientCert>,
 }
 
 impl RequestOptions {
@@ -80,6 +146,19 @@ impl RequestOptions {
         let x = serde_json::from_value(x.into())?;
         Ok(x)
     }
+
+    /// Load credentials for the auth section and the client certificate from the keyring
+    pub fn resolve_keyring<F>(&mut self, lookup: F) -> Result<()>
+        where F: Fn(&str) -> Option<(String, Option<String>)>
+    {
+        if let Some(auth) = &mut self.auth {
+            auth.resolve_keyring(&lookup)?;
+        }
+        if let Some(query) = &self.client_cert {
+            self.tls_identity = Some(ClientCert::from_keyring(query, &lookup)?);
+        }
+        Ok(())
+    }
 }
 
 pub fn default_user_agent() -> String {
@@ -96,11 +175,16 @@ pub struct HttpRequest {
     query: Option<HashMap<String, String>>,
     headers: Option<HashMap<String, String>>,
     basic_auth: Option<(String, String)>,
+    auth: Option<Auth>,
     user_agent: Option<String>,
     body: Option<ReqBody>,
     timeout: Option<Duration>,
     into_blob: bool,
-    proxy: Option<SocketAddr>,
+    max_size: Option<u64>,
+    #[serde(serialize_with="crate::proxy::credentials::serialize_option")]
+    proxy: Option<Proxy>,
+    http2: bool,
+    client_cert: Option<ClientCert>,
 }
 
 impl HttpRequest {
@@ -118,11 +202,15 @@ impl HttpRequest {
             query: options.query,
             headers: options.headers,
             basic_auth: options.basic_auth,
+            auth: options.auth,
             user_agent,
             body: None,
             timeout,
             into_blob: options.into_blob,
+            max_size: options.max_size,
             proxy: options.proxy,
+            http2: options.http2,
+            client_cert: options.tls_identity,
         };
 
         if let Some(json) = options.json {
@@ -137,10 +225,65 @@ impl HttpRequest {
             request.body = Some(ReqBody::Raw(text));
         }
 
+        if let Some(multipart) = options.multipart {
+            request.body = Some(ReqBody::Multipart(multipart));
+        }
+
         request
     }
 
-    pub fn send(&self, state: &dyn WebState) -> Result<Response> {
+    pub fn send<S>(&self, state: &S) -> Result<HttpResponse>
+        where S: WebState + BlobState
+    {
+        let authorization = match &self.auth {
+            Some(Auth::Basic { user, password }) => Some(basic_auth_header(user, password)),
+            Some(Auth::Bearer { token: Some(token), .. }) => Some(format!("Bearer {}", token)),
+            Some(Auth::Bearer { token: None, .. }) => bail!("Bearer auth is missing a token"),
+            Some(Auth::OAuth2 { .. }) => Some(format!("Bearer {}", self.oauth2_token(state, false)?)),
+            Some(Auth::Digest { .. }) => None,
+            None => self.basic_auth.as_ref()
+                .map(|(user, password)| basic_auth_header(user, password)),
+        };
+
+        let res = self.send_with_auth(state, authorization.as_ref())?;
+        if res.status != 401 {
+            return Ok(res);
+        }
+
+        match &self.auth {
+            Some(Auth::Digest { user, password }) => {
+                let challenge = res.headers.get("www-authenticate")
+                    .ok_or_else(|| format_err!("Server didn't send an authentication challenge"))?;
+                let challenge = DigestChallenge::parse(challenge)?;
+
+                let url = self.url()?;
+                let uri = url.path_and_query()
+                    .map(|x| x.as_str())
+                    .unwrap_or("/");
+
+                debug!("Responding to digest challenge: {:?}", challenge);
+                let authorization = challenge.authorization(user, password, &self.method, uri);
+                self.send_with_auth(state, Some(&authorization))
+            },
+            Some(Auth::OAuth2 { .. }) => {
+                // the cached token might have been revoked
+                debug!("Refreshing oauth2 token");
+                let authorization = format!("Bearer {}", self.oauth2_token(state, true)?);
+                self.send_with_auth(state, Some(&authorization))
+            },
+            _ => Ok(res),
+        }
+    }
+
+    fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            http2: self.http2,
+            client_cert: self.client_cert.clone(),
+            connect_timeout: self.timeout,
+        }
+    }
+
+    fn url(&self) -> Result<Uri> {
         let mut url = self.url.parse::<Uri>()?;
 
         // set query string
@@ -148,6 +291,14 @@ impl HttpRequest {
             url = url_set_qs(url, query)?;
         }
 
+        Ok(url)
+    }
+
+    fn send_with_auth<S>(&self, state: &S, authorization: Option<&String>) -> Result<HttpResponse>
+        where S: WebState + BlobState
+    {
+        let url = self.url()?;
+
         // start setting up request
         let mut req = Request::builder();
         req.method(self.method.as_str());
@@ -160,7 +311,7 @@ impl HttpRequest {
             use chrootable_https::header::COOKIE;
             let mut cookies = String::new();
 
-            for (key, value) in self.cookies.iter() {
+            for (key, value) in self.cookies.active() {
                 if !cookies.is_empty() {
                     cookies += "; ";
                 }
@@ -180,18 +331,15 @@ impl HttpRequest {
             observed_headers.insert(USER_AGENT.as_str().to_lowercase());
         }
 
-        if let Some(ref auth) = self.basic_auth {
+        if let Some(auth) = authorization {
             use chrootable_https::header::AUTHORIZATION;
-            let &(ref user, ref password) = auth;
-
-            let auth = base64::encode(&format!("{}:{}", user, password));
-            let auth = format!("Basic {}", auth);
             req.header(AUTHORIZATION, auth.as_str());
             observed_headers.insert(AUTHORIZATION.as_str().to_lowercase());
         }
 
         if let Some(ref headers) = self.headers {
             for (k, v) in headers {
+                check_header_value(k, v)?;
                 req.header(k.as_str(), v.as_str());
                 observed_headers.insert(k.to_lowercase());
             }
@@ -214,18 +362,47 @@ impl HttpRequest {
                 }
                 Body::from(serde_json::to_string(x)?)
             },
+            Some(ReqBody::Multipart(ref x)) => {
+                let boundary = multipart_boundary();
+                // if Content-Type is not set, set header
+                if !observed_headers.contains("content-type") {
+                    let content_type = format!("multipart/form-data; boundary={}", boundary);
+                    req.header("Content-Type", content_type.as_str());
+                }
+                Body::from(encode_multipart(state, &boundary, x)?)
+            },
             None => Body::empty(),
         };
         let req = req.body(body)?;
 
         debug!("Getting http client");
-        let client = state.http(&self.proxy)?;
+        let client = state.http(&self.proxy, &self.client_options())?;
 
         // send request
         debug!("Sending http request: {:?}", req);
-        let res = client.request(req)
-            .with_timeout(self.timeout)
-            .wait_for_response()?;
+        let res = if self.into_blob {
+            let mut sink = BlobSink {
+                writer: state.blob_writer(),
+                accepted: false,
+            };
+            let response = client.stream(req, self.timeout, self.max_size, &mut sink)?;
+
+            let BlobSink { writer, accepted } = sink;
+            let blob = if accepted {
+                Some(writer.finish()?)
+            } else {
+                None
+            };
+            HttpResponse {
+                response,
+                blob,
+            }
+        } else {
+            HttpResponse {
+                response: client.send(req, self.timeout, self.max_size)?,
+                blob: None,
+            }
+        };
 
         for cookie in &res.cookies {
             HttpRequest::register_cookies_on_state(&self.session, state, cookie);
@@ -234,13 +411,64 @@ impl HttpRequest {
         Ok(res)
     }
 
-    pub fn response_to_lua<S>(&self, state: &S, res: Response) -> Result<LuaMap>
-        where S: WebState + BlobState
-    {
+    /// Get an access token with the oauth2 client credentials flow
+    ///
+    /// Tokens are cached in the session until they expire.
+    fn oauth2_token<S: WebState>(&self, state: &S, refresh: bool) -> Result<String> {
+        let (token_url, client_id, client_secret, scope) = match &self.auth {
+            Some(Auth::OAuth2 { token_url, client_id: Some(client_id), client_secret, scope, .. }) => {
+                (token_url, client_id, client_secret, scope)
+            },
+            Some(Auth::OAuth2 { .. }) => bail!("OAuth2 auth is missing a client_id"),
+            _ => bail!("Request isn't using oauth2"),
+        };
+
+        let key = format!("{} {} {}", token_url, client_id, scope.as_deref().unwrap_or(""));
+        if !refresh {
+            if let Some(token) = state.oauth2_token(&self.session, &key) {
+                return Ok(token);
+            }
+        }
+
+        let mut form = vec![("grant_type", "client_credentials")];
+        if let Some(scope) = scope {
+            form.push(("scope", scope.as_str()));
+        }
+
+        let mut req = Request::builder();
+        req.method("POST");
+        req.uri(token_url.as_str());
+        req.header("Content-Type", "application/x-www-form-urlencoded");
+        req.header("Accept", "application/json");
+        let authorization = basic_auth_header(client_id, client_secret.as_deref().unwrap_or(""));
+        req.header("Authorization", authorization.as_str());
+        if let Some(ref agent) = self.user_agent {
+            req.header("User-Agent", agent.as_str());
+        }
+        let req = req.body(Body::from(serde_urlencoded::to_string(&form)?))?;
+
+        debug!("Requesting oauth2 token: {:?}", req);
+        let client = state.http(&self.proxy, &self.client_options())?;
+        let res = client.send(req, self.timeout, None)?;
+        if res.status < 200 || res.status > 299 {
+            bail!("Failed to get oauth2 token, http status error: {}", res.status);
+        }
+
+        let reply = serde_json::from_slice::<OAuth2TokenResponse>(&res.body)
+            .context("Failed to parse oauth2 token response")?;
+        let token = OAuth2Token::new(reply.access_token, reply.expires_in);
+        state.register_oauth2_token(&self.session, key, token.clone());
+
+        Ok(token.token)
+    }
+
+    pub fn response_to_lua(&self, res: HttpResponse) -> Result<LuaMap> {
         // map result to LuaMap
         let mut resp = LuaMap::new();
         resp.insert_num("status", f64::from(res.status));
 
+        let HttpResponse { response: res, blob } = res;
+
         let mut headers = LuaMap::new();
         for (key, value) in res.headers {
             headers.insert_str(key.to_lowercase(), value);
@@ -251,9 +479,7 @@ impl HttpRequest {
             resp.insert_str("ipaddr", ipaddr.to_string());
         }
 
-        if self.into_blob {
-            let blob = Blob::create(res.body);
-            let id = state.register_blob(blob);
+        if let Some(id) = blob {
             resp.insert_str("blob", id);
         } else {
             resp.insert_str("text", String::from_utf8_lossy(&res.body));
@@ -263,20 +489,40 @@ impl HttpRequest {
     }
 
     fn register_cookies_on_state(session: &str, state: &dyn WebState, cookie: &str) {
-        let mut key = String::new();
-        let mut value = String::new();
-        let mut in_key = true;
-
-        for c in cookie.as_bytes() {
-            match *c as char {
-                '=' if in_key => in_key = false,
-                ';' => break,
-                c if in_key => key.push(c),
-                c => value.push(c),
-            }
-        }
+        let (key, value, expires) = parse_set_cookie(cookie);
+        state.register_in_jar(session, key, value, expires);
+    }
+}
+
+/// A response, the body of a successful `into_blob` request is in blob storage instead
+#[derive(Debug)]
+pub struct HttpResponse {
+    response: Response,
+    pub blob: Option<String>,
+}
 
-        state.register_in_jar(session, key, value);
+impl Deref for HttpResponse {
+    type Target = Response;
+
+    fn deref(&self) -> &Response {
+        &self.response
+    }
+}
+
+/// Streams the body of successful responses into blob storage
+struct BlobSink<'a> {
+    writer: Box<dyn BlobWriter + 'a>,
+    accepted: bool,
+}
+
+impl<'a> BodySink for BlobSink<'a> {
+    fn accept(&mut self, status: u16) -> bool {
+        self.accepted = status >= 200 && status <= 299;
+        self.accepted
+    }
+
+    fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        self.writer.write(chunk)
     }
 }
 
@@ -297,13 +543,64 @@ impl Into<AnyLuaValue> for HttpRequest {
 
 // see https://github.com/seanmonstar/reqwest/issues/14 for proper cookie jars
 // maybe change this to reqwest::header::Cookie
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
-pub struct CookieJar(HashMap<String, String>);
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CookieJar {
+    cookies: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    expires: HashMap<String, DateTime<Utc>>,
+}
 
 impl CookieJar {
-    #[inline(always)]
-    pub fn register_in_jar(&mut self, key: String, value: String) {
-        self.0.insert(key, value);
+    pub fn register_in_jar(&mut self, key: String, value: String, expires: Option<DateTime<Utc>>) {
+        match expires {
+            // the server asked us to delete this cookie
+            Some(expires) if expires <= Utc::now() => {
+                self.cookies.remove(&key);
+                self.expires.remove(&key);
+            },
+            Some(expires) => {
+                self.expires.insert(key.clone(), expires);
+                self.cookies.insert(key, value);
+            },
+            None => {
+                self.expires.remove(&key);
+                self.cookies.insert(key, value);
+            },
+        }
+    }
+
+    #[inline]
+    pub fn is_expired(&self, key: &str) -> bool {
+        match self.expires.get(key) {
+            Some(expires) => *expires <= Utc::now(),
+            None => false,
+        }
+    }
+
+    /// Iterate over all cookies that didn't expire yet
+    pub fn active(&self) -> impl Iterator<Item=(&String, &String)> {
+        self.cookies.iter()
+            .filter(move |(key, _)| !self.is_expired(key))
+    }
+
+    pub fn remove_expired(&mut self) {
+        let expired = self.cookies.keys()
+            .filter(|key| self.is_expired(key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in expired {
+            self.cookies.remove(&key);
+            self.expires.remove(&key);
+        }
+    }
+
+    /// Copy all cookies from an other jar into this one
+    pub fn merge(&mut self, other: &CookieJar) {
+        for (key, value) in other.active() {
+            let expires = other.expires.get(key).cloned();
+            self.register_in_jar(key.clone(), value.clone(), expires);
+        }
     }
 }
 
@@ -312,8 +609,51 @@ impl Deref for CookieJar {
 
     #[inline(always)]
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.cookies
+    }
+}
+
+/// Parse a `Set-Cookie` header into key, value and expiry
+pub fn parse_set_cookie(cookie: &str) -> (String, String, Option<DateTime<Utc>>) {
+    let mut parts = cookie.split(';');
+
+    let (key, value) = match parts.next() {
+        Some(kv) => match kv.find('=') {
+            Some(idx) => (kv[..idx].to_string(), kv[idx+1..].to_string()),
+            None => (kv.to_string(), String::new()),
+        },
+        None => (String::new(), String::new()),
+    };
+
+    let mut expires = None;
+    let mut max_age = None;
+    for attr in parts {
+        let (k, v) = match attr.find('=') {
+            Some(idx) => (attr[..idx].trim(), attr[idx+1..].trim()),
+            None => (attr.trim(), ""),
+        };
+
+        if k.eq_ignore_ascii_case("max-age") {
+            if let Ok(secs) = v.parse::<i64>() {
+                max_age = Some(Utc::now() + chrono::Duration::seconds(secs));
+            }
+        } else if k.eq_ignore_ascii_case("expires") {
+            expires = parse_cookie_date(v);
+        }
     }
+
+    // max-age has precedence over expires
+    (key, value, max_age.or(expires))
+}
+
+fn parse_cookie_date(x: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc2822(x) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    // some servers use dashes in the date
+    NaiveDateTime::parse_from_str(x, "%a, %d-%b-%Y %H:%M:%S GMT").ok()
+        .map(|date| DateTime::from_utc(date, Utc))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -321,4 +661,264 @@ pub enum ReqBody {
     Raw(String), // TODO: maybe Vec<u8>
     Form(serde_json::Value),
     Json(serde_json::Value),
+    Multipart(Vec<MultipartField>),
+}
+
+/// A single part of a multipart/form-data body
+///
+/// The content is either `text` or a reference to a `blob`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MultipartField {
+    name: String,
+    text: Option<String>,
+    blob: Option<String>,
+    filename: Option<String>,
+    content_type: Option<String>,
+}
+
+fn multipart_boundary() -> String {
+    let random: String = thread_rng().sample_iter(&Alphanumeric).take(24).collect();
+    format!("------------------------{}", random)
+}
+
+fn check_header_value(name: &str, value: &str) -> Result<()> {
+    if value.contains(|c| c == '\r' || c == '\n') {
+        bail!("Header {:?} contains a line break", name);
+    }
+    Ok(())
+}
+
+#[inline]
+fn escape_quoted(x: &str) -> String {
+    x.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn encode_multipart<S: BlobState>(state: &S, boundary: &str, fields: &[MultipartField]) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+
+    for field in fields {
+        body.extend(format!("--{}\r\n", boundary).as_bytes());
+        body.extend(format!("Content-Disposition: form-data; name=\"{}\"", escape_quoted(&field.name)).as_bytes());
+
+        let content = match (&field.text, &field.blob) {
+            (Some(text), None) => {
+                if let Some(filename) = &field.filename {
+                    body.extend(format!("; filename=\"{}\"", escape_quoted(filename)).as_bytes());
+                }
+                body.extend(b"\r\n");
+                if let Some(content_type) = &field.content_type {
+                    check_header_value("Content-Type", content_type)?;
+                    body.extend(format!("Content-Type: {}\r\n", content_type).as_bytes());
+                }
+                text.as_bytes().to_vec()
+            },
+            (None, Some(blob)) => {
+                let blob = state.get_blob(blob)?;
+                let filename = field.filename.as_ref().unwrap_or(&blob.id);
+                body.extend(format!("; filename=\"{}\"\r\n", escape_quoted(filename)).as_bytes());
+                let content_type = field.content_type.as_deref()
+                    .unwrap_or("application/octet-stream");
+                check_header_value("Content-Type", content_type)?;
+                body.extend(format!("Content-Type: {}\r\n", content_type).as_bytes());
+                blob.bytes.to_vec()
+            },
+            (Some(_), Some(_)) => bail!("Multipart field {:?} can't have both text and blob", field.name),
+            (None, None) => bail!("Multipart field {:?} needs either text or blob", field.name),
+        };
+
+        body.extend(b"\r\n");
+        body.extend(&content);
+        body.extend(b"\r\n");
+    }
+
+    body.extend(format!("--{}--\r\n", boundary).as_bytes());
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blobs::Blob;
+    use bytes::Bytes;
+
+    struct DummyState(Arc<Blob>);
+
+    impl BlobState for DummyState {
+        fn register_blob(&self, blob: Blob) -> String {
+            blob.id
+        }
+
+        fn get_blob(&self, id: &str) -> Result<Arc<Blob>> {
+            if id == self.0.id {
+                Ok(self.0.clone())
+            } else {
+                bail!("Invalid blob reference")
+            }
+        }
+    }
+
+    struct DummyTransport(u16);
+
+    impl HttpTransport for DummyTransport {
+        fn send(&self, _req: Request<Body>, _timeout: Option<Duration>, _max_size: Option<u64>) -> Result<Response> {
+            Ok(Response {
+                status: self.0,
+                headers: HashMap::new(),
+                cookies: Vec::new(),
+                ipaddr: None,
+                body: Bytes::from(&b"asdf"[..]),
+            })
+        }
+    }
+
+    fn stream(status: u16) -> (Response, Option<String>) {
+        let state = DummyState(Arc::new(Blob::create(Bytes::new())));
+        let mut sink = BlobSink {
+            writer: state.blob_writer(),
+            accepted: false,
+        };
+        let req = Request::get("https://example.com").body(Body::empty()).unwrap();
+        let res = DummyTransport(status).stream(req, None, None, &mut sink).unwrap();
+
+        let blob = if sink.accepted {
+            Some(sink.writer.finish().unwrap())
+        } else {
+            None
+        };
+        (res, blob)
+    }
+
+    #[test]
+    fn verify_stream_into_blob() {
+        let (res, blob) = stream(200);
+        assert!(res.body.is_empty());
+        assert_eq!(blob.as_deref(), Some("DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz"));
+    }
+
+    #[test]
+    fn verify_stream_error_status_is_buffered() {
+        let (res, blob) = stream(404);
+        assert_eq!(&res.body[..], b"asdf");
+        assert_eq!(blob, None);
+    }
+
+    #[test]
+    fn verify_parse_set_cookie() {
+        let (key, value, expires) = parse_set_cookie("foo=bar; Path=/; HttpOnly");
+        assert_eq!((key.as_str(), value.as_str(), expires), ("foo", "bar", None));
+    }
+
+    #[test]
+    fn verify_parse_set_cookie_expires() {
+        let (key, value, expires) = parse_set_cookie("id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure");
+        assert_eq!((key.as_str(), value.as_str()), ("id", "a3fWa"));
+        assert_eq!(expires.unwrap().to_rfc3339(), "2015-10-21T07:28:00+00:00");
+    }
+
+    #[test]
+    fn verify_parse_set_cookie_expires_dashes() {
+        let (_, _, expires) = parse_set_cookie("id=a3fWa; expires=Wed, 21-Oct-2015 07:28:00 GMT");
+        assert_eq!(expires.unwrap().to_rfc3339(), "2015-10-21T07:28:00+00:00");
+    }
+
+    #[test]
+    fn verify_parse_set_cookie_max_age() {
+        let (_, _, expires) = parse_set_cookie("id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Max-Age=3600");
+        assert!(expires.unwrap() > Utc::now());
+    }
+
+    #[test]
+    fn verify_cookie_expiry() {
+        let mut jar = CookieJar::default();
+        jar.register_in_jar("a".into(), "1".into(), None);
+        jar.register_in_jar("b".into(), "2".into(), Some(Utc::now() + chrono::Duration::seconds(60)));
+        jar.register_in_jar("c".into(), "3".into(), Some(Utc::now() + chrono::Duration::seconds(60)));
+        jar.expires.insert("c".into(), Utc::now() - chrono::Duration::seconds(60));
+
+        let mut active = jar.active().map(|(k, _)| k.as_str()).collect::<Vec<_>>();
+        active.sort();
+        assert_eq!(active, vec!["a", "b"]);
+
+        jar.remove_expired();
+        assert_eq!(jar.len(), 2);
+
+        // delete a cookie
+        jar.register_in_jar("a".into(), "".into(), Some(Utc::now() - chrono::Duration::seconds(60)));
+        assert!(!jar.contains_key("a"));
+    }
+
+    #[test]
+    fn verify_encode_multipart() {
+        let blob = Blob::create(Bytes::from(&b"asdf"[..]));
+        let state = DummyState(Arc::new(blob));
+
+        let body = encode_multipart(&state, "xyz", &[
+            MultipartField {
+                name: "foo".into(),
+                text: Some("bar".into()),
+                ..Default::default()
+            },
+            MultipartField {
+                name: "file".into(),
+                blob: Some("DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz".into()),
+                filename: Some("a\"b.txt".into()),
+                ..Default::default()
+            },
+        ]).unwrap();
+
+        assert_eq!(String::from_utf8(body).unwrap(), "--xyz\r\n\
+Content-Disposition: form-data; name=\"foo\"\r\n\
+\r\n\
+bar\r\n\
+--xyz\r\n\
+Content-Disposition: form-data; name=\"file\"; filename=\"a\\\"b.txt\"\r\n\
+Content-Type: application/octet-stream\r\n\
+\r\n\
+asdf\r\n\
+--xyz--\r\n");
+    }
+
+    #[test]
+    fn verify_encode_multipart_invalid_blob() {
+        let blob = Blob::create(Bytes::from(&b"asdf"[..]));
+        let state = DummyState(Arc::new(blob));
+
+        let r = encode_multipart(&state, "xyz", &[
+            MultipartField {
+                name: "file".into(),
+                blob: Some("invalid".into()),
+                ..Default::default()
+            },
+        ]);
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn verify_encode_multipart_content_type_newline() {
+        let blob = Blob::create(Bytes::from(&b"asdf"[..]));
+        let state = DummyState(Arc::new(blob));
+
+        let r = encode_multipart(&state, "xyz", &[
+            MultipartField {
+                name: "file".into(),
+                blob: Some("DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz".into()),
+                content_type: Some("text/plain\r\nX-Injected: 1".into()),
+                ..Default::default()
+            },
+        ]);
+        assert!(r.is_err());
+
+        let r = encode_multipart(&state, "xyz", &[
+            MultipartField {
+                name: "foo".into(),
+                text: Some("bar".into()),
+                content_type: Some("text/plain\nX-Injected: 1".into()),
+                ..Default::default()
+            },
+        ]);
+        assert!(r.is_err());
+    }
 }
@@ -2,10 +2,10 @@ use chrootable_https::DnsResolver;
 use crate::errors::*;
 use crate::hlua::AnyLuaValue;
 use crate::json::LuaJsonValue;
+use crate::proxy::Proxy;
 use crate::sockets::{Stream, SocketOptions};
 use http::Request;
 use std::collections::HashMap;
-use std::net::SocketAddr;
 use std::io;
 use tungstenite::protocol::{self, Message};
 use url::Url;
@@ -13,7 +13,7 @@ use url::Url;
 #[derive(Debug, Default, Deserialize)]
 pub struct WebSocketOptions {
     pub headers: Option<HashMap<String, String>>,
-    pub proxy: Option<SocketAddr>,
+    pub proxy: Option<Proxy>,
 
     #[serde(default)]
     pub connect_timeout: u64,
@@ -60,7 +60,7 @@ impl WebSocket {
         })
     }
 
-    pub fn connect<R: DnsResolver>(resolver: &R, url: Url, options: &WebSocketOptions) -> Result<WebSocket> {
+    pub fn connect<R: DnsResolver>(resolver: &R, url: Url, options: &WebSocketOptions, proxy: Option<&Proxy>) -> Result<WebSocket> {
         let tls = match url.scheme() {
             "ws" => false,
             "wss" => true,
@@ -76,16 +76,23 @@ impl WebSocket {
             (None, false) => 80,
         };
 
-        let stream = Stream::connect_stream(resolver, host, port, &SocketOptions {
+        let socket_options = SocketOptions {
             tls,
             sni_value: None,
             disable_tls_verify: false,
-            proxy: options.proxy,
+            proxy: None,
+            client_cert: None,
+            tls_identity: None,
 
             connect_timeout: options.connect_timeout,
             read_timeout: options.read_timeout,
             write_timeout: options.write_timeout,
-        })?;
+        };
+
+        let stream = match proxy {
+            Some(proxy) => Stream::connect_proxy_stream(proxy, host, port, &socket_options)?,
+            None => Stream::connect_stream(resolver, host, port, &socket_options)?,
+        };
         Self::negotiate(stream, url, options.headers.as_ref())
     }
 
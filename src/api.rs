@@ -1,7 +1,7 @@
 use crate::errors::*;
 use std::fmt;
 use crate::config::Config;
-use chrootable_https::{self, HttpClient, Body, Request, Uri};
+use chrootable_https::{self, Body, Request, Uri};
 use chrootable_https::http::request::Builder as RequestBuilder;
 use chrootable_https::header::CONTENT_TYPE;
 use rand::{Rng, thread_rng};
@@ -11,20 +11,25 @@ use serde::ser::Serialize;
 use serde_json;
 use sn0int_common::api::*;
 use sn0int_common::{ModuleID, ApiResponse};
-use crate::web;
+use crate::proxy::{Proxy, ProxyKind};
+use crate::transport::{ClientOptions, HttpsClient};
+use crate::web::{self, HttpTransport};
 
 
 pub struct Client {
     server: String,
-    client: chrootable_https::Client<chrootable_https::Resolver>,
+    client: Box<dyn HttpTransport>,
     session: Option<String>,
 }
 
 impl Client {
     pub fn new(config: &Config) -> Result<Client> {
-        let client = match config.network.proxy {
-            Some(proxy) => chrootable_https::Client::with_socks5(proxy),
-            _ => chrootable_https::Client::with_system_resolver()?,
+        let client: Box<dyn HttpTransport> = match config.network.proxy_pool().next() {
+            Some(Proxy { kind: ProxyKind::Socks5, addr, auth: None }) => {
+                Box::new(chrootable_https::Client::with_socks5(addr))
+            },
+            Some(proxy) => Box::new(HttpsClient::proxy(proxy, &ClientOptions::default())?),
+            _ => Box::new(chrootable_https::Client::with_system_resolver()?),
         };
         Ok(Client {
             server: config.core.registry.clone(),
@@ -50,8 +55,7 @@ impl Client {
 
         let request = request.body(body)?;
 
-        let resp = self.client.request(request)
-            .wait_for_response()?;
+        let resp = self.client.send(request, None, None)?;
         info!("response: {:?}", resp);
 
         let reply = serde_json::from_slice::<ApiResponse<T>>(&resp.body)?;
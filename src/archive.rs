@@ -0,0 +1,468 @@
+use crate::errors::*;
+
+use bytes::Bytes;
+use chrono::{NaiveDateTime, Utc};
+use crate::blobs::{Blob, BlobStorage};
+use crate::config::{Config, LocalConfig};
+use crate::db::Database;
+use crate::migrations;
+use crate::paths;
+use crate::term;
+use crate::workspaces::Workspace;
+use diesel::prelude::*;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+
+/// Bumped if the layout of the archive changes in an incompatible way
+const VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = "db.sqlite";
+const CONFIG: &str = "config.toml";
+const BLOBS_DIR: &str = "blobs";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub sn0int: String,
+    pub workspace: String,
+    pub created: NaiveDateTime,
+    pub blobs: Vec<String>,
+}
+
+/// A workspace that is being assembled next to the real workspaces, it's
+/// removed again unless it's installed
+///
+/// The name starts with a dot so it's never picked up as a workspace.
+struct Staging {
+    path: PathBuf,
+}
+
+impl Staging {
+    fn new(action: &str) -> Result<Staging> {
+        let path = paths::data_dir()?
+            .join(format!(".{}.{}", action, process::id()));
+        if path.exists() {
+            fs::remove_dir_all(&path)
+                .context("Failed to remove old staging directory")?;
+        }
+        fs::create_dir_all(path.join(BLOBS_DIR))
+            .context("Failed to create staging directory")?;
+        Ok(Staging {
+            path,
+        })
+    }
+
+    fn install(self, workspace: &Workspace, config: &Config) -> Result<()> {
+        ensure_available(workspace, config)?;
+
+        let path = paths::data_dir()?
+            .join(workspace.as_str());
+        fs::rename(&self.path, path)
+            .context("Failed to move workspace into place")?;
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.path).ok();
+    }
+}
+
+fn ensure_available(workspace: &Workspace, config: &Config) -> Result<()> {
+    if workspace.exists()? {
+        bail!("Workspace {:?} already exists", workspace.as_str());
+    }
+    if config.workspace_database(workspace).is_some() {
+        bail!("Workspace {:?} is configured as a shared workspace", workspace.as_str());
+    }
+    Ok(())
+}
+
+/// Copy the database and the blobs of a workspace into a new workspace
+///
+/// Returns the number of blobs that have been copied.
+pub fn clone(db: &Database, dst: &Workspace, config: &Config) -> Result<usize> {
+    ensure_available(dst, config)?;
+
+    let staging = Staging::new("clone")?;
+    db.snapshot(&staging.path.join(DATABASE))?;
+
+    let config_path = LocalConfig::path(db.workspace())?;
+    if config_path.exists() {
+        let raw = fs::read_to_string(config_path)
+            .context("Failed to read workspace config")?;
+        let config = toml::from_str::<LocalConfig>(&raw)
+            .context("Failed to parse workspace config")?;
+        write_config(&staging.path.join(CONFIG), &raw, config)?;
+    }
+
+    let storage = BlobStorage::workspace(db.workspace())?;
+    let blobs = storage.list()?;
+    for id in &blobs {
+        fs::copy(storage.join(id)?, staging.path.join(BLOBS_DIR).join(id))
+            .context("Failed to copy blob")?;
+    }
+
+    staging.install(dst, config)?;
+    Ok(blobs.len())
+}
+
+/// Write the database and the blobs of a workspace into a compressed tarball
+pub fn archive(db: &Database, path: &Path) -> Result<Manifest> {
+    let staging = Staging::new("archive")?;
+    let db_path = staging.path.join(DATABASE);
+    db.snapshot(&db_path)?;
+
+    let storage = BlobStorage::workspace(db.workspace())?;
+    let mut blobs = storage.list()?;
+    blobs.sort();
+
+    let manifest = Manifest {
+        version: VERSION,
+        sn0int: env!("CARGO_PKG_VERSION").to_string(),
+        workspace: db.name().to_string(),
+        created: Utc::now().naive_utc(),
+        blobs,
+    };
+
+    let config_path = LocalConfig::path(db.workspace())?;
+    let config_path = if config_path.exists() {
+        Some(config_path.as_path())
+    } else {
+        None
+    };
+
+    let file = File::create(path)
+        .context("Failed to create archive")?;
+    if let Err(err) = write_archive(file, &manifest, &db_path, config_path, &storage) {
+        fs::remove_file(path).ok();
+        return Err(err);
+    }
+
+    Ok(manifest)
+}
+
+fn write_archive<W: Write>(w: W, manifest: &Manifest, db_path: &Path, config_path: Option<&Path>, storage: &BlobStorage) -> Result<()> {
+    let mut tar = tar::Builder::new(GzEncoder::new(w, Compression::default()));
+
+    let json = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created.timestamp() as u64);
+    tar.append_data(&mut header, MANIFEST, &json[..])?;
+
+    tar.append_path_with_name(db_path, DATABASE)?;
+    if let Some(config_path) = config_path {
+        tar.append_path_with_name(config_path, CONFIG)?;
+    }
+    for id in &manifest.blobs {
+        tar.append_path_with_name(storage.join(id)?, format!("{}/{}", BLOBS_DIR, id))?;
+    }
+
+    tar.into_inner()?
+        .finish()?
+        .flush()?;
+    Ok(())
+}
+
+/// Restore a workspace from an archive, the name from the manifest is used
+/// if no workspace is given
+pub fn restore(path: &Path, workspace: Option<Workspace>, config: &Config) -> Result<(Workspace, Manifest)> {
+    if let Some(workspace) = &workspace {
+        ensure_available(workspace, config)?;
+    }
+
+    let file = File::open(path)
+        .context("Failed to open archive")?;
+    let staging = Staging::new("restore")?;
+    let manifest = read_archive(file, &staging.path)?;
+
+    // this also upgrades archives of older versions
+    let db_path = staging.path.join(DATABASE);
+    let db_path = db_path.to_str()
+        .ok_or_else(|| format_err!("Failed to convert db path to utf-8"))?;
+    let db = SqliteConnection::establish(db_path)
+        .context("Failed to open restored database")?;
+    migrations::run(&db)
+        .context("Failed to run migrations on restored database")?;
+    drop(db);
+
+    let workspace = match workspace {
+        Some(workspace) => workspace,
+        None => Workspace::from_str(&manifest.workspace)?,
+    };
+    staging.install(&workspace, config)?;
+
+    Ok((workspace, manifest))
+}
+
+fn blob_id(name: &str) -> Option<&str> {
+    let prefix = format!("{}/", BLOBS_DIR);
+    if !name.starts_with(&prefix) {
+        return None;
+    }
+
+    let id = &name[prefix.len()..];
+    if !id.is_empty() && id.chars().all(char::is_alphanumeric) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// Extract an archive into a directory, every blob is verified against its id
+fn read_archive<R: Read>(r: R, dir: &Path) -> Result<Manifest> {
+    let mut tar = tar::Archive::new(GzDecoder::new(r));
+    let storage = BlobStorage::new(dir.join(BLOBS_DIR));
+
+    let mut manifest: Option<Manifest> = None;
+    let mut has_db = false;
+    let mut blobs = HashSet::new();
+
+    for entry in tar.entries().context("Failed to read archive")? {
+        let mut entry = entry.context("Failed to read archive")?;
+        let name = entry.path()?
+            .to_str()
+            .ok_or_else(|| format_err!("Archive contains file with invalid name"))?
+            .to_string();
+
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            bail!("Archive contains unexpected entry: {:?}", name);
+        }
+
+        if name == MANIFEST {
+            let m = serde_json::from_reader(&mut entry)
+                .context("Failed to parse manifest")?;
+            manifest = Some(m);
+        } else if name == DATABASE {
+            let mut f = File::create(dir.join(DATABASE))?;
+            io::copy(&mut entry, &mut f)
+                .context("Failed to extract database")?;
+            has_db = true;
+        } else if name == CONFIG {
+            let mut config = String::new();
+            entry.read_to_string(&mut config)
+                .context("Failed to extract workspace config")?;
+            let parsed = toml::from_str::<LocalConfig>(&config)
+                .context("Archive contains invalid workspace config")?;
+            write_config(&dir.join(CONFIG), &config, parsed)?;
+        } else if let Some(id) = blob_id(&name) {
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)
+                .context("Failed to extract blob")?;
+            if Blob::hash(&bytes) != id {
+                bail!("Blob {:?} is corrupted", id);
+            }
+            storage.save(&Blob {
+                id: id.to_string(),
+                bytes: Bytes::from(bytes),
+            })?;
+            blobs.insert(id.to_string());
+        } else {
+            bail!("Archive contains unexpected file: {:?}", name);
+        }
+    }
+
+    let manifest = manifest
+        .ok_or_else(|| format_err!("Archive has no manifest"))?;
+    if manifest.version > VERSION {
+        bail!("Archive was created by a newer version of sn0int ({})", manifest.sn0int);
+    }
+    if !has_db {
+        bail!("Archive has no database");
+    }
+
+    let expected = manifest.blobs.iter().cloned().collect::<HashSet<_>>();
+    if let Some(id) = expected.difference(&blobs).next() {
+        bail!("Blob {:?} is missing from archive", id);
+    }
+    if let Some(id) = blobs.difference(&expected).next() {
+        bail!("Blob {:?} isn't listed in the manifest", id);
+    }
+
+    Ok(manifest)
+}
+
+/// Grants and network settings of a copied config aren't trusted, they have
+/// to be configured again for the new workspace
+fn write_config(path: &Path, raw: &str, mut config: LocalConfig) -> Result<()> {
+    let removed = config.strip_privileged();
+    if removed.is_empty() {
+        fs::write(path, raw)
+            .context("Failed to write workspace config")?;
+        return Ok(());
+    }
+
+    for key in removed {
+        term::warn(&format!("Dropping {:?} from the workspace config, set it again if needed", key));
+    }
+    let config = toml::to_string_pretty(&config)?;
+    fs::write(path, config)
+        .context("Failed to write workspace config")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile;
+
+    fn manifest(blobs: &[&[u8]]) -> Manifest {
+        Manifest {
+            version: VERSION,
+            sn0int: "0.17.1".to_string(),
+            workspace: "demo".to_string(),
+            created: NaiveDateTime::from_timestamp(1_577_836_800, 0),
+            blobs: blobs.iter()
+                .map(|bytes| Blob::hash(bytes))
+                .collect(),
+        }
+    }
+
+    fn build(manifest: &Manifest, files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        {
+            let mut tar = tar::Builder::new(GzEncoder::new(&mut archive, Compression::default()));
+            let json = serde_json::to_vec(manifest).unwrap();
+            let mut files = files.to_vec();
+            files.insert(0, (MANIFEST, &json[..]));
+
+            for (name, data) in files {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                tar.append_data(&mut header, name, data).unwrap();
+            }
+            tar.into_inner().unwrap().finish().unwrap();
+        }
+        archive
+    }
+
+    fn extract(archive: &[u8]) -> (tempfile::TempDir, Result<Manifest>) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::create_dir(dir.path().join(BLOBS_DIR)).unwrap();
+        let manifest = read_archive(archive, dir.path());
+        (dir, manifest)
+    }
+
+    #[test]
+    fn test_blob_id() {
+        assert_eq!(blob_id("blobs/DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz"), Some("DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz"));
+        assert_eq!(blob_id("blobs/"), None);
+        assert_eq!(blob_id("blobs/../db.sqlite"), None);
+        assert_eq!(blob_id("cookies/asdf"), None);
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let src = tempfile::tempdir().expect("tempdir");
+        let db_path = src.path().join(DATABASE);
+        fs::write(&db_path, b"database").unwrap();
+        let storage = BlobStorage::new(src.path());
+        storage.save(&Blob::create(Bytes::from(&b"asdf"[..]))).unwrap();
+
+        let manifest = manifest(&[b"asdf"]);
+        let mut archive = Vec::new();
+        write_archive(&mut archive, &manifest, &db_path, None, &storage).unwrap();
+
+        let (dst, restored) = extract(&archive);
+        assert_eq!(restored.unwrap(), manifest);
+        assert_eq!(fs::read(dst.path().join(DATABASE)).unwrap(), b"database");
+
+        let blob = BlobStorage::new(dst.path().join(BLOBS_DIR))
+            .load("DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz")
+            .unwrap();
+        assert_eq!(&blob.bytes[..], b"asdf");
+    }
+
+    #[test]
+    fn test_archive_config_privileged() {
+        let archive = build(&manifest(&[]), &[
+            (DATABASE, b"database"),
+            (CONFIG, b"[network]\nproxy = \"127.0.0.1:9050\"\n\n[run]\nthreads = 8\ngrants = [\"shodan\"]\n"),
+        ]);
+        let (dst, restored) = extract(&archive);
+        restored.unwrap();
+
+        let config = fs::read_to_string(dst.path().join(CONFIG)).unwrap();
+        let config = toml::from_str::<LocalConfig>(&config).unwrap();
+        assert_eq!(config.network, None);
+        assert_eq!(config.run.threads, Some(8));
+        assert!(config.run.grants.is_empty());
+    }
+
+    #[test]
+    fn test_archive_corrupted_blob() {
+        let archive = build(&manifest(&[b"asdf"]), &[
+            (DATABASE, b"database"),
+            ("blobs/DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz", b"asdF"),
+        ]);
+        let (_dst, restored) = extract(&archive);
+        assert!(restored.is_err());
+    }
+
+    #[test]
+    fn test_archive_missing_blob() {
+        let archive = build(&manifest(&[b"asdf", b"missing"]), &[
+            (DATABASE, b"database"),
+            ("blobs/DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz", b"asdf"),
+        ]);
+        let (_dst, restored) = extract(&archive);
+        assert!(restored.is_err());
+    }
+
+    #[test]
+    fn test_archive_unlisted_blob() {
+        let archive = build(&manifest(&[]), &[
+            (DATABASE, b"database"),
+            ("blobs/DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz", b"asdf"),
+        ]);
+        let (_dst, restored) = extract(&archive);
+        assert!(restored.is_err());
+    }
+
+    #[test]
+    fn test_archive_unexpected_file() {
+        let archive = build(&manifest(&[]), &[
+            (DATABASE, b"database"),
+            ("cookies/session", b"asdf"),
+        ]);
+        let (_dst, restored) = extract(&archive);
+        assert!(restored.is_err());
+    }
+
+    #[test]
+    fn test_archive_config() {
+        let archive = build(&manifest(&[]), &[
+            (DATABASE, b"database"),
+            (CONFIG, b"[run]\nthreads = 8\n"),
+        ]);
+        let (dst, restored) = extract(&archive);
+        assert!(restored.is_ok());
+        assert_eq!(fs::read(dst.path().join(CONFIG)).unwrap(), b"[run]\nthreads = 8\n");
+
+        let archive = build(&manifest(&[]), &[
+            (DATABASE, b"database"),
+            (CONFIG, b"[run]\nthreads = \"many\"\n"),
+        ]);
+        let (_dst, restored) = extract(&archive);
+        assert!(restored.is_err());
+    }
+
+    #[test]
+    fn test_archive_without_database() {
+        let archive = build(&manifest(&[]), &[]);
+        let (_dst, restored) = extract(&archive);
+        assert!(restored.is_err());
+    }
+}
@@ -96,11 +96,11 @@ pub struct Run {
     /// Run a module from a path
     #[structopt(short="f", long="file")]
     pub file: bool,
-    /// Run modules concurrently
-    #[structopt(short="j", long="threads", default_value="1")]
-    pub threads: usize,
+    /// Run modules concurrently, defaults to the threads setting or 1
+    #[structopt(short="j", long="threads")]
+    pub threads: Option<usize>,
     /// Verbose logging, once to print inserts even if they don't add new
-    /// data, twice to activate the debug() function
+    /// data and database metrics, twice to activate the debug() function
     #[structopt(short="v", long="verbose", parse(from_occurrences))]
     pub verbose: u64,
     /// Expose stdin to modules
@@ -0,0 +1,145 @@
+use crate::errors::*;
+use crate::autonoscope::{Autonoscope, IntoRule, AutoRule};
+use crate::models::*;
+use std::convert::TryFrom;
+
+
+#[derive(Debug, PartialEq)]
+pub struct AsnRule {
+    asn: i32,
+}
+
+impl ToString for AsnRule {
+    fn to_string(&self) -> String {
+        self.asn.to_string()
+    }
+}
+
+impl TryFrom<&str> for AsnRule {
+    type Error = Error;
+
+    fn try_from(x: &str) -> Result<AsnRule> {
+        let num = if x.len() > 2 && x[..2].eq_ignore_ascii_case("as") {
+            &x[2..]
+        } else {
+            x
+        };
+        let asn = num.parse::<u32>()
+            .map_err(|_| format_err!("Invalid asn: {:?}", x))?;
+        let asn = i32::try_from(asn)
+            .map_err(|_| format_err!("Asn is out of range: {:?}", x))?;
+        Ok(AsnRule {
+            asn,
+        })
+    }
+}
+
+impl TryFrom<Autonoscope> for AsnRule {
+    type Error = Error;
+
+    fn try_from(x: Autonoscope) -> Result<AsnRule> {
+        AsnRule::try_from(x.value.as_str())
+    }
+}
+
+impl AutoRule<NewAutonomousSystem> for AsnRule {
+    fn matches(&self, asn: &NewAutonomousSystem) -> Result<bool> {
+        self.matches(&asn.value)
+    }
+}
+
+impl AutoRule<NewIpAddr> for AsnRule {
+    fn matches(&self, ipaddr: &NewIpAddr) -> Result<bool> {
+        self.matches(&ipaddr.asn)
+    }
+}
+
+impl AutoRule<NewNetblock> for AsnRule {
+    fn matches(&self, netblock: &NewNetblock) -> Result<bool> {
+        self.matches(&netblock.asn)
+    }
+}
+
+impl AutoRule<Option<i32>> for AsnRule {
+    fn matches(&self, asn: &Option<i32>) -> Result<bool> {
+        Ok(*asn == Some(self.asn))
+    }
+}
+
+impl AutoRule<i32> for AsnRule {
+    fn matches(&self, asn: &i32) -> Result<bool> {
+        Ok(*asn == self.asn)
+    }
+}
+
+impl IntoRule for AsnRule {
+    fn into_rule(&self) -> (&'static str, String) {
+        ("asn", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn netblock(asn: Option<i32>) -> NewNetblock {
+        NewNetblock {
+            family: String::from("4"),
+            value: String::from("192.0.2.0/24"),
+            asn,
+            as_org: None,
+            description: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_asn_rule_parse() {
+        assert_eq!(AsnRule::try_from("13335").unwrap(), AsnRule { asn: 13335 });
+        assert_eq!(AsnRule::try_from("AS13335").unwrap(), AsnRule { asn: 13335 });
+        assert_eq!(AsnRule::try_from("as13335").unwrap(), AsnRule { asn: 13335 });
+        assert_eq!(AsnRule::try_from("AS13335").unwrap().to_string(), "13335");
+    }
+
+    #[test]
+    fn test_asn_rule_parse_invalid() {
+        assert!(AsnRule::try_from("").is_err());
+        assert!(AsnRule::try_from("AS").is_err());
+        assert!(AsnRule::try_from("-1").is_err());
+        assert!(AsnRule::try_from("example.com").is_err());
+        assert!(AsnRule::try_from("4294967296").is_err());
+    }
+
+    #[test]
+    fn test_asn_rule_autonomous_system() {
+        let rule = AsnRule::try_from("AS13335").unwrap();
+        assert!(rule.matches(&NewAutonomousSystem {
+            value: 13335,
+            name: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        }).unwrap());
+        assert!(!rule.matches(&NewAutonomousSystem {
+            value: 15169,
+            name: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        }).unwrap());
+    }
+
+    #[test]
+    fn test_asn_rule_netblock() {
+        let rule = AsnRule::try_from("13335").unwrap();
+        assert!(rule.matches(&netblock(Some(13335))).unwrap());
+        assert!(!rule.matches(&netblock(Some(15169))).unwrap());
+        assert!(!rule.matches(&netblock(None)).unwrap());
+    }
+}
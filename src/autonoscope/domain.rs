@@ -55,6 +55,13 @@ impl AutoRule<NewSubdomain> for DomainRule {
     }
 }
 
+impl AutoRule<NewDnsRecord> for DomainRule {
+    #[inline]
+    fn matches(&self, record: &NewDnsRecord) -> Result<bool> {
+        self.matches(record.name.as_str())
+    }
+}
+
 impl AutoRule<NewUrl> for DomainRule {
     #[inline]
     fn matches(&self, url: &NewUrl) -> Result<bool> {
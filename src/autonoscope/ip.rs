@@ -157,6 +157,9 @@ mod tests {
             as_org: None,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }).unwrap());
     }
 
@@ -170,6 +173,9 @@ mod tests {
             as_org: None,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }).unwrap());
     }
 
@@ -183,6 +189,9 @@ mod tests {
             as_org: None,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }).unwrap());
     }
 
@@ -196,6 +205,9 @@ mod tests {
             as_org: None,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }).unwrap());
     }
 
@@ -209,6 +221,9 @@ mod tests {
             as_org: None,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }).unwrap());
     }
 }
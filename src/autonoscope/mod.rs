@@ -8,6 +8,8 @@ use std::cmp::Ordering;
 use std::convert::{TryInto, TryFrom};
 use std::str::FromStr;
 
+mod asn;
+pub use self::asn::AsnRule;
 mod domain;
 pub use self::domain::DomainRule;
 mod ip;
@@ -37,6 +39,7 @@ pub struct RuleSet {
     domains: Vec<Rule<DomainRule>>,
     ips: Vec<Rule<IpRule>>,
     urls: Vec<Rule<UrlRule>>,
+    asns: Vec<Rule<AsnRule>>,
 }
 
 #[inline(always)]
@@ -49,7 +52,7 @@ fn sort_precision_desc<T: RulePrecision>(a: &T, b: &T) -> Ordering {
 impl RuleSet {
     pub fn load(db: &DatabaseSock) -> Result<Self> {
         use crate::schema::autonoscope::dsl::*;
-        let rules = autonoscope.load::<Autonoscope>(db)?;
+        let rules = with_conn!(db, |conn| autonoscope.load::<Autonoscope>(conn))?;
 
         let mut set = RuleSet::default();
         for rule in rules {
@@ -59,6 +62,7 @@ impl RuleSet {
                 RuleType::Domain => set.domains.push(Rule::new(rule.try_into()?, is_scoped)),
                 RuleType::Ip => set.ips.push(Rule::new(rule.try_into()?, is_scoped)),
                 RuleType::Url => set.urls.push(Rule::new(rule.try_into()?, is_scoped)),
+                RuleType::Asn => set.asns.push(Rule::new(rule.try_into()?, is_scoped)),
             }
         }
         set.sort_rules();
@@ -94,6 +98,12 @@ impl RuleSet {
                 self.db_add(db, &rule)?;
                 self.urls.push(rule);
             },
+            RuleType::Asn => {
+                let rule = AsnRule::try_from(value)?;
+                let rule = Rule::new(rule, scoped);
+                self.db_add(db, &rule)?;
+                self.asns.push(rule);
+            },
         }
         self.sort_rules();
 
@@ -102,9 +112,9 @@ impl RuleSet {
 
     fn db_add<I: Into<NewAutonoscope>>(&mut self, db: &DatabaseSock, rule: I) -> Result<()> {
         use crate::schema::autonoscope::dsl::*;
-        diesel::insert_into(autonoscope)
+        with_conn!(db, |conn| diesel::insert_into(autonoscope)
             .values(rule.into())
-            .execute(db)?;
+            .execute(conn))?;
         Ok(())
     }
 
@@ -122,16 +132,22 @@ impl RuleSet {
                 self.urls.retain(|x| x.to_string().as_str() != rule);
                 self.db_delete(db, obj, &rule)?;
             },
+            RuleType::Asn => {
+                // AS13335 and 13335 refer to the same rule
+                let rule = AsnRule::try_from(rule)?.to_string();
+                self.asns.retain(|x| x.to_string() != rule);
+                self.db_delete(db, obj, &rule)?;
+            },
         }
         Ok(())
     }
 
     fn db_delete(&mut self, db: &DatabaseSock, obj: &RuleType, rule: &str) -> Result<()> {
         use crate::schema::autonoscope::dsl::*;
-        diesel::delete(autonoscope
+        with_conn!(db, |conn| diesel::delete(autonoscope
             .filter(object.eq(obj.as_str()))
             .filter(value.eq(rule)))
-            .execute(db)?;
+            .execute(conn))?;
         Ok(())
     }
 
@@ -140,6 +156,7 @@ impl RuleSet {
         Self::push_rules_display(&mut rules, &self.domains);
         Self::push_rules_display(&mut rules, &self.ips);
         Self::push_rules_display(&mut rules, &self.urls);
+        Self::push_rules_display(&mut rules, &self.asns);
         rules
     }
 
@@ -151,11 +168,22 @@ impl RuleSet {
         }
     }
 
-    pub fn matches(&self, object: &Insert) -> Result<bool> {
+    /// Returns the scope of the most specific matching rule, if any
+    ///
+    /// If no rule matches, the caller decides on the default. The database
+    /// uses the scope of the netblock for ipaddrs and ports and puts
+    /// everything else in scope.
+    pub fn matches(&self, object: &Insert) -> Result<Option<bool>> {
         let scoped = match object {
             Insert::Domain(domain) => Self::matches_any(&self.domains, domain)?,
             Insert::Subdomain(subdomain) => Self::matches_any(&self.domains, subdomain)?,
-            Insert::IpAddr(ip_addr) => Self::matches_any(&self.ips, ip_addr)?,
+            Insert::IpAddr(ip_addr) => {
+                if let Some(result) = Self::matches_any(&self.ips, ip_addr)? {
+                    Some(result)
+                } else {
+                    Self::matches_any(&self.asns, ip_addr)?
+                }
+            },
             Insert::Url(url) => {
                 if let Some(result) = Self::matches_any(&self.domains, url)? {
                     Some(result)
@@ -168,10 +196,18 @@ impl RuleSet {
             // Insert::Email(email) => unimplemented!(),
             // Insert::Account(account) => unimplemented!(),
             Insert::Port(port) => Self::matches_any(&self.ips, port)?,
-            Insert::Netblock(netblock) => Self::matches_any(&self.ips, netblock)?,
+            Insert::Netblock(netblock) => {
+                if let Some(result) = Self::matches_any(&self.ips, netblock)? {
+                    Some(result)
+                } else {
+                    Self::matches_any(&self.asns, netblock)?
+                }
+            },
+            Insert::DnsRecord(record) => Self::matches_any(&self.domains, record)?,
+            Insert::AutonomousSystem(asn) => Self::matches_any(&self.asns, asn)?,
             _ => None,
         };
-        Ok(scoped.unwrap_or(true))
+        Ok(scoped)
     }
 
     fn matches_any<T1, T2>(rules: &[Rule<T1>], object: &T2) -> Result<Option<bool>>
@@ -192,6 +228,7 @@ pub enum RuleType {
     Domain,
     Ip,
     Url,
+    Asn,
 }
 
 impl RuleType {
@@ -200,6 +237,7 @@ impl RuleType {
             RuleType::Domain => "domain",
             RuleType::Ip => "ip",
             RuleType::Url => "url",
+            RuleType::Asn => "asn",
         }
     }
 
@@ -209,6 +247,7 @@ impl RuleType {
                 RuleType::Domain.as_str(),
                 RuleType::Ip.as_str(),
                 RuleType::Url.as_str(),
+                RuleType::Asn.as_str(),
             ];
         }
 
@@ -224,6 +263,7 @@ impl FromStr for RuleType {
             "domain" => Ok(RuleType::Domain),
             "ip" => Ok(RuleType::Ip),
             "url" => Ok(RuleType::Url),
+            "asn" => Ok(RuleType::Asn),
             _ => bail!("unknown rule type"),
         }
     }
@@ -298,6 +338,7 @@ mod tests {
             ],
             ips: vec![],
             urls: vec![],
+            asns: vec![],
         };
         set.sort_rules();
         assert_eq!(set, RuleSet {
@@ -308,6 +349,7 @@ mod tests {
             ],
             ips: vec![],
             urls: vec![],
+            asns: vec![],
         });
     }
 
@@ -321,6 +363,7 @@ mod tests {
                 Rule::new(IpRule::try_from("10.5.6.0/24").unwrap(), true),
             ],
             urls: vec![],
+            asns: vec![],
         };
         set.sort_rules();
         // TODO: add ipv6
@@ -332,6 +375,7 @@ mod tests {
                 Rule::new(IpRule::try_from("0.0.0.0/0").unwrap(), true),
             ],
             urls: vec![],
+            asns: vec![],
         });
     }
 
@@ -345,6 +389,7 @@ mod tests {
                 Rule::new(UrlRule::try_from("https://example.com/").unwrap(), true),
                 Rule::new(UrlRule::try_from("https://example.com/foo/bar/?asdf=1").unwrap(), true),
             ],
+            asns: vec![],
         };
         set.sort_rules();
         assert_eq!(set, RuleSet {
@@ -355,6 +400,79 @@ mod tests {
                 Rule::new(UrlRule::try_from("http://example.com/foo/").unwrap(), true),
                 Rule::new(UrlRule::try_from("https://example.com/").unwrap(), true),
             ],
+            asns: vec![],
         });
     }
+
+    fn ipaddr(value: &str, asn: Option<i32>) -> Insert {
+        Insert::IpAddr(NewIpAddr {
+            family: String::from("4"),
+            value: value.to_string(),
+            continent: None,
+            continent_code: None,
+            country: None,
+            country_code: None,
+            city: None,
+            latitude: None,
+            longitude: None,
+            asn,
+            as_org: None,
+            description: None,
+            reverse_dns: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        })
+    }
+
+    #[test]
+    fn test_rule_matches_asn() {
+        let set = RuleSet {
+            asns: vec![
+                Rule::new(AsnRule::try_from("AS13335").unwrap(), false),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(set.matches(&ipaddr("192.0.2.1", Some(13335))).unwrap(), Some(false));
+        assert_eq!(set.matches(&ipaddr("192.0.2.1", Some(15169))).unwrap(), None);
+        assert_eq!(set.matches(&ipaddr("192.0.2.1", None)).unwrap(), None);
+        assert_eq!(set.matches(&Insert::AutonomousSystem(NewAutonomousSystem {
+            value: 13335,
+            name: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        })).unwrap(), Some(false));
+    }
+
+    #[test]
+    fn test_rule_matches_ip_before_asn() {
+        let set = RuleSet {
+            ips: vec![
+                Rule::new(IpRule::try_from("192.0.2.0/24").unwrap(), true),
+            ],
+            asns: vec![
+                Rule::new(AsnRule::try_from("AS13335").unwrap(), false),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(set.matches(&ipaddr("192.0.2.1", Some(13335))).unwrap(), Some(true));
+        assert_eq!(set.matches(&ipaddr("198.51.100.1", Some(13335))).unwrap(), Some(false));
+    }
+
+    #[test]
+    fn test_rule_matches_none() {
+        let set = RuleSet {
+            ips: vec![
+                Rule::new(IpRule::try_from("192.0.2.0/24").unwrap(), false),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(set.matches(&ipaddr("192.0.2.1", None)).unwrap(), Some(false));
+        // the database falls back to the scope of the netblock
+        assert_eq!(set.matches(&ipaddr("198.51.100.1", None)).unwrap(), None);
+        assert_eq!(RuleSet::default().matches(&ipaddr("192.0.2.1", None)).unwrap(), None);
+    }
 }
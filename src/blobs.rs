@@ -1,16 +1,27 @@
 use crate::errors::*;
+use crate::ipc::parent::IpcParent;
 use crate::paths;
-use crate::worker::{EventWithCallback, Event2};
+use crate::worker::{EventSender, EventWithCallback, Event2};
 use crate::workspaces::Workspace;
 
 use bytes::Bytes;
+use serde::ser::{Serialize, Serializer};
+use serde::de::{self, Deserialize, Deserializer};
+use sn0int_std::blobs::BlobHasher;
+use std::collections::HashSet;
 use std::fs;
+use std::io::Write;
+use std::mem;
 use std::path::{Path, PathBuf};
+use std::process;
 use std::result;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
 
 pub use sn0int_std::blobs::Blob;
 
+static NEXT_UPLOAD: AtomicUsize = AtomicUsize::new(0);
+
 impl EventWithCallback for Blob {
     type Payload = ();
 
@@ -20,6 +31,7 @@ impl EventWithCallback for Blob {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct BlobStorage {
     path: PathBuf,
 }
@@ -74,6 +86,24 @@ impl BlobStorage {
         Ok(())
     }
 
+    /// Start writing a blob whose id isn't known yet
+    pub fn upload(&self) -> Result<BlobUpload> {
+        let n = NEXT_UPLOAD.fetch_add(1, Ordering::SeqCst);
+        let path = self.path.join(format!(".upload.{}.{}", process::id(), n));
+
+        debug!("Writing blob upload to {:?}", path);
+        let file = fs::File::create(&path)
+            .context("Failed to create blob")?;
+
+        Ok(BlobUpload {
+            storage: self.clone(),
+            path,
+            file,
+            hasher: BlobHasher::new(),
+            done: false,
+        })
+    }
+
     pub fn delete(&self, id: &str) -> Result<()> {
         let path = self.join(id)?;
         debug!("Deleting blob: {:?}", path);
@@ -89,12 +119,169 @@ impl BlobStorage {
                 .file_name()
                 .into_string()
                 .map_err(|_| format_err!("Invalid filename"))?;
-            blobs.push(blob);
+            // skip unfinished uploads
+            if !blob.starts_with('.') {
+                blobs.push(blob);
+            }
         }
         Ok(blobs)
     }
 }
 
+/// A blob that is written in chunks
+///
+/// The chunks go into a temporary file that is renamed once the id is known.
+pub struct BlobUpload {
+    storage: BlobStorage,
+    path: PathBuf,
+    file: fs::File,
+    hasher: BlobHasher,
+    done: bool,
+}
+
+impl BlobUpload {
+    pub fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.hasher.update(bytes);
+        self.file.write_all(bytes)
+            .context("Failed to write blob")?;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<String> {
+        let hasher = mem::replace(&mut self.hasher, BlobHasher::new());
+        let id = hasher.finish();
+
+        let path = self.storage.join(&id)?;
+        debug!("Moving blob upload to {:?}", path);
+        fs::rename(&self.path, path)
+            .context("Failed to write blob")?;
+        self.done = true;
+
+        Ok(id)
+    }
+}
+
+impl Drop for BlobUpload {
+    fn drop(&mut self) {
+        if !self.done {
+            debug!("Deleting unfinished blob upload: {:?}", self.path);
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+/// Part of a blob that is streamed out of the sandbox
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobChunk(pub Bytes);
+
+impl Serialize for BlobChunk {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = base64::encode(&self.0);
+        serializer.serialize_str(&s)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlobChunk {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let bytes = base64::decode(&s)
+            .map_err(de::Error::custom)?;
+        Ok(BlobChunk(Bytes::from(bytes)))
+    }
+}
+
+/// Sent by a script to write a blob into storage without holding it in memory
+#[derive(Debug, Serialize, Deserialize)]
+pub enum BlobStreamEvent {
+    Start,
+    Write(BlobChunk),
+    Finish,
+    Abort,
+    /// Load a blob that was streamed into storage earlier
+    Load(String),
+}
+
+/// The blob uploads of a single script
+pub struct BlobStreams {
+    storage: BlobStorage,
+    upload: Option<BlobUpload>,
+    uploaded: HashSet<String>,
+}
+
+impl BlobStreams {
+    pub fn new(storage: BlobStorage) -> BlobStreams {
+        BlobStreams {
+            storage,
+            upload: None,
+            uploaded: HashSet::new(),
+        }
+    }
+
+    fn start(&mut self) -> Result<()> {
+        self.upload = Some(self.storage.upload()?);
+        Ok(())
+    }
+
+    fn write(&mut self, chunk: &[u8]) -> Result<()> {
+        let upload = self.upload.as_mut()
+            .ok_or_else(|| format_err!("No blob upload in progress"))?;
+        upload.write(chunk)
+    }
+
+    fn finish(&mut self) -> Result<String> {
+        let upload = self.upload.take()
+            .ok_or_else(|| format_err!("No blob upload in progress"))?;
+        let id = upload.finish()?;
+        self.uploaded.insert(id.clone());
+        Ok(id)
+    }
+
+    fn abort(&mut self) {
+        self.upload.take();
+    }
+
+    fn load(&self, id: &str) -> Result<Blob> {
+        // scripts may only load the blobs they've uploaded themselves
+        if !self.uploaded.contains(id) {
+            bail!("Invalid blob reference");
+        }
+        self.storage.load(id)
+    }
+
+    pub fn apply(&mut self, event: BlobStreamEvent, ipc_parent: &mut IpcParent, tx: &EventSender) {
+        match event {
+            BlobStreamEvent::Start => {
+                let reply = self.start().map_err(|e| e.to_string());
+                ipc_parent.send_struct(reply, tx);
+            },
+            BlobStreamEvent::Write(chunk) => {
+                let reply = self.write(&chunk.0).map_err(|e| e.to_string());
+                ipc_parent.send_struct(reply, tx);
+            },
+            BlobStreamEvent::Finish => {
+                let reply = self.finish().map_err(|e| e.to_string());
+                ipc_parent.send_struct(reply, tx);
+            },
+            BlobStreamEvent::Abort => {
+                self.abort();
+                ipc_parent.send_struct(Ok::<_, String>(()), tx);
+            },
+            BlobStreamEvent::Load(id) => {
+                let reply = self.load(&id).map_err(|e| e.to_string());
+                ipc_parent.send_struct(reply, tx);
+            },
+        }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -130,6 +317,74 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_blobstorage_upload() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let s = BlobStorage::new(dir.path());
+
+        let mut upload = s.upload().expect("upload failed");
+        upload.write(b"as").unwrap();
+        assert_eq!(s.list().unwrap(), Vec::<String>::new());
+        upload.write(b"df").unwrap();
+        let id = upload.finish().expect("finish failed");
+
+        assert_eq!(id, "DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz");
+        assert_eq!(s.list().unwrap(), vec![id.clone()]);
+        assert_eq!(s.load(&id).unwrap(), blob().1);
+    }
+
+    #[test]
+    fn test_blobstorage_upload_dropped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let s = BlobStorage::new(dir.path());
+
+        let mut upload = s.upload().expect("upload failed");
+        upload.write(b"asdf").unwrap();
+        drop(upload);
+
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_blobstreams() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut streams = BlobStreams::new(BlobStorage::new(dir.path()));
+
+        assert!(streams.write(b"asdf").is_err());
+        assert!(streams.load("DTTV3EjpHBNJx3Zw7eJsVPm4bYXKmNkJQpVNkcvTtTSz").is_err());
+
+        streams.start().unwrap();
+        streams.write(b"asdf").unwrap();
+        let id = streams.finish().unwrap();
+        assert_eq!(streams.load(&id).unwrap(), blob().1);
+
+        streams.start().unwrap();
+        streams.write(b"fdsa").unwrap();
+        streams.abort();
+        assert!(streams.finish().is_err());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_blobstreams_load_foreign_blob() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let s = BlobStorage::new(dir.path());
+        let (_, blob) = blob();
+        s.save(&blob).unwrap();
+
+        let streams = BlobStreams::new(s);
+        assert!(streams.load(&blob.id).is_err());
+    }
+
+    #[test]
+    fn test_blobchunk_serialize() {
+        let chunk = BlobChunk(Bytes::from(&b"asdf"[..]));
+        let json = serde_json::to_string(&chunk).unwrap();
+        assert_eq!(&json, "\"YXNkZg==\"");
+        let chunk2: BlobChunk = serde_json::from_str(&json).unwrap();
+        assert_eq!(chunk, chunk2);
+    }
+
     #[test]
     fn test_path_validation() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -128,6 +128,9 @@ impl IntoInsert for AddDomain {
         Ok(Insert::Domain(NewDomain {
             value: domain,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -150,6 +153,9 @@ impl IntoInsert for AddSubdomain {
         let domain_id = match rl.db().insert_struct(NewDomain {
             value: dns_name.root,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }, true)? {
             Some((_, domain_id)) => domain_id,
             _ => bail!("Domain is out out of scope"),
@@ -160,6 +166,9 @@ impl IntoInsert for AddSubdomain {
             value: subdomain,
             resolvable: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -199,6 +208,9 @@ impl IntoInsert for AddIpAddr {
             description: None,
             reverse_dns: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -225,6 +237,9 @@ impl IntoInsert for AddUrl {
         let domain_id = match rl.db().insert_struct(NewDomain {
             value: dns_name.root,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }, true)? {
             Some((_, domain_id)) => domain_id,
             _ => bail!("Domain is out out of scope"),
@@ -235,6 +250,9 @@ impl IntoInsert for AddUrl {
             domain_id,
             resolvable: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }, true)? {
             Some((_, subdomain_id)) => subdomain_id,
             _ => bail!("Subdomain is out out of scope"),
@@ -248,6 +266,9 @@ impl IntoInsert for AddUrl {
             online: None,
             title: None,
             redirect: None,
+            confidence: None,
+            verified: None,
+            extra: None,
         }.try_into_new()?))
     }
 }
@@ -269,6 +290,9 @@ impl IntoInsert for AddEmail {
             displayname: None,
             valid: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -303,6 +327,9 @@ impl IntoInsert for AddPhoneNumber {
             caller_name: None,
             caller_type: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -333,6 +360,9 @@ impl IntoInsert for AddDevice {
             vendor: None,
             last_seen: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -362,6 +392,9 @@ impl IntoInsert for AddNetwork {
             longitude,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -404,6 +437,9 @@ impl IntoInsert for AddAccount {
             phonenumber: None,
             profile_pic: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -426,6 +462,9 @@ impl IntoInsert for AddBreach {
         Ok(Insert::Breach(NewBreach {
             value: name,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -520,6 +559,9 @@ impl IntoInsert for AddImage {
                         phash: None,
 
                         unscoped: false,
+                        confidence: DEFAULT_CONFIDENCE,
+                        verified: false,
+                        extra: None,
                     }))?;
                 }
             }
@@ -556,6 +598,9 @@ impl IntoInsert for AddNetblock {
             as_org: None,
             description: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -601,6 +646,9 @@ impl IntoInsert for AddPort {
             description: None,
             reverse_dns: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }, true)? {
             Some((_, ip_addr_id)) => ip_addr_id,
             _ => bail!("IpAddr is out out of scope"),
@@ -621,6 +669,9 @@ impl IntoInsert for AddPort {
             version: None,
 
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         }))
     }
 }
@@ -647,6 +698,9 @@ impl IntoInsert for AddCryptoAddr {
             first_seen: None,
             last_withdrawal: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
             description: None,
         }))
     }
@@ -0,0 +1,84 @@
+use crate::errors::*;
+
+use crate::cmd::Cmd;
+use crate::db::merge;
+use crate::shell::Shell;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+use crate::models::*;
+use crate::term;
+
+
+/// Entity types that have normalization rules
+pub const FAMILIES: &[&str] = &[
+    "domains",
+    "subdomains",
+    "ipaddrs",
+    "urls",
+    "emails",
+    "phonenumbers",
+    "accounts",
+    "netblocks",
+];
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub struct Args {
+    /// Only look for duplicates of this type, eg. emails
+    family: Option<String>,
+    /// Merge the duplicates instead of only listing them
+    #[structopt(long="apply")]
+    apply: bool,
+}
+
+impl Cmd for Args {
+    fn run(self, rl: &mut Shell) -> Result<()> {
+        let families = match &self.family {
+            Some(family) => vec![family.as_str()],
+            None => FAMILIES.to_vec(),
+        };
+
+        let mut n = 0;
+        for family in families {
+            n += match family {
+                "domains" => dedupe::<Domain>(rl, family, self.apply),
+                "subdomains" => dedupe::<Subdomain>(rl, family, self.apply),
+                "ipaddrs" => dedupe::<IpAddr>(rl, family, self.apply),
+                "urls" => dedupe::<Url>(rl, family, self.apply),
+                "emails" => dedupe::<Email>(rl, family, self.apply),
+                "phonenumbers" => dedupe::<PhoneNumber>(rl, family, self.apply),
+                "accounts" => dedupe::<Account>(rl, family, self.apply),
+                "netblocks" => dedupe::<Netblock>(rl, family, self.apply),
+                x => bail!("No deduplication rules for entity type: {:?}", x),
+            }?;
+        }
+
+        if self.apply {
+            term::info(&format!("Merged {} duplicates", n));
+        } else if n > 0 {
+            term::info(&format!("Found {} duplicates, run with --apply to merge them", n));
+        } else {
+            term::info("No duplicates found");
+        }
+        Ok(())
+    }
+}
+
+fn dedupe<T: Model>(rl: &mut Shell, family: &str, apply: bool) -> Result<usize> {
+    let duplicates = merge::duplicates::<T>(rl.db())?;
+
+    for duplicate in &duplicates {
+        if apply {
+            merge::merge::<T>(rl.db(), duplicate.from, duplicate.into)?;
+        }
+
+        println!("merge {} {} {}\t\x1b[2m# {:?} -> {:?}\x1b[0m",
+            family,
+            duplicate.from,
+            duplicate.into,
+            duplicate.from_value,
+            duplicate.into_value);
+    }
+
+    Ok(duplicates.len())
+}
@@ -1,6 +1,7 @@
 use crate::errors::*;
 
 use crate::cmd::Cmd;
+use crate::db::trash::Trash;
 use crate::filters::{Target, Filter};
 use crate::shell::Shell;
 use structopt::StructOpt;
@@ -33,8 +34,15 @@ impl Cmd for Args {
             Target::Ports(filter) => delete::<Port>(rl, &filter),
             Target::Netblocks(filter) => delete::<Netblock>(rl, &filter),
             Target::CryptoAddrs(filter) => delete::<CryptoAddr>(rl, &filter),
+            Target::DnsRecords(filter) => delete::<DnsRecord>(rl, &filter),
+            Target::Certificates(filter) => delete::<Certificate>(rl, &filter),
+            Target::Organizations(filter) => delete::<Organization>(rl, &filter),
+            Target::Persons(filter) => delete::<Person>(rl, &filter),
+            Target::AutonomousSystems(filter) => delete::<AutonomousSystem>(rl, &filter),
+            Target::Documents(filter) => delete::<Document>(rl, &filter),
+            Target::Locations(filter) => delete::<Location>(rl, &filter),
         }?;
-        term::info(&format!("Deleted {} rows", rows));
+        term::info(&format!("Moved {} rows to trash", rows));
         Ok(())
     }
 }
@@ -46,5 +54,5 @@ pub fn run(rl: &mut Shell, args: &[String]) -> Result<()> {
 
 #[inline]
 fn delete<T: Model + Detailed>(rl: &mut Shell, filter: &Filter) -> Result<usize> {
-    T::delete(rl.db(), &filter.parse()?)
+    Trash::create::<T>(rl.db(), &filter.parse()?.for_model::<T>(rl.db().db().backend()), &filter.to_string())
 }
@@ -64,21 +64,46 @@ trait ExportFormat {
 #[derive(Serialize, Deserialize)]
 struct JsonFormat {
     accounts: Vec<Account>,
+    account_locations: Vec<AccountLocation>,
+    autonomous_systems: Vec<AutonomousSystem>,
     breaches: Vec<Breach>,
     breach_emails: Vec<BreachEmail>,
+    certificates: Vec<Certificate>,
     devices: Vec<Device>,
+    dns_records: Vec<DnsRecord>,
+    documents: Vec<Document>,
     domains: Vec<Domain>,
+    domain_locations: Vec<DomainLocation>,
     emails: Vec<Email>,
     images: Vec<Image>,
     ipaddrs: Vec<IpAddr>,
+    locations: Vec<Location>,
     netblocks: Vec<Netblock>,
     networks: Vec<Netblock>,
     network_devices: Vec<NetworkDevice>,
+    notes: Vec<Note>,
+    organizations: Vec<Organization>,
+    organization_accounts: Vec<OrganizationAccount>,
+    organization_domains: Vec<OrganizationDomain>,
+    organization_emails: Vec<OrganizationEmail>,
+    organization_netblocks: Vec<OrganizationNetblock>,
+    organization_phonenumbers: Vec<OrganizationPhoneNumber>,
+    persons: Vec<Person>,
+    person_accounts: Vec<PersonAccount>,
+    person_domains: Vec<PersonDomain>,
+    person_emails: Vec<PersonEmail>,
+    person_netblocks: Vec<PersonNetblock>,
+    person_phonenumbers: Vec<PersonPhoneNumber>,
     phonenumbers: Vec<PhoneNumber>,
+    phonenumber_locations: Vec<PhoneNumberLocation>,
     ports: Vec<Port>,
+    port_certificates: Vec<PortCertificate>,
     subdomains: Vec<Subdomain>,
+    subdomain_certificates: Vec<SubdomainCertificate>,
     subdomain_ipaddrs: Vec<SubdomainIpAddr>,
+    tags: Vec<Tag>,
     urls: Vec<Url>,
+    url_documents: Vec<UrlDocument>,
 }
 
 impl ExportFormat for JsonFormat {
@@ -87,21 +112,46 @@ impl ExportFormat for JsonFormat {
 
         Ok(Box::new(JsonFormat {
             accounts: Account::list(db)?,
+            account_locations: AccountLocation::list(db)?,
+            autonomous_systems: AutonomousSystem::list(db)?,
             breaches: Breach::list(db)?,
             breach_emails: BreachEmail::list(db)?,
+            certificates: Certificate::list(db)?,
             devices: Device::list(db)?,
+            dns_records: DnsRecord::list(db)?,
+            documents: Document::list(db)?,
             domains: Domain::list(db)?,
+            domain_locations: DomainLocation::list(db)?,
             emails: Email::list(db)?,
             images: Image::list(db)?,
             ipaddrs: IpAddr::list(db)?,
+            locations: Location::list(db)?,
             netblocks: Netblock::list(db)?,
             networks: Netblock::list(db)?,
             network_devices: NetworkDevice::list(db)?,
+            notes: Note::list(db)?,
+            organizations: Organization::list(db)?,
+            organization_accounts: OrganizationAccount::list(db)?,
+            organization_domains: OrganizationDomain::list(db)?,
+            organization_emails: OrganizationEmail::list(db)?,
+            organization_netblocks: OrganizationNetblock::list(db)?,
+            organization_phonenumbers: OrganizationPhoneNumber::list(db)?,
+            persons: Person::list(db)?,
+            person_accounts: PersonAccount::list(db)?,
+            person_domains: PersonDomain::list(db)?,
+            person_emails: PersonEmail::list(db)?,
+            person_netblocks: PersonNetblock::list(db)?,
+            person_phonenumbers: PersonPhoneNumber::list(db)?,
             phonenumbers: PhoneNumber::list(db)?,
+            phonenumber_locations: PhoneNumberLocation::list(db)?,
             ports: Port::list(db)?,
+            port_certificates: PortCertificate::list(db)?,
             subdomains: Subdomain::list(db)?,
+            subdomain_certificates: SubdomainCertificate::list(db)?,
             subdomain_ipaddrs: SubdomainIpAddr::list(db)?,
+            tags: Tag::list(db)?,
             urls: Url::list(db)?,
+            url_documents: UrlDocument::list(db)?,
         }))
     }
 }
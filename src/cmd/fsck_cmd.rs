@@ -2,6 +2,8 @@ use crate::errors::*;
 
 use crate::blobs::Blob;
 use crate::cmd::Cmd;
+use crate::db::{Database, Table};
+use crate::db::trash::{Trash, TrashRow};
 use crate::shell::Shell;
 use crate::term;
 use crate::worker;
@@ -15,10 +17,10 @@ pub struct Args {
     /// Verbose output
     #[structopt(short="v", long="verbose", parse(from_occurrences))]
     verbose: u64,
-    /// Delete only dangling blobs
+    /// Delete only dangling blobs and purge expired trash
     #[structopt(long="gc")]
     gc: bool,
-    /// Delete dangling and corrupted blobs
+    /// Delete dangling and corrupted blobs and purge expired trash
     #[structopt(long="gc-all")]
     gc_all: bool,
 }
@@ -27,11 +29,32 @@ impl Cmd for Args {
     fn run(self, rl: &mut Shell) -> Result<()> {
         let blobs = rl.blobs();
 
+        let expire = Trash::expire_date(rl.config().core.trash_retention);
+        if self.gc || self.gc_all {
+            let n = Trash::purge(rl.db(), Some(expire))?;
+            term::info(&format!("Permanently removed {} deletes from trash", n));
+        } else {
+            let n = Trash::list(rl.db())?.into_iter()
+                .filter(|x| x.time < expire)
+                .count();
+            if n > 0 {
+                term::warn(&format!("{} deletes in trash are expired", n));
+            }
+        }
+
+        let n = IpAddr::link_all_netblocks(rl.db())?;
+        if n > 0 {
+            term::info(&format!("Linked {} ipaddrs to their netblock", n));
+        }
+
         let hashset = worker::spawn_fn("Building reference set...", || {
-            let mut hashset = HashSet::new();
+            let mut hashset = blob_references(rl.db())?;
 
-            for x in rl.db().list::<Image>()? {
-                hashset.insert(x.value);
+            // blobs of deleted entities are kept until the trash is purged
+            for table in &[Table::Images, Table::Documents] {
+                for value in TrashRow::list_values(rl.db(), table)? {
+                    hashset.insert(value);
+                }
             }
 
             Ok(hashset)
@@ -81,6 +104,21 @@ impl Cmd for Args {
     }
 }
 
+/// Collect the blobs that are referenced by images and documents
+fn blob_references(db: &Database) -> Result<HashSet<String>> {
+    let mut hashset = HashSet::new();
+
+    for x in db.list::<Image>()? {
+        hashset.insert(x.value);
+    }
+
+    for x in db.list::<Document>()? {
+        hashset.insert(x.value);
+    }
+
+    Ok(hashset)
+}
+
 enum State {
     Valid,
     Dangling,
@@ -14,17 +14,24 @@ pub fn run(_rl: &mut Shell, _args: &[String]) -> Result<()> {
     help("add",         "Add new entities to the database");
     help("autonoscope", "Manage rules to automatically remove entities from scope");
     help("autoscope",   "Manage rules to automatically add entities to scope");
-    help("delete",      "Delete entities from the database");
+    help("dedupe",      "Find and merge entities that only differ in spelling");
+    help("delete",      "Move entities from the database to the trash");
     help("keyring",     "Manage saved credentials");
+    help("merge",       "Merge an entity into another entity of the same type");
     help("mod",         "Manage installed modules");
     help("noscope",     "Exclude entities from scope");
+    help("note",        "Attach a note to entities");
     help("quickstart",  "Install all featured modules");
     help("run",         "Run the currently selected module");
     help("scope",       "Include entities in the scope again");
+    help("search",      "Search all entities for words");
     help("select",      "Select entities from the database");
+    help("tag",         "Add a tag to entities");
     help("target",      "Preview targeted entities or narrow them down");
+    help("trash",       "Restore or permanently remove deleted entities");
+    help("untag",       "Remove a tag from entities");
     help("use",         "Select a module");
-    help("workspace",   "Switch to a different workspace");
+    help("workspace",   "Manage workspaces");
     help("help",        "Prints this message");
     println!("\nRun <command> -h for more help.\n");
 
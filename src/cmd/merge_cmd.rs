@@ -0,0 +1,53 @@
+use crate::errors::*;
+
+use crate::cmd::Cmd;
+use crate::db::merge;
+use crate::shell::Shell;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+use crate::models::*;
+use crate::term;
+
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub struct Args {
+    /// The type of the entities, eg. emails
+    family: String,
+    /// The entity that is merged and removed afterwards
+    from: i32,
+    /// The entity that is kept
+    into: i32,
+}
+
+impl Cmd for Args {
+    fn run(self, rl: &mut Shell) -> Result<()> {
+        let db = rl.db();
+        match self.family.as_str() {
+            "domains" => merge::merge::<Domain>(db, self.from, self.into),
+            "subdomains" => merge::merge::<Subdomain>(db, self.from, self.into),
+            "ipaddrs" => merge::merge::<IpAddr>(db, self.from, self.into),
+            "urls" => merge::merge::<Url>(db, self.from, self.into),
+            "emails" => merge::merge::<Email>(db, self.from, self.into),
+            "phonenumbers" => merge::merge::<PhoneNumber>(db, self.from, self.into),
+            "devices" => merge::merge::<Device>(db, self.from, self.into),
+            "networks" => merge::merge::<Network>(db, self.from, self.into),
+            "accounts" => merge::merge::<Account>(db, self.from, self.into),
+            "breaches" => merge::merge::<Breach>(db, self.from, self.into),
+            "images" => merge::merge::<Image>(db, self.from, self.into),
+            "ports" => merge::merge::<Port>(db, self.from, self.into),
+            "netblocks" => merge::merge::<Netblock>(db, self.from, self.into),
+            "cryptoaddrs" => merge::merge::<CryptoAddr>(db, self.from, self.into),
+            "dns-records" => merge::merge::<DnsRecord>(db, self.from, self.into),
+            "certificates" => merge::merge::<Certificate>(db, self.from, self.into),
+            "organizations" => merge::merge::<Organization>(db, self.from, self.into),
+            "persons" => merge::merge::<Person>(db, self.from, self.into),
+            "autonomous-systems" => merge::merge::<AutonomousSystem>(db, self.from, self.into),
+            "documents" => merge::merge::<Document>(db, self.from, self.into),
+            "locations" => merge::merge::<Location>(db, self.from, self.into),
+            x => bail!("Unknown entity type: {:?}", x),
+        }?;
+        term::info(&format!("Merged #{} into #{}", self.from, self.into));
+        Ok(())
+    }
+}
@@ -20,18 +20,25 @@ pub mod activity_cmd;
 pub mod add_cmd;
 pub mod autonoscope_cmd;
 pub mod autoscope_cmd;
+pub mod dedupe_cmd;
 pub mod delete_cmd;
 pub mod export_cmd;
 pub mod fsck_cmd;
 pub mod help_cmd;
+pub mod merge_cmd;
 pub mod run_cmd;
 pub mod use_cmd;
+pub mod search_cmd;
 pub mod select_cmd;
 pub mod keyring_cmd;
 pub mod noscope_cmd;
+pub mod note_cmd;
 pub mod pkg_cmd;
 pub mod set_cmd;
 pub mod scope_cmd;
+pub mod tag_cmd;
 pub mod target_cmd;
+pub mod trash_cmd;
 pub mod quickstart_cmd;
+pub mod untag_cmd;
 pub mod workspace_cmd;
@@ -33,6 +33,13 @@ impl Cmd for Args {
             Target::Ports(filter) => noscope::<Port>(rl, &filter),
             Target::Netblocks(filter) => noscope::<Netblock>(rl, &filter),
             Target::CryptoAddrs(filter) => noscope::<CryptoAddr>(rl, &filter),
+            Target::DnsRecords(filter) => noscope::<DnsRecord>(rl, &filter),
+            Target::Certificates(filter) => noscope::<Certificate>(rl, &filter),
+            Target::Organizations(filter) => noscope::<Organization>(rl, &filter),
+            Target::Persons(filter) => noscope::<Person>(rl, &filter),
+            Target::AutonomousSystems(filter) => noscope::<AutonomousSystem>(rl, &filter),
+            Target::Documents(filter) => noscope::<Document>(rl, &filter),
+            Target::Locations(filter) => noscope::<Location>(rl, &filter),
         }?;
         term::info(&format!("Updated {} rows", rows));
         Ok(())
@@ -46,5 +53,5 @@ pub fn run(rl: &mut Shell, args: &[String]) -> Result<()> {
 
 #[inline]
 fn noscope<T: Model + Detailed>(rl: &mut Shell, filter: &Filter) -> Result<usize> {
-    T::noscope(rl.db(), &filter.parse()?)
+    T::noscope(rl.db(), &filter.parse()?.for_model::<T>(rl.db().db().backend()))
 }
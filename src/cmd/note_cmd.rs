@@ -0,0 +1,80 @@
+use crate::errors::*;
+
+use chrono::Utc;
+use crate::cmd::Cmd;
+use crate::filters::{Target, Filter};
+use crate::shell::Shell;
+use std::env;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+use crate::models::*;
+use crate::term;
+
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub struct Args {
+    /// The author of the note, defaults to $USER
+    #[structopt(short="a", long="author")]
+    author: Option<String>,
+    /// The text of the note
+    content: String,
+    #[structopt(subcommand)]
+    subcommand: Target,
+}
+
+impl Cmd for Args {
+    fn run(self, rl: &mut Shell) -> Result<()> {
+        let author = match self.author {
+            Some(author) => author,
+            None => env::var("USER")
+                .unwrap_or_else(|_| String::from("anonymous")),
+        };
+
+        let rows = match self.subcommand {
+            Target::Domains(filter) => note::<Domain>(rl, &author, &self.content, &filter),
+            Target::Subdomains(filter) => note::<Subdomain>(rl, &author, &self.content, &filter),
+            Target::IpAddrs(filter) => note::<IpAddr>(rl, &author, &self.content, &filter),
+            Target::Urls(filter) => note::<Url>(rl, &author, &self.content, &filter),
+            Target::Emails(filter) => note::<Email>(rl, &author, &self.content, &filter),
+            Target::PhoneNumbers(filter) => note::<PhoneNumber>(rl, &author, &self.content, &filter),
+            Target::Devices(filter) => note::<Device>(rl, &author, &self.content, &filter),
+            Target::Networks(filter) => note::<Network>(rl, &author, &self.content, &filter),
+            Target::Accounts(filter) => note::<Account>(rl, &author, &self.content, &filter),
+            Target::Breaches(filter) => note::<Breach>(rl, &author, &self.content, &filter),
+            Target::Images(filter) => note::<Image>(rl, &author, &self.content, &filter),
+            Target::Ports(filter) => note::<Port>(rl, &author, &self.content, &filter),
+            Target::Netblocks(filter) => note::<Netblock>(rl, &author, &self.content, &filter),
+            Target::CryptoAddrs(filter) => note::<CryptoAddr>(rl, &author, &self.content, &filter),
+            Target::DnsRecords(filter) => note::<DnsRecord>(rl, &author, &self.content, &filter),
+            Target::Certificates(filter) => note::<Certificate>(rl, &author, &self.content, &filter),
+            Target::Organizations(filter) => note::<Organization>(rl, &author, &self.content, &filter),
+            Target::Persons(filter) => note::<Person>(rl, &author, &self.content, &filter),
+            Target::AutonomousSystems(filter) => note::<AutonomousSystem>(rl, &author, &self.content, &filter),
+            Target::Documents(filter) => note::<Document>(rl, &author, &self.content, &filter),
+            Target::Locations(filter) => note::<Location>(rl, &author, &self.content, &filter),
+        }?;
+        term::info(&format!("Added note to {} rows", rows));
+        Ok(())
+    }
+}
+
+fn note<T: Model>(rl: &mut Shell, author: &str, content: &str, filter: &Filter) -> Result<usize> {
+    let family = T::family()
+        .ok_or_else(|| format_err!("Notes can't be attached to this entity"))?;
+
+    let db = rl.db();
+    let time = Utc::now().naive_utc();
+    let mut rows = 0;
+    for obj in db.filter::<T>(&filter.parse()?)? {
+        NewNote {
+            family: family.as_str(),
+            entity_id: obj.id(),
+            author,
+            time,
+            content,
+        }.insert(db)?;
+        rows += 1;
+    }
+    Ok(rows)
+}
@@ -3,7 +3,9 @@ use crate::errors::*;
 use chrootable_https::dns::Resolver;
 use crate::args;
 use crate::blobs::{Blob, BlobStorage};
+use crate::cookies::CookieStorage;
 use crate::cmd::Cmd;
+use crate::config::Config;
 use crate::db::{ttl, Filter};
 use crate::engine::Module;
 use crate::ipc::common::StartCommand;
@@ -24,15 +26,16 @@ use structopt::clap::AppSettings;
 #[derive(Debug, StructOpt)]
 #[structopt(global_settings = &[AppSettings::ColoredHelp])]
 pub struct Args {
-    #[structopt(short="j", long="threads", default_value="1")]
-    threads: usize,
+    /// Run modules concurrently, defaults to the threads setting or 1
+    #[structopt(short="j", long="threads")]
+    threads: Option<usize>,
     #[structopt(short="v", long="verbose", parse(from_occurrences))]
     verbose: u64,
 }
 
 #[derive(Debug, Clone)]
 pub struct Params<'a> {
-    pub threads: usize,
+    pub threads: Option<usize>,
     pub verbose: u64,
     pub stdin: bool,
     pub grants: &'a [String],
@@ -41,6 +44,15 @@ pub struct Params<'a> {
     pub exit_on_error: bool,
 }
 
+impl<'a> Params<'a> {
+    #[inline]
+    pub fn threads(&self, config: &Config) -> usize {
+        self.threads
+            .or(config.run.threads)
+            .unwrap_or(1)
+    }
+}
+
 impl<'a> From<&'a args::Run> for Params<'a> {
     fn from(args: &args::Run) -> Params {
         Params {
@@ -92,11 +104,11 @@ fn prepare_args<T: Scopable + Serialize + Model>(rl: &Shell, filter: &Filter, pa
         .collect()
 }
 
-fn prepare_keyring(keyring: &mut KeyRing, module: &Module, params: &Params) -> Result<()> {
+fn prepare_keyring(keyring: &mut KeyRing, module: &Module, params: &Params, config: &Config) -> Result<()> {
     for namespace in keyring.unauthorized_namespaces(&module) {
         let grant_access = if params.deny_keyring {
             false
-        } else if params.grant_full_keyring || params.grants.contains(namespace) {
+        } else if params.grant_full_keyring || params.grants.contains(namespace) || config.run.grants.contains(namespace) {
             true
         } else {
             let msg = format!("Grant access to {:?} credentials?", namespace);
@@ -115,6 +127,13 @@ fn prepare_keyring(keyring: &mut KeyRing, module: &Module, params: &Params) -> R
     Ok(())
 }
 
+/// Options that are set explicitly take precedence over the configured defaults
+fn layer_options(config: &Config, options: HashMap<String, String>) -> HashMap<String, String> {
+    let mut layered = config.options.clone();
+    layered.extend(options);
+    layered
+}
+
 fn get_args(rl: &mut Shell, module: &Module) -> Result<Vec<(serde_json::Value, Option<String>, Vec<Blob>)>> {
     let filter = rl.scoped_targets();
 
@@ -133,6 +152,13 @@ fn get_args(rl: &mut Shell, module: &Module) -> Result<Vec<(serde_json::Value, O
         Some(Source::Ports) => prepare_args::<Port>(rl, &filter, None),
         Some(Source::Netblocks) => prepare_args::<Netblock>(rl, &filter, None),
         Some(Source::CryptoAddrs(currency)) => prepare_args::<CryptoAddr>(rl, &filter, currency.as_ref()),
+        Some(Source::DnsRecords(record_type)) => prepare_args::<DnsRecord>(rl, &filter, record_type.as_ref()),
+        Some(Source::Certificates) => prepare_args::<Certificate>(rl, &filter, None),
+        Some(Source::Organizations) => prepare_args::<Organization>(rl, &filter, None),
+        Some(Source::Persons) => prepare_args::<Person>(rl, &filter, None),
+        Some(Source::AutonomousSystems) => prepare_args::<AutonomousSystem>(rl, &filter, None),
+        Some(Source::Documents) => prepare_args::<Document>(rl, &filter, None),
+        Some(Source::Locations) => prepare_args::<Location>(rl, &filter, None),
         Some(Source::KeyRing(namespace)) => {
             let keyring = rl.keyring();
             if keyring.is_access_granted(&module, &namespace) {
@@ -156,22 +182,27 @@ pub fn dump_sandbox_init_msg(rl: &mut Shell, params: Params, options: HashMap<St
         .map(|m| m.to_owned())
         .ok_or_else(|| format_err!("No module selected"))?;
 
-    prepare_keyring(rl.keyring_mut(), &module, &params)?;
+    let config = rl.config().clone();
+    prepare_keyring(rl.keyring_mut(), &module, &params, &config)?;
     let keyring = rl.keyring().request_keys(&module);
+    let options = layer_options(&config, options);
 
     let dns_config = Resolver::from_system()?;
-    let proxy = rl.config().network.proxy.clone();
+    let proxy = config.network.proxy_pool();
+    let cookie_jars = CookieStorage::workspace(rl.db().workspace())?
+        .load_all(&module.id())?;
 
     let args = get_args(rl, &module)?;
     for (arg, _pretty_arg, blobs) in args {
         let start_cmd = StartCommand::new(params.verbose,
                                           keyring.clone(),
                                           dns_config.clone(),
-                                          proxy.clone(),
+                                          proxy.for_sandbox(),
                                           options.clone(),
                                           module.clone(),
                                           arg,
-                                          blobs);
+                                          blobs,
+                                          cookie_jars.clone());
         let out = serde_json::to_string(&start_cmd)?;
         println!("{}", out);
     }
@@ -184,11 +215,15 @@ pub fn execute(rl: &mut Shell, params: Params, options: HashMap<String, String>)
         .map(|m| m.to_owned())
         .ok_or_else(|| format_err!("No module selected"))?;
 
-    prepare_keyring(rl.keyring_mut(), &module, &params)?;
+    let config = rl.config().clone();
+    prepare_keyring(rl.keyring_mut(), &module, &params, &config)?;
     let args = get_args(rl, &module)?;
+    let options = layer_options(&config, options);
 
     rl.signal_register().catch_ctrl();
-    let errors = worker::spawn(rl, &module, args, &params, rl.config().network.proxy.clone(), options);
+    let proxy = config.network.proxy_pool();
+    let cookies = CookieStorage::workspace(rl.db().workspace())?;
+    let errors = worker::spawn(rl, &module, args, &params, &proxy, &cookies, options);
     rl.signal_register().reset_ctrlc();
 
     if errors > 0 {
@@ -33,6 +33,13 @@ impl Cmd for Args {
             Target::Ports(filter) => scope::<Port>(rl, &filter),
             Target::Netblocks(filter) => scope::<Netblock>(rl, &filter),
             Target::CryptoAddrs(filter) => scope::<CryptoAddr>(rl, &filter),
+            Target::DnsRecords(filter) => scope::<DnsRecord>(rl, &filter),
+            Target::Certificates(filter) => scope::<Certificate>(rl, &filter),
+            Target::Organizations(filter) => scope::<Organization>(rl, &filter),
+            Target::Persons(filter) => scope::<Person>(rl, &filter),
+            Target::AutonomousSystems(filter) => scope::<AutonomousSystem>(rl, &filter),
+            Target::Documents(filter) => scope::<Document>(rl, &filter),
+            Target::Locations(filter) => scope::<Location>(rl, &filter),
         }?;
         term::info(&format!("Updated {} rows", rows));
         Ok(())
@@ -46,5 +53,5 @@ pub fn run(rl: &mut Shell, args: &[String]) -> Result<()> {
 
 #[inline]
 fn scope<T: Model + Detailed>(rl: &mut Shell, filter: &Filter) -> Result<usize> {
-    T::scope(rl.db(), &filter.parse()?)
+    T::scope(rl.db(), &filter.parse()?.for_model::<T>(rl.db().db().backend()))
 }
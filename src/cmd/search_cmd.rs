@@ -0,0 +1,67 @@
+use crate::errors::*;
+
+use crate::cmd::Cmd;
+use crate::db::Database;
+use crate::db::search::{self, SearchResult};
+use crate::shell::Shell;
+use std::fmt;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+use crate::models::*;
+
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub struct Args {
+    /// Maximum number of results
+    #[structopt(short="l", long="limit", default_value="50")]
+    limit: i64,
+    /// Words that need to be part of the entity, eg. acme
+    #[structopt(required=true)]
+    query: Vec<String>,
+}
+
+impl Cmd for Args {
+    fn run(self, rl: &mut Shell) -> Result<()> {
+        let db = rl.db();
+        for result in search::search(db, &self.query.join(" "), self.limit)? {
+            match result.family.as_str() {
+                "domains" => print::<Domain, _>(db, &result),
+                "subdomains" => print::<Subdomain, _>(db, &result),
+                "ipaddrs" => print::<IpAddr, _>(db, &result),
+                "urls" => print::<Url, _>(db, &result),
+                "emails" => print::<Email, _>(db, &result),
+                "phonenumbers" => print::<PhoneNumber, _>(db, &result),
+                "devices" => print::<Device, _>(db, &result),
+                "networks" => print::<Network, _>(db, &result),
+                "accounts" => print::<Account, _>(db, &result),
+                "breaches" => print::<Breach, _>(db, &result),
+                "images" => print::<Image, _>(db, &result),
+                "ports" => print::<Port, _>(db, &result),
+                "netblocks" => print::<Netblock, _>(db, &result),
+                "cryptoaddrs" => print::<CryptoAddr, _>(db, &result),
+                "dns-records" => print::<DnsRecord, _>(db, &result),
+                "certificates" => print::<Certificate, _>(db, &result),
+                "organizations" => print::<Organization, _>(db, &result),
+                "persons" => print::<Person, _>(db, &result),
+                "autonomous-systems" => print::<AutonomousSystem, _>(db, &result),
+                "documents" => print::<Document, _>(db, &result),
+                "locations" => print::<Location, _>(db, &result),
+                x => bail!("Unknown entity type in search index: {:?}", x),
+            }?;
+        }
+        Ok(())
+    }
+}
+
+fn print<T, P>(db: &Database, result: &SearchResult) -> Result<()>
+    where T: Model + Printable<P>,
+          P: fmt::Display,
+{
+    let entity = T::by_id(db, result.entity_id)?;
+    println!("{} \x1b[32m#{}\x1b[0m, {}",
+        result.family,
+        result.entity_id,
+        entity.printable(db)?);
+    Ok(())
+}
@@ -9,6 +9,7 @@ use serde_json;
 use structopt::StructOpt;
 use structopt::clap::AppSettings;
 use crate::models::*;
+use sn0int_std::geo::Polygon;
 
 
 #[derive(Debug, StructOpt)]
@@ -22,6 +23,9 @@ pub struct Args {
     /// Print paths to blobs
     #[structopt(long="paths", group="output")]
     paths: bool,
+    /// Only select locations inside of this area, a list of lat,lon points
+    #[structopt(long="within")]
+    within: Option<Polygon>,
 }
 
 enum Output {
@@ -53,29 +57,45 @@ impl<'a, 'b> Printer<'a, 'b> {
 
     pub fn select<T: Model + Detailed + Serialize>(&self, filter: &Filter) -> Result<()> {
         for obj in self.rl.db().filter::<T>(&filter.parse_optional()?)? {
-            match self.output {
-                Output::Normal => println!("{}", obj.detailed(self.rl.db())?),
-                Output::Json => {
-                    let v = serde_json::to_string(&obj)?;
-                    println!("{}", v);
-                },
-                Output::Paths => {
-                    let blob = obj.blob()
-                        .ok_or_else(|| format_err!("This model isn't linked to blob storage"))?;
-
-                    let path = self.rl.blobs()
-                        .join(blob)?;
-
-                    let path = path.to_str()
-                        .ok_or_else(|| format_err!("Path is invalid utf-8"))?;
-
-                    println!("{}", path);
-                },
+            self.print(&obj)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn select_within(&self, filter: &Filter, polygon: &Polygon) -> Result<()> {
+        for obj in self.rl.db().filter::<Location>(&filter.parse_optional()?)? {
+            if obj.within(polygon) {
+                self.print(&obj)?;
             }
         }
 
         Ok(())
     }
+
+    fn print<T: Model + Detailed + Serialize>(&self, obj: &T) -> Result<()> {
+        match self.output {
+            Output::Normal => println!("{}", obj.annotated(self.rl.db())?),
+            Output::Json => {
+                let v = serde_json::to_string(&obj)?;
+                println!("{}", v);
+            },
+            Output::Paths => {
+                let blob = obj.blob()
+                    .ok_or_else(|| format_err!("This model isn't linked to blob storage"))?;
+
+                let path = self.rl.blobs()
+                    .join(blob)?;
+
+                let path = path.to_str()
+                    .ok_or_else(|| format_err!("Path is invalid utf-8"))?;
+
+                println!("{}", path);
+            },
+        }
+
+        Ok(())
+    }
 }
 
 impl Cmd for Args {
@@ -83,6 +103,13 @@ impl Cmd for Args {
         ttl::reap_expired(rl.db())?;
         let printer = Printer::new(rl, &self);
 
+        if let Some(polygon) = &self.within {
+            return match &self.subcommand {
+                Target::Locations(filter) => printer.select_within(&filter, polygon),
+                _ => bail!("--within can only be used with locations"),
+            };
+        }
+
         match &self.subcommand {
             Target::Domains(filter) => printer.select::<Domain>(&filter),
             Target::Subdomains(filter) => printer.select::<Subdomain>(&filter),
@@ -98,6 +125,13 @@ impl Cmd for Args {
             Target::Ports(filter) => printer.select::<Port>(&filter),
             Target::Netblocks(filter) => printer.select::<Netblock>(&filter),
             Target::CryptoAddrs(filter) => printer.select::<CryptoAddr>(&filter),
+            Target::DnsRecords(filter) => printer.select::<DnsRecord>(&filter),
+            Target::Certificates(filter) => printer.select::<Certificate>(&filter),
+            Target::Organizations(filter) => printer.select::<Organization>(&filter),
+            Target::Persons(filter) => printer.select::<Person>(&filter),
+            Target::AutonomousSystems(filter) => printer.select::<AutonomousSystem>(&filter),
+            Target::Documents(filter) => printer.select::<Document>(&filter),
+            Target::Locations(filter) => printer.select::<Location>(&filter),
         }
     }
 }
@@ -0,0 +1,64 @@
+use crate::errors::*;
+
+use crate::cmd::Cmd;
+use crate::filters::{Target, Filter};
+use crate::shell::Shell;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+use crate::models::*;
+use crate::term;
+
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub struct Args {
+    /// The tag that should be added
+    tag: String,
+    #[structopt(subcommand)]
+    subcommand: Target,
+}
+
+impl Cmd for Args {
+    fn run(self, rl: &mut Shell) -> Result<()> {
+        Tag::validate(&self.tag)?;
+        let rows = match self.subcommand {
+            Target::Domains(filter) => tag::<Domain>(rl, &self.tag, &filter),
+            Target::Subdomains(filter) => tag::<Subdomain>(rl, &self.tag, &filter),
+            Target::IpAddrs(filter) => tag::<IpAddr>(rl, &self.tag, &filter),
+            Target::Urls(filter) => tag::<Url>(rl, &self.tag, &filter),
+            Target::Emails(filter) => tag::<Email>(rl, &self.tag, &filter),
+            Target::PhoneNumbers(filter) => tag::<PhoneNumber>(rl, &self.tag, &filter),
+            Target::Devices(filter) => tag::<Device>(rl, &self.tag, &filter),
+            Target::Networks(filter) => tag::<Network>(rl, &self.tag, &filter),
+            Target::Accounts(filter) => tag::<Account>(rl, &self.tag, &filter),
+            Target::Breaches(filter) => tag::<Breach>(rl, &self.tag, &filter),
+            Target::Images(filter) => tag::<Image>(rl, &self.tag, &filter),
+            Target::Ports(filter) => tag::<Port>(rl, &self.tag, &filter),
+            Target::Netblocks(filter) => tag::<Netblock>(rl, &self.tag, &filter),
+            Target::CryptoAddrs(filter) => tag::<CryptoAddr>(rl, &self.tag, &filter),
+            Target::DnsRecords(filter) => tag::<DnsRecord>(rl, &self.tag, &filter),
+            Target::Certificates(filter) => tag::<Certificate>(rl, &self.tag, &filter),
+            Target::Organizations(filter) => tag::<Organization>(rl, &self.tag, &filter),
+            Target::Persons(filter) => tag::<Person>(rl, &self.tag, &filter),
+            Target::AutonomousSystems(filter) => tag::<AutonomousSystem>(rl, &self.tag, &filter),
+            Target::Documents(filter) => tag::<Document>(rl, &self.tag, &filter),
+            Target::Locations(filter) => tag::<Location>(rl, &self.tag, &filter),
+        }?;
+        term::info(&format!("Tagged {} rows", rows));
+        Ok(())
+    }
+}
+
+fn tag<T: Model>(rl: &mut Shell, tag: &str, filter: &Filter) -> Result<usize> {
+    let family = T::family()
+        .ok_or_else(|| format_err!("This entity can't be tagged"))?;
+
+    let db = rl.db();
+    let mut rows = 0;
+    for obj in db.filter::<T>(&filter.parse()?)? {
+        if Tag::add(db, &family, obj.id(), tag)? {
+            rows += 1;
+        }
+    }
+    Ok(rows)
+}
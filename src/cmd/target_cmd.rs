@@ -44,6 +44,13 @@ pub fn run(rl: &mut Shell, args: &[String]) -> Result<()> {
             Source::Ports => select::<Port>(rl, None)?,
             Source::Netblocks => select::<Netblock>(rl, None)?,
             Source::CryptoAddrs(currency) => select::<CryptoAddr>(rl, currency.as_ref())?,
+            Source::DnsRecords(record_type) => select::<DnsRecord>(rl, record_type.as_ref())?,
+            Source::Certificates => select::<Certificate>(rl, None)?,
+            Source::Organizations => select::<Organization>(rl, None)?,
+            Source::Persons => select::<Person>(rl, None)?,
+            Source::AutonomousSystems => select::<AutonomousSystem>(rl, None)?,
+            Source::Documents => select::<Document>(rl, None)?,
+            Source::Locations => select::<Location>(rl, None)?,
             Source::KeyRing(namespace) => {
                 for key in rl.keyring().list_for(&namespace) {
                     println!("{}:{}", key.namespace, key.name);
@@ -79,6 +86,13 @@ fn count_selected(rl: &mut Shell, source: &Source) -> Result<usize> {
         Source::Ports => db.filter::<Port>(&filter)?.len(),
         Source::Netblocks => db.filter::<Netblock>(&filter)?.len(),
         Source::CryptoAddrs(currency) => db.filter_with_param::<CryptoAddr>(&filter, currency.as_ref())?.len(),
+        Source::DnsRecords(record_type) => db.filter_with_param::<DnsRecord>(&filter, record_type.as_ref())?.len(),
+        Source::Certificates => db.filter::<Certificate>(&filter)?.len(),
+        Source::Organizations => db.filter::<Organization>(&filter)?.len(),
+        Source::Persons => db.filter::<Person>(&filter)?.len(),
+        Source::AutonomousSystems => db.filter::<AutonomousSystem>(&filter)?.len(),
+        Source::Documents => db.filter::<Document>(&filter)?.len(),
+        Source::Locations => db.filter::<Location>(&filter)?.len(),
         Source::KeyRing(namespace) => rl.keyring().list_for(&namespace).len(),
     };
     Ok(num)
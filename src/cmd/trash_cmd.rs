@@ -0,0 +1,82 @@
+use crate::errors::*;
+
+use crate::db::trash::Trash;
+use crate::shell::Shell;
+use crate::term;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub enum Args {
+    #[structopt(name="list")]
+    /// List deleted entities that can be restored
+    List,
+    #[structopt(name="restore")]
+    /// Restore deleted entities, defaults to the last delete
+    Restore(TrashRestore),
+    #[structopt(name="purge")]
+    /// Permanently remove deleted entities after the retention period
+    Purge(TrashPurge),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TrashRestore {
+    id: Option<i32>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TrashPurge {
+    #[structopt(long="all")]
+    /// Remove everything, regardless of the retention period
+    all: bool,
+}
+
+pub fn run(rl: &mut Shell, args: &[String]) -> Result<()> {
+    let args = Args::from_iter_safe(args)?;
+    match args {
+        Args::List => trash_list(rl),
+        Args::Restore(restore) => trash_restore(rl, restore),
+        Args::Purge(purge) => trash_purge(rl, purge),
+    }
+}
+
+fn trash_list(rl: &mut Shell) -> Result<()> {
+    for trash in Trash::list(rl.db())? {
+        let rows = trash.rows(rl.db())?.len();
+        println!("\x1b[32m#{}\x1b[0m, {} - {:?} ({} rows)",
+            trash.id,
+            trash.time.format("%Y-%m-%d %H:%M:%S"),
+            trash.description,
+            rows);
+    }
+    Ok(())
+}
+
+fn trash_restore(rl: &mut Shell, restore: TrashRestore) -> Result<()> {
+    let trash = match restore.id {
+        Some(id) => Trash::by_id(rl.db(), id)?,
+        None => Trash::latest(rl.db())?
+            .ok_or_else(|| format_err!("Trash is empty"))?,
+    };
+
+    let restored = trash.restore(rl.db())?;
+    for (table, from, into) in &restored.merged {
+        term::info(&format!("{}#{} has been added again since it was deleted, merged into #{}", table, from, into));
+    }
+    term::info(&format!("Restored {} rows", restored.rows));
+    Ok(())
+}
+
+fn trash_purge(rl: &mut Shell, purge: TrashPurge) -> Result<()> {
+    let before = if purge.all {
+        None
+    } else {
+        Some(Trash::expire_date(rl.config().core.trash_retention))
+    };
+
+    let n = Trash::purge(rl.db(), before)?;
+    term::info(&format!("Permanently removed {} deletes from trash", n));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::errors::*;
+
+use crate::cmd::Cmd;
+use crate::filters::{Target, Filter};
+use crate::shell::Shell;
+use structopt::StructOpt;
+use structopt::clap::AppSettings;
+use crate::models::*;
+use crate::term;
+
+
+#[derive(Debug, StructOpt)]
+#[structopt(global_settings = &[AppSettings::ColoredHelp])]
+pub struct Args {
+    /// The tag that should be removed
+    tag: String,
+    #[structopt(subcommand)]
+    subcommand: Target,
+}
+
+impl Cmd for Args {
+    fn run(self, rl: &mut Shell) -> Result<()> {
+        Tag::validate(&self.tag)?;
+        let rows = match self.subcommand {
+            Target::Domains(filter) => untag::<Domain>(rl, &self.tag, &filter),
+            Target::Subdomains(filter) => untag::<Subdomain>(rl, &self.tag, &filter),
+            Target::IpAddrs(filter) => untag::<IpAddr>(rl, &self.tag, &filter),
+            Target::Urls(filter) => untag::<Url>(rl, &self.tag, &filter),
+            Target::Emails(filter) => untag::<Email>(rl, &self.tag, &filter),
+            Target::PhoneNumbers(filter) => untag::<PhoneNumber>(rl, &self.tag, &filter),
+            Target::Devices(filter) => untag::<Device>(rl, &self.tag, &filter),
+            Target::Networks(filter) => untag::<Network>(rl, &self.tag, &filter),
+            Target::Accounts(filter) => untag::<Account>(rl, &self.tag, &filter),
+            Target::Breaches(filter) => untag::<Breach>(rl, &self.tag, &filter),
+            Target::Images(filter) => untag::<Image>(rl, &self.tag, &filter),
+            Target::Ports(filter) => untag::<Port>(rl, &self.tag, &filter),
+            Target::Netblocks(filter) => untag::<Netblock>(rl, &self.tag, &filter),
+            Target::CryptoAddrs(filter) => untag::<CryptoAddr>(rl, &self.tag, &filter),
+            Target::DnsRecords(filter) => untag::<DnsRecord>(rl, &self.tag, &filter),
+            Target::Certificates(filter) => untag::<Certificate>(rl, &self.tag, &filter),
+            Target::Organizations(filter) => untag::<Organization>(rl, &self.tag, &filter),
+            Target::Persons(filter) => untag::<Person>(rl, &self.tag, &filter),
+            Target::AutonomousSystems(filter) => untag::<AutonomousSystem>(rl, &self.tag, &filter),
+            Target::Documents(filter) => untag::<Document>(rl, &self.tag, &filter),
+            Target::Locations(filter) => untag::<Location>(rl, &self.tag, &filter),
+        }?;
+        term::info(&format!("Untagged {} rows", rows));
+        Ok(())
+    }
+}
+
+fn untag<T: Model>(rl: &mut Shell, tag: &str, filter: &Filter) -> Result<usize> {
+    let family = T::family()
+        .ok_or_else(|| format_err!("This entity can't be tagged"))?;
+
+    let db = rl.db();
+    let mut rows = 0;
+    for obj in db.filter::<T>(&filter.parse()?)? {
+        if Tag::remove(db, &family, obj.id(), tag)? {
+            rows += 1;
+        }
+    }
+    Ok(rows)
+}
@@ -1,12 +1,15 @@
 use crate::errors::*;
 
+use crate::archive;
 use crate::blobs::BlobStorage;
 use crate::cmd::{Cmd, LiteCmd};
-use crate::config::Config;
+use crate::config::{Config, LocalConfig};
 use crate::db::Database;
+use crate::db::import;
 use crate::shell::Shell;
 use crate::term;
 use crate::utils;
+use std::path::PathBuf;
 use structopt::StructOpt;
 use structopt::clap::AppSettings;
 use crate::workspaces::{self, Workspace};
@@ -15,7 +18,7 @@ use crate::workspaces::{self, Workspace};
 #[derive(Debug, StructOpt)]
 #[structopt(global_settings = &[AppSettings::ColoredHelp])]
 pub struct Args {
-    /// Delete a workspaceb
+    /// Delete a workspace
     #[structopt(long = "delete", group = "action")]
     delete: bool,
     /// Show disk usage of workspace
@@ -24,7 +27,107 @@ pub struct Args {
     /// Skip confirmation
     #[structopt(short = "f", long = "force")]
     force: bool,
+    /// Switch to this workspace, use `workspace switch` if the name is also a subcommand
     workspace: Option<Workspace>,
+    #[structopt(subcommand)]
+    subcommand: Option<Subcommand>,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+    #[structopt(name="switch")]
+    /// Switch to a different workspace
+    Switch(WorkspaceSwitch),
+    #[structopt(name="list")]
+    /// List all workspaces
+    List,
+    #[structopt(name="delete")]
+    /// Delete a workspace
+    Delete(WorkspaceDelete),
+    #[structopt(name="usage")]
+    /// Show disk usage of workspaces
+    Usage(WorkspaceUsage),
+    #[structopt(name="merge")]
+    /// Copy everything from a workspace into another workspace
+    Merge(WorkspaceMerge),
+    #[structopt(name="clone")]
+    /// Copy a workspace into a new workspace
+    Clone(WorkspaceClone),
+    #[structopt(name="archive")]
+    /// Write a workspace into a compressed tarball
+    Archive(WorkspaceArchive),
+    #[structopt(name="restore")]
+    /// Restore a workspace from a tarball
+    Restore(WorkspaceRestore),
+    #[structopt(name="config")]
+    /// Show or change the settings of a workspace
+    Config(WorkspaceSettings),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceSwitch {
+    /// The workspace to switch to
+    workspace: Workspace,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceDelete {
+    /// The workspace that is deleted
+    workspace: Workspace,
+    /// Skip confirmation
+    #[structopt(short = "f", long = "force")]
+    force: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceUsage {
+    /// Only show the usage of this workspace
+    workspace: Option<Workspace>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceMerge {
+    /// The workspace that is copied
+    src: Workspace,
+    /// The workspace that everything is copied into
+    dst: Workspace,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceClone {
+    /// The workspace that is copied
+    src: Workspace,
+    /// The name of the new workspace
+    dst: Workspace,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceArchive {
+    /// The workspace that is archived
+    workspace: Workspace,
+    /// Path of the archive, defaults to <workspace>.tar.gz
+    #[structopt(short="o", long="output")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceRestore {
+    /// Path of the archive
+    path: PathBuf,
+    /// The name of the new workspace, defaults to the name of the archived workspace
+    workspace: Option<Workspace>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct WorkspaceSettings {
+    /// The workspace that is configured, defaults to the current workspace
+    workspace: Option<Workspace>,
+    /// Change a setting, eg. run.threads=8 or network.proxy=127.0.0.1:9050
+    #[structopt(long="set")]
+    set: Vec<String>,
+    /// Remove a setting so the global config is used again
+    #[structopt(long="unset")]
+    unset: Vec<String>,
 }
 
 fn delete(workspace: Workspace, force: bool) -> Result<()> {
@@ -52,13 +155,110 @@ fn usage(workspace: Option<Workspace>) -> Result<()> {
     Ok(())
 }
 
+fn ensure_exists(workspace: &Workspace, config: &Config) -> Result<()> {
+    if !workspace.exists()? && config.workspace_database(workspace).is_none() {
+        bail!("Workspace {:?} doesn't exist", workspace.as_str());
+    }
+    Ok(())
+}
+
+fn merge(args: WorkspaceMerge, config: &Config) -> Result<()> {
+    if args.src == args.dst {
+        bail!("Can't merge a workspace into itself");
+    }
+    ensure_exists(&args.src, config)?;
+
+    let src = Database::establish(args.src, config)?;
+    let dst = Database::establish(args.dst, config)?;
+    let report = import::import(&src, &dst)?;
+
+    for conflict in &report.conflicts {
+        term::warn(&conflict.to_string());
+    }
+
+    for table in &report.tables {
+        if table.inserted + table.existing + table.skipped > 0 {
+            println!("{:30} {} new, {} existing, {} skipped",
+                table.table, table.inserted, table.existing, table.skipped);
+        }
+    }
+
+    let inserted = report.tables.iter().map(|table| table.inserted).sum::<usize>();
+    let existing = report.tables.iter().map(|table| table.existing).sum::<usize>();
+    term::success(&format!("Merged {:?} into {:?}: {} new rows, {} existing rows, {} conflicts, {} blobs",
+        src.name(), dst.name(), inserted, existing, report.conflicts.len(), report.blobs));
+
+    Ok(())
+}
+
+fn clone(args: WorkspaceClone, config: &Config) -> Result<()> {
+    ensure_exists(&args.src, config)?;
+
+    let db = Database::establish(args.src, config)?;
+    let blobs = archive::clone(&db, &args.dst, config)?;
+    term::success(&format!("Cloned {:?} into {:?} with {} blobs",
+        db.name(), args.dst.as_str(), blobs));
+
+    Ok(())
+}
+
+fn archive(args: WorkspaceArchive, config: &Config) -> Result<()> {
+    ensure_exists(&args.workspace, config)?;
+
+    let path = match args.output {
+        Some(path) => path,
+        None => PathBuf::from(format!("{}.tar.gz", args.workspace.as_str())),
+    };
+    let db = Database::establish(args.workspace, config)?;
+    let manifest = archive::archive(&db, &path)?;
+    term::success(&format!("Archived {:?} with {} blobs to {:?}",
+        db.name(), manifest.blobs.len(), path));
+
+    Ok(())
+}
+
+fn restore(args: WorkspaceRestore, config: &Config) -> Result<()> {
+    let (workspace, manifest) = archive::restore(&args.path, args.workspace, config)?;
+    term::success(&format!("Restored {:?} with {} verified blobs from archive created at {}",
+        workspace.as_str(), manifest.blobs.len(), manifest.created.format("%Y-%m-%d %H:%M:%S")));
+
+    Ok(())
+}
+
+fn settings(args: WorkspaceSettings, workspace: Workspace, config: &Config) -> Result<()> {
+    ensure_exists(&workspace, config)?;
+
+    for set in &args.set {
+        let idx = set.find('=')
+            .ok_or_else(|| format_err!("Setting is missing a value: {:?}", set))?;
+        let (key, value) = set.split_at(idx);
+        LocalConfig::set(&workspace, key, &value[1..])?;
+    }
+
+    for key in &args.unset {
+        LocalConfig::unset(&workspace, key)?;
+    }
+
+    if args.set.is_empty() && args.unset.is_empty() {
+        let settings = config.for_workspace(&workspace)?
+            .workspace_settings();
+        print!("{}", toml::to_string_pretty(&settings)?);
+    } else {
+        term::success(&format!("Updated {:?}", LocalConfig::path(&workspace)?));
+    }
+
+    Ok(())
+}
+
 fn change(rl: &mut Shell, workspace: Workspace) -> Result<()> {
     workspace.migrate()?;
 
+    let config = rl.global_config().for_workspace(&workspace)?;
     let blobs = BlobStorage::workspace(&workspace)?;
-    let db = Database::establish(workspace)?;
+    let db = Database::establish(workspace, &config)?;
     rl.set_blobstorage(blobs);
     rl.set_db(db);
+    rl.set_config(config);
     Ok(())
 }
 
@@ -69,42 +269,91 @@ fn list() -> Result<()> {
     Ok(())
 }
 
-fn run(args: Args, rl: Option<&mut Shell>) -> Result<()> {
-    if args.delete {
-        if let Some(workspace) = args.workspace {
+fn run(args: Args, rl: Option<&mut Shell>, config: &Config) -> Result<()> {
+    let subcommand = match (args.subcommand, args.workspace) {
+        (Some(subcommand), _) => subcommand,
+        // the flags and the positional workspace from before the subcommands
+        (None, Some(workspace)) if args.delete => Subcommand::Delete(WorkspaceDelete {
+            workspace,
+            force: args.force,
+        }),
+        (None, None) if args.delete => bail!("--delete requires workspace"),
+        (None, workspace) if args.usage => Subcommand::Usage(WorkspaceUsage {
+            workspace,
+        }),
+        (None, Some(workspace)) => Subcommand::Switch(WorkspaceSwitch {
+            workspace,
+        }),
+        (None, None) => Subcommand::List,
+    };
+
+    match subcommand {
+        Subcommand::Switch(args) => {
             if let Some(rl) = rl {
-                if *rl.db().workspace() == workspace {
+                change(rl, args.workspace)
+            } else {
+                bail!("Use -w to select the workspace when starting sn0int")
+            }
+        },
+        Subcommand::List => list(),
+        Subcommand::Delete(args) => {
+            if let Some(rl) = rl {
+                if *rl.db().workspace() == args.workspace {
                     bail!("Can't delete current workspace")
                 }
             }
 
-            delete(workspace, args.force)
-        } else {
-            bail!("--delete requires workspace")
-        }
-    } else if args.usage {
-        usage(args.workspace)
-    } else if let Some(workspace) = args.workspace {
-        if let Some(rl) = rl {
-            change(rl, workspace)
-        } else {
+            delete(args.workspace, args.force)
+        },
+        Subcommand::Usage(args) => usage(args.workspace),
+        Subcommand::Merge(args) => {
+            let dst = args.dst.clone();
+            merge(args, config)?;
+
+            // reload the autonoscope rules if we merged into the current workspace
+            if let Some(rl) = rl {
+                if *rl.db().workspace() == dst {
+                    change(rl, dst)?;
+                }
+            }
+
             Ok(())
-        }
-    } else {
-        list()
+        },
+        Subcommand::Clone(args) => clone(args, config),
+        Subcommand::Archive(args) => archive(args, config),
+        Subcommand::Restore(args) => restore(args, config),
+        Subcommand::Config(args) => {
+            let workspace = match (&args.workspace, &rl) {
+                (Some(workspace), _) => workspace.clone(),
+                (None, Some(rl)) => rl.db().workspace().clone(),
+                (None, None) => bail!("workspace config requires workspace"),
+            };
+            let changed = !args.set.is_empty() || !args.unset.is_empty();
+            settings(args, workspace.clone(), config)?;
+
+            // apply the new settings if we changed the current workspace
+            if let Some(rl) = rl {
+                if changed && *rl.db().workspace() == workspace {
+                    rl.set_config(config.for_workspace(&workspace)?);
+                }
+            }
+
+            Ok(())
+        },
     }
 }
 
 impl Cmd for Args {
     #[inline]
     fn run(self, rl: &mut Shell) -> Result<()> {
-        run(self, Some(rl))
+        let config = rl.global_config();
+        run(self, Some(rl), config)
     }
 }
 
 impl LiteCmd for Args {
     #[inline]
-    fn run(self, _config: &Config) -> Result<()> {
-        run(self, None)
+    fn run(self, config: &Config) -> Result<()> {
+        run(self, None, config)
     }
 }
@@ -0,0 +1,204 @@
+use crate::errors::*;
+use crate::paths;
+use crate::worker::{EventWithCallback, Event2, VoidSender};
+use crate::workspaces::Workspace;
+
+use serde_json;
+use sn0int_common::ModuleID;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::result;
+use std::sync::mpsc;
+
+pub use sn0int_std::web::CookieJar;
+
+
+/// A request from a script to persist the cookies of a session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PersistCookieJar {
+    pub name: String,
+    pub jar: CookieJar,
+}
+
+impl EventWithCallback for PersistCookieJar {
+    type Payload = ();
+
+    #[inline(always)]
+    fn with_callback(self, tx: mpsc::Sender<result::Result<Self::Payload, String>>) -> Event2 {
+        Event2::CookieJar((self, tx))
+    }
+}
+
+#[inline]
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Named cookie jars that are stored in the workspace
+///
+/// Jars are namespaced by module, a module can only restore jars it
+/// persisted itself.
+#[derive(Debug, Clone)]
+pub struct CookieStorage {
+    path: PathBuf,
+}
+
+impl CookieStorage {
+    #[inline]
+    pub fn new<I: Into<PathBuf>>(path: I) -> CookieStorage {
+        CookieStorage {
+            path: path.into(),
+        }
+    }
+
+    #[inline]
+    pub fn workspace(workspace: &Workspace) -> Result<CookieStorage> {
+        let path = paths::cookies_dir(workspace)?;
+        Ok(CookieStorage::new(path))
+    }
+
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        self.path.as_ref()
+    }
+
+    fn module_dir(&self, module: &ModuleID) -> Result<PathBuf> {
+        if !valid_name(&module.author) || !valid_name(&module.name) {
+            bail!("module name contains invalid characters");
+        }
+        Ok(self.path.join(&module.author).join(&module.name))
+    }
+
+    pub fn join(&self, module: &ModuleID, name: &str) -> Result<PathBuf> {
+        if !valid_name(name) {
+            bail!("cookie jar name contains invalid characters");
+        }
+        let path = self.module_dir(module)?
+            .join(format!("{}.json", name));
+        Ok(path)
+    }
+
+    /// Load all jars of a module, expired cookies are removed
+    pub fn load_all(&self, module: &ModuleID) -> Result<HashMap<String, CookieJar>> {
+        let mut jars = HashMap::new();
+
+        let dir = self.module_dir(module)?;
+        if !dir.exists() {
+            return Ok(jars);
+        }
+
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+
+            let name = match (path.file_stem(), path.extension()) {
+                (Some(name), Some(ext)) if ext == "json" => name.to_string_lossy().to_string(),
+                _ => continue,
+            };
+
+            debug!("Loading cookie jar from {:?}", path);
+            let buf = fs::read(&path)
+                .context("Failed to read cookie jar")?;
+            let mut jar = serde_json::from_slice::<CookieJar>(&buf)
+                .context("Failed to parse cookie jar")?;
+
+            jar.remove_expired();
+            if jar.is_empty() {
+                debug!("Deleting expired cookie jar: {:?}", path);
+                fs::remove_file(&path)
+                    .context("Failed to delete cookie jar")?;
+            } else {
+                jars.insert(name, jar);
+            }
+        }
+
+        Ok(jars)
+    }
+
+    pub fn save(&self, module: &ModuleID, name: &str, jar: &CookieJar) -> Result<()> {
+        let path = self.join(module, name)?;
+
+        let mut jar = jar.clone();
+        jar.remove_expired();
+
+        if jar.is_empty() {
+            if path.exists() {
+                debug!("Deleting empty cookie jar: {:?}", path);
+                fs::remove_file(path)
+                    .context("Failed to delete cookie jar")?;
+            }
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context("Failed to create cookie jar directory")?;
+        }
+
+        debug!("Writing cookie jar to {:?}", path);
+        let buf = serde_json::to_vec(&jar)?;
+        fs::write(path, &buf)
+            .context("Failed to write cookie jar")?;
+
+        Ok(())
+    }
+
+    pub fn store(&self, tx: VoidSender, module: &ModuleID, event: &PersistCookieJar) {
+        let result = self.save(module, &event.name, &event.jar)
+            .map_err(|err| err.to_string());
+        tx.send(result).unwrap();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use tempfile;
+
+    fn module() -> ModuleID {
+        ModuleID {
+            author: "anonymous".into(),
+            name: "cookies".into(),
+        }
+    }
+
+    #[test]
+    fn test_cookie_storage_roundtrip() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let s = CookieStorage::new(dir.path());
+        let module = module();
+
+        let mut jar = CookieJar::default();
+        jar.register_in_jar("session".into(), "asdf".into(), None);
+        jar.register_in_jar("tracking".into(), "1".into(), Some(Utc::now() + Duration::days(1)));
+        s.save(&module, "login", &jar).expect("save failed");
+
+        let jars = s.load_all(&module).expect("load failed");
+        assert_eq!(jars.len(), 1);
+        assert_eq!(jars["login"], jar);
+    }
+
+    #[test]
+    fn test_cookie_storage_empty_jar() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let s = CookieStorage::new(dir.path());
+        let module = module();
+
+        s.save(&module, "login", &CookieJar::default()).expect("save failed");
+
+        let jars = s.load_all(&module).expect("load failed");
+        assert!(jars.is_empty());
+    }
+
+    #[test]
+    fn test_path_validation() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let s = CookieStorage::new(dir.path());
+        let module = module();
+
+        let result = s.save(&module, "../../../../etc/passwd", &CookieJar::default());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,680 @@
+use crate::errors::*;
+use crate::blobs::BlobStorage;
+use crate::db::{Database, DatabaseSock, Family, Table};
+use crate::db::trash::{self, Column};
+use crate::models::*;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text};
+use serde_json::{self, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+
+
+/// Tables that are imported in addition to the entity tables
+const TABLES: &[&str] = &["activity", "autonoscope", "tags", "notes", "ttls"];
+
+/// Tables that reference entities by family and id instead of a foreign key
+const POLYMORPHIC: &[&str] = &["tags", "notes", "ttls"];
+
+/// A field that has a different value in both workspaces, the value of the
+/// workspace we import into is kept
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    pub table: String,
+    pub entity: String,
+    pub column: String,
+    pub kept: Value,
+    pub discarded: Value,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{} {}: {} is {} (kept), other workspace has {}",
+            self.table, self.entity, self.column, self.kept, self.discarded)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TableReport {
+    pub table: String,
+    pub inserted: usize,
+    pub existing: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub tables: Vec<TableReport>,
+    pub conflicts: Vec<Conflict>,
+    pub blobs: usize,
+}
+
+#[derive(QueryableByName)]
+struct TableName {
+    #[sql_type="Text"]
+    name: String,
+}
+
+#[derive(QueryableByName)]
+struct KeyColumn {
+    #[sql_type="Text"]
+    index_name: String,
+    #[sql_type="Text"]
+    column_name: String,
+}
+
+#[derive(QueryableByName)]
+struct ForeignKey {
+    #[sql_type="Text"]
+    column_name: String,
+    #[sql_type="Text"]
+    parent_table: String,
+}
+
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[sql_type="Integer"]
+    id: i32,
+    #[sql_type="Text"]
+    data: String,
+}
+
+struct TableInfo {
+    name: String,
+    columns: Vec<Column>,
+    key: Vec<String>,
+    foreign_keys: Vec<ForeignKey>,
+}
+
+impl TableInfo {
+    fn column(&self, name: &str) -> Result<&Column> {
+        self.columns.iter()
+            .find(|column| column.name == name)
+            .ok_or_else(|| format_err!("Unknown column {}.{}", self.name, name))
+    }
+
+    /// Columns that identify a row or point to other rows, they are never merged
+    fn is_reference(&self, name: &str) -> bool {
+        name == "id"
+            || self.key.iter().any(|key| key == name)
+            || self.foreign_keys.iter().any(|fk| fk.column_name == name)
+    }
+}
+
+type Row = Map<String, Value>;
+
+/// Copy all entities, relations, activity, autonoscope rules, ttls and blobs
+/// of a workspace into another workspace
+///
+/// Rows are matched by their unique key and references are translated to the
+/// ids of the workspace we import into. Empty fields of existing rows are
+/// filled in, fields that differ are reported as conflicts.
+pub fn import(src: &Database, dst: &Database) -> Result<ImportReport> {
+    let tables = sort_tables(tables(dst)?)?;
+
+    let mut report = ImportReport::default();
+    dst.db().transaction::<_, Error, _>(|| {
+        let mut ids = HashMap::new();
+        for table in &tables {
+            let result = import_table(src, dst, table, &mut ids, &mut report.conflicts)
+                .with_context(|_| format!("Failed to import {}", table.name))?;
+            report.tables.push(result);
+        }
+
+        // netblocks of the other workspace may be more specific
+        Netblock::update_missing_ranges(dst)?;
+        IpAddr::update_missing_range_keys(dst)?;
+        IpAddr::link_all_netblocks(dst)?;
+
+        Ok(())
+    })?;
+
+    report.blobs = copy_blobs(&BlobStorage::workspace(src.workspace())?,
+                              &BlobStorage::workspace(dst.workspace())?)?;
+
+    Ok(report)
+}
+
+fn import_table(src: &Database, dst: &Database, table: &TableInfo, ids: &mut HashMap<(String, i32), i32>, conflicts: &mut Vec<Conflict>) -> Result<TableReport> {
+    let mut report = TableReport {
+        table: table.name.clone(),
+        ..Default::default()
+    };
+
+    for (src_id, mut row) in read_rows(src, table, None)? {
+        if !remap(table, &mut row, ids)? {
+            report.skipped += 1;
+            continue;
+        }
+
+        let id = if let Some((id, existing)) = find(dst, table, &row)? {
+            update(dst, table, id, &row, &existing, conflicts)?;
+            report.existing += 1;
+            id
+        } else {
+            insert(dst, table, &row)?;
+            report.inserted += 1;
+            find(dst, table, &row)?
+                .ok_or_else(|| format_err!("Failed to find inserted row"))?
+                .0
+        };
+
+        ids.insert((table.name.clone(), src_id), id);
+    }
+
+    Ok(report)
+}
+
+/// The tables that hold the data of a workspace, without the trash and the search index
+fn tables(db: &Database) -> Result<Vec<TableInfo>> {
+    let names = match db.db() {
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT name FROM sqlite_master
+            WHERE type = 'table' ORDER BY name")
+            .load::<TableName>(conn),
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT CAST(c.relname AS TEXT) AS name
+            FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind = 'r' AND n.nspname = current_schema() ORDER BY c.relname")
+            .load::<TableName>(conn),
+    }?;
+
+    let mut tables = Vec::new();
+    for TableName { name } in names {
+        if name.parse::<Table>().is_err() && !TABLES.contains(&name.as_str()) {
+            continue;
+        }
+
+        tables.push(TableInfo {
+            columns: trash::table_columns(db, &name)?,
+            key: unique_key(db, &name)?,
+            foreign_keys: foreign_keys(db, &name)?,
+            name,
+        });
+    }
+
+    Ok(tables)
+}
+
+/// The columns of the first unique index of a table, excluding the primary key
+fn unique_key(db: &Database, table: &str) -> Result<Vec<String>> {
+    let columns = match db.db() {
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT il.name AS index_name, ii.name AS column_name
+            FROM pragma_index_list(?) il JOIN pragma_index_info(il.name) ii
+            WHERE il.\"unique\" = 1 AND il.origin != 'pk'
+            ORDER BY il.name, ii.seqno")
+            .bind::<Text, _>(table)
+            .load::<KeyColumn>(conn),
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT CAST(c.relname AS TEXT) AS index_name,
+            CAST(a.attname AS TEXT) AS column_name
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indexrelid
+            JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+            WHERE i.indrelid = to_regclass($1) AND i.indisunique AND NOT i.indisprimary
+            ORDER BY c.relname, a.attnum")
+            .bind::<Text, _>(table)
+            .load::<KeyColumn>(conn),
+    }?;
+
+    let index = match columns.first() {
+        Some(column) => column.index_name.clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    Ok(columns.into_iter()
+        .filter(|column| column.index_name == index)
+        .map(|column| column.column_name)
+        .collect())
+}
+
+fn foreign_keys(db: &Database, table: &str) -> Result<Vec<ForeignKey>> {
+    match db.db() {
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT \"from\" AS column_name, \"table\" AS parent_table
+            FROM pragma_foreign_key_list(?)")
+            .bind::<Text, _>(table)
+            .load::<ForeignKey>(conn),
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT CAST(a.attname AS TEXT) AS column_name,
+            CAST(p.relname AS TEXT) AS parent_table
+            FROM pg_constraint f
+            JOIN pg_class p ON p.oid = f.confrelid
+            JOIN pg_attribute a ON a.attrelid = f.conrelid AND a.attnum = f.conkey[1]
+            WHERE f.contype = 'f' AND f.conrelid = to_regclass($1)")
+            .bind::<Text, _>(table)
+            .load::<ForeignKey>(conn),
+    }
+        .map_err(Error::from)
+}
+
+/// Order the tables so every table comes after the tables it references
+fn sort_tables(mut tables: Vec<TableInfo>) -> Result<Vec<TableInfo>> {
+    let mut sorted = Vec::with_capacity(tables.len());
+
+    while !tables.is_empty() {
+        let pending = tables.iter()
+            .map(|table| table.name.clone())
+            .collect::<HashSet<_>>();
+
+        let (ready, blocked): (Vec<_>, Vec<_>) = tables.into_iter()
+            .partition(|table| if POLYMORPHIC.contains(&table.name.as_str()) {
+                pending.iter().all(|name| POLYMORPHIC.contains(&name.as_str()))
+            } else {
+                table.foreign_keys.iter()
+                    .all(|fk| fk.parent_table == table.name || !pending.contains(&fk.parent_table))
+            });
+
+        if ready.is_empty() {
+            bail!("Tables have circular references");
+        }
+
+        sorted.extend(ready);
+        tables = blocked;
+    }
+
+    Ok(sorted)
+}
+
+/// Read rows as json, values are normalized so both backends can be compared
+fn read_rows(db: &Database, table: &TableInfo, filter: Option<&str>) -> Result<Vec<(i32, Row)>> {
+    let mut query = format!("SELECT id, {} AS data FROM \"{}\"",
+        trash::json_object(db.db().backend(), &table.columns), table.name);
+    if let Some(filter) = filter {
+        query.push_str(&format!(" WHERE {}", filter));
+    }
+    query.push_str(" ORDER BY id");
+
+    let rows = with_conn!(db.db(), |conn| diesel::sql_query(query)
+        .load::<JsonRow>(conn))?;
+
+    rows.into_iter()
+        .map(|row| {
+            let mut data = serde_json::from_str::<Row>(&row.data)?;
+            for column in &table.columns {
+                if let Some(value) = data.get_mut(&column.name) {
+                    normalize(column, value);
+                }
+            }
+            Ok((row.id, data))
+        })
+        .collect()
+}
+
+/// sqlite stores booleans as integers and timestamps with a different format
+/// and precision
+fn normalize(column: &Column, value: &mut Value) {
+    if column.is_bool() {
+        if let Some(n) = value.as_i64() {
+            *value = Value::Bool(n != 0);
+        }
+    } else if column.is_timestamp() {
+        if let Value::String(time) = value {
+            *time = normalize_timestamp(time);
+        }
+    }
+}
+
+fn normalize_timestamp(time: &str) -> String {
+    let time = time.replacen('T', " ", 1);
+    match time.find('.') {
+        Some(idx) => time.chars()
+            .take(idx + 7)
+            .collect(),
+        None => time,
+    }
+}
+
+/// The table of the entity that is referenced by family and id
+fn polymorphic_parent(table: &str, family: &str) -> Result<Table> {
+    match table {
+        "ttls" => family.parse::<Table>(),
+        _ => family.parse::<Family>().map(|family| family.table()),
+    }
+        .map_err(|_| format_err!("Unknown family: {:?}", family))
+}
+
+/// Translate references to the ids of the workspace we import into
+///
+/// Returns false if the row references something that doesn't exist.
+fn remap(table: &TableInfo, row: &mut Row, ids: &HashMap<(String, i32), i32>) -> Result<bool> {
+    for fk in &table.foreign_keys {
+        let src_id = match row.get(&fk.column_name).and_then(Value::as_i64) {
+            Some(id) => id as i32,
+            None => continue,
+        };
+
+        match ids.get(&(fk.parent_table.clone(), src_id)) {
+            Some(id) => {
+                row.insert(fk.column_name.clone(), Value::from(*id));
+            },
+            None if table.column(&fk.column_name)?.nullable_parent.is_some() => {
+                row.insert(fk.column_name.clone(), Value::Null);
+            },
+            None => return Ok(false),
+        }
+    }
+
+    if !POLYMORPHIC.contains(&table.name.as_str()) {
+        return Ok(true);
+    }
+
+    let id_column = if table.name == "ttls" { "key" } else { "entity_id" };
+    let parent = match row.get("family") {
+        Some(Value::String(family)) => polymorphic_parent(&table.name, family)?,
+        _ => return Ok(false),
+    };
+    let src_id = match row.get(id_column).and_then(Value::as_i64) {
+        Some(id) => id as i32,
+        None => return Ok(false),
+    };
+
+    match ids.get(&(parent.as_str().to_string(), src_id)) {
+        Some(id) => {
+            row.insert(id_column.to_string(), Value::from(*id));
+            Ok(true)
+        },
+        None => Ok(false),
+    }
+}
+
+/// Find the row with the same unique key
+fn find(db: &Database, table: &TableInfo, row: &Row) -> Result<Option<(i32, Row)>> {
+    let backend = db.db().backend();
+    let key_is_null = table.key.iter()
+        .any(|name| row.get(name).map_or(true, Value::is_null));
+
+    // NULL never equals NULL, compare all columns that can be compared reliably instead
+    let columns = if table.key.is_empty() || key_is_null {
+        table.columns.iter()
+            .filter(|column| column.name != "id" && !column.is_float() && !column.is_blob())
+            .collect::<Vec<_>>()
+    } else {
+        table.key.iter()
+            .map(|name| table.column(name))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let filter = columns.into_iter()
+        .map(|column| match row.get(&column.name) {
+            Some(Value::Null) | None => Ok(format!("\"{}\" IS NULL", column.name)),
+            Some(value) => {
+                let value = trash::sql_literal(value, column.is_blob(), backend)?;
+                Ok(format!("\"{}\" = {}", column.name, value))
+            },
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(" AND ");
+
+    Ok(read_rows(db, table, Some(&filter))?
+        .into_iter()
+        .next())
+}
+
+fn insert(db: &Database, table: &TableInfo, row: &Row) -> Result<()> {
+    let backend = db.db().backend();
+
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for column in &table.columns {
+        if column.name == "id" {
+            continue;
+        }
+        if let Some(value) = row.get(&column.name) {
+            names.push(format!("\"{}\"", column.name));
+            values.push(trash::sql_literal(value, column.is_blob(), backend)?);
+        }
+    }
+
+    let query = format!("INSERT INTO \"{}\" ({}) VALUES ({})",
+        table.name, names.join(", "), values.join(", "));
+    with_conn!(db.db(), |conn| diesel::sql_query(query)
+        .execute(conn))?;
+
+    Ok(())
+}
+
+fn update(db: &Database, table: &TableInfo, id: i32, src: &Row, dst: &Row, conflicts: &mut Vec<Conflict>) -> Result<()> {
+    let backend = db.db().backend();
+
+    let columns = table.columns.iter()
+        .map(|column| column.name.as_str())
+        .filter(|name| !table.is_reference(name))
+        .collect::<Vec<_>>();
+    let (changes, differences) = merge_row(&columns, src, dst);
+
+    let entity = match dst.get("value") {
+        Some(Value::String(value)) => format!("{:?}", value),
+        _ => format!("#{}", id),
+    };
+    conflicts.extend(differences.into_iter()
+        .map(|(column, kept, discarded)| Conflict {
+            table: table.name.clone(),
+            entity: entity.clone(),
+            column,
+            kept,
+            discarded,
+        }));
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let changes = changes.into_iter()
+        .map(|(name, value)| {
+            let column = table.column(&name)?;
+            let value = trash::sql_literal(&value, column.is_blob(), backend)?;
+            Ok(format!("\"{}\" = {}", name, value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let query = format!("UPDATE \"{}\" SET {} WHERE id = {}", table.name, changes.join(", "), id);
+    with_conn!(db.db(), |conn| diesel::sql_query(query)
+        .execute(conn))?;
+
+    Ok(())
+}
+
+fn is_greater(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() > b.as_f64(),
+        (Value::String(a), Value::String(b)) => a > b,
+        (Value::Bool(a), Value::Bool(b)) => a > b,
+        _ => false,
+    }
+}
+
+type Change = (String, Value);
+type Difference = (String, Value, Value);
+
+/// Merge the fields of a row into an existing row
+///
+/// Returns the fields that need to be updated and the fields that have a
+/// different value in both rows, the existing value is kept in that case.
+fn merge_row(columns: &[&str], src: &Row, dst: &Row) -> (Vec<Change>, Vec<Difference>) {
+    let mut changes = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for &name in columns {
+        let src_value = src.get(name).unwrap_or(&Value::Null);
+        let dst_value = dst.get(name).unwrap_or(&Value::Null);
+
+        if src_value.is_null() || src_value == dst_value {
+            continue;
+        }
+
+        if dst_value.is_null() {
+            changes.push((name.to_string(), src_value.clone()));
+            continue;
+        }
+
+        match name {
+            "first_seen" => if is_greater(dst_value, src_value) {
+                changes.push((name.to_string(), src_value.clone()));
+            },
+            "last_seen" | "confidence" | "verified" | "expire" => if is_greater(src_value, dst_value) {
+                changes.push((name.to_string(), src_value.clone()));
+            },
+            "extra" => {
+                let objects = src_value.as_str().and_then(|v| serde_json::from_str::<Row>(v).ok())
+                    .and_then(|src| dst_value.as_str().and_then(|v| serde_json::from_str::<Row>(v).ok())
+                        .map(|dst| (src, dst)));
+
+                match objects {
+                    Some((src_extra, mut dst_extra)) => {
+                        let mut changed = false;
+                        for (key, value) in src_extra {
+                            match dst_extra.get(&key) {
+                                Some(existing) if *existing == value => (),
+                                Some(existing) => {
+                                    conflicts.push((format!("extra.{}", key), existing.clone(), value));
+                                },
+                                None => {
+                                    dst_extra.insert(key, value);
+                                    changed = true;
+                                },
+                            }
+                        }
+
+                        if changed {
+                            let extra = Value::Object(dst_extra).to_string();
+                            changes.push((name.to_string(), Value::String(extra)));
+                        }
+                    },
+                    None => conflicts.push((name.to_string(), dst_value.clone(), src_value.clone())),
+                }
+            },
+            _ => conflicts.push((name.to_string(), dst_value.clone(), src_value.clone())),
+        }
+    }
+
+    (changes, conflicts)
+}
+
+/// Blobs are content addressed, blobs with the same id are identical
+fn copy_blobs(src: &BlobStorage, dst: &BlobStorage) -> Result<usize> {
+    let mut n = 0;
+    for id in src.list()? {
+        let path = dst.join(&id)?;
+        if !path.exists() {
+            fs::copy(src.join(&id)?, path)
+                .context("Failed to copy blob")?;
+            n += 1;
+        }
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table(name: &str, parents: &[&str]) -> TableInfo {
+        TableInfo {
+            name: name.to_string(),
+            columns: Vec::new(),
+            key: vec!["value".to_string()],
+            foreign_keys: parents.iter()
+                .map(|&parent| ForeignKey {
+                    column_name: format!("{}_id", parent),
+                    parent_table: parent.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn row(value: Value) -> Row {
+        match value {
+            Value::Object(row) => row,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn test_sort_tables() {
+        let tables = sort_tables(vec![
+            table("tags", &[]),
+            table("subdomain_ipaddrs", &["subdomains", "ipaddrs"]),
+            table("subdomains", &["domains"]),
+            table("ipaddrs", &["netblocks"]),
+            table("domains", &[]),
+            table("netblocks", &[]),
+        ]).unwrap();
+        let tables = tables.iter()
+            .map(|table| table.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(tables, vec!["domains", "netblocks", "subdomains", "ipaddrs", "subdomain_ipaddrs", "tags"]);
+    }
+
+    #[test]
+    fn test_sort_tables_circular() {
+        assert!(sort_tables(vec![
+            table("a", &["b"]),
+            table("b", &["a"]),
+        ]).is_err());
+    }
+
+    #[test]
+    fn test_polymorphic_parent() {
+        assert_eq!(polymorphic_parent("tags", "dns-record").unwrap().as_str(), "dns_records");
+        assert_eq!(polymorphic_parent("ttls", "subdomain_ipaddrs").unwrap().as_str(), "subdomain_ipaddrs");
+        assert!(polymorphic_parent("notes", "foo").is_err());
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        assert_eq!(normalize_timestamp("2020-01-01T13:37:00"), "2020-01-01 13:37:00");
+        assert_eq!(normalize_timestamp("2020-01-01 13:37:00.123"), "2020-01-01 13:37:00.123");
+        assert_eq!(normalize_timestamp("2020-01-01 13:37:00.226498073"), "2020-01-01 13:37:00.226498");
+    }
+
+    #[test]
+    fn test_merge_row_fill_in() {
+        let src = row(json!({"resolvable": true, "unscoped": false}));
+        let dst = row(json!({"resolvable": null, "unscoped": false}));
+        let (changes, conflicts) = merge_row(&["resolvable", "unscoped"], &src, &dst);
+        assert_eq!(changes, vec![("resolvable".to_string(), json!(true))]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_row_conflict() {
+        let src = row(json!({"resolvable": true}));
+        let dst = row(json!({"resolvable": false}));
+        let (changes, conflicts) = merge_row(&["resolvable"], &src, &dst);
+        assert!(changes.is_empty());
+        assert_eq!(conflicts, vec![("resolvable".to_string(), json!(false), json!(true))]);
+    }
+
+    #[test]
+    fn test_merge_row_aggregate() {
+        let src = row(json!({
+            "first_seen": "2020-01-01 00:00:00",
+            "last_seen": "2020-01-02 00:00:00",
+            "confidence": 80,
+            "verified": true,
+        }));
+        let dst = row(json!({
+            "first_seen": "2020-01-03 00:00:00",
+            "last_seen": "2020-01-04 00:00:00",
+            "confidence": 100,
+            "verified": false,
+        }));
+        let (changes, conflicts) = merge_row(&["first_seen", "last_seen", "confidence", "verified"], &src, &dst);
+        assert_eq!(changes, vec![
+            ("first_seen".to_string(), json!("2020-01-01 00:00:00")),
+            ("verified".to_string(), json!(true)),
+        ]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_row_extra() {
+        let src = row(json!({"extra": r#"{"a":"1","b":"2"}"#}));
+        let dst = row(json!({"extra": r#"{"a":"3","c":"4"}"#}));
+        let (changes, conflicts) = merge_row(&["extra"], &src, &dst);
+        assert_eq!(changes, vec![("extra".to_string(), json!(r#"{"a":"3","b":"2","c":"4"}"#))]);
+        assert_eq!(conflicts, vec![("extra.a".to_string(), json!("3"), json!("1"))]);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::errors::*;
+use crate::db::{Backend, Database, DatabaseSock, Filter, Table};
+use crate::db::trash::{self, Dependent, Trash};
+use crate::models::*;
+use diesel;
+use diesel::prelude::*;
+use std::collections::BTreeMap;
+use std::net;
+
+
+#[derive(Debug, PartialEq)]
+pub struct Duplicate {
+    pub from: i32,
+    pub from_value: String,
+    pub into: i32,
+    pub into_value: String,
+}
+
+/// Merge an entity into another entity of the same family
+///
+/// Relations, tags and notes are moved to the entity we merge into, fields
+/// that are empty are filled in from the other entity. The merged entity is
+/// moved to the trash afterwards.
+pub fn merge<T: Model>(db: &Database, from: i32, into: i32) -> Result<()> {
+    if from == into {
+        bail!("Can't merge an entity into itself");
+    }
+
+    let my_family = T::family()
+        .ok_or_else(|| format_err!("Entity can't be merged"))?;
+    let table = my_family.table();
+    let table = table.as_str();
+
+    db.db().transaction::<_, Error, _>(|| {
+        T::by_id(db, from)
+            .with_context(|_| format!("Entity #{} doesn't exist", from))?;
+        T::by_id(db, into)
+            .with_context(|_| format!("Entity #{} doesn't exist", into))?;
+
+        merge_fields(db, table, from, into)?;
+
+        // rows that would become duplicates are left behind and moved to the trash
+        for dependent in trash::dependents(db, table)? {
+            if dependent.cascades {
+                move_dependent(db, &dependent, from, into)?;
+            }
+        }
+
+        {
+            use crate::schema::tags::dsl::*;
+            let my_tags = with_conn!(db.db(), |conn| tags
+                .filter(family.eq(my_family.as_str()))
+                .filter(entity_id.eq(from))
+                .load::<Tag>(conn))?;
+            for tag in my_tags {
+                Tag::add(db, &my_family, into, &tag.value)?;
+            }
+            with_conn!(db.db(), |conn| diesel::delete(tags
+                    .filter(family.eq(my_family.as_str()))
+                    .filter(entity_id.eq(from)))
+                .execute(conn))?;
+        }
+
+        {
+            use crate::schema::notes::dsl::*;
+            with_conn!(db.db(), |conn| diesel::update(notes
+                    .filter(family.eq(my_family.as_str()))
+                    .filter(entity_id.eq(from)))
+                .set(entity_id.eq(into))
+                .execute(conn))?;
+        }
+
+        {
+            use crate::schema::ttls::dsl::*;
+            with_conn!(db.db(), |conn| diesel::delete(ttls
+                    .filter(family.eq(table))
+                    .filter(key.eq(from)))
+                .execute(conn))?;
+        }
+
+        Trash::create::<T>(db, &Filter::new(format!("id = {}", from)),
+            &format!("#{} merged into #{}", from, into))?;
+
+        Ok(())
+    })
+}
+
+/// Point the rows of a dependent table to the entity we merge into
+///
+/// Rows that would violate a unique constraint are left untouched.
+fn move_dependent(db: &Database, dependent: &Dependent, from: i32, into: i32) -> Result<()> {
+    match db.db() {
+        DatabaseSock::Sqlite(conn) => {
+            let query = format!("UPDATE OR IGNORE \"{0}\" SET \"{1}\" = {2} WHERE \"{1}\" = {3}",
+                dependent.child_table, dependent.child_column, into, from);
+            diesel::sql_query(query)
+                .execute(conn)?;
+        },
+        // postgres has no UPDATE OR IGNORE, every row is updated in a savepoint instead
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => {
+            use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+            let query = format!("SELECT id FROM \"{}\" WHERE \"{}\" = {}",
+                dependent.child_table, dependent.child_column, from);
+            let rows = diesel::sql_query(query)
+                .load::<trash::RowId>(conn)?;
+
+            for row in rows {
+                let query = format!("UPDATE \"{}\" SET \"{}\" = {} WHERE id = {}",
+                    dependent.child_table, dependent.child_column, into, row.id);
+                match conn.transaction(|| diesel::sql_query(query).execute(conn)) {
+                    Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => (),
+                    res => { res?; },
+                }
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Fill in empty fields and aggregate the metadata of both entities
+fn merge_fields(db: &Database, table: &str, from: i32, into: i32) -> Result<()> {
+    let (min, max, patch) = match db.db().backend() {
+        Backend::Sqlite => ("MIN", "MAX", "json_patch(s.\"{c}\", \"{t}\".\"{c}\")"),
+        #[cfg(feature="postgres")]
+        Backend::Postgres => ("LEAST", "GREATEST", "CAST(CAST(s.\"{c}\" AS JSONB) || CAST(\"{t}\".\"{c}\" AS JSONB) AS TEXT)"),
+    };
+
+    let fields = trash::table_columns(db, table)?.into_iter()
+        .filter(|column| column.name != "id" && column.name != "value")
+        .map(|column| {
+            let expr = match column.name.as_str() {
+                "first_seen" => "COALESCE({min}(\"{t}\".\"{c}\", s.\"{c}\"), \"{t}\".\"{c}\", s.\"{c}\")",
+                "last_seen" | "confidence" | "verified" => "COALESCE({max}(\"{t}\".\"{c}\", s.\"{c}\"), \"{t}\".\"{c}\", s.\"{c}\")",
+                "extra" => "COALESCE({patch}, \"{t}\".\"{c}\", s.\"{c}\")",
+                _ => "COALESCE(\"{t}\".\"{c}\", s.\"{c}\")",
+            };
+            let expr = expr.replace("{min}", min)
+                .replace("{max}", max)
+                .replace("{patch}", patch)
+                .replace("{t}", table)
+                .replace("{c}", &column.name);
+            format!("\"{}\" = (SELECT {} FROM \"{}\" s WHERE s.id = {})", column.name, expr, table, from)
+        })
+        .collect::<Vec<_>>();
+
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    let query = format!("UPDATE \"{}\" SET {} WHERE id = {}", table, fields.join(", "), into);
+    with_conn!(db.db(), |conn| diesel::sql_query(query)
+        .execute(conn))?;
+
+    Ok(())
+}
+
+/// Normalize a value so different spellings of the same thing compare equal
+///
+/// Returns `None` if this family isn't deduplicated.
+pub fn normalize(table: &Table, value: &str) -> Option<String> {
+    match table {
+        Table::Domains | Table::Subdomains => Some(value.trim_end_matches('.').to_lowercase()),
+        Table::Emails | Table::Accounts => Some(value.to_lowercase()),
+        Table::Ipaddrs => value.parse::<net::IpAddr>().ok()
+            .map(|ipaddr| ipaddr.to_string()),
+        Table::Netblocks => value.parse::<ipnetwork::IpNetwork>().ok()
+            .map(|netblock| netblock.to_string()),
+        Table::Urls => url::Url::parse(value).ok()
+            .map(|url| url.into_string()),
+        Table::Phonenumbers => Some(value.chars()
+            .filter(|c| !c.is_whitespace() && !"-()./".contains(*c))
+            .collect()),
+        _ => None,
+    }
+}
+
+/// Find entities that should be merged into each other
+pub fn duplicates<T: Model>(db: &Database) -> Result<Vec<Duplicate>> {
+    let table = T::family()
+        .ok_or_else(|| format_err!("Entity can't be merged"))?
+        .table();
+
+    let entities = T::list(db)?.iter()
+        .map(|entity| (entity.id(), entity.to_string()))
+        .collect::<Vec<_>>();
+
+    Ok(find_duplicates(&table, entities))
+}
+
+fn find_duplicates(table: &Table, entities: Vec<(i32, String)>) -> Vec<Duplicate> {
+    let mut groups = BTreeMap::<_, Vec<_>>::new();
+    for (id, value) in entities {
+        if let Some(normalized) = normalize(table, &value) {
+            groups.entry(normalized)
+                .or_default()
+                .push((id, value));
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for (normalized, mut group) in groups {
+        if group.len() < 2 {
+            continue;
+        }
+
+        // prefer the entity that is already normalized, otherwise the oldest
+        group.sort_by_key(|(id, value)| (*value != normalized, *id));
+        let (into, into_value) = group.remove(0);
+
+        for (from, from_value) in group {
+            duplicates.push(Duplicate {
+                from,
+                from_value,
+                into,
+                into_value: into_value.clone(),
+            });
+        }
+    }
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_subdomain() {
+        assert_eq!(normalize(&Table::Subdomains, "WWW.Example.com."), Some("www.example.com".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_ipaddr() {
+        assert_eq!(normalize(&Table::Ipaddrs, "2001:db8:0:0:0:0:0:1"), Some("2001:db8::1".to_string()));
+        assert_eq!(normalize(&Table::Ipaddrs, "not an ip"), None);
+    }
+
+    #[test]
+    fn test_normalize_phonenumber() {
+        assert_eq!(normalize(&Table::Phonenumbers, "+49 (123) 456-789"), Some("+49123456789".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(normalize(&Table::Urls, "HTTPS://Example.com"), Some("https://example.com/".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_unsupported() {
+        assert_eq!(normalize(&Table::Cryptoaddrs, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"), None);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let duplicates = find_duplicates(&Table::Emails, vec![
+            (1, "Foo@Example.com".to_string()),
+            (2, "bar@example.com".to_string()),
+            (3, "foo@example.com".to_string()),
+            (4, "FOO@example.com".to_string()),
+        ]);
+        assert_eq!(duplicates, vec![
+            Duplicate {
+                from: 1,
+                from_value: "Foo@Example.com".to_string(),
+                into: 3,
+                into_value: "foo@example.com".to_string(),
+            },
+            Duplicate {
+                from: 4,
+                from_value: "FOO@example.com".to_string(),
+                into: 3,
+                into_value: "foo@example.com".to_string(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_find_duplicates_oldest() {
+        let duplicates = find_duplicates(&Table::Subdomains, vec![
+            (5, "WWW.example.com".to_string()),
+            (2, "www.EXAMPLE.com".to_string()),
+        ]);
+        assert_eq!(duplicates, vec![
+            Duplicate {
+                from: 5,
+                from_value: "WWW.example.com".to_string(),
+                into: 2,
+                into_value: "www.EXAMPLE.com".to_string(),
+            },
+        ]);
+    }
+}
@@ -1,18 +1,65 @@
 use crate::errors::*;
 
 use diesel;
+#[cfg(feature="postgres")]
+use diesel::pg::PgConnection;
 use diesel::expression::SqlLiteral;
 use diesel::expression::sql_literal::sql;
-use diesel::sql_types::Bool;
+use diesel::sql_types::{Bool, Text};
+use diesel::connection::TransactionManager;
 use diesel::prelude::*;
+use chrono::Utc;
 use strum_macros::{EnumString, IntoStaticStr};
 use crate::autonoscope::{RuleSet, RuleType};
+use crate::config::Config;
 use crate::models::*;
 use crate::schema::*;
 use crate::migrations;
 use crate::worker;
 use crate::workspaces::Workspace;
+use std::fs;
+use std::net;
+use std::path::{Path, PathBuf};
+use std::result;
+
+/// Run a query on the connection of a workspace
+///
+/// Queries are generic over the backend, the body is compiled once for every
+/// database we support.
+macro_rules! with_conn {
+    ($conn:expr, |$c:ident| $body:expr) => {
+        match $conn {
+            $crate::db::DatabaseSock::Sqlite($c) => $body,
+            #[cfg(feature="postgres")]
+            $crate::db::DatabaseSock::Postgres($c) => $body,
+        }
+    };
+}
+
+/// Insert a row unless it conflicts with a unique constraint, evaluates to
+/// the number of inserted rows
+///
+/// Other clients of a postgres workspace may insert the same entity between
+/// our lookup and our insert.
+macro_rules! insert_ignore {
+    ($conn:expr, $table:expr, $values:expr) => {
+        match $conn {
+            $crate::db::DatabaseSock::Sqlite(conn) => diesel::insert_into($table)
+                .values($values)
+                .execute(conn),
+            #[cfg(feature="postgres")]
+            $crate::db::DatabaseSock::Postgres(conn) => diesel::insert_into($table)
+                .values($values)
+                .on_conflict_do_nothing()
+                .execute(conn),
+        }
+    };
+}
 
+pub mod import;
+pub mod merge;
+pub mod search;
+pub mod trash;
 pub mod ttl;
 
 
@@ -53,6 +100,29 @@ pub enum Family {
     Port,
     Netblock,
     Cryptoaddr,
+    DnsRecord,
+    Certificate,
+    SubdomainCertificate,
+    PortCertificate,
+    Organization,
+    Person,
+    OrganizationDomain,
+    OrganizationEmail,
+    OrganizationPhonenumber,
+    OrganizationAccount,
+    OrganizationNetblock,
+    PersonDomain,
+    PersonEmail,
+    PersonPhonenumber,
+    PersonAccount,
+    PersonNetblock,
+    AutonomousSystem,
+    Document,
+    UrlDocument,
+    Location,
+    DomainLocation,
+    PhonenumberLocation,
+    AccountLocation,
 }
 
 impl Family {
@@ -60,6 +130,51 @@ impl Family {
     pub fn as_str(&self) -> &'static str {
         self.into()
     }
+
+    pub fn table(&self) -> Table {
+        match self {
+            Family::Domain => Table::Domains,
+            Family::Subdomain => Table::Subdomains,
+            Family::Ipaddr => Table::Ipaddrs,
+            Family::SubdomainIpaddr => Table::SubdomainIpaddrs,
+            Family::Url => Table::Urls,
+            Family::Email => Table::Emails,
+            Family::Phonenumber => Table::Phonenumbers,
+            Family::Device => Table::Devices,
+            Family::Network => Table::Networks,
+            Family::NetworkDevice => Table::NetworkDevices,
+            Family::Account => Table::Accounts,
+            Family::Breach => Table::Breaches,
+            Family::BreachEmail => Table::BreachEmails,
+            Family::Image => Table::Images,
+            Family::Port => Table::Ports,
+            Family::Netblock => Table::Netblocks,
+            Family::Cryptoaddr => Table::Cryptoaddrs,
+            Family::DnsRecord => Table::DnsRecords,
+            Family::Certificate => Table::Certificates,
+            Family::SubdomainCertificate => Table::SubdomainCertificates,
+            Family::PortCertificate => Table::PortCertificates,
+            Family::Organization => Table::Organizations,
+            Family::Person => Table::Persons,
+            Family::OrganizationDomain => Table::OrganizationDomains,
+            Family::OrganizationEmail => Table::OrganizationEmails,
+            Family::OrganizationPhonenumber => Table::OrganizationPhonenumbers,
+            Family::OrganizationAccount => Table::OrganizationAccounts,
+            Family::OrganizationNetblock => Table::OrganizationNetblocks,
+            Family::PersonDomain => Table::PersonDomains,
+            Family::PersonEmail => Table::PersonEmails,
+            Family::PersonPhonenumber => Table::PersonPhonenumbers,
+            Family::PersonAccount => Table::PersonAccounts,
+            Family::PersonNetblock => Table::PersonNetblocks,
+            Family::AutonomousSystem => Table::AutonomousSystems,
+            Family::Document => Table::Documents,
+            Family::UrlDocument => Table::UrlDocuments,
+            Family::Location => Table::Locations,
+            Family::DomainLocation => Table::DomainLocations,
+            Family::PhonenumberLocation => Table::PhonenumberLocations,
+            Family::AccountLocation => Table::AccountLocations,
+        }
+    }
 }
 
 #[derive(EnumString, IntoStaticStr)]
@@ -82,6 +197,29 @@ pub enum Table {
     Ports,
     Netblocks,
     Cryptoaddrs,
+    DnsRecords,
+    Certificates,
+    SubdomainCertificates,
+    PortCertificates,
+    Organizations,
+    Persons,
+    OrganizationDomains,
+    OrganizationEmails,
+    OrganizationPhonenumbers,
+    OrganizationAccounts,
+    OrganizationNetblocks,
+    PersonDomains,
+    PersonEmails,
+    PersonPhonenumbers,
+    PersonAccounts,
+    PersonNetblocks,
+    AutonomousSystems,
+    Documents,
+    UrlDocuments,
+    Locations,
+    DomainLocations,
+    PhonenumberLocations,
+    AccountLocations,
 }
 
 impl Table {
@@ -91,30 +229,40 @@ impl Table {
     }
 }
 
-pub struct Database {
-    workspace: Workspace,
-    db: SqliteConnection,
-    autonoscope: RuleSet,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    Sqlite,
+    #[cfg(feature="postgres")]
+    Postgres,
 }
 
-pub type DatabaseSock = diesel::SqliteConnection;
-
-impl Database {
-    pub fn establish(workspace: Workspace) -> Result<Database> {
-        let db = worker::spawn_fn("Connecting to database", || {
-            Database::establish_quiet(workspace)
-        }, false)?;
+/// The connection of a workspace, a local sqlite file by default or a postgres
+/// server that is shared with other users
+pub enum DatabaseSock {
+    Sqlite(SqliteConnection),
+    #[cfg(feature="postgres")]
+    Postgres(PgConnection),
+}
 
-        Ok(db)
+impl DatabaseSock {
+    fn connect(workspace: &Workspace, config: &Config) -> Result<DatabaseSock> {
+        match config.workspace_database(workspace) {
+            Some(url) if url.starts_with("postgres://") || url.starts_with("postgresql://") => {
+                Self::connect_postgres(url)
+            },
+            Some(url) => bail!("Unsupported database url: {:?}", url),
+            None => Self::connect_sqlite(workspace),
+        }
     }
 
-    pub fn establish_quiet(workspace: Workspace) -> Result<Database> {
+    fn connect_sqlite(workspace: &Workspace) -> Result<DatabaseSock> {
         let path = workspace.db_path()?;
         let path = path.into_os_string().into_string()
             .map_err(|_| format_err!("Failed to convert db path to utf-8"))?;
 
         let db = SqliteConnection::establish(&path)
             .context("Failed to connect to database")?;
+        Self::ensure_fts5(&db)?;
         migrations::run(&db)
             .context("Failed to run migrations")?;
         db.execute("PRAGMA journal_mode = WAL")
@@ -124,12 +272,143 @@ impl Database {
         db.execute("PRAGMA synchronous = NORMAL")
             .context("Failed to enforce foreign keys")?;
 
+        Ok(DatabaseSock::Sqlite(db))
+    }
+
+    /// The search index needs fts5, which isn't available in every system sqlite
+    fn ensure_fts5(db: &SqliteConnection) -> Result<()> {
+        #[derive(QueryableByName)]
+        struct CompileOption {
+            #[sql_type="Bool"]
+            used: bool,
+        }
+
+        let option = diesel::sql_query("SELECT sqlite_compileoption_used('ENABLE_FTS5') AS used")
+            .get_result::<CompileOption>(db)?;
+        if !option.used {
+            bail!("sqlite has been built without fts5, rebuild sn0int with --features=sqlite-bundled");
+        }
+        Ok(())
+    }
+
+    fn sqlite_version(db: &SqliteConnection) -> Result<Vec<u32>> {
+        #[derive(QueryableByName)]
+        struct Version {
+            #[sql_type="Text"]
+            version: String,
+        }
+
+        let version = diesel::sql_query("SELECT sqlite_version() AS version")
+            .get_result::<Version>(db)?;
+        version.version.split('.')
+            .map(|x| x.parse::<u32>()
+                .map_err(|_| format_err!("Invalid sqlite version: {:?}", version.version)))
+            .collect()
+    }
+
+    #[cfg(feature="postgres")]
+    fn connect_postgres(url: &str) -> Result<DatabaseSock> {
+        let db = PgConnection::establish(url)
+            .context("Failed to connect to database")?;
+        migrations::postgres::run(&db)
+            .context("Failed to run migrations")?;
+
+        Ok(DatabaseSock::Postgres(db))
+    }
+
+    #[cfg(not(feature="postgres"))]
+    fn connect_postgres(_url: &str) -> Result<DatabaseSock> {
+        bail!("sn0int has been built without postgres support")
+    }
+
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        match self {
+            DatabaseSock::Sqlite(_) => Backend::Sqlite,
+            #[cfg(feature="postgres")]
+            DatabaseSock::Postgres(_) => Backend::Postgres,
+        }
+    }
+
+    pub fn transaction<T, E, F>(&self, f: F) -> result::Result<T, E>
+        where F: FnOnce() -> result::Result<T, E>,
+              E: From<diesel::result::Error>,
+    {
+        with_conn!(self, |conn| conn.transaction(f))
+    }
+
+    /// Start a transaction that is committed with `commit`, if a transaction
+    /// is already open a savepoint is created instead
+    pub fn begin(&self) -> Result<()> {
+        with_conn!(self, |conn| conn.transaction_manager().begin_transaction(conn))?;
+        Ok(())
+    }
+
+    pub fn commit(&self) -> Result<()> {
+        with_conn!(self, |conn| conn.transaction_manager().commit_transaction(conn))?;
+        Ok(())
+    }
+
+    pub fn rollback(&self) -> Result<()> {
+        with_conn!(self, |conn| conn.transaction_manager().rollback_transaction(conn))?;
+        Ok(())
+    }
+}
+
+fn copy_with_wal(src: &Path, dst: &Path) -> Result<()> {
+    fs::copy(src, dst)?;
+
+    let mut src_wal = src.as_os_str().to_owned();
+    src_wal.push("-wal");
+    let src_wal = PathBuf::from(src_wal);
+    if src_wal.exists() {
+        let mut dst_wal = dst.as_os_str().to_owned();
+        dst_wal.push("-wal");
+        fs::copy(src_wal, dst_wal)?;
+    }
+
+    Ok(())
+}
+
+pub struct Database {
+    workspace: Workspace,
+    db: DatabaseSock,
+    autonoscope: RuleSet,
+}
+
+impl Database {
+    pub fn establish(workspace: Workspace, config: &Config) -> Result<Database> {
+        let db = worker::spawn_fn("Connecting to database", || {
+            Database::establish_quiet(workspace, config)
+        }, false)?;
+
+        Ok(db)
+    }
+
+    pub fn establish_quiet(workspace: Workspace, config: &Config) -> Result<Database> {
+        let db = DatabaseSock::connect(&workspace, config)?;
         let autonoscope = RuleSet::load(&db)?;
 
-        Ok(Database {
+        let db = Database {
             workspace,
             db,
             autonoscope,
+        };
+        Netblock::update_missing_ranges(&db)?;
+        IpAddr::update_missing_range_keys(&db)?;
+        Ok(db)
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Database> {
+        let db = SqliteConnection::establish(":memory:")?;
+        migrations::run(&db)?;
+        db.execute("PRAGMA foreign_keys = ON")?;
+
+        Ok(Database {
+            workspace: "test".parse()?,
+            db: DatabaseSock::Sqlite(db),
+            autonoscope: RuleSet::default(),
         })
     }
 
@@ -139,7 +418,7 @@ impl Database {
     }
 
     #[inline(always)]
-    pub fn db(&self) -> &SqliteConnection {
+    pub fn db(&self) -> &DatabaseSock {
         &self.db
     }
 
@@ -148,6 +427,52 @@ impl Database {
         &self.workspace
     }
 
+    /// Write a consistent copy of the database to a new sqlite file, this
+    /// works while the workspace is in use
+    pub fn snapshot(&self, path: &Path) -> Result<()> {
+        match &self.db {
+            DatabaseSock::Sqlite(conn) => {
+                if DatabaseSock::sqlite_version(conn)? >= vec![3, 27] {
+                    let path = path.to_str()
+                        .ok_or_else(|| format_err!("Failed to convert snapshot path to utf-8"))?;
+                    diesel::sql_query("VACUUM INTO ?")
+                        .bind::<Text, _>(path)
+                        .execute(conn)
+                        .context("Failed to copy database")?;
+                } else {
+                    self.copy_locked(conn, path)?;
+                }
+            },
+            #[cfg(feature="postgres")]
+            DatabaseSock::Postgres(_) => bail!("Shared workspaces can't be copied, use `workspace merge` instead"),
+        }
+
+        Ok(())
+    }
+
+    /// `VACUUM INTO` requires sqlite 3.27, older versions copy the database
+    /// and its write ahead log while nobody else is allowed to write
+    fn copy_locked(&self, conn: &SqliteConnection, path: &Path) -> Result<()> {
+        let src = self.workspace.db_path()?;
+
+        conn.execute("BEGIN IMMEDIATE")
+            .context("Failed to lock database")?;
+        let copied = copy_with_wal(&src, path);
+        conn.execute("ROLLBACK")
+            .context("Failed to unlock database")?;
+        copied.context("Failed to copy database")?;
+
+        // merge the write ahead log into the copy
+        let path = path.to_str()
+            .ok_or_else(|| format_err!("Failed to convert snapshot path to utf-8"))?;
+        let copy = SqliteConnection::establish(path)
+            .context("Failed to open database copy")?;
+        copy.execute("PRAGMA journal_mode = DELETE")
+            .context("Failed to checkpoint database copy")?;
+
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn autonoscope_add_rule(&mut self, object: &RuleType, value: &str, scoped: bool) -> Result<()> {
         self.autonoscope.add_rule(&self.db, object, value, scoped)
@@ -165,11 +490,14 @@ impl Database {
 
     /// Returns true if we didn't have this value yet
     pub fn insert_generic(&self, object: Insert) -> Result<Option<(DbChange, i32)>> {
-        let scoped = self.autonoscope.matches(&object)?;
+        let scoped = match self.autonoscope.matches(&object)? {
+            Some(scoped) => scoped,
+            None => self.netblock_scope(&object)?,
+        };
         match object {
             Insert::Domain(object) => self.insert_struct(object, scoped),
             Insert::Subdomain(object) => self.insert_struct(object, scoped),
-            Insert::IpAddr(object) => self.insert_struct(object, scoped),
+            Insert::IpAddr(object) => self.insert_ipaddr_struct(object, scoped),
             Insert::SubdomainIpAddr(object) => self.insert_subdomain_ipaddr_struct(&object),
             Insert::Url(object) => self.insert_struct(object, scoped),
             Insert::Email(object) => self.insert_struct(object, scoped),
@@ -182,32 +510,99 @@ impl Database {
             Insert::BreachEmail(object) => self.insert_breach_email_struct(object),
             Insert::Image(object) => self.insert_struct(object, scoped),
             Insert::Port(object) => self.insert_struct(object, scoped),
-            Insert::Netblock(object) => self.insert_struct(object, scoped),
+            Insert::Netblock(object) => self.insert_netblock_struct(object, scoped),
             Insert::CryptoAddr(object) => self.insert_struct(object, scoped),
+            Insert::DnsRecord(object) => self.insert_struct(object, scoped),
+            Insert::Certificate(object) => self.insert_struct(object, scoped),
+            Insert::SubdomainCertificate(object) => self.insert_subdomain_certificate_struct(&object),
+            Insert::PortCertificate(object) => self.insert_port_certificate_struct(&object),
+            Insert::Organization(object) => self.insert_struct(object, scoped),
+            Insert::Person(object) => self.insert_struct(object, scoped),
+            Insert::OrganizationDomain(object) => self.insert_organization_domain_struct(&object),
+            Insert::OrganizationEmail(object) => self.insert_organization_email_struct(&object),
+            Insert::OrganizationPhoneNumber(object) => self.insert_organization_phonenumber_struct(&object),
+            Insert::OrganizationAccount(object) => self.insert_organization_account_struct(&object),
+            Insert::OrganizationNetblock(object) => self.insert_organization_netblock_struct(&object),
+            Insert::PersonDomain(object) => self.insert_person_domain_struct(&object),
+            Insert::PersonEmail(object) => self.insert_person_email_struct(&object),
+            Insert::PersonPhoneNumber(object) => self.insert_person_phonenumber_struct(&object),
+            Insert::PersonAccount(object) => self.insert_person_account_struct(&object),
+            Insert::PersonNetblock(object) => self.insert_person_netblock_struct(&object),
+            Insert::AutonomousSystem(object) => self.insert_struct(object, scoped),
+            Insert::Document(object) => self.insert_struct(object, scoped),
+            Insert::UrlDocument(object) => self.insert_url_document_struct(&object),
+            Insert::Location(object) => self.insert_struct(object, scoped),
+            Insert::DomainLocation(object) => self.insert_domain_location_struct(&object),
+            Insert::PhoneNumberLocation(object) => self.insert_phonenumber_location_struct(&object),
+            Insert::AccountLocation(object) => self.insert_account_location_struct(&object),
         }
     }
 
     /// Returns true if we didn't have this value yet
-    pub fn insert_struct<T: InsertableStruct<M>, M: Model + Scopable>(&self, mut obj: T, scoped: bool) -> Result<Option<(DbChange, i32)>> {
-        if let Some(existing) = M::get_opt(self, obj.value())? {
-            // entity is out of scope
-            if !existing.scoped() {
-                return Ok(None);
-            }
+    pub fn insert_struct<T: InsertableStruct<M>, M: Model + Scopable + Seen>(&self, mut obj: T, scoped: bool) -> Result<Option<(DbChange, i32)>> {
+        let now = Utc::now().naive_utc();
+
+        let existing = match M::get_opt(self, obj.value())? {
+            Some(existing) => existing,
+            None => {
+                obj.set_scoped(scoped);
+                if obj.insert(&self)? {
+                    let id = M::get_id(self, obj.value())?;
+                    M::seen(self, id, now, true)?;
+                    return Ok(Some((DbChange::Insert, id)));
+                }
+                // the entity has been added by somebody else in the meantime
+                M::get(self, obj.value())?
+            },
+        };
+
+        // entity is out of scope
+        if !existing.scoped() {
+            return Ok(None);
+        }
 
-            let update = obj.upsert(&existing);
-            if update.is_dirty() {
-                update.apply(&self)?;
-                Ok(Some((DbChange::Update(update.generic()), existing.id())))
-            } else {
-                Ok(Some((DbChange::None, existing.id())))
-            }
+        let update = obj.upsert(&existing);
+        let change = if update.is_dirty() {
+            update.apply(&self)?;
+            DbChange::Update(update.generic())
         } else {
-            obj.set_scoped(scoped);
-            obj.insert(&self)?;
-            let id = M::get_id(self, obj.value())?;
-            Ok(Some((DbChange::Insert, id)))
+            DbChange::None
+        };
+        M::seen(self, existing.id(), now, false)?;
+        Ok(Some((change, existing.id())))
+    }
+
+    /// Entities that aren't matched by any autonoscope rule inherit the scope
+    /// of the netblock they are part of
+    fn netblock_scope(&self, object: &Insert) -> Result<bool> {
+        let addr = match object {
+            Insert::IpAddr(ipaddr) => ipaddr.value.parse::<net::IpAddr>()?,
+            Insert::Port(port) => port.value.parse::<net::SocketAddr>()?.ip(),
+            _ => return Ok(true),
+        };
+
+        let scoped = Netblock::containing(self, addr)?
+            .map(|netblock| netblock.scoped())
+            .unwrap_or(true);
+        Ok(scoped)
+    }
+
+    pub fn insert_ipaddr_struct(&self, obj: NewIpAddr, scoped: bool) -> Result<Option<(DbChange, i32)>> {
+        let result = self.insert_struct(obj, scoped)?;
+        if let Some((DbChange::Insert, id)) = result {
+            IpAddr::by_id(self, id)?
+                .link_netblock(self)?;
+        }
+        Ok(result)
+    }
+
+    pub fn insert_netblock_struct(&self, obj: NewNetblock, scoped: bool) -> Result<Option<(DbChange, i32)>> {
+        let result = self.insert_struct(obj, scoped)?;
+        if let Some((DbChange::Insert, id)) = result {
+            Netblock::by_id(self, id)?
+                .link_ipaddrs(self)?;
         }
+        Ok(result)
     }
 
     pub fn insert_activity(&self, obj: NewActivity) -> Result<bool> {
@@ -225,21 +620,177 @@ impl Database {
         if let Some(subdomain_ipaddr_id) = SubdomainIpAddr::get_id_opt(self, &(subdomain_ipaddr.subdomain_id, subdomain_ipaddr.ip_addr_id))? {
             Ok(Some((DbChange::None, subdomain_ipaddr_id)))
         } else {
-            diesel::insert_into(subdomain_ipaddrs::table)
-                .values(subdomain_ipaddr)
-                .execute(&self.db)?;
+            insert_ignore!(&self.db, subdomain_ipaddrs::table, subdomain_ipaddr)?;
             let id = SubdomainIpAddr::get_id(self, &(subdomain_ipaddr.subdomain_id, subdomain_ipaddr.ip_addr_id))?;
             Ok(Some((DbChange::Insert, id)))
         }
     }
 
+    pub fn insert_subdomain_certificate_struct(&self, subdomain_certificate: &NewSubdomainCertificate) -> Result<Option<(DbChange, i32)>> {
+        if let Some(subdomain_certificate_id) = SubdomainCertificate::get_id_opt(self, &(subdomain_certificate.subdomain_id, subdomain_certificate.certificate_id))? {
+            Ok(Some((DbChange::None, subdomain_certificate_id)))
+        } else {
+            insert_ignore!(&self.db, subdomain_certificates::table, subdomain_certificate)?;
+            let id = SubdomainCertificate::get_id(self, &(subdomain_certificate.subdomain_id, subdomain_certificate.certificate_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_port_certificate_struct(&self, port_certificate: &NewPortCertificate) -> Result<Option<(DbChange, i32)>> {
+        if let Some(port_certificate_id) = PortCertificate::get_id_opt(self, &(port_certificate.port_id, port_certificate.certificate_id))? {
+            Ok(Some((DbChange::None, port_certificate_id)))
+        } else {
+            insert_ignore!(&self.db, port_certificates::table, port_certificate)?;
+            let id = PortCertificate::get_id(self, &(port_certificate.port_id, port_certificate.certificate_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_organization_domain_struct(&self, organization_domain: &NewOrganizationDomain) -> Result<Option<(DbChange, i32)>> {
+        if let Some(organization_domain_id) = OrganizationDomain::get_id_opt(self, &(organization_domain.organization_id, organization_domain.domain_id))? {
+            Ok(Some((DbChange::None, organization_domain_id)))
+        } else {
+            insert_ignore!(&self.db, organization_domains::table, organization_domain)?;
+            let id = OrganizationDomain::get_id(self, &(organization_domain.organization_id, organization_domain.domain_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_organization_email_struct(&self, organization_email: &NewOrganizationEmail) -> Result<Option<(DbChange, i32)>> {
+        if let Some(organization_email_id) = OrganizationEmail::get_id_opt(self, &(organization_email.organization_id, organization_email.email_id))? {
+            Ok(Some((DbChange::None, organization_email_id)))
+        } else {
+            insert_ignore!(&self.db, organization_emails::table, organization_email)?;
+            let id = OrganizationEmail::get_id(self, &(organization_email.organization_id, organization_email.email_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_organization_phonenumber_struct(&self, organization_phonenumber: &NewOrganizationPhoneNumber) -> Result<Option<(DbChange, i32)>> {
+        if let Some(organization_phonenumber_id) = OrganizationPhoneNumber::get_id_opt(self, &(organization_phonenumber.organization_id, organization_phonenumber.phone_number_id))? {
+            Ok(Some((DbChange::None, organization_phonenumber_id)))
+        } else {
+            insert_ignore!(&self.db, organization_phonenumbers::table, organization_phonenumber)?;
+            let id = OrganizationPhoneNumber::get_id(self, &(organization_phonenumber.organization_id, organization_phonenumber.phone_number_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_organization_account_struct(&self, organization_account: &NewOrganizationAccount) -> Result<Option<(DbChange, i32)>> {
+        if let Some(organization_account_id) = OrganizationAccount::get_id_opt(self, &(organization_account.organization_id, organization_account.account_id))? {
+            Ok(Some((DbChange::None, organization_account_id)))
+        } else {
+            insert_ignore!(&self.db, organization_accounts::table, organization_account)?;
+            let id = OrganizationAccount::get_id(self, &(organization_account.organization_id, organization_account.account_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_organization_netblock_struct(&self, organization_netblock: &NewOrganizationNetblock) -> Result<Option<(DbChange, i32)>> {
+        if let Some(organization_netblock_id) = OrganizationNetblock::get_id_opt(self, &(organization_netblock.organization_id, organization_netblock.netblock_id))? {
+            Ok(Some((DbChange::None, organization_netblock_id)))
+        } else {
+            insert_ignore!(&self.db, organization_netblocks::table, organization_netblock)?;
+            let id = OrganizationNetblock::get_id(self, &(organization_netblock.organization_id, organization_netblock.netblock_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_person_domain_struct(&self, person_domain: &NewPersonDomain) -> Result<Option<(DbChange, i32)>> {
+        if let Some(person_domain_id) = PersonDomain::get_id_opt(self, &(person_domain.person_id, person_domain.domain_id))? {
+            Ok(Some((DbChange::None, person_domain_id)))
+        } else {
+            insert_ignore!(&self.db, person_domains::table, person_domain)?;
+            let id = PersonDomain::get_id(self, &(person_domain.person_id, person_domain.domain_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_person_email_struct(&self, person_email: &NewPersonEmail) -> Result<Option<(DbChange, i32)>> {
+        if let Some(person_email_id) = PersonEmail::get_id_opt(self, &(person_email.person_id, person_email.email_id))? {
+            Ok(Some((DbChange::None, person_email_id)))
+        } else {
+            insert_ignore!(&self.db, person_emails::table, person_email)?;
+            let id = PersonEmail::get_id(self, &(person_email.person_id, person_email.email_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_person_phonenumber_struct(&self, person_phonenumber: &NewPersonPhoneNumber) -> Result<Option<(DbChange, i32)>> {
+        if let Some(person_phonenumber_id) = PersonPhoneNumber::get_id_opt(self, &(person_phonenumber.person_id, person_phonenumber.phone_number_id))? {
+            Ok(Some((DbChange::None, person_phonenumber_id)))
+        } else {
+            insert_ignore!(&self.db, person_phonenumbers::table, person_phonenumber)?;
+            let id = PersonPhoneNumber::get_id(self, &(person_phonenumber.person_id, person_phonenumber.phone_number_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_person_account_struct(&self, person_account: &NewPersonAccount) -> Result<Option<(DbChange, i32)>> {
+        if let Some(person_account_id) = PersonAccount::get_id_opt(self, &(person_account.person_id, person_account.account_id))? {
+            Ok(Some((DbChange::None, person_account_id)))
+        } else {
+            insert_ignore!(&self.db, person_accounts::table, person_account)?;
+            let id = PersonAccount::get_id(self, &(person_account.person_id, person_account.account_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_person_netblock_struct(&self, person_netblock: &NewPersonNetblock) -> Result<Option<(DbChange, i32)>> {
+        if let Some(person_netblock_id) = PersonNetblock::get_id_opt(self, &(person_netblock.person_id, person_netblock.netblock_id))? {
+            Ok(Some((DbChange::None, person_netblock_id)))
+        } else {
+            insert_ignore!(&self.db, person_netblocks::table, person_netblock)?;
+            let id = PersonNetblock::get_id(self, &(person_netblock.person_id, person_netblock.netblock_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_url_document_struct(&self, url_document: &NewUrlDocument) -> Result<Option<(DbChange, i32)>> {
+        if let Some(url_document_id) = UrlDocument::get_id_opt(self, &(url_document.url_id, url_document.document_id))? {
+            Ok(Some((DbChange::None, url_document_id)))
+        } else {
+            insert_ignore!(&self.db, url_documents::table, url_document)?;
+            let id = UrlDocument::get_id(self, &(url_document.url_id, url_document.document_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_domain_location_struct(&self, domain_location: &NewDomainLocation) -> Result<Option<(DbChange, i32)>> {
+        if let Some(domain_location_id) = DomainLocation::get_id_opt(self, &(domain_location.domain_id, domain_location.location_id))? {
+            Ok(Some((DbChange::None, domain_location_id)))
+        } else {
+            insert_ignore!(&self.db, domain_locations::table, domain_location)?;
+            let id = DomainLocation::get_id(self, &(domain_location.domain_id, domain_location.location_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_phonenumber_location_struct(&self, phonenumber_location: &NewPhoneNumberLocation) -> Result<Option<(DbChange, i32)>> {
+        if let Some(phonenumber_location_id) = PhoneNumberLocation::get_id_opt(self, &(phonenumber_location.phone_number_id, phonenumber_location.location_id))? {
+            Ok(Some((DbChange::None, phonenumber_location_id)))
+        } else {
+            insert_ignore!(&self.db, phonenumber_locations::table, phonenumber_location)?;
+            let id = PhoneNumberLocation::get_id(self, &(phonenumber_location.phone_number_id, phonenumber_location.location_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
+    pub fn insert_account_location_struct(&self, account_location: &NewAccountLocation) -> Result<Option<(DbChange, i32)>> {
+        if let Some(account_location_id) = AccountLocation::get_id_opt(self, &(account_location.account_id, account_location.location_id))? {
+            Ok(Some((DbChange::None, account_location_id)))
+        } else {
+            insert_ignore!(&self.db, account_locations::table, account_location)?;
+            let id = AccountLocation::get_id(self, &(account_location.account_id, account_location.location_id))?;
+            Ok(Some((DbChange::Insert, id)))
+        }
+    }
+
     pub fn insert_network_device_struct(&self, network_device: &NewNetworkDevice) -> Result<Option<(DbChange, i32)>> {
         if let Some(network_device_id) = NetworkDevice::get_id_opt(self, &(network_device.network_id, network_device.device_id))? {
             Ok(Some((DbChange::None, network_device_id)))
         } else {
-            diesel::insert_into(network_devices::table)
-                .values(network_device)
-                .execute(&self.db)?;
+            insert_ignore!(&self.db, network_devices::table, network_device)?;
             let id = NetworkDevice::get_id(self, &(network_device.network_id, network_device.device_id))?;
             Ok(Some((DbChange::Insert, id)))
         }
@@ -259,9 +810,7 @@ impl Database {
                 Ok(Some((DbChange::None, id)))
             }
         } else {
-            diesel::insert_into(breach_emails::table)
-                .values(obj)
-                .execute(&self.db)?;
+            insert_ignore!(&self.db, breach_emails::table, obj)?;
             let id = BreachEmail::get_id(self, value)?;
             Ok(Some((DbChange::Insert, id)))
         }
@@ -270,7 +819,9 @@ impl Database {
     //
 
     pub fn update_generic(&self, update: &Update) -> Result<i32> {
+        update.validate()?;
         match update {
+            Update::Domain(update) => self.update_domain(update),
             Update::Subdomain(update) => self.update_subdomain(update),
             Update::IpAddr(update) => self.update_ipaddr(update),
             Update::Url(update) => self.update_url(update),
@@ -285,121 +836,201 @@ impl Database {
             Update::Port(update) => self.update_port(update),
             Update::Netblock(update) => self.update_netblock(update),
             Update::CryptoAddr(update) => self.update_cryptoaddr(update),
+            Update::DnsRecord(update) => self.update_dns_record(update),
+            Update::Organization(update) => self.update_organization(update),
+            Update::Person(update) => self.update_person(update),
+            Update::AutonomousSystem(update) => self.update_autonomous_system(update),
+            Update::Document(update) => self.update_document(update),
+            Update::Location(update) => self.update_location(update),
+            Update::Breach(update) => self.update_breach(update),
+            Update::Certificate(update) => self.update_certificate(update),
         }
     }
 
+    pub fn update_domain(&self, domain_update: &DomainUpdate) -> Result<i32> {
+        use crate::schema::domains::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(domains::table.filter(id.eq(domain_update.id)))
+            .set(domain_update)
+            .execute(conn))?;
+        Ok(domain_update.id)
+    }
+
     pub fn update_subdomain(&self, subdomain_update: &SubdomainUpdate) -> Result<i32> {
         use crate::schema::subdomains::columns::*;
-        diesel::update(subdomains::table.filter(id.eq(subdomain_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(subdomains::table.filter(id.eq(subdomain_update.id)))
             .set(subdomain_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(subdomain_update.id)
     }
 
     pub fn update_ipaddr(&self, ipaddr_update: &IpAddrUpdate) -> Result<i32> {
         use crate::schema::ipaddrs::columns::*;
-        diesel::update(ipaddrs::table.filter(id.eq(ipaddr_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(ipaddrs::table.filter(id.eq(ipaddr_update.id)))
             .set(ipaddr_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(ipaddr_update.id)
     }
 
     pub fn update_url(&self, url_update: &UrlUpdate) -> Result<i32> {
         use crate::schema::urls::columns::*;
-        diesel::update(urls::table.filter(id.eq(url_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(urls::table.filter(id.eq(url_update.id)))
             .set(url_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(url_update.id)
     }
 
     pub fn update_email(&self, email_update: &EmailUpdate) -> Result<i32> {
         use crate::schema::emails::columns::*;
-        diesel::update(emails::table.filter(id.eq(email_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(emails::table.filter(id.eq(email_update.id)))
             .set(email_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(email_update.id)
     }
 
     pub fn update_phonenumber(&self, phonenumber_update: &PhoneNumberUpdate) -> Result<i32> {
         use crate::schema::phonenumbers::columns::*;
-        diesel::update(phonenumbers::table.filter(id.eq(phonenumber_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(phonenumbers::table.filter(id.eq(phonenumber_update.id)))
             .set(phonenumber_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(phonenumber_update.id)
     }
 
     pub fn update_device(&self, device_update: &DeviceUpdate) -> Result<i32> {
         use crate::schema::devices::columns::*;
-        diesel::update(devices::table.filter(id.eq(device_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(devices::table.filter(id.eq(device_update.id)))
             .set(device_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(device_update.id)
     }
 
     pub fn update_network(&self, network_update: &NetworkUpdate) -> Result<i32> {
         use crate::schema::networks::columns::*;
-        diesel::update(networks::table.filter(id.eq(network_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(networks::table.filter(id.eq(network_update.id)))
             .set(network_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(network_update.id)
     }
 
     pub fn update_network_device(&self, network_device_update: &NetworkDeviceUpdate) -> Result<i32> {
         use crate::schema::network_devices::columns::*;
-        diesel::update(network_devices::table.filter(id.eq(network_device_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(network_devices::table.filter(id.eq(network_device_update.id)))
             .set(network_device_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(network_device_update.id)
     }
 
     pub fn update_account(&self, account_update: &AccountUpdate) -> Result<i32> {
         use crate::schema::accounts::columns::*;
-        diesel::update(accounts::table.filter(id.eq(account_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(accounts::table.filter(id.eq(account_update.id)))
             .set(account_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(account_update.id)
     }
 
     pub fn update_breach_email(&self, breach_email_update: &BreachEmailUpdate) -> Result<i32> {
         use crate::schema::breach_emails::columns::*;
-        diesel::update(breach_emails::table.filter(id.eq(breach_email_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(breach_emails::table.filter(id.eq(breach_email_update.id)))
             .set(breach_email_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(breach_email_update.id)
     }
 
     pub fn update_image(&self, image_update: &ImageUpdate) -> Result<i32> {
         use crate::schema::images::columns::*;
-        diesel::update(images::table.filter(id.eq(image_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(images::table.filter(id.eq(image_update.id)))
             .set(image_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(image_update.id)
     }
 
     pub fn update_port(&self, port_update: &PortUpdate) -> Result<i32> {
         use crate::schema::ports::columns::*;
-        diesel::update(ports::table.filter(id.eq(port_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(ports::table.filter(id.eq(port_update.id)))
             .set(port_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(port_update.id)
     }
 
     pub fn update_netblock(&self, netblock_update: &NetblockUpdate) -> Result<i32> {
         use crate::schema::netblocks::columns::*;
-        diesel::update(netblocks::table.filter(id.eq(netblock_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(netblocks::table.filter(id.eq(netblock_update.id)))
             .set(netblock_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(netblock_update.id)
     }
 
     pub fn update_cryptoaddr(&self, cryptoaddr_update: &CryptoAddrUpdate) -> Result<i32> {
         use crate::schema::cryptoaddrs::columns::*;
-        diesel::update(cryptoaddrs::table.filter(id.eq(cryptoaddr_update.id)))
+        with_conn!(&self.db, |conn| diesel::update(cryptoaddrs::table.filter(id.eq(cryptoaddr_update.id)))
             .set(cryptoaddr_update)
-            .execute(&self.db)?;
+            .execute(conn))?;
         Ok(cryptoaddr_update.id)
     }
 
+    pub fn update_dns_record(&self, dns_record_update: &DnsRecordUpdate) -> Result<i32> {
+        use crate::schema::dns_records::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(dns_records::table.filter(id.eq(dns_record_update.id)))
+            .set(dns_record_update)
+            .execute(conn))?;
+        Ok(dns_record_update.id)
+    }
+
+    pub fn update_organization(&self, organization_update: &OrganizationUpdate) -> Result<i32> {
+        use crate::schema::organizations::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(organizations::table.filter(id.eq(organization_update.id)))
+            .set(organization_update)
+            .execute(conn))?;
+        Ok(organization_update.id)
+    }
+
+    pub fn update_person(&self, person_update: &PersonUpdate) -> Result<i32> {
+        use crate::schema::persons::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(persons::table.filter(id.eq(person_update.id)))
+            .set(person_update)
+            .execute(conn))?;
+        Ok(person_update.id)
+    }
+
+    pub fn update_autonomous_system(&self, autonomous_system_update: &AutonomousSystemUpdate) -> Result<i32> {
+        use crate::schema::autonomous_systems::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(autonomous_systems::table.filter(id.eq(autonomous_system_update.id)))
+            .set(autonomous_system_update)
+            .execute(conn))?;
+        Ok(autonomous_system_update.id)
+    }
+
+    pub fn update_document(&self, document_update: &DocumentUpdate) -> Result<i32> {
+        use crate::schema::documents::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(documents::table.filter(id.eq(document_update.id)))
+            .set(document_update)
+            .execute(conn))?;
+        Ok(document_update.id)
+    }
+
+    pub fn update_location(&self, location_update: &LocationUpdate) -> Result<i32> {
+        use crate::schema::locations::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(locations::table.filter(id.eq(location_update.id)))
+            .set(location_update)
+            .execute(conn))?;
+        Ok(location_update.id)
+    }
+
+    pub fn update_breach(&self, breach_update: &BreachUpdate) -> Result<i32> {
+        use crate::schema::breaches::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(breaches::table.filter(id.eq(breach_update.id)))
+            .set(breach_update)
+            .execute(conn))?;
+        Ok(breach_update.id)
+    }
+
+    pub fn update_certificate(&self, certificate_update: &CertificateUpdate) -> Result<i32> {
+        use crate::schema::certificates::columns::*;
+        with_conn!(&self.db, |conn| diesel::update(certificates::table.filter(id.eq(certificate_update.id)))
+            .set(certificate_update)
+            .execute(conn))?;
+        Ok(certificate_update.id)
+    }
+
     fn get_opt_typed<T: Model + Scopable>(&self, value: &T::ID) -> Result<Option<i32>> {
         match T::get_opt(self, &value)? {
             Some(ref obj) if obj.scoped() => Ok(Some(obj.id())),
@@ -426,6 +1057,29 @@ impl Database {
             Family::Port => self.get_opt_typed::<Port>(&value),
             Family::Netblock => self.get_opt_typed::<Netblock>(&value),
             Family::Cryptoaddr => self.get_opt_typed::<CryptoAddr>(&value),
+            Family::DnsRecord => self.get_opt_typed::<DnsRecord>(&value),
+            Family::Certificate => self.get_opt_typed::<Certificate>(&value),
+            Family::SubdomainCertificate => bail!("Unsupported operation"),
+            Family::PortCertificate => bail!("Unsupported operation"),
+            Family::Organization => self.get_opt_typed::<Organization>(&value),
+            Family::Person => self.get_opt_typed::<Person>(&value),
+            Family::OrganizationDomain => bail!("Unsupported operation"),
+            Family::OrganizationEmail => bail!("Unsupported operation"),
+            Family::OrganizationPhonenumber => bail!("Unsupported operation"),
+            Family::OrganizationAccount => bail!("Unsupported operation"),
+            Family::OrganizationNetblock => bail!("Unsupported operation"),
+            Family::PersonDomain => bail!("Unsupported operation"),
+            Family::PersonEmail => bail!("Unsupported operation"),
+            Family::PersonPhonenumber => bail!("Unsupported operation"),
+            Family::PersonAccount => bail!("Unsupported operation"),
+            Family::PersonNetblock => bail!("Unsupported operation"),
+            Family::AutonomousSystem => self.get_opt_typed::<AutonomousSystem>(&value.parse()?),
+            Family::Document => self.get_opt_typed::<Document>(&value),
+            Family::UrlDocument => bail!("Unsupported operation"),
+            Family::Location => self.get_opt_typed::<Location>(&value),
+            Family::DomainLocation => bail!("Unsupported operation"),
+            Family::PhonenumberLocation => bail!("Unsupported operation"),
+            Family::AccountLocation => bail!("Unsupported operation"),
         }
     }
 
@@ -436,10 +1090,11 @@ impl Database {
     }
 
     pub fn filter<T: Model>(&self, filter: &Filter) -> Result<Vec<T>> {
-        T::filter(self, filter)
+        T::filter(self, &filter.for_model::<T>(self.db.backend()))
     }
 
     pub fn filter_with_param<T: Model>(&self, filter: &Filter, param: Option<&String>) -> Result<Vec<T>> {
+        let filter = &filter.for_model::<T>(self.db.backend());
         match param {
             Some(param) => T::filter_with_param(self, filter, param),
             _ => T::filter(self, filter),
@@ -447,18 +1102,80 @@ impl Database {
     }
 }
 
+const TAG_FAMILY_PLACEHOLDER: &str = "$family";
+const EXTRA_PREFIX: &str = "extra.";
+const BOOL_COLUMNS: &[&str] = &["unscoped", "verified"];
+
+/// A part of a filter, most of it is passed to the database as is but some
+/// expressions are written differently depending on the backend
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Sql(String),
+    /// Case insensitive in sqlite, postgres needs ILIKE
+    Like(String),
+    /// Custom attribute, casted to a number in postgres if compared with one
+    Extra {
+        path: String,
+        numeric: bool,
+    },
+    Bool(bool),
+}
+
+impl Token {
+    fn render(&self, backend: Backend) -> String {
+        match (self, backend) {
+            (Token::Sql(sql), _) => sql.to_string(),
+            (Token::Like(like), Backend::Sqlite) => like.to_string(),
+            (Token::Extra { path, .. }, Backend::Sqlite) => {
+                format!("json_extract(extra, {})", Filter::escape(&format!("$.{}", path)))
+            },
+            (Token::Bool(value), Backend::Sqlite) => if *value { "1" } else { "0" }.to_string(),
+            #[cfg(feature="postgres")]
+            (Token::Like(_), Backend::Postgres) => String::from("ILIKE"),
+            #[cfg(feature="postgres")]
+            (Token::Extra { path, numeric }, Backend::Postgres) => {
+                let path = path.split('.')
+                    .map(Filter::escape)
+                    .collect::<Vec<_>>();
+                let value = format!("(CAST(extra AS JSONB) #>> ARRAY[{}])", path.join(", "));
+                if *numeric {
+                    format!("CAST({} AS NUMERIC)", value)
+                } else {
+                    value
+                }
+            },
+            #[cfg(feature="postgres")]
+            (Token::Bool(value), Backend::Postgres) => if *value { "TRUE" } else { "FALSE" }.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Filter {
-    query: String,
+    tokens: Vec<Token>,
 }
 
 impl Filter {
     pub fn new<I: Into<String>>(query: I) -> Filter {
         Filter {
-            query: query.into(),
+            tokens: vec![Token::Sql(query.into())],
         }
     }
 
+    fn push(&mut self, token: Token) {
+        if let Token::Sql(sql) = &token {
+            if let Some(Token::Sql(last)) = self.tokens.last_mut() {
+                last.push_str(sql);
+                return;
+            }
+        }
+        self.tokens.push(token);
+    }
+
+    fn push_sql(&mut self, sql: &str) {
+        self.push(Token::Sql(sql.to_string()));
+    }
+
     fn escape(value: &str) -> String {
         let mut out = String::from("'");
         for c in value.chars() {
@@ -484,36 +1201,138 @@ impl Filter {
             bail!("Filter must begin with WHERE");
         }
 
-        let mut query = String::new();
+        let mut filter = Filter {
+            tokens: Vec::new(),
+        };
 
         let mut expect_value = false;
+        let mut key = None;
+        let mut expect_tag_op = false;
+        let mut tag_op = None;
 
         for arg in args {
-            if ["=", "!=", "<", ">", "<=", ">=", "like"].contains(&arg.to_lowercase().as_str()) {
+            if expect_tag_op {
+                match arg.as_str() {
+                    "=" => tag_op = Some(true),
+                    "!=" => tag_op = Some(false),
+                    _ => bail!("Tags can only be compared with = or !="),
+                }
+                expect_tag_op = false;
+                continue;
+            }
+
+            if let Some(included) = tag_op.take() {
+                filter.push_sql(&Self::tag_condition(included, arg));
+                continue;
+            }
+
+            if !expect_value && arg.to_lowercase() == "tag" {
+                expect_tag_op = true;
+                continue;
+            }
+
+            if arg.to_lowercase() == "like" {
                 expect_value = true;
-                query += &format!(" {}", arg);
+                filter.push_sql(" ");
+                filter.push(Token::Like(arg.to_string()));
+                continue;
+            }
+
+            if ["=", "!=", "<", ">", "<=", ">="].contains(&arg.as_str()) {
+                expect_value = true;
+                filter.push_sql(&format!(" {}", arg));
                 continue;
             }
 
             if let Some(idx) = arg.find('=') {
                 if idx != 0 {
                     let (key, value) = arg.split_at(idx);
-                    query += &format!(" {} = {}", key, Self::escape(&value[1..]));
+                    if key.to_lowercase() == "tag" {
+                        filter.push_sql(&Self::tag_condition(true, &value[1..]));
+                    } else {
+                        filter.push_sql(" ");
+                        let column = filter.push_column(key);
+                        filter.push_sql(" =");
+                        filter.push_literal(&value[1..], column);
+                    }
                     continue;
                 }
             }
 
             if expect_value {
-                query.push(' ');
-                query.push_str(&Self::escape(arg));
+                filter.push_literal(arg, key.take());
                 expect_value = false;
             } else {
-                query += &format!(" {}", arg);
+                filter.push_sql(" ");
+                key = filter.push_column(arg);
+            }
+        }
+
+        if expect_tag_op || tag_op.is_some() {
+            bail!("Tag filter is incomplete");
+        }
+        debug!("Parsed query: {:?}", filter);
+
+        Ok(filter)
+    }
+
+    fn tag_condition(included: bool, tag: &str) -> String {
+        format!(" id {} (SELECT entity_id FROM tags WHERE family = {} AND value = {})",
+            if included { "IN" } else { "NOT IN" },
+            TAG_FAMILY_PLACEHOLDER,
+            Self::escape(tag))
+    }
+
+    /// Custom attributes are referenced with `extra.key`, returns the index of
+    /// the token if the value it's compared with needs special treatment
+    fn push_column(&mut self, key: &str) -> Option<usize> {
+        if key.starts_with(EXTRA_PREFIX) {
+            self.push(Token::Extra {
+                path: key[EXTRA_PREFIX.len()..].to_string(),
+                numeric: false,
+            });
+            Some(self.tokens.len() - 1)
+        } else if BOOL_COLUMNS.contains(&key.to_lowercase().as_str()) {
+            self.push_sql(key);
+            Some(self.tokens.len() - 1)
+        } else {
+            self.push_sql(key);
+            None
+        }
+    }
+
+    /// Custom attributes don't have a column type, numbers need to be passed as
+    /// numbers. Booleans are integers in sqlite, but not in postgres.
+    fn push_literal(&mut self, value: &str, column: Option<usize>) {
+        let column = column.map(|idx| &mut self.tokens[idx]);
+
+        if let Some(Token::Extra { numeric, .. }) = column {
+            if let Ok(num) = value.parse::<i64>() {
+                *numeric = true;
+                self.push_sql(&format!(" {}", num));
+                return;
+            }
+            if let Ok(num) = value.parse::<f64>() {
+                if num.is_finite() {
+                    *numeric = true;
+                    self.push_sql(&format!(" {}", num));
+                    return;
+                }
+            }
+        } else if column.is_some() {
+            let value = match value.to_lowercase().as_str() {
+                "1" | "true" => Some(true),
+                "0" | "false" => Some(false),
+                _ => None,
+            };
+            if let Some(value) = value {
+                self.push_sql(" ");
+                self.push(Token::Bool(value));
+                return;
             }
         }
-        debug!("Parsed query: {:?}", query);
 
-        Ok(Filter::new(query))
+        self.push_sql(&format!(" {}", Self::escape(value)));
     }
 
     pub fn parse_optional(args: &[String]) -> Result<Filter> {
@@ -521,23 +1340,65 @@ impl Filter {
 
         if args.is_empty() {
             debug!("Using filter with no condition");
-            return Ok(Filter::new("1"));
+            return Ok(Filter::new("1=1"));
         }
 
         Self::parse(args)
     }
 
-    pub fn query(&self) -> &str {
-        &self.query
+    /// Fill in the family that tag conditions are matched against and write
+    /// the query in the dialect of the database
+    pub fn for_model<T: Model>(&self, backend: Backend) -> Filter {
+        let family = match T::family() {
+            Some(family) => Self::escape(family.as_str()),
+            None => String::from("NULL"),
+        };
+
+        let mut query = String::new();
+        for token in &self.tokens {
+            let sql = match token {
+                Token::Sql(sql) => sql,
+                token => {
+                    query.push_str(&token.render(backend));
+                    continue;
+                },
+            };
+
+            let mut quoted = false;
+            let mut rest = sql.as_str();
+            while let Some(c) = rest.chars().next() {
+                if !quoted && rest.starts_with(TAG_FAMILY_PLACEHOLDER) {
+                    query.push_str(&family);
+                    rest = &rest[TAG_FAMILY_PLACEHOLDER.len()..];
+                    continue;
+                }
+
+                if c == '\'' {
+                    quoted = !quoted;
+                }
+                query.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+
+        Filter::new(query)
     }
 
     pub fn and_scoped(&self) -> Filter {
-        let query = format!("({}) AND unscoped=0", self.query);
-        Filter::new(query)
+        let mut filter = Filter::new("(");
+        for token in &self.tokens {
+            filter.push(token.clone());
+        }
+        filter.push_sql(") AND NOT unscoped");
+        filter
     }
 
+    /// The filter needs to be passed through `for_model` first
     pub fn sql(&self) -> SqlLiteral<Bool> {
-        sql::<Bool>(&self.query)
+        let query = self.tokens.iter()
+            .map(|token| token.render(Backend::Sqlite))
+            .collect::<String>();
+        sql::<Bool>(&query)
     }
 }
 
@@ -593,7 +1454,7 @@ mod tests {
                                      "like".to_string(),
                                      "%foobar".to_string(),
                                     ]).unwrap();
-        assert_eq!(filter, Filter::new(" value like '%foobar'"));
+        assert_eq!(filter.for_model::<Domain>(Backend::Sqlite), Filter::new(" value like '%foobar'"));
     }
 
     #[test]
@@ -672,6 +1533,123 @@ mod tests {
         assert_eq!(filter, Filter::new(" value <= '123'"));
     }
 
+    #[test]
+    fn test_filter_tag1() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "tag=interesting".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter, Filter::new(" id IN (SELECT entity_id FROM tags WHERE family = $family AND value = 'interesting')"));
+    }
+
+    #[test]
+    fn test_filter_tag2() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "tag".to_string(),
+                                     "!=".to_string(),
+                                     "boring".to_string(),
+                                     "and".to_string(),
+                                     "value".to_string(),
+                                     "like".to_string(),
+                                     "%.com".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.for_model::<Domain>(Backend::Sqlite), Filter::new(" id NOT IN (SELECT entity_id FROM tags WHERE family = 'domain' AND value = 'boring') and value like '%.com'"));
+    }
+
+    #[test]
+    fn test_filter_tag_invalid_op() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "tag".to_string(),
+                                     "like".to_string(),
+                                     "foo%".to_string(),
+                                    ]);
+        assert!(filter.is_err());
+    }
+
+    #[test]
+    fn test_filter_tag_incomplete() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "tag".to_string(),
+                                     "=".to_string(),
+                                    ]);
+        assert!(filter.is_err());
+    }
+
+    #[test]
+    fn test_filter_tag_for_model() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "value=$family".to_string(),
+                                     "and".to_string(),
+                                     "tag=it's".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.for_model::<DnsRecord>(Backend::Sqlite), Filter::new(" value = '$family' and id IN (SELECT entity_id FROM tags WHERE family = 'dns-record' AND value = 'it''s')"));
+        assert_eq!(filter.for_model::<SubdomainIpAddr>(Backend::Sqlite), Filter::new(" value = '$family' and id IN (SELECT entity_id FROM tags WHERE family = NULL AND value = 'it''s')"));
+    }
+
+    #[test]
+    fn test_filter_extra() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "extra.server=nginx".to_string(),
+                                     "and".to_string(),
+                                     "extra.http.status".to_string(),
+                                     "!=".to_string(),
+                                     "404".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.for_model::<Domain>(Backend::Sqlite), Filter::new(" json_extract(extra, '$.server') = 'nginx' and json_extract(extra, '$.http.status') != 404"));
+    }
+
+    #[test]
+    fn test_filter_extra_escape() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "extra.it's".to_string(),
+                                     ">=".to_string(),
+                                     "NaN".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.for_model::<Domain>(Backend::Sqlite), Filter::new(" json_extract(extra, '$.it''s') >= 'NaN'"));
+    }
+
+    #[test]
+    fn test_filter_bool() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "verified=true".to_string(),
+                                     "and".to_string(),
+                                     "unscoped".to_string(),
+                                     "!=".to_string(),
+                                     "1".to_string(),
+                                     "and".to_string(),
+                                     "value=1".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.for_model::<Domain>(Backend::Sqlite), Filter::new(" verified = 1 and unscoped != 1 and value = '1'"));
+    }
+
+    #[test]
+    #[cfg(feature="postgres")]
+    fn test_filter_postgres() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "extra.server=nginx".to_string(),
+                                     "and".to_string(),
+                                     "extra.http.status".to_string(),
+                                     ">=".to_string(),
+                                     "400".to_string(),
+                                     "and".to_string(),
+                                     "value".to_string(),
+                                     "like".to_string(),
+                                     "%.com".to_string(),
+                                     "and".to_string(),
+                                     "verified=0".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.for_model::<Domain>(Backend::Postgres), Filter::new(" (CAST(extra AS JSONB) #>> ARRAY['server']) = 'nginx' and CAST((CAST(extra AS JSONB) #>> ARRAY['http', 'status']) AS NUMERIC) >= 400 and value ILIKE '%.com' and verified = FALSE"));
+    }
+
+    #[test]
+    fn test_filter_scoped() {
+        let filter = Filter::parse(&["where".to_string(),
+                                     "value".to_string(),
+                                     "like".to_string(),
+                                     "%.com".to_string(),
+                                    ]).unwrap();
+        assert_eq!(filter.and_scoped().for_model::<Domain>(Backend::Sqlite), Filter::new("( value like '%.com') AND NOT unscoped"));
+    }
+
     #[test]
     fn test_family_names() {
         assert_eq!(Family::Domain.as_str(),             "domain");
@@ -712,4 +1690,197 @@ mod tests {
         assert_eq!(Table::Ports.as_str(),               "ports");
         assert_eq!(Table::Netblocks.as_str(),           "netblocks");
     }
+
+    #[test]
+    fn test_cryptoaddr_names() {
+        use super::Table;
+        assert_eq!(Family::Cryptoaddr.as_str(),         "cryptoaddr");
+        assert_eq!(Table::Cryptoaddrs.as_str(),         "cryptoaddrs");
+    }
+
+    #[test]
+    fn test_dns_record_names() {
+        use super::Table;
+        assert_eq!(Family::DnsRecord.as_str(),          "dns-record");
+        assert_eq!(Table::DnsRecords.as_str(),          "dns_records");
+    }
+
+    #[test]
+    fn test_certificate_names() {
+        use super::Table;
+        assert_eq!(Family::Certificate.as_str(),        "certificate");
+        assert_eq!(Family::SubdomainCertificate.as_str(), "subdomain-certificate");
+        assert_eq!(Family::PortCertificate.as_str(),    "port-certificate");
+        assert_eq!(Table::Certificates.as_str(),        "certificates");
+        assert_eq!(Table::SubdomainCertificates.as_str(), "subdomain_certificates");
+        assert_eq!(Table::PortCertificates.as_str(),    "port_certificates");
+    }
+
+    #[test]
+    fn test_owner_names() {
+        use super::Table;
+        assert_eq!(Family::Organization.as_str(),       "organization");
+        assert_eq!(Family::Person.as_str(),             "person");
+        assert_eq!(Table::Organizations.as_str(),       "organizations");
+        assert_eq!(Table::Persons.as_str(),             "persons");
+    }
+
+    #[test]
+    fn test_autonomous_system_names() {
+        use super::Table;
+        assert_eq!(Family::AutonomousSystem.as_str(),   "autonomous-system");
+        assert_eq!(Table::AutonomousSystems.as_str(),   "autonomous_systems");
+    }
+
+    #[test]
+    fn test_document_names() {
+        use super::Table;
+        assert_eq!(Family::Document.as_str(),           "document");
+        assert_eq!(Family::UrlDocument.as_str(),        "url-document");
+        assert_eq!(Table::Documents.as_str(),           "documents");
+        assert_eq!(Table::UrlDocuments.as_str(),        "url_documents");
+    }
+
+    #[test]
+    fn test_location_names() {
+        use super::Table;
+        assert_eq!(Family::Location.as_str(),           "location");
+        assert_eq!(Family::DomainLocation.as_str(),     "domain-location");
+        assert_eq!(Family::PhonenumberLocation.as_str(), "phonenumber-location");
+        assert_eq!(Family::AccountLocation.as_str(),    "account-location");
+        assert_eq!(Table::Locations.as_str(),           "locations");
+        assert_eq!(Table::DomainLocations.as_str(),     "domain_locations");
+        assert_eq!(Table::PhonenumberLocations.as_str(), "phonenumber_locations");
+        assert_eq!(Table::AccountLocations.as_str(),    "account_locations");
+    }
+
+    #[test]
+    fn test_owner_relation_family_names() {
+        use super::Family;
+        assert_eq!(Family::OrganizationDomain.as_str(), "organization-domain");
+        assert_eq!(Family::OrganizationEmail.as_str(),  "organization-email");
+        assert_eq!(Family::OrganizationPhonenumber.as_str(), "organization-phonenumber");
+        assert_eq!(Family::OrganizationAccount.as_str(), "organization-account");
+        assert_eq!(Family::OrganizationNetblock.as_str(), "organization-netblock");
+        assert_eq!(Family::PersonDomain.as_str(),       "person-domain");
+        assert_eq!(Family::PersonEmail.as_str(),        "person-email");
+        assert_eq!(Family::PersonPhonenumber.as_str(),  "person-phonenumber");
+        assert_eq!(Family::PersonAccount.as_str(),      "person-account");
+        assert_eq!(Family::PersonNetblock.as_str(),     "person-netblock");
+    }
+
+    #[test]
+    fn test_owner_relation_table_names() {
+        use super::Table;
+        assert_eq!(Table::OrganizationDomains.as_str(), "organization_domains");
+        assert_eq!(Table::OrganizationEmails.as_str(),  "organization_emails");
+        assert_eq!(Table::OrganizationPhonenumbers.as_str(), "organization_phonenumbers");
+        assert_eq!(Table::OrganizationAccounts.as_str(), "organization_accounts");
+        assert_eq!(Table::OrganizationNetblocks.as_str(), "organization_netblocks");
+        assert_eq!(Table::PersonDomains.as_str(),       "person_domains");
+        assert_eq!(Table::PersonEmails.as_str(),        "person_emails");
+        assert_eq!(Table::PersonPhonenumbers.as_str(),  "person_phonenumbers");
+        assert_eq!(Table::PersonAccounts.as_str(),      "person_accounts");
+        assert_eq!(Table::PersonNetblocks.as_str(),     "person_netblocks");
+    }
+
+    fn insert_netblock(db: &Database, value: &str, asn: Option<i32>) -> i32 {
+        let (_, id) = db.insert_generic(Insert::Netblock(NewNetblock {
+            family: String::from("4"),
+            value: value.to_string(),
+            unscoped: false,
+            asn,
+            as_org: None,
+            description: None,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        })).unwrap().unwrap();
+        id
+    }
+
+    fn insert_ipaddr(db: &Database, value: &str) -> IpAddr {
+        let (_, id) = db.insert_generic(Insert::IpAddr(NewIpAddr {
+            family: String::from("4"),
+            value: value.to_string(),
+            continent: None,
+            continent_code: None,
+            country: None,
+            country_code: None,
+            city: None,
+            latitude: None,
+            longitude: None,
+            asn: None,
+            as_org: None,
+            description: None,
+            reverse_dns: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        })).unwrap().unwrap();
+        IpAddr::by_id(db, id).unwrap()
+    }
+
+    fn insert_port(db: &Database, ipaddr: &IpAddr, port: i32) -> Port {
+        let (_, id) = db.insert_generic(Insert::Port(NewPort {
+            ip_addr_id: ipaddr.id,
+            value: format!("{}:{}", ipaddr.value, port),
+            ip_addr: ipaddr.value.clone(),
+            port,
+            protocol: String::from("tcp"),
+            status: None,
+            banner: None,
+            service: None,
+            version: None,
+            unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        })).unwrap().unwrap();
+        Port::by_id(db, id).unwrap()
+    }
+
+    #[test]
+    fn test_link_netblocks() {
+        let db = Database::in_memory().unwrap();
+        let outer = insert_netblock(&db, "10.0.0.0/8", Some(64500));
+        let a = insert_ipaddr(&db, "10.1.2.3");
+        let b = insert_ipaddr(&db, "10.9.9.9");
+        let c = insert_ipaddr(&db, "192.0.2.1");
+        assert_eq!(a.netblock_id, Some(outer));
+        assert_eq!(b.netblock_id, Some(outer));
+        assert_eq!(c.netblock_id, None);
+        // the asn of the netblock isn't copied
+        assert_eq!(a.asn, None);
+
+        let inner = insert_netblock(&db, "10.1.0.0/16", None);
+        assert_eq!(IpAddr::by_id(&db, a.id).unwrap().netblock_id, Some(inner));
+        assert_eq!(IpAddr::by_id(&db, b.id).unwrap().netblock_id, Some(outer));
+
+        Netblock::delete_id(&db, inner).unwrap();
+        assert_eq!(IpAddr::by_id(&db, a.id).unwrap().netblock_id, Some(outer));
+
+        Netblock::delete(&db, &Filter::new("value = '10.0.0.0/8'")).unwrap();
+        assert_eq!(IpAddr::by_id(&db, a.id).unwrap().netblock_id, None);
+        assert_eq!(IpAddr::link_all_netblocks(&db).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_netblock_scope() {
+        let mut db = Database::in_memory().unwrap();
+        let netblock = insert_netblock(&db, "10.1.0.0/16", None);
+        Netblock::noscope(&db, &Filter::new(format!("id = {}", netblock))).unwrap();
+
+        let ipaddr = insert_ipaddr(&db, "10.1.2.3");
+        assert!(ipaddr.unscoped);
+        assert!(insert_port(&db, &ipaddr, 443).unscoped);
+        assert!(!insert_ipaddr(&db, "192.0.2.1").unscoped);
+
+        // autonoscope rules take precedence over the netblock
+        db.autonoscope_add_rule(&RuleType::Ip, "10.1.2.0/24", true).unwrap();
+        let ipaddr = insert_ipaddr(&db, "10.1.2.4");
+        assert!(!ipaddr.unscoped);
+        assert!(!insert_port(&db, &ipaddr, 443).unscoped);
+    }
 }
@@ -0,0 +1,125 @@
+use crate::errors::*;
+use crate::db::{Database, DatabaseSock};
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Double, Integer, Text};
+
+
+/// An entity matching a search query
+///
+/// The index is maintained by triggers on every entity table, the family is
+/// the name that is also used by `select`.
+#[derive(QueryableByName, Debug, PartialEq)]
+pub struct SearchResult {
+    #[sql_type="Text"]
+    pub family: String,
+    #[sql_type="Integer"]
+    pub entity_id: i32,
+    #[sql_type="Double"]
+    pub rank: f64,
+}
+
+/// Search the textual fields of all entities, the best matches come first
+pub fn search(db: &Database, query: &str, limit: i64) -> Result<Vec<SearchResult>> {
+    match db.db() {
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT family, entity_id, rank FROM search
+            WHERE search MATCH ? ORDER BY rank LIMIT ?")
+            .bind::<Text, _>(fts_query(query)?)
+            .bind::<BigInt, _>(limit)
+            .load::<SearchResult>(conn),
+        // every table has an indexed search_vector column
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT family, entity_id,
+            CAST(ts_rank(search_vector, to_tsquery('simple', $1)) AS DOUBLE PRECISION) AS rank
+            FROM search
+            WHERE search_vector @@ to_tsquery('simple', $1)
+            ORDER BY rank DESC LIMIT $2")
+            .bind::<Text, _>(tsquery(query)?)
+            .bind::<BigInt, _>(limit)
+            .load::<SearchResult>(conn),
+    }
+        .map_err(Error::from)
+}
+
+/// Turn user input into an fts query that can't fail to parse
+///
+/// Every word is quoted and matched as prefix, all words need to match.
+fn fts_query(query: &str) -> Result<String> {
+    let words = query.split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        bail!("Search query can't be empty");
+    }
+
+    Ok(words.join(" "))
+}
+
+/// Turn user input into a postgres tsquery that can't fail to parse
+///
+/// Punctuation is a word boundary in the index, so words containing punctuation
+/// become a phrase of prefixes, all words need to match.
+#[cfg(any(feature="postgres", test))]
+fn tsquery(query: &str) -> Result<String> {
+    let words = query.split_whitespace()
+        .filter_map(|word| {
+            let parts = word.split(|c: char| !c.is_alphanumeric())
+                .filter(|part| !part.is_empty())
+                .map(|part| format!("{}:*", part))
+                .collect::<Vec<_>>();
+
+            if parts.is_empty() {
+                None
+            } else {
+                Some(format!("({})", parts.join(" <-> ")))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        bail!("Search query can't be empty");
+    }
+
+    Ok(words.join(" & "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("acme").unwrap(), "\"acme\"*");
+        assert_eq!(fts_query("  acme  corp ").unwrap(), "\"acme\"* \"corp\"*");
+    }
+
+    #[test]
+    fn test_fts_query_escape() {
+        assert_eq!(fts_query("acme.com").unwrap(), "\"acme.com\"*");
+        assert_eq!(fts_query("\"OR 1").unwrap(), "\"\"\"OR\"* \"1\"*");
+    }
+
+    #[test]
+    fn test_fts_query_empty() {
+        assert!(fts_query("   ").is_err());
+    }
+
+    #[test]
+    fn test_tsquery() {
+        assert_eq!(tsquery("acme").unwrap(), "(acme:*)");
+        assert_eq!(tsquery("  acme  corp ").unwrap(), "(acme:*) & (corp:*)");
+    }
+
+    #[test]
+    fn test_tsquery_escape() {
+        assert_eq!(tsquery("acme.com").unwrap(), "(acme:* <-> com:*)");
+        assert_eq!(tsquery("'OR 1").unwrap(), "(OR:*) & (1:*)");
+        assert_eq!(tsquery("acme !|&").unwrap(), "(acme:*)");
+    }
+
+    #[test]
+    fn test_tsquery_empty() {
+        assert!(tsquery("  !! ").is_err());
+    }
+}
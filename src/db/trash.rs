@@ -0,0 +1,787 @@
+use crate::errors::*;
+use crate::db::{Backend, Database, DatabaseSock, Filter, Table};
+use crate::schema::*;
+use crate::models::*;
+use chrono::{NaiveDateTime, Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Integer, Nullable, Text};
+use serde_json::{self, Value};
+use std::collections::{HashMap, HashSet};
+
+
+#[derive(Identifiable, Queryable, PartialEq, Debug)]
+#[table_name="trash"]
+pub struct Trash {
+    pub id: i32,
+    pub time: NaiveDateTime,
+    pub description: String,
+}
+
+#[derive(Insertable)]
+#[table_name="trash"]
+pub struct NewTrash<'a> {
+    pub time: NaiveDateTime,
+    pub description: &'a str,
+}
+
+#[derive(Identifiable, Queryable, PartialEq, Debug)]
+#[table_name="trash_rows"]
+pub struct TrashRow {
+    pub id: i32,
+    pub trash_id: i32,
+    pub family: String,
+    pub key: i32,
+    pub data: String,
+    pub reference: bool,
+}
+
+/// Summary of a restored trash
+#[derive(Debug, Default, PartialEq)]
+pub struct Restored {
+    pub rows: usize,
+    /// Rows that have been added again since they've been deleted, as
+    /// (table, trashed id, existing id)
+    pub merged: Vec<(String, i32, i32)>,
+}
+
+#[derive(QueryableByName)]
+pub(super) struct Column {
+    #[sql_type="Text"]
+    pub(super) name: String,
+    #[sql_type="Text"]
+    kind: String,
+    #[sql_type="Nullable<Text>"]
+    pub(super) parent: Option<String>,
+    #[sql_type="Nullable<Text>"]
+    pub(super) nullable_parent: Option<String>,
+}
+
+impl Column {
+    #[inline]
+    pub(super) fn is_blob(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("blob") || self.kind.eq_ignore_ascii_case("bytea")
+    }
+
+    #[inline]
+    pub(super) fn is_bool(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("boolean")
+    }
+
+    #[inline]
+    pub(super) fn is_float(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("real") || self.kind.eq_ignore_ascii_case("float")
+    }
+
+    #[inline]
+    pub(super) fn is_timestamp(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("datetime") || self.kind.to_lowercase().starts_with("timestamp")
+    }
+}
+
+#[derive(QueryableByName)]
+pub(super) struct Dependent {
+    #[sql_type="Text"]
+    pub(super) child_table: String,
+    #[sql_type="Text"]
+    pub(super) child_column: String,
+    #[sql_type="Bool"]
+    pub(super) cascades: bool,
+}
+
+#[derive(QueryableByName)]
+struct UniqueColumn {
+    #[sql_type="Text"]
+    index_name: String,
+    #[sql_type="Text"]
+    column_name: String,
+}
+
+#[derive(QueryableByName)]
+pub(super) struct RowId {
+    #[sql_type="Integer"]
+    pub(super) id: i32,
+}
+
+impl Trash {
+    pub fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::trash::dsl::*;
+
+        with_conn!(db.db(), |conn| trash
+            .order_by(id.asc())
+            .load::<Self>(conn))
+            .map_err(Error::from)
+    }
+
+    pub fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::trash::dsl::*;
+
+        with_conn!(db.db(), |conn| trash.filter(id.eq(my_id))
+            .first::<Self>(conn))
+            .map_err(Error::from)
+    }
+
+    pub fn latest(db: &Database) -> Result<Option<Self>> {
+        use crate::schema::trash::dsl::*;
+
+        with_conn!(db.db(), |conn| trash
+            .order_by(id.desc())
+            .first::<Self>(conn))
+            .optional()
+            .map_err(Error::from)
+    }
+
+    pub fn rows(&self, db: &Database) -> Result<Vec<TrashRow>> {
+        use crate::schema::trash_rows::dsl::*;
+
+        with_conn!(db.db(), |conn| trash_rows
+            .filter(trash_id.eq(self.id))
+            .order_by(id.asc())
+            .load::<TrashRow>(conn))
+            .map_err(Error::from)
+    }
+
+    /// Move all entities matching the filter, and everything that would be
+    /// deleted by cascade, into the trash
+    ///
+    /// References that are cleared by the delete are saved as well, so they
+    /// can be set again when the trash is restored.
+    pub fn create<T: Model>(db: &Database, filter: &Filter, description: &str) -> Result<usize> {
+        let table = T::family()
+            .ok_or_else(|| format_err!("Entity can't be moved to trash"))?
+            .table();
+        let description = format!("{} {}", table.as_str(), description);
+
+        db.db().transaction::<_, Error, _>(|| {
+            let ids = T::filter(db, filter)?.iter()
+                .map(Model::id)
+                .collect::<Vec<_>>();
+
+            if ids.is_empty() {
+                return Ok(0);
+            }
+
+            let new_trash = NewTrash {
+                time: Utc::now().naive_utc(),
+                description: &description,
+            };
+            // use the id of our own insert, another client of a shared
+            // workspace might have created a newer entry in the meantime
+            let my_trash_id = match db.db() {
+                DatabaseSock::Sqlite(conn) => {
+                    diesel::insert_into(trash::table)
+                        .values(&new_trash)
+                        .execute(conn)?;
+                    diesel::sql_query("SELECT last_insert_rowid() AS id")
+                        .get_result::<RowId>(conn)?
+                        .id
+                },
+                #[cfg(feature="postgres")]
+                DatabaseSock::Postgres(conn) => diesel::insert_into(trash::table)
+                    .values(&new_trash)
+                    .returning(trash::id)
+                    .get_result::<i32>(conn)?,
+            };
+
+            // parents are always saved before their children, so restoring in
+            // order never violates a foreign key
+            let mut queue = vec![(table.as_str().to_string(), ids)];
+            let mut visited = HashSet::new();
+            while let Some((table, ids)) = queue.pop() {
+                let ids = ids.into_iter()
+                    .filter(|id| visited.insert((table.clone(), *id)))
+                    .collect::<Vec<_>>();
+                if ids.is_empty() {
+                    continue;
+                }
+
+                snapshot(db, my_trash_id, &table, &ids)?;
+
+                for dependent in dependents(db, &table)? {
+                    if !dependent.cascades {
+                        snapshot_references(db, my_trash_id, &dependent, &ids)?;
+                        continue;
+                    }
+
+                    let query = format!("SELECT id FROM \"{}\" WHERE \"{}\" IN ({})",
+                        dependent.child_table, dependent.child_column, id_list(&ids));
+                    let ids = with_conn!(db.db(), |conn| diesel::sql_query(query)
+                        .load::<RowId>(conn))?
+                        .into_iter()
+                        .map(|x| x.id)
+                        .collect::<Vec<_>>();
+
+                    if !ids.is_empty() {
+                        queue.push((dependent.child_table, ids));
+                    }
+                }
+            }
+
+            T::delete(db, filter)
+        })
+    }
+
+    /// Insert all rows of this trash back into their tables
+    ///
+    /// Rows that have been added again in the meantime are merged into the
+    /// existing row, rows that reference them are pointed to the existing row.
+    pub fn restore(&self, db: &Database) -> Result<Restored> {
+        db.db().transaction::<_, Error, _>(|| {
+            let rows = self.rows(db)?;
+            let mut tables = HashMap::new();
+            let mut ids = HashMap::new();
+            let mut restored = Restored::default();
+            let mut netblocks = Vec::new();
+
+            for row in &rows {
+                // ensure we only write into known tables
+                row.family.parse::<Table>()?;
+
+                if !tables.contains_key(&row.family) {
+                    tables.insert(row.family.clone(), RestoreTable::load(db, &row.family)?);
+                }
+                let existing = row.restore(db, &tables[&row.family], &mut ids)
+                    .with_context(|_| format!("Failed to restore {}#{}", row.family, row.key))?;
+
+                if row.reference {
+                    continue;
+                }
+                match existing {
+                    Some(existing) => restored.merged.push((row.family.clone(), row.key, existing)),
+                    None => {
+                        if row.family == Table::Netblocks.as_str() {
+                            netblocks.push(ids.get(&(row.family.clone(), row.key))
+                                .copied()
+                                .unwrap_or(row.key));
+                        }
+                        restored.rows += 1;
+                    },
+                }
+            }
+
+            // explicit ids don't advance the sequence of a postgres table
+            #[cfg(feature="postgres")]
+            {
+                if let DatabaseSock::Postgres(conn) = db.db() {
+                    for table in tables.keys() {
+                        let query = format!("SELECT setval(pg_get_serial_sequence('{0}', 'id'), (SELECT MAX(id) FROM \"{0}\"))", table);
+                        diesel::sql_query(query)
+                            .execute(conn)?;
+                    }
+                }
+            }
+
+            // ipaddrs that have been deleted before range keys were tracked
+            if tables.contains_key(Table::Ipaddrs.as_str()) {
+                IpAddr::update_missing_range_keys(db)?;
+            }
+
+            // restored netblocks may be more specific than the current ones
+            for netblock in netblocks {
+                Netblock::by_id(db, netblock)?
+                    .link_ipaddrs(db)?;
+            }
+
+            with_conn!(db.db(), |conn| diesel::delete(self)
+                .execute(conn))?;
+
+            Ok(restored)
+        })
+    }
+
+    pub fn expire_date(retention: u32) -> NaiveDateTime {
+        let expire_at = Utc::now() - Duration::days(i64::from(retention));
+        expire_at.naive_utc()
+    }
+
+    /// Permanently delete everything that has been moved to the trash before
+    /// the given date, or everything if no date is given
+    pub fn purge(db: &Database, before: Option<NaiveDateTime>) -> Result<usize> {
+        use crate::schema::trash::dsl::*;
+
+        let n = match before {
+            Some(before) => with_conn!(db.db(), |conn| diesel::delete(trash.filter(time.lt(before)))
+                .execute(conn))?,
+            None => with_conn!(db.db(), |conn| diesel::delete(trash)
+                .execute(conn))?,
+        };
+
+        Ok(n)
+    }
+}
+
+impl TrashRow {
+    pub fn list_values(db: &Database, table: &Table) -> Result<Vec<String>> {
+        use crate::schema::trash_rows::dsl::*;
+
+        let rows = with_conn!(db.db(), |conn| trash_rows
+            .filter(family.eq(table.as_str()))
+            .filter(reference.eq(false))
+            .load::<Self>(conn))?;
+
+        let mut values = Vec::new();
+        for row in rows {
+            let mut object = serde_json::from_str::<HashMap<String, Value>>(&row.data)?;
+            if let Some(Value::String(value)) = object.remove("value") {
+                values.push(value);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Write this row back into its table, returns the id of the existing
+    /// row if the row has been merged into it
+    fn restore(&self, db: &Database, table: &RestoreTable, ids: &mut HashMap<(String, i32), i32>) -> Result<Option<i32>> {
+        let data = serde_json::from_str::<HashMap<String, Value>>(&self.data)?;
+
+        let mut values = Vec::new();
+        // columns that have been added since the row has been deleted get their default
+        for column in &table.columns {
+            if column.name == "id" {
+                continue;
+            }
+
+            if let Some(value) = data.get(&column.name) {
+                let mut value = sql_literal(value, column.is_blob(), db.db().backend())?;
+                // the parent may have been merged into an existing row
+                if let (Some(parent), Some(key)) = (&column.parent, data[&column.name].as_i64()) {
+                    if let Some(id) = ids.get(&(parent.clone(), key as i32)) {
+                        value = id.to_string();
+                    }
+                }
+                // references that have been deleted in the meantime are cleared
+                if let Some(parent) = &column.nullable_parent {
+                    value = format!("(SELECT id FROM \"{}\" WHERE id = {})", parent, value);
+                }
+                values.push((format!("\"{}\"", column.name), value));
+            }
+        }
+
+        let key = ids.get(&(self.family.clone(), self.key))
+            .copied()
+            .unwrap_or(self.key);
+
+        // references are only set again if they haven't been set in the meantime
+        if self.reference {
+            if !values.is_empty() {
+                let query = format!("UPDATE \"{}\" SET {} WHERE id = {}",
+                    self.family, coalesce(&values), key);
+                with_conn!(db.db(), |conn| diesel::sql_query(query)
+                    .execute(conn))?;
+            }
+            return Ok(None);
+        }
+
+        if let Some(existing) = table.find_existing(db, &values)? {
+            if !values.is_empty() {
+                let query = format!("UPDATE \"{}\" SET {} WHERE id = {}",
+                    self.family, coalesce(&values), existing);
+                with_conn!(db.db(), |conn| diesel::sql_query(query)
+                    .execute(conn))?;
+            }
+            ids.insert((self.family.clone(), self.key), existing);
+            return Ok(Some(existing));
+        }
+
+        let names = values.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        let literals = values.iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>();
+
+        // the id has been taken by a new row, the restored row gets a new one
+        if table.id_exists(db, self.key)? {
+            let query = format!("INSERT INTO \"{}\" ({}) VALUES ({})",
+                self.family, names.join(", "), literals.join(", "));
+            let id = insert_returning_id(db, query)?;
+            ids.insert((self.family.clone(), self.key), id);
+        } else {
+            let query = format!("INSERT INTO \"{}\" (id, {}) VALUES ({}, {})",
+                self.family, names.join(", "), self.key, literals.join(", "));
+            with_conn!(db.db(), |conn| diesel::sql_query(query)
+                .execute(conn))?;
+        }
+
+        Ok(None)
+    }
+}
+
+/// The columns and unique constraints of a table that is restored into
+struct RestoreTable {
+    name: String,
+    columns: Vec<Column>,
+    unique: Vec<Vec<String>>,
+}
+
+impl RestoreTable {
+    fn load(db: &Database, table: &str) -> Result<RestoreTable> {
+        Ok(RestoreTable {
+            name: table.to_string(),
+            columns: table_columns(db, table)?,
+            unique: unique_columns(db, table)?,
+        })
+    }
+
+    /// Find a row that would conflict with a unique constraint
+    fn find_existing(&self, db: &Database, values: &[(String, String)]) -> Result<Option<i32>> {
+        for columns in &self.unique {
+            let conditions = columns.iter()
+                .map(|column| {
+                    let name = format!("\"{}\"", column);
+                    values.iter()
+                        .find(|(n, value)| *n == name && value != "NULL")
+                        .map(|(_, value)| format!("{} = {}", name, value))
+                })
+                .collect::<Option<Vec<_>>>();
+
+            // NULL never conflicts with a unique constraint
+            let conditions = match conditions {
+                Some(conditions) => conditions,
+                None => continue,
+            };
+
+            let query = format!("SELECT id FROM \"{}\" WHERE {}",
+                self.name, conditions.join(" AND "));
+            let existing = with_conn!(db.db(), |conn| diesel::sql_query(query)
+                .load::<RowId>(conn))?;
+            if let Some(existing) = existing.first() {
+                return Ok(Some(existing.id));
+            }
+        }
+        Ok(None)
+    }
+
+    fn id_exists(&self, db: &Database, id: i32) -> Result<bool> {
+        let query = format!("SELECT id FROM \"{}\" WHERE id = {}", self.name, id);
+        let rows = with_conn!(db.db(), |conn| diesel::sql_query(query)
+            .load::<RowId>(conn))?;
+        Ok(!rows.is_empty())
+    }
+}
+
+pub(super) fn table_columns(db: &Database, table: &str) -> Result<Vec<Column>> {
+    match db.db() {
+        // subqueries because a left join of two pragma functions drops rows in older sqlite
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT c.name, c.type AS kind,
+            (SELECT f.\"table\" FROM pragma_foreign_key_list(?) f WHERE f.\"from\" = c.name) AS parent,
+            (SELECT f.\"table\" FROM pragma_foreign_key_list(?) f WHERE f.\"from\" = c.name
+                AND f.on_delete = 'SET NULL') AS nullable_parent
+            FROM pragma_table_info(?) c")
+            .bind::<Text, _>(table)
+            .bind::<Text, _>(table)
+            .bind::<Text, _>(table)
+            .load::<Column>(conn),
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT CAST(a.attname AS TEXT) AS name,
+            format_type(a.atttypid, a.atttypmod) AS kind,
+            CAST(p.relname AS TEXT) AS parent,
+            CAST(CASE WHEN f.confdeltype = 'n' THEN p.relname END AS TEXT) AS nullable_parent
+            FROM pg_attribute a
+            LEFT JOIN pg_constraint f ON f.conrelid = a.attrelid AND f.contype = 'f'
+                AND f.conkey[1] = a.attnum
+            LEFT JOIN pg_class p ON p.oid = f.confrelid
+            WHERE a.attrelid = to_regclass($1) AND a.attnum > 0 AND NOT a.attisdropped
+                AND a.attgenerated = ''
+            ORDER BY a.attnum")
+            .bind::<Text, _>(table)
+            .load::<Column>(conn),
+    }
+        .map_err(Error::from)
+}
+
+/// Tables that reference this table, their rows are either deleted by cascade
+/// or their reference is cleared
+pub(super) fn dependents(db: &Database, table: &str) -> Result<Vec<Dependent>> {
+    match db.db() {
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT m.name AS child_table, f.\"from\" AS child_column,
+            f.on_delete = 'CASCADE' AS cascades
+            FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f
+            WHERE m.type = 'table' AND f.\"table\" = ? AND f.on_delete IN ('CASCADE', 'SET NULL')")
+            .bind::<Text, _>(table)
+            .load::<Dependent>(conn),
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT CAST(c.relname AS TEXT) AS child_table,
+            CAST(a.attname AS TEXT) AS child_column,
+            f.confdeltype = 'c' AS cascades
+            FROM pg_constraint f
+            JOIN pg_class c ON c.oid = f.conrelid
+            JOIN pg_attribute a ON a.attrelid = f.conrelid AND a.attnum = f.conkey[1]
+            WHERE f.contype = 'f' AND f.confrelid = to_regclass($1) AND f.confdeltype IN ('c', 'n')")
+            .bind::<Text, _>(table)
+            .load::<Dependent>(conn),
+    }
+        .map_err(Error::from)
+}
+
+/// The columns of every unique constraint of a table, excluding the primary key
+fn unique_columns(db: &Database, table: &str) -> Result<Vec<Vec<String>>> {
+    let columns = match db.db() {
+        DatabaseSock::Sqlite(conn) => diesel::sql_query("SELECT l.name AS index_name, i.name AS column_name
+            FROM pragma_index_list(?) l JOIN pragma_index_info(l.name) i
+            WHERE l.\"unique\" = 1 AND l.origin != 'pk'
+            ORDER BY l.name, i.seqno")
+            .bind::<Text, _>(table)
+            .load::<UniqueColumn>(conn),
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query("SELECT CAST(c.relname AS TEXT) AS index_name,
+            CAST(a.attname AS TEXT) AS column_name
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indexrelid
+            JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+            WHERE i.indrelid = to_regclass($1) AND i.indisunique AND NOT i.indisprimary
+            ORDER BY c.relname")
+            .bind::<Text, _>(table)
+            .load::<UniqueColumn>(conn),
+    }?;
+
+    let mut unique: Vec<(String, Vec<String>)> = Vec::new();
+    for column in columns {
+        match unique.last_mut() {
+            Some((name, columns)) if *name == column.index_name => columns.push(column.column_name),
+            _ => unique.push((column.index_name, vec![column.column_name])),
+        }
+    }
+    Ok(unique.into_iter()
+        .map(|(_, columns)| columns)
+        .collect())
+}
+
+/// An expression that serializes a row into a json object, blobs are hex encoded
+pub(super) fn json_object(backend: Backend, columns: &[Column]) -> String {
+    let object = columns.iter()
+        .map(|column| match backend {
+            Backend::Sqlite if column.is_blob() => {
+                format!("'{0}', CASE WHEN \"{0}\" IS NULL THEN NULL ELSE hex(\"{0}\") END", column.name)
+            },
+            #[cfg(feature="postgres")]
+            Backend::Postgres if column.is_blob() => {
+                format!("'{0}', encode(\"{0}\", 'hex')", column.name)
+            },
+            _ => format!("'{0}', \"{0}\"", column.name),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match backend {
+        Backend::Sqlite => format!("json_object({})", object),
+        #[cfg(feature="postgres")]
+        Backend::Postgres => format!("CAST(json_build_object({}) AS TEXT)", object),
+    }
+}
+
+fn snapshot(db: &Database, my_trash_id: i32, table: &str, ids: &[i32]) -> Result<()> {
+    let object = json_object(db.db().backend(), &table_columns(db, table)?);
+
+    let query = format!("INSERT INTO trash_rows (trash_id, family, key, data)
+        SELECT {trash_id}, {family}, id, {object} FROM \"{table}\"
+        WHERE id IN ({ids}) AND id NOT IN (SELECT key FROM trash_rows WHERE trash_id = {trash_id} AND family = {family})",
+        trash_id=my_trash_id,
+        family=Filter::escape(table),
+        object=object,
+        table=table,
+        ids=id_list(ids));
+    with_conn!(db.db(), |conn| diesel::sql_query(query)
+        .execute(conn))?;
+
+    Ok(())
+}
+
+/// Save the references of rows that are cleared by the delete
+fn snapshot_references(db: &Database, my_trash_id: i32, dependent: &Dependent, ids: &[i32]) -> Result<()> {
+    let columns = table_columns(db, &dependent.child_table)?.into_iter()
+        .filter(|column| column.name == dependent.child_column)
+        .collect::<Vec<_>>();
+    let object = json_object(db.db().backend(), &columns);
+
+    let query = format!("INSERT INTO trash_rows (trash_id, family, key, data, reference)
+        SELECT {trash_id}, {family}, id, {object}, {reference} FROM \"{table}\"
+        WHERE \"{column}\" IN ({ids})",
+        trash_id=my_trash_id,
+        family=Filter::escape(&dependent.child_table),
+        object=object,
+        reference=sql_literal(&Value::Bool(true), false, db.db().backend())?,
+        table=dependent.child_table,
+        column=dependent.child_column,
+        ids=id_list(ids));
+    with_conn!(db.db(), |conn| diesel::sql_query(query)
+        .execute(conn))?;
+
+    Ok(())
+}
+
+/// Insert a row and return the id it has been assigned
+fn insert_returning_id(db: &Database, query: String) -> Result<i32> {
+    let row = match db.db() {
+        DatabaseSock::Sqlite(conn) => {
+            diesel::sql_query(query)
+                .execute(conn)?;
+            diesel::sql_query("SELECT last_insert_rowid() AS id")
+                .get_result::<RowId>(conn)?
+        },
+        #[cfg(feature="postgres")]
+        DatabaseSock::Postgres(conn) => diesel::sql_query(format!("{} RETURNING id", query))
+            .get_result::<RowId>(conn)?,
+    };
+    Ok(row.id)
+}
+
+#[inline]
+fn coalesce(values: &[(String, String)]) -> String {
+    values.iter()
+        .map(|(name, value)| format!("{0} = COALESCE({0}, {1})", name, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(super) fn sql_literal(value: &Value, blob: bool, backend: Backend) -> Result<String> {
+    match value {
+        Value::Null => Ok(String::from("NULL")),
+        Value::Number(n) => Ok(n.to_string()),
+        // sqlite stores booleans as integers
+        Value::Bool(b) => Ok(match backend {
+            Backend::Sqlite => if *b { "1" } else { "0" },
+            #[cfg(feature="postgres")]
+            Backend::Postgres => if *b { "TRUE" } else { "FALSE" },
+        }.to_string()),
+        Value::String(s) if blob => {
+            if !s.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!("Invalid blob: {:?}", s);
+            }
+            match backend {
+                Backend::Sqlite => Ok(format!("X'{}'", s)),
+                #[cfg(feature="postgres")]
+                Backend::Postgres => Ok(format!("decode('{}', 'hex')", s)),
+            }
+        },
+        Value::String(s) => Ok(Filter::escape(s)),
+        _ => bail!("Unexpected value: {:?}", value),
+    }
+}
+
+#[inline]
+fn id_list(ids: &[i32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_literal() {
+        assert_eq!(sql_literal(&Value::Null, false, Backend::Sqlite).unwrap(), "NULL");
+        assert_eq!(sql_literal(&serde_json::json!(1234), false, Backend::Sqlite).unwrap(), "1234");
+        assert_eq!(sql_literal(&serde_json::json!(53.5), false, Backend::Sqlite).unwrap(), "53.5");
+        assert_eq!(sql_literal(&serde_json::json!(true), false, Backend::Sqlite).unwrap(), "1");
+        assert_eq!(sql_literal(&serde_json::json!("it's"), false, Backend::Sqlite).unwrap(), "'it''s'");
+        assert_eq!(sql_literal(&serde_json::json!("C0FFEE"), true, Backend::Sqlite).unwrap(), "X'C0FFEE'");
+    }
+
+    #[cfg(feature="postgres")]
+    #[test]
+    fn test_sql_literal_postgres() {
+        assert_eq!(sql_literal(&serde_json::json!(true), false, Backend::Postgres).unwrap(), "TRUE");
+        assert_eq!(sql_literal(&serde_json::json!("C0FFEE"), true, Backend::Postgres).unwrap(), "decode('C0FFEE', 'hex')");
+    }
+
+    #[test]
+    fn test_sql_literal_invalid_blob() {
+        assert!(sql_literal(&serde_json::json!("' OR 1=1"), true, Backend::Sqlite).is_err());
+    }
+
+    #[test]
+    fn test_id_list() {
+        assert_eq!(id_list(&[1, 2, 3]), "1,2,3");
+    }
+
+    fn execute(db: &Database, query: &str) {
+        with_conn!(db.db(), |conn| diesel::sql_query(query)
+            .execute(conn))
+            .unwrap();
+    }
+
+    fn subdomain_parent(db: &Database) -> i32 {
+        let subdomain = Subdomain::by_id(db, 1).unwrap();
+        subdomain.domain_id
+    }
+
+    #[test]
+    fn test_restore() {
+        let db = Database::in_memory().unwrap();
+        execute(&db, "INSERT INTO domains (id, value) VALUES (1, 'example.com')");
+        execute(&db, "INSERT INTO subdomains (id, domain_id, value) VALUES (1, 1, 'www.example.com')");
+
+        let n = Trash::create::<Domain>(&db, &Filter::new("value = 'example.com'"), "test").unwrap();
+        assert_eq!(n, 1);
+        assert!(Subdomain::by_id(&db, 1).is_err());
+
+        let restored = Trash::latest(&db).unwrap().unwrap()
+            .restore(&db).unwrap();
+        assert_eq!(restored, Restored {
+            rows: 2,
+            merged: vec![],
+        });
+        assert_eq!(subdomain_parent(&db), 1);
+        assert!(Trash::latest(&db).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_restore_merge_existing() {
+        let db = Database::in_memory().unwrap();
+        execute(&db, "INSERT INTO domains (id, value) VALUES (1, 'example.com')");
+        execute(&db, "INSERT INTO subdomains (id, domain_id, value) VALUES (1, 1, 'www.example.com')");
+        Trash::create::<Domain>(&db, &Filter::new("value = 'example.com'"), "test").unwrap();
+
+        execute(&db, "INSERT INTO domains (id, value, extra) VALUES (2, 'example.com', NULL)");
+        execute(&db, "UPDATE trash_rows SET data = json_set(data, '$.extra', '{\"a\":1}') WHERE family = 'domains'");
+
+        let restored = Trash::latest(&db).unwrap().unwrap()
+            .restore(&db).unwrap();
+        assert_eq!(restored, Restored {
+            rows: 1,
+            merged: vec![(String::from("domains"), 1, 2)],
+        });
+        assert_eq!(subdomain_parent(&db), 2);
+        assert!(Domain::by_id(&db, 1).is_err());
+        // empty fields are filled in from the restored row
+        assert_eq!(Domain::by_id(&db, 2).unwrap().extra, Some(String::from("{\"a\":1}")));
+    }
+
+    #[test]
+    fn test_restore_id_taken() {
+        let db = Database::in_memory().unwrap();
+        execute(&db, "INSERT INTO domains (id, value) VALUES (1, 'example.com')");
+        execute(&db, "INSERT INTO subdomains (id, domain_id, value) VALUES (1, 1, 'www.example.com')");
+        Trash::create::<Domain>(&db, &Filter::new("value = 'example.com'"), "test").unwrap();
+
+        execute(&db, "INSERT INTO domains (id, value) VALUES (1, 'example.org')");
+
+        let restored = Trash::latest(&db).unwrap().unwrap()
+            .restore(&db).unwrap();
+        assert_eq!(restored.rows, 2);
+        let id = subdomain_parent(&db);
+        assert_ne!(id, 1);
+        assert_eq!(Domain::by_id(&db, id).unwrap().value, "example.com");
+        assert_eq!(Domain::by_id(&db, 1).unwrap().value, "example.org");
+    }
+
+    #[test]
+    fn test_restore_cleared_references() {
+        let db = Database::in_memory().unwrap();
+        execute(&db, "INSERT INTO netblocks (id, family, value) VALUES (1, '4', '192.0.2.0/24')");
+        execute(&db, "INSERT INTO ipaddrs (id, family, value, netblock_id) VALUES (1, '4', '192.0.2.1', 1)");
+
+        Trash::create::<Netblock>(&db, &Filter::new("value = '192.0.2.0/24'"), "test").unwrap();
+        assert_eq!(IpAddr::by_id(&db, 1).unwrap().netblock_id, None);
+
+        let trash = Trash::latest(&db).unwrap().unwrap();
+        let rows = trash.rows(&db).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[1].reference);
+        assert_eq!(rows[1].family, "ipaddrs");
+        assert_eq!(TrashRow::list_values(&db, &Table::Ipaddrs).unwrap(), Vec::<String>::new());
+
+        trash.restore(&db).unwrap();
+        assert_eq!(IpAddr::by_id(&db, 1).unwrap().netblock_id, Some(1));
+    }
+}
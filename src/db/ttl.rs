@@ -36,10 +36,10 @@ impl Ttl {
     pub fn find(obj: &Insert, my_key: i32, db: &Database) -> Result<Option<Ttl>> {
         use crate::schema::ttls::dsl::*;
 
-        ttls
+        with_conn!(db.db(), |conn| ttls
             .filter(family.eq(obj.table()))
             .filter(key.eq(my_key))
-            .first::<Self>(db.db())
+            .first::<Self>(conn))
             .optional()
             .map_err(Error::from)
     }
@@ -47,9 +47,9 @@ impl Ttl {
     pub fn expired(db: &Database) -> Result<Vec<Ttl>> {
         use crate::schema::ttls::dsl::*;
 
-        ttls
+        with_conn!(db.db(), |conn| ttls
             .filter(expire.lt(Self::ttl_to_datetime(0)))
-            .load::<Self>(db.db())
+            .load::<Self>(conn))
             .map_err(Error::from)
     }
 
@@ -63,13 +63,13 @@ impl Ttl {
         debug!("Creating ttl on record");
         let expire = Self::ttl_to_datetime(ttl);
 
-        diesel::insert_into(ttls::table)
+        with_conn!(db.db(), |conn| diesel::insert_into(ttls::table)
             .values(NewTtl {
                 family: obj.table(),
                 key,
                 expire,
             })
-            .execute(db.db())?;
+            .execute(conn))?;
 
         Ok(())
     }
@@ -87,9 +87,9 @@ impl Ttl {
 
                 old.expire = new_expire;
 
-                diesel::update(ttls.filter(id.eq(old.id)))
+                with_conn!(db.db(), |conn| diesel::update(ttls.filter(id.eq(old.id)))
                     .set(old)
-                    .execute(db.db())?;
+                    .execute(conn))?;
             }
         } else {
             debug!("Existing record doesn't expire, not setting a ttl");
@@ -118,10 +118,33 @@ impl Ttl {
             Table::Ports => Port::delete_id(db, self.key)?,
             Table::Netblocks => Netblock::delete_id(db, self.key)?,
             Table::Cryptoaddrs => CryptoAddr::delete_id(db, self.key)?,
+            Table::DnsRecords => DnsRecord::delete_id(db, self.key)?,
+            Table::Certificates => Certificate::delete_id(db, self.key)?,
+            Table::SubdomainCertificates => SubdomainCertificate::delete_id(db, self.key)?,
+            Table::PortCertificates => PortCertificate::delete_id(db, self.key)?,
+            Table::Organizations => Organization::delete_id(db, self.key)?,
+            Table::Persons => Person::delete_id(db, self.key)?,
+            Table::OrganizationDomains => OrganizationDomain::delete_id(db, self.key)?,
+            Table::OrganizationEmails => OrganizationEmail::delete_id(db, self.key)?,
+            Table::OrganizationPhonenumbers => OrganizationPhoneNumber::delete_id(db, self.key)?,
+            Table::OrganizationAccounts => OrganizationAccount::delete_id(db, self.key)?,
+            Table::OrganizationNetblocks => OrganizationNetblock::delete_id(db, self.key)?,
+            Table::PersonDomains => PersonDomain::delete_id(db, self.key)?,
+            Table::PersonEmails => PersonEmail::delete_id(db, self.key)?,
+            Table::PersonPhonenumbers => PersonPhoneNumber::delete_id(db, self.key)?,
+            Table::PersonAccounts => PersonAccount::delete_id(db, self.key)?,
+            Table::PersonNetblocks => PersonNetblock::delete_id(db, self.key)?,
+            Table::AutonomousSystems => AutonomousSystem::delete_id(db, self.key)?,
+            Table::Documents => Document::delete_id(db, self.key)?,
+            Table::UrlDocuments => UrlDocument::delete_id(db, self.key)?,
+            Table::Locations => Location::delete_id(db, self.key)?,
+            Table::DomainLocations => DomainLocation::delete_id(db, self.key)?,
+            Table::PhonenumberLocations => PhoneNumberLocation::delete_id(db, self.key)?,
+            Table::AccountLocations => AccountLocation::delete_id(db, self.key)?,
         };
 
-        diesel::delete(self)
-                .execute(db.db())?;
+        with_conn!(db.db(), |conn| diesel::delete(self)
+                .execute(conn))?;
 
         Ok(())
     }
@@ -1,5 +1,7 @@
 use crate::errors::*;
 
+use crate::blobs::{BlobChunk, BlobStreamEvent};
+use crate::cookies::{CookieJar, PersistCookieJar};
 use crate::db::Family;
 use crate::engine::{Environment, IpcChild};
 use crate::geoip::{MaxmindReader, GeoIP, AsnDB};
@@ -7,27 +9,31 @@ use crate::hlua::{self, AnyLuaValue};
 use crate::keyring::KeyRingEntry;
 use crate::models::*;
 use crate::psl::{Psl, PslReader};
+use crate::proxy::{Proxy, ProxyPool};
 use crate::lazy::Lazy;
 use crate::runtime;
 use crate::sockets::{Socket, SocketOptions, TlsData};
-use crate::web::{HttpSession, HttpRequest, RequestOptions};
+use crate::transport::{ClientOptions, HttpsClient};
+use crate::web::{HttpSession, HttpRequest, HttpTransport, RequestOptions};
 use crate::websockets::{WebSocket, WebSocketOptions};
 use crate::worker::{Event, LogEvent, DatabaseEvent, DatabaseResponse, StdioEvent, RatelimitEvent};
 use crate::ratelimits::RatelimitResponse;
-use chrootable_https::{self, Resolver};
+use chrootable_https::Resolver;
+use chrono::{DateTime, Utc};
 use serde_json;
-use sn0int_std::blobs::{Blob, BlobState};
+use sn0int_std::auth::OAuth2Token;
+use serde::de::DeserializeOwned;
+use sn0int_std::blobs::{Blob, BlobState, BlobWriter};
 use sn0int_std::web::WebState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::result;
-use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use rand::prelude::*;
 use rand::distributions::Alphanumeric;
 
 
-pub trait State {
+pub trait State: BlobState {
     fn clear_error(&self);
 
     fn last_error(&self) -> Option<String>;
@@ -120,6 +126,20 @@ pub trait State {
             .map_err(Error::from)
     }
 
+    fn db_tag(&self, family: Family, id: i32, tag: String) -> Result<DatabaseResponse> {
+        self.send(&Event::Database(DatabaseEvent::Tag((family, id, tag))));
+        self.db_recv()
+            .context("Failed to add tag")
+            .map_err(Error::from)
+    }
+
+    fn db_untag(&self, family: Family, id: i32, tag: String) -> Result<DatabaseResponse> {
+        self.send(&Event::Database(DatabaseEvent::Untag((family, id, tag))));
+        self.db_recv()
+            .context("Failed to remove tag")
+            .map_err(Error::from)
+    }
+
     fn stdin_read_line(&self) -> Result<Option<String>> {
         self.send(&Event::Stdio(StdioEvent::Readline));
         let reply = self.recv()?;
@@ -158,7 +178,7 @@ pub trait State {
 
     fn dns_config(&self) -> &Resolver;
 
-    fn proxy(&self) -> Option<&SocketAddr>;
+    fn proxy(&self) -> Option<&ProxyPool>;
 
     fn getopt(&self, key: &str) -> Option<&String>;
 
@@ -180,17 +200,13 @@ pub trait State {
 
     fn http_mksession(&self) -> String;
 
-    fn http_request(&self, session_id: &str, method: String, url: String, options: RequestOptions) -> HttpRequest;
+    fn http_request(&self, session_id: &str, method: String, url: String, options: RequestOptions) -> Result<HttpRequest>;
 
-    fn get_blob(&self, id: &str) -> Result<Arc<Blob>>;
+    fn http_jar_restore(&self, session_id: &str, name: &str) -> Result<bool>;
 
-    fn persist_blob(&self, id: &str) -> Result<()> {
-        let blob = self.get_blob(id)?;
-        self.send(&Event::Blob(blob.as_ref().clone()));
-        let reply = self.recv()?;
-        let reply: result::Result<(), String> = serde_json::from_value(reply)?;
-        reply.map_err(|err| format_err!("Failed to store blob: {:?}", err))
-    }
+    fn http_jar_persist(&self, session_id: &str, name: &str) -> Result<()>;
+
+    fn persist_blob(&self, id: &str) -> Result<()>;
 }
 
 // #[derive(Debug)]
@@ -200,8 +216,11 @@ pub struct LuaState {
     socket_sessions: Mutex<HashMap<String, Arc<Mutex<Socket>>>>,
     ws_sessions: Mutex<HashMap<String, Arc<Mutex<WebSocket>>>>,
     blobs: Mutex<HashMap<String, Arc<Blob>>>,
+    /// Blobs that were streamed into storage and are loaded on demand
+    stored_blobs: Mutex<HashSet<String>>,
     http_sessions: Mutex<HashMap<String, HttpSession>>,
-    http_clients: Mutex<HashMap<String, Arc<chrootable_https::Client<Resolver>>>>,
+    http_clients: Mutex<HashMap<(String, ClientOptions), Arc<dyn HttpTransport>>>,
+    cookie_jars: Mutex<HashMap<String, CookieJar>>,
 
     verbose: u64,
     keyring: Vec<KeyRingEntry>, // TODO: maybe hashmap
@@ -209,7 +228,7 @@ pub struct LuaState {
     psl: Mutex<Lazy<PslReader, Arc<Psl>>>,
     geoip: Option<Mutex<Lazy<MaxmindReader, Arc<GeoIP>>>>,
     asn: Option<Mutex<Lazy<MaxmindReader, Arc<AsnDB>>>>,
-    proxy: Option<SocketAddr>,
+    proxy: Option<ProxyPool>,
     options: HashMap<String, String>,
 }
 
@@ -241,6 +260,19 @@ impl State for LuaState {
         tx.recv()
     }
 
+    fn persist_blob(&self, id: &str) -> Result<()> {
+        // blobs that were streamed into storage don't need to be sent again
+        if self.stored_blobs.lock().unwrap().contains(id) {
+            return Ok(());
+        }
+
+        let blob = self.get_blob(id)?;
+        self.send(&Event::Blob(blob.as_ref().clone()));
+        let reply = self.recv()?;
+        let reply: result::Result<(), String> = serde_json::from_value(reply)?;
+        reply.map_err(|err| format_err!("Failed to store blob: {:?}", err))
+    }
+
     fn verbose(&self) -> u64 {
         self.verbose
     }
@@ -255,7 +287,7 @@ impl State for LuaState {
         &self.dns_config
     }
 
-    fn proxy(&self) -> Option<&SocketAddr> {
+    fn proxy(&self) -> Option<&ProxyPool> {
         self.proxy.as_ref()
     }
 
@@ -293,10 +325,13 @@ impl State for LuaState {
         let mut mtx = self.socket_sessions.lock().unwrap();
         let id = self.random_id();
 
+        let mut options = options.clone();
+        options.resolve_keyring(|query| self.keyring_lookup(query))?;
+
         let sock = if let Some(proxy) = self.resolve_proxy_options(&options.proxy)? {
-            Socket::connect_socks5(proxy, host, port, options)?
+            Socket::connect_proxy(&proxy, host, port, &options)?
         } else {
-            Socket::connect(&self.dns_config, host, port, options)?
+            Socket::connect(&self.dns_config, host, port, &options)?
         };
 
         mtx.insert(id.clone(), Arc::new(Mutex::new(sock)));
@@ -317,7 +352,10 @@ impl State for LuaState {
         let sock = Arc::try_unwrap(sock).unwrap();
         let sock = sock.into_inner().unwrap();
 
-        let (sock, tls) = sock.upgrade_to_tls(options)?;
+        let mut options = options.clone();
+        options.resolve_keyring(|query| self.keyring_lookup(query))?;
+
+        let (sock, tls) = sock.upgrade_to_tls(&options)?;
 
         mtx.insert(id.to_string(), Arc::new(Mutex::new(sock)));
 
@@ -328,7 +366,8 @@ impl State for LuaState {
         let mut mtx = self.ws_sessions.lock().unwrap();
         let id = self.random_id();
 
-        let sock = WebSocket::connect(&self.dns_config, url, options)?;
+        let proxy = self.resolve_proxy_options(&options.proxy)?;
+        let sock = WebSocket::connect(&self.dns_config, url, options, proxy.as_ref())?;
         mtx.insert(id.clone(), Arc::new(Mutex::new(sock)));
 
         Ok(id)
@@ -347,52 +386,97 @@ impl State for LuaState {
         id
     }
 
-    fn http_request(&self, session_id: &str, method: String, url: String, options: RequestOptions) -> HttpRequest {
+    fn http_request(&self, session_id: &str, method: String, url: String, mut options: RequestOptions) -> Result<HttpRequest> {
+        options.resolve_keyring(|query| self.keyring_lookup(query))?;
+
         let mtx = self.http_sessions.lock().unwrap();
         let session = mtx.get(session_id).expect("Invalid session reference"); // TODO
 
-        HttpRequest::new(&session, method, url, options)
+        Ok(HttpRequest::new(&session, method, url, options))
     }
 
-    fn get_blob(&self, id: &str) -> Result<Arc<Blob>> {
-        let mtx = self.blobs.lock().unwrap();
-        let blob = mtx.get(id)
-            .ok_or_else(|| format_err!("Invalid blob reference"))?;
-        Ok(blob.clone())
+    fn http_jar_restore(&self, session_id: &str, name: &str) -> Result<bool> {
+        let jars = self.cookie_jars.lock().unwrap();
+        let jar = match jars.get(name) {
+            Some(jar) => jar,
+            None => return Ok(false),
+        };
+
+        let mut mtx = self.http_sessions.lock().unwrap();
+        let session = mtx.get_mut(session_id)
+            .ok_or_else(|| format_err!("Invalid session reference"))?;
+        session.cookies.merge(jar);
+
+        Ok(true)
+    }
+
+    fn http_jar_persist(&self, session_id: &str, name: &str) -> Result<()> {
+        let mut jar = {
+            let mtx = self.http_sessions.lock().unwrap();
+            let session = mtx.get(session_id)
+                .ok_or_else(|| format_err!("Invalid session reference"))?;
+            session.cookies.clone()
+        };
+        jar.remove_expired();
+
+        self.send(&Event::CookieJar(PersistCookieJar {
+            name: name.to_string(),
+            jar: jar.clone(),
+        }));
+        let reply = self.recv()?;
+        let reply: result::Result<(), String> = serde_json::from_value(reply)?;
+        reply.map_err(|err| format_err!("Failed to store cookie jar: {:?}", err))?;
+
+        let mut jars = self.cookie_jars.lock().unwrap();
+        jars.insert(name.to_string(), jar);
+
+        Ok(())
     }
 }
 
 impl WebState for LuaState {
-    fn http(&self, proxy: &Option<SocketAddr>) -> Result<Arc<chrootable_https::Client<Resolver>>> {
+    fn http(&self, proxy: &Option<Proxy>, options: &ClientOptions) -> Result<Arc<dyn HttpTransport>> {
         let proxy = self.resolve_proxy_options(proxy)?;
 
         let proxy_str = if let Some(proxy) = &proxy {
-            proxy.to_string()
+            proxy.to_url()
         } else {
             String::new()
         };
 
+        let key = (proxy_str, options.clone());
         let mut clients = self.http_clients.lock().unwrap();
 
-        if let Some(client) = clients.get(&proxy_str) {
+        if let Some(client) = clients.get(&key) {
             Ok(client.clone())
         } else {
-            let client = if let Some(proxy) = proxy {
-                chrootable_https::Client::with_socks5(*proxy)
-            } else {
-                let resolver = self.dns_config.clone();
-                chrootable_https::Client::new(resolver)
+            let client = match proxy {
+                Some(proxy) => HttpsClient::proxy(proxy, options)?,
+                None => HttpsClient::direct(self.dns_config.clone(), options)?,
             };
-            let client = Arc::new(client);
-            clients.insert(proxy_str, client.clone());
+            let client: Arc<dyn HttpTransport> = Arc::new(client);
+            clients.insert(key, client.clone());
             Ok(client)
         }
     }
 
-    fn register_in_jar(&self, session: &str, key: String, value: String) {
+    fn register_in_jar(&self, session: &str, key: String, value: String, expires: Option<DateTime<Utc>>) {
         let mut mtx = self.http_sessions.lock().unwrap();
         if let Some(session) = mtx.get_mut(session) {
-            session.cookies.register_in_jar(key, value);
+            session.cookies.register_in_jar(key, value, expires);
+        }
+    }
+
+    fn oauth2_token(&self, session: &str, key: &str) -> Option<String> {
+        let mtx = self.http_sessions.lock().unwrap();
+        mtx.get(session)
+            .and_then(|session| session.oauth2_token(key))
+    }
+
+    fn register_oauth2_token(&self, session: &str, key: String, token: OAuth2Token) {
+        let mut mtx = self.http_sessions.lock().unwrap();
+        if let Some(session) = mtx.get_mut(session) {
+            session.oauth2_tokens.insert(key, token);
         }
     }
 }
@@ -407,18 +491,102 @@ impl BlobState for LuaState {
 
         id
     }
+
+    fn get_blob(&self, id: &str) -> Result<Arc<Blob>> {
+        if let Some(blob) = self.blobs.lock().unwrap().get(id) {
+            return Ok(blob.clone());
+        }
+
+        if !self.stored_blobs.lock().unwrap().contains(id) {
+            bail!("Invalid blob reference");
+        }
+
+        debug!("Loading blob from storage: {:?}", id);
+        let blob: Blob = self.blob_stream(BlobStreamEvent::Load(id.to_string()))?;
+        if blob.id != id {
+            bail!("Blob storage returned wrong blob");
+        }
+
+        let blob = Arc::new(blob);
+        let mut mtx = self.blobs.lock().unwrap();
+        mtx.insert(id.to_string(), blob.clone());
+        Ok(blob)
+    }
+
+    fn blob_writer<'a>(&'a self) -> Box<dyn BlobWriter + 'a> {
+        Box::new(IpcBlobWriter {
+            state: self,
+            started: false,
+        })
+    }
+}
+
+/// Streams a blob into the blob storage of the parent process
+struct IpcBlobWriter<'a> {
+    state: &'a LuaState,
+    started: bool,
+}
+
+impl<'a> IpcBlobWriter<'a> {
+    fn start(&mut self) -> Result<()> {
+        if !self.started {
+            self.state.blob_stream::<()>(BlobStreamEvent::Start)?;
+            self.started = true;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> BlobWriter for IpcBlobWriter<'a> {
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.start()?;
+        let chunk = BlobChunk(bytes.into());
+        self.state.blob_stream(BlobStreamEvent::Write(chunk))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<String> {
+        self.start()?;
+        self.started = false;
+        let id: String = self.state.blob_stream(BlobStreamEvent::Finish)?;
+
+        debug!("Registered stored blob: {:?}", id);
+        let mut mtx = self.state.stored_blobs.lock().unwrap();
+        mtx.insert(id.clone());
+        Ok(id)
+    }
+}
+
+impl<'a> Drop for IpcBlobWriter<'a> {
+    fn drop(&mut self) {
+        if self.started {
+            self.state.blob_stream::<()>(BlobStreamEvent::Abort).ok();
+        }
+    }
 }
 
 impl LuaState {
-    fn resolve_proxy_options<'a>(&'a self, options: &'a Option<SocketAddr>) -> Result<Option<&'a SocketAddr>> {
+    fn blob_stream<T: DeserializeOwned>(&self, event: BlobStreamEvent) -> Result<T> {
+        self.send(&Event::BlobStream(event));
+        let reply = self.recv()?;
+        let reply: result::Result<T, String> = serde_json::from_value(reply)?;
+        reply.map_err(|err| format_err!("Blob storage error: {}", err))
+    }
+
+    fn resolve_proxy_options(&self, options: &Option<Proxy>) -> Result<Option<Proxy>> {
         match (&self.proxy, options) {
-            (Some(system),  Some(options))  if system == options => Ok(Some(system)),
+            (Some(system),  Some(options))  if system.contains(options) => Ok(Some(options.clone())),
             (Some(_),       Some(_))        => bail!("Overriding the system proxy isn't allowed"),
-            (Some(system),  None)           => Ok(Some(system)),
-            (None,          Some(options))  => Ok(Some(options)),
+            (Some(system),  None)           => Ok(system.next()),
+            (None,          Some(options))  => Ok(Some(options.clone())),
             (None,          None)           => Ok(None),
         }
     }
+
+    /// Returns the access key and secret key of the first matching keyring entry
+    fn keyring_lookup(&self, query: &str) -> Option<(String, Option<String>)> {
+        self.keyring(query).first()
+            .map(|key| (key.access_key.clone(), key.secret_key.clone()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -440,8 +608,10 @@ pub fn ctx<'a>(env: Environment, logger: Arc<Mutex<Box<dyn IpcChild>>>) -> (hlua
         socket_sessions: Mutex::new(HashMap::new()),
         ws_sessions: Mutex::new(HashMap::new()),
         blobs: Mutex::new(HashMap::new()),
+        stored_blobs: Mutex::new(HashSet::new()),
         http_sessions: Mutex::new(HashMap::new()),
         http_clients: Mutex::new(HashMap::new()),
+        cookie_jars: Mutex::new(env.cookie_jars),
 
         verbose: env.verbose,
         keyring: env.keyring,
@@ -472,6 +642,8 @@ pub fn ctx<'a>(env: Environment, logger: Arc<Mutex<Box<dyn IpcChild>>>) -> (hlua
     runtime::db_add_ttl(&mut lua, state.clone());
     runtime::db_activity(&mut lua, state.clone());
     runtime::db_select(&mut lua, state.clone());
+    runtime::db_tag(&mut lua, state.clone());
+    runtime::db_untag(&mut lua, state.clone());
     runtime::db_update(&mut lua, state.clone());
     runtime::debug(&mut lua, state.clone());
     runtime::dns(&mut lua, state.clone());
@@ -491,6 +663,8 @@ pub fn ctx<'a>(env: Environment, logger: Arc<Mutex<Box<dyn IpcChild>>>) -> (hlua
     runtime::http_mksession(&mut lua, state.clone());
     runtime::http_request(&mut lua, state.clone());
     runtime::http_send(&mut lua, state.clone());
+    runtime::http_jar_restore(&mut lua, state.clone());
+    runtime::http_jar_persist(&mut lua, state.clone());
     runtime::http_fetch(&mut lua, state.clone());
     runtime::http_fetch_json(&mut lua, state.clone());
     runtime::img_exif(&mut lua, state.clone());
@@ -504,6 +678,8 @@ pub fn ctx<'a>(env: Environment, logger: Arc<Mutex<Box<dyn IpcChild>>>) -> (hlua
     runtime::keyring(&mut lua, state.clone());
     runtime::last_err(&mut lua, state.clone());
     runtime::md5(&mut lua, state.clone());
+    runtime::office_metadata(&mut lua, state.clone());
+    runtime::pdf_metadata(&mut lua, state.clone());
     runtime::pgp_pubkey(&mut lua, state.clone());
     runtime::pgp_pubkey_armored(&mut lua, state.clone());
     runtime::print(&mut lua, state.clone());
@@ -648,6 +824,7 @@ a.prod.fastly.net
             proxy,
             options: HashMap::new(),
             blobs: Vec::new(),
+            cookie_jars: HashMap::new(),
             psl,
             geoip,
             asn,
@@ -1,6 +1,7 @@
 use crate::errors::*;
 
 use crate::blobs::Blob;
+use crate::cookies::CookieJar;
 use crate::config::Config;
 use crate::geoip::MaxmindReader;
 use crate::json::LuaJsonValue;
@@ -9,12 +10,12 @@ use std::fs;
 use std::fmt::Debug;
 use std::path::PathBuf;
 use std::collections::HashMap;
-use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use crate::engine::ctx::Script;
 use crate::ipc::child::IpcChild;
 use sn0int_common::ModuleID;
 use sn0int_common::metadata::{Metadata, Source};
+use sn0int_std::proxy::ProxyPool;
 use chrootable_https::dns::Resolver;
 use crate::psl::PslReader;
 use crate::paths;
@@ -33,9 +34,10 @@ pub struct Environment {
     pub verbose: u64,
     pub keyring: Vec<KeyRingEntry>,
     pub dns_config: Resolver,
-    pub proxy: Option<SocketAddr>,
+    pub proxy: Option<ProxyPool>,
     pub options: HashMap<String, String>,
     pub blobs: Vec<Blob>,
+    pub cookie_jars: HashMap<String, CookieJar>,
     pub psl: PslReader,
     pub geoip: Option<MaxmindReader>,
     pub asn: Option<MaxmindReader>,
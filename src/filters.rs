@@ -1,6 +1,7 @@
 use crate::errors::*;
 
 use crate::db;
+use std::fmt;
 use structopt::StructOpt;
 
 
@@ -48,6 +49,27 @@ pub enum Target {
     /// On crypto currency addresses
     #[structopt(name="cryptoaddrs")]
     CryptoAddrs(Filter),
+    /// On dns records
+    #[structopt(name="dns-records")]
+    DnsRecords(Filter),
+    /// On certificates
+    #[structopt(name="certificates")]
+    Certificates(Filter),
+    /// On organizations
+    #[structopt(name="organizations")]
+    Organizations(Filter),
+    /// On persons
+    #[structopt(name="persons")]
+    Persons(Filter),
+    /// On autonomous systems
+    #[structopt(name="autonomous-systems")]
+    AutonomousSystems(Filter),
+    /// On documents
+    #[structopt(name="documents")]
+    Documents(Filter),
+    /// On locations
+    #[structopt(name="locations")]
+    Locations(Filter),
 }
 
 #[derive(Debug, StructOpt)]
@@ -64,3 +86,9 @@ impl Filter {
         db::Filter::parse(&self.args)
     }
 }
+
+impl fmt::Display for Filter {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{}", self.args.join(" "))
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt;
 pub use std::fmt::Write;
 pub use std::fmt::{Result, Formatter, Display, Debug};
+use crate::models::{extra, DEFAULT_CONFIDENCE};
 
 
 pub mod colors {
@@ -222,6 +223,32 @@ impl<'a, 'b> DetailFormatter<'a, 'b> {
             write!(self, "\n\t\x1b[90m{}\x1b[0m", c)
         }
     }
+
+    #[inline]
+    pub fn note<D: fmt::Display>(&mut self, author: &str, time: D, content: &str) -> fmt::Result {
+        write!(self, "\n\t\x1b[90m# {} @ {}: {:?}\x1b[0m", author, time, content)
+    }
+
+    /// Show the verification state and confidence if they differ from the default
+    pub fn confidence(&mut self, confidence: i32, verified: bool) -> fmt::Result {
+        self.start_group();
+        if verified {
+            self.display::<colors::Green, _>("verified")?;
+        }
+        if confidence != DEFAULT_CONFIDENCE {
+            self.display_label::<colors::Red, _>("confidence", confidence)?;
+        }
+        self.end_group()
+    }
+
+    /// Show custom attributes as key=value pairs
+    pub fn extra(&mut self, extra: &Option<String>) -> fmt::Result {
+        self.start_group();
+        for (key, value) in extra::parse(extra) {
+            self.display_label::<colors::Yellow, _>(&key, value)?;
+        }
+        self.end_group()
+    }
 }
 
 impl<'a, 'b> fmt::Write for DetailFormatter<'a, 'b> {
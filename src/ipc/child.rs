@@ -36,9 +36,18 @@ impl StdioIpcChild {
     }
 
     pub fn recv_start(&mut self) -> Result<StartCommand> {
-        let value = self.recv()?;
-        let event = serde_json::from_value(value)?;
-        Ok(event)
+        let value = self.read()?;
+        let start = serde_json::from_value(value)?;
+        // the json contains the proxy credentials, the struct redacts them
+        debug!("IpcChild received: {:?}", start);
+        Ok(start)
+    }
+
+    fn read(&mut self) -> Result<serde_json::Value> {
+        let mut line = String::new();
+        let len = self.stdin.read_line(&mut line)?;
+        let value = serde_json::from_str(&line[..len])?;
+        Ok(value)
     }
 }
 
@@ -52,10 +61,7 @@ impl IpcChild for StdioIpcChild {
     }
 
     fn recv(&mut self) -> Result<serde_json::Value> {
-        let mut line = String::new();
-        let len = self.stdin.read_line(&mut line)?;
-
-        let event = serde_json::from_str(&line[..len])?;
+        let event = self.read()?;
         debug!("IpcChild received: {:?}", event);
         Ok(event)
     }
@@ -91,6 +97,7 @@ pub fn run(geoip: Option<MaxmindReader>, asn: Option<MaxmindReader>, psl: PslRea
         proxy: start.proxy,
         options: start.options,
         blobs: start.blobs,
+        cookie_jars: start.cookie_jars,
         psl,
         geoip,
         asn,
@@ -1,11 +1,12 @@
 use chrootable_https::dns::Resolver;
 use crate::blobs::Blob;
+use crate::cookies::CookieJar;
 use crate::engine::Module;
 use crate::keyring::KeyRingEntry;
 use serde_json;
+use sn0int_std::proxy::ProxyPool;
 
 use std::collections::HashMap;
-use std::net::SocketAddr;
 
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -13,22 +14,25 @@ pub struct StartCommand {
     pub verbose: u64,
     pub keyring: Vec<KeyRingEntry>,
     pub dns_config: Resolver,
-    pub proxy: Option<SocketAddr>,
+    pub proxy: Option<ProxyPool>,
     pub options: HashMap<String, String>,
     pub module: Module,
     pub arg: serde_json::Value,
     pub blobs: Vec<Blob>,
+    #[serde(default)]
+    pub cookie_jars: HashMap<String, CookieJar>,
 }
 
 impl StartCommand {
     pub fn new(verbose: u64,
                keyring: Vec<KeyRingEntry>,
                dns_config: Resolver,
-               proxy: Option<SocketAddr>,
+               proxy: Option<ProxyPool>,
                options: HashMap<String, String>,
                module: Module,
                arg: serde_json::Value,
                blobs: Vec<Blob>,
+               cookie_jars: HashMap<String, CookieJar>,
     ) -> StartCommand {
         StartCommand {
             verbose,
@@ -39,6 +43,7 @@ impl StartCommand {
             module,
             arg,
             blobs,
+            cookie_jars,
         }
     }
 }
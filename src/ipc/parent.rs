@@ -1,10 +1,12 @@
 use crate::errors::*;
 use crate::ipc::common::*;
 use chrootable_https::dns::Resolver;
-use crate::blobs::Blob;
+use crate::blobs::{Blob, BlobStorage, BlobStreams};
+use crate::cookies::CookieJar;
 use crate::engine::Module;
 use crate::keyring::KeyRingEntry;
 use serde_json;
+use sn0int_std::proxy::ProxyPool;
 use crate::worker::{Event, Event2, LogEvent, ExitEvent, EventSender, EventWithCallback};
 
 use std::collections::HashMap;
@@ -12,7 +14,6 @@ use std::env;
 use std::ffi::OsString;
 use std::io::prelude::*;
 use std::io::{BufReader, BufRead, stdin};
-use std::net::SocketAddr;
 use std::sync::mpsc;
 use std::process::{Command, Child, Stdio, ChildStdin, ChildStdout};
 
@@ -50,19 +51,26 @@ impl IpcParent {
     }
 
     pub fn send_start(&mut self, start: &StartCommand) -> Result<()> {
-        let start = serde_json::to_value(&start)?;
-        self.send(&start)?;
+        let value = serde_json::to_value(&start)?;
+        self.write(&value)?;
+        // the json contains the proxy credentials, the struct redacts them
+        debug!("IpcParent sent: {:?}", start);
         Ok(())
     }
 
     pub fn send(&mut self, value: &serde_json::Value) -> Result<()> {
-        let mut value = serde_json::to_string(value)?;
-        value.push('\n');
-        self.stdin.write_all(value.as_bytes())?;
-        debug!("IpcParent sent: {:?}", value);
+        let line = self.write(value)?;
+        debug!("IpcParent sent: {:?}", line);
         Ok(())
     }
 
+    fn write(&mut self, value: &serde_json::Value) -> Result<String> {
+        let mut line = serde_json::to_string(value)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        Ok(line)
+    }
+
     pub fn send_struct<T: serde::Serialize>(&mut self, value: T, tx: &EventSender) {
         let value = serde_json::to_value(value).expect("Failed to serialize reply");
         if let Err(_) = self.send(&value) {
@@ -107,9 +115,11 @@ pub fn run(module: Module,
            keyring: Vec<KeyRingEntry>,
            verbose: u64,
            has_stdin: bool,
-           proxy: Option<SocketAddr>,
+           proxy: Option<ProxyPool>,
            options: HashMap<String, String>,
            blobs: Vec<Blob>,
+           storage: BlobStorage,
+           cookie_jars: HashMap<String, CookieJar>,
 ) -> Result<ExitEvent> {
     let dns_config = Resolver::from_system()?;
 
@@ -120,7 +130,9 @@ pub fn run(module: Module,
     };
 
     let mut ipc_parent = IpcParent::setup(&module)?;
-    ipc_parent.send_start(&StartCommand::new(verbose, keyring, dns_config, proxy, options, module, arg, blobs))?;
+    ipc_parent.send_start(&StartCommand::new(verbose, keyring, dns_config, proxy, options, module, arg, blobs, cookie_jars))?;
+
+    let mut streams = BlobStreams::new(storage);
 
     let exit = loop {
         match ipc_parent.recv()? {
@@ -129,6 +141,8 @@ pub fn run(module: Module,
             Event::Stdio(object) => object.apply(&mut ipc_parent, tx, &mut reader),
             Event::Ratelimit(req) => ipc_parent.send_event_callback(req, &tx),
             Event::Blob(blob) => ipc_parent.send_event_callback(blob, &tx),
+            Event::BlobStream(event) => streams.apply(event, &mut ipc_parent, tx),
+            Event::CookieJar(jar) => ipc_parent.send_event_callback(jar, &tx),
             Event::Exit(event) => {
                 if let ExitEvent::Err(err) = &event {
                     tx.send(Event2::Log(LogEvent::Error(err.clone())));
@@ -9,15 +9,18 @@ use hlua_badtouch as hlua;
 #[macro_use] extern crate diesel_migrations;
 #[macro_use] extern crate crossbeam_channel as channel;
 
+#[macro_use] pub mod db;
 pub mod api;
+pub mod archive;
 pub mod args;
 pub mod auth;
 pub mod autonoscope;
 pub mod blobs;
 pub mod cmd;
 pub mod config;
+pub mod cookies;
 use sn0int_std::crt;
-pub mod db;
+use sn0int_std::documents;
 pub mod errors;
 pub mod engine;
 pub mod filters;
@@ -33,6 +36,7 @@ use sn0int_std::lazy;
 pub mod migrations;
 pub mod models;
 pub mod paths;
+use sn0int_std::proxy;
 pub use sn0int_std::psl;
 pub mod options;
 use sn0int_std::ratelimits;
@@ -44,6 +48,7 @@ pub mod schema;
 pub mod ser;
 pub mod shell;
 use sn0int_std::sockets;
+use sn0int_std::transport;
 pub mod term;
 pub mod update;
 pub mod utils;
@@ -9,3 +9,16 @@ pub fn run(conn: &SqliteConnection) -> Result<()> {
     embedded_migrations::run(conn)?;
     Ok(())
 }
+
+#[cfg(feature="postgres")]
+pub mod postgres {
+    use crate::errors::*;
+    use diesel::pg::PgConnection;
+
+    embed_migrations!("migrations-postgres");
+
+    pub fn run(conn: &PgConnection) -> Result<()> {
+        embedded_migrations::run(conn)?;
+        Ok(())
+    }
+}
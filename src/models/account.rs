@@ -21,11 +21,20 @@ pub struct Account {
     pub phonenumber: Option<String>,
     pub profile_pic: Option<String>,
     pub birthday: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Account {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Account)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -33,7 +42,7 @@ impl Model for Account {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::accounts::dsl::*;
 
-        let results = accounts.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| accounts.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -42,7 +51,7 @@ impl Model for Account {
         use crate::schema::accounts::dsl::*;
 
         let query = accounts.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -53,7 +62,7 @@ impl Model for Account {
         let query = accounts
             .filter(service.eq(param))
             .filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -61,16 +70,16 @@ impl Model for Account {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::accounts::dsl::*;
 
-        diesel::delete(accounts.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(accounts.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::accounts::dsl::*;
 
-        diesel::delete(accounts.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(accounts.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -85,8 +94,8 @@ impl Model for Account {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::accounts::dsl::*;
 
-        let account = accounts.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let account = with_conn!(db.db(), |conn| accounts.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(account)
     }
@@ -94,8 +103,8 @@ impl Model for Account {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::accounts::dsl::*;
 
-        let account = accounts.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let account = with_conn!(db.db(), |conn| accounts.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(account)
     }
@@ -103,8 +112,8 @@ impl Model for Account {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::accounts::dsl::*;
 
-        let account = accounts.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let account = with_conn!(db.db(), |conn| accounts.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(account)
@@ -119,22 +128,40 @@ impl Scopable for Account {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::accounts::dsl::*;
 
-        diesel::update(accounts.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(accounts.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::accounts::dsl::*;
 
-        diesel::update(accounts.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(accounts.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Account {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::accounts::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(accounts.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(accounts.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 pub struct PrintableAccount {
     value: String,
 }
@@ -164,6 +191,9 @@ pub struct DetailedAccount {
     birthday: Option<String>,
     phonenumber: Option<String>,
     profile_pic: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedAccount {
@@ -189,6 +219,9 @@ impl DisplayableDetailed for DetailedAccount {
         w.opt_debug_label::<Yellow, _>("profile_pic", &self.profile_pic)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -215,6 +248,9 @@ impl Detailed for Account {
             birthday: self.birthday.clone(),
             phonenumber: self.phonenumber.clone(),
             profile_pic: self.profile_pic.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -233,6 +269,10 @@ pub struct NewAccount {
     pub birthday: Option<String>,
     pub phonenumber: Option<String>,
     pub profile_pic: Option<String>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Account> for NewAccount {
@@ -244,11 +284,9 @@ impl InsertableStruct<Account> for NewAccount {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(accounts::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), accounts::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -265,6 +303,9 @@ impl Upsertable<Account> for NewAccount {
             birthday: Self::upsert_opt(self.birthday, &existing.birthday),
             phonenumber: Self::upsert_opt(self.phonenumber, &existing.phonenumber),
             profile_pic: Self::upsert_opt(self.profile_pic, &existing.profile_pic),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -288,6 +329,10 @@ pub struct InsertAccount {
     pub birthday: Option<String>,
     pub phonenumber: Option<String>,
     pub profile_pic: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertAccount {
@@ -310,6 +355,9 @@ impl InsertToNew for InsertAccount {
             phonenumber: self.phonenumber,
             profile_pic: self.profile_pic,
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -325,6 +373,10 @@ pub struct AccountUpdate {
     pub birthday: Option<String>,
     pub phonenumber: Option<String>,
     pub profile_pic: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for AccountUpdate {
@@ -335,7 +387,10 @@ impl Upsert for AccountUpdate {
         self.last_seen.is_some() ||
         self.birthday.is_some() ||
         self.phonenumber.is_some() ||
-        self.profile_pic.is_some()
+        self.profile_pic.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -345,6 +400,11 @@ impl Upsert for AccountUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_account(&self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Account> for AccountUpdate {
@@ -356,6 +416,9 @@ impl Updateable<Account> for AccountUpdate {
         Self::clear_if_equal(&mut self.birthday, &existing.birthday);
         Self::clear_if_equal(&mut self.phonenumber, &existing.phonenumber);
         Self::clear_if_equal(&mut self.profile_pic, &existing.profile_pic);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -366,5 +429,8 @@ impl Updateable<Account> for AccountUpdate {
         Self::push_value(updates, "birthday", &self.birthday);
         Self::push_value(updates, "phonenumber", &self.phonenumber);
         Self::push_value(updates, "profile_pic", &self.profile_pic);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Account)]
+#[belongs_to(Location)]
+#[table_name="account_locations"]
+pub struct AccountLocation {
+    pub id: i32,
+    pub account_id: i32,
+    pub location_id: i32,
+}
+
+impl Model for AccountLocation {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("AccountLocation can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::account_locations::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| account_locations.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::account_locations::dsl::*;
+
+        let query = account_locations.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::account_locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(account_locations.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::account_locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(account_locations.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::account_locations::dsl::*;
+
+        let account_location = with_conn!(db.db(), |conn| account_locations.filter(id.eq(my_id))
+                                                .first::<Self>(conn))?;
+
+        Ok(account_location)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::account_locations::dsl::*;
+
+        let (my_account_id, my_location_id) = query;
+        let account_location = with_conn!(db.db(), |conn| account_locations.filter(account_id.eq(my_account_id))
+                                                .filter(location_id.eq(my_location_id))
+                                                .first::<Self>(conn))?;
+
+        Ok(account_location)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::account_locations::dsl::*;
+
+        let (my_account_id, my_location_id) = query;
+        let account_location = with_conn!(db.db(), |conn| account_locations.filter(account_id.eq(my_account_id))
+                                                .filter(location_id.eq(my_location_id))
+                                                .first::<Self>(conn))
+                                                .optional()?;
+
+        Ok(account_location)
+    }
+}
+
+pub struct PrintableAccountLocation {
+    account: String,
+    location: String,
+}
+
+impl fmt::Display for PrintableAccountLocation {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.account, self.location)
+    }
+}
+
+impl Printable<PrintableAccountLocation> for AccountLocation {
+    fn printable(&self, db: &Database) -> Result<PrintableAccountLocation> {
+        let account = Account::by_id(db, self.account_id)?;
+        let location = Location::by_id(db, self.location_id)?;
+        Ok(PrintableAccountLocation {
+            account: account.value.to_string(),
+            location: location.value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="account_locations"]
+pub struct NewAccountLocation {
+    pub account_id: i32,
+    pub location_id: i32,
+}
+
+impl Printable<PrintableAccountLocation> for NewAccountLocation {
+    fn printable(&self, db: &Database) -> Result<PrintableAccountLocation> {
+        let account = Account::by_id(db, self.account_id)?;
+        let location = Location::by_id(db, self.location_id)?;
+        Ok(PrintableAccountLocation {
+            account: account.value.to_string(),
+            location: location.value,
+        })
+    }
+}
+
+pub type InsertAccountLocation = NewAccountLocation;
+
+impl InsertToNew for InsertAccountLocation {
+    type Target = NewAccountLocation;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewAccountLocation> {
+        Ok(self)
+    }
+}
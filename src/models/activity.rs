@@ -2,7 +2,6 @@ use crate::errors::*;
 use crate::schema::activity;
 use diesel;
 use diesel::prelude::*;
-use diesel::query_builder::BoxedSelectStatement;
 use crate::models::*;
 use chrono::NaiveDateTime;
 use std::convert::TryFrom;
@@ -22,61 +21,68 @@ pub struct Activity {
     pub content: String,
 }
 
-impl Activity {
-    pub fn uniq(db: &Database, my_uniq: &str) -> Result<Option<Self>> {
-        use crate::schema::activity::dsl::*;
-        activity.filter(uniq.eq(my_uniq))
-            .first::<Self>(db.db())
-            .optional()
-            .map_err(Error::from)
-    }
-
-    fn build_query_except_since(filter: &ActivityFilter) -> BoxedSelectStatement<(diesel::sql_types::Integer, diesel::sql_types::Text, diesel::sql_types::Timestamp, diesel::sql_types::Nullable<diesel::sql_types::Text>, diesel::sql_types::Nullable<diesel::sql_types::Float>, diesel::sql_types::Nullable<diesel::sql_types::Float>, diesel::sql_types::Nullable<diesel::sql_types::Integer>, diesel::sql_types::Text), activity::table, diesel::sqlite::Sqlite> {
+/// Build the boxed activity query without the "since" filter
+///
+/// This is a macro so the query is boxed for the backend of the connection it's executed on.
+macro_rules! build_query_except_since {
+    ($filter:expr) => {{
         use crate::schema::activity::dsl::*;
 
         let mut query = activity.into_boxed();
 
-        if let Some(my_topic) = &filter.topic {
+        if let Some(my_topic) = &$filter.topic {
             query = query.filter(topic.eq(my_topic));
         }
 
         // "since" filter is not applied
 
-        if let Some(until) = &filter.until {
+        if let Some(until) = &$filter.until {
             query = query.filter(time.le(until));
         }
 
-        if filter.location {
+        if $filter.location {
             query = query
                 .filter(latitude.is_not_null())
                 .filter(longitude.is_not_null());
         }
 
         query
+    }};
+}
+
+impl Activity {
+    pub fn uniq(db: &Database, my_uniq: &str) -> Result<Option<Self>> {
+        use crate::schema::activity::dsl::*;
+        with_conn!(db.db(), |conn| activity.filter(uniq.eq(my_uniq))
+            .first::<Self>(conn))
+            .optional()
+            .map_err(Error::from)
     }
 
     pub fn query(db: &Database, filter: &ActivityFilter) -> Result<Vec<Self>> {
         use crate::schema::activity::dsl::*;
 
-        let mut query = Self::build_query_except_since(filter);
+        with_conn!(db.db(), |conn| {
+            let mut query = build_query_except_since!(filter);
 
-        if let Some(since) = &filter.since {
-            query = query.filter(time.ge(since));
-        }
+            if let Some(since) = &filter.since {
+                query = query.filter(time.ge(since));
+            }
 
-        query
-            .order_by((time.asc(), id.asc()))
-            .load::<Self>(db.db())
+            query
+                .order_by((time.asc(), id.asc()))
+                .load::<Self>(conn)
+        })
             .map_err(Error::from)
     }
 
     pub fn previous(db: &Database, previous: &Activity, filter: &ActivityFilter) -> Result<Option<Self>> {
         use crate::schema::activity::dsl::*;
 
-        Self::build_query_except_since(filter)
+        with_conn!(db.db(), |conn| build_query_except_since!(filter)
             .filter(time.lt(previous.time))
             .order_by((time.desc(), id.desc()))
-            .first::<Self>(db.db())
+            .first::<Self>(conn))
             .optional()
             .map_err(Error::from)
     }
@@ -148,9 +154,9 @@ pub struct NewActivity {
 
 impl NewActivity {
     pub fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(activity::table)
+        with_conn!(db.db(), |conn| diesel::insert_into(activity::table)
             .values(self)
-            .execute(db.db())?;
+            .execute(conn))?;
         Ok(())
     }
 }
@@ -0,0 +1,462 @@
+use crate::errors::*;
+use crate::fmt::colors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+use chrono::NaiveDateTime;
+
+
+#[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="autonomous_systems"]
+pub struct AutonomousSystem {
+    pub id: i32,
+    pub value: i32,
+    pub unscoped: bool,
+    pub name: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Model for AutonomousSystem {
+    type ID = i32;
+
+    fn family() -> Option<Family> {
+        Some(Family::AutonomousSystem)
+    }
+
+    fn to_string(&self) -> String {
+        format!("AS{}", self.value)
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| autonomous_systems.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        let query = autonomous_systems.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(autonomous_systems.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(autonomous_systems.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn value(&self) -> &Self::ID {
+        &self.value
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        let autonomous_system = with_conn!(db.db(), |conn| autonomous_systems.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(autonomous_system)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        let autonomous_system = with_conn!(db.db(), |conn| autonomous_systems.filter(value.eq(query))
+            .first::<Self>(conn))?;
+
+        Ok(autonomous_system)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        let autonomous_system = with_conn!(db.db(), |conn| autonomous_systems.filter(value.eq(query))
+            .first::<Self>(conn))
+            .optional()?;
+
+        Ok(autonomous_system)
+    }
+}
+
+impl Scopable for AutonomousSystem {
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    fn scope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(autonomous_systems.filter(filter.sql()))
+            .set(unscoped.eq(false))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(autonomous_systems.filter(filter.sql()))
+            .set(unscoped.eq(true))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+}
+
+impl Seen for AutonomousSystem {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::autonomous_systems::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(autonomous_systems.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(autonomous_systems.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
+impl AutonomousSystem {
+    fn netblocks(&self, db: &Database) -> Result<Vec<Netblock>> {
+        with_conn!(db.db(), |conn| netblocks::table
+            .filter(netblocks::asn.eq(self.value))
+            .load::<Netblock>(conn))
+            .map_err(Error::from)
+    }
+
+    fn ipaddrs(&self, db: &Database) -> Result<Vec<IpAddr>> {
+        with_conn!(db.db(), |conn| ipaddrs::table
+            .filter(ipaddrs::asn.eq(self.value))
+            .load::<IpAddr>(conn))
+            .map_err(Error::from)
+    }
+}
+
+pub struct PrintableAutonomousSystem {
+    value: i32,
+}
+
+impl fmt::Display for PrintableAutonomousSystem {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "AS{}", self.value)
+    }
+}
+
+impl Printable<PrintableAutonomousSystem> for AutonomousSystem {
+    fn printable(&self, _db: &Database) -> Result<PrintableAutonomousSystem> {
+        Ok(PrintableAutonomousSystem {
+            value: self.value,
+        })
+    }
+}
+
+pub struct DetailedAutonomousSystem {
+    id: i32,
+    value: i32,
+    unscoped: bool,
+    name: Option<String>,
+    netblocks: Vec<PrintableNetblock>,
+    ipaddrs: Vec<PrintableIpAddr>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
+}
+
+impl DisplayableDetailed for DetailedAutonomousSystem {
+    #[inline]
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        w.id(self.id)?;
+        w.display::<Green, _>(format!("AS{}", self.value))?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.name)?;
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        for netblock in &self.netblocks {
+            w.child(netblock)?;
+        }
+        for ipaddr in &self.ipaddrs {
+            w.child(ipaddr)?;
+        }
+        Ok(())
+    }
+}
+
+display_detailed!(DetailedAutonomousSystem);
+
+impl Detailed for AutonomousSystem {
+    type T = DetailedAutonomousSystem;
+
+    fn detailed(&self, db: &Database) -> Result<Self::T> {
+        let netblocks = self.netblocks(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let ipaddrs = self.ipaddrs(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        Ok(DetailedAutonomousSystem {
+            id: self.id,
+            value: self.value,
+            unscoped: self.unscoped,
+            name: self.name.clone(),
+            netblocks,
+            ipaddrs,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="autonomous_systems"]
+pub struct NewAutonomousSystem {
+    pub value: i32,
+    pub name: Option<String>,
+    pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertableStruct<AutonomousSystem> for NewAutonomousSystem {
+    fn value(&self) -> &i32 {
+        &self.value
+    }
+
+    fn set_scoped(&mut self, scoped: bool) {
+        self.unscoped = !scoped;
+    }
+
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), autonomous_systems::table, self)?;
+        Ok(n > 0)
+    }
+}
+
+impl Upsertable<AutonomousSystem> for NewAutonomousSystem {
+    type Update = AutonomousSystemUpdate;
+
+    fn upsert(self, existing: &AutonomousSystem) -> Self::Update {
+        Self::Update {
+            id: existing.id,
+            name: Self::upsert_opt(self.name, &existing.name),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
+        }
+    }
+}
+
+impl Printable<PrintableAutonomousSystem> for NewAutonomousSystem {
+    fn printable(&self, _db: &Database) -> Result<PrintableAutonomousSystem> {
+        Ok(PrintableAutonomousSystem {
+            value: self.value,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertAutonomousSystem {
+    pub value: i32,
+    pub name: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertToNew for InsertAutonomousSystem {
+    type Target = NewAutonomousSystem;
+
+    fn try_into_new(self) -> Result<NewAutonomousSystem> {
+        if self.value < 0 {
+            bail!("Invalid autonomous system number: {}", self.value);
+        }
+
+        Ok(NewAutonomousSystem {
+            value: self.value,
+            name: self.name,
+
+            unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
+    }
+}
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="autonomous_systems"]
+pub struct AutonomousSystemUpdate {
+    pub id: i32,
+    pub name: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for AutonomousSystemUpdate {
+    fn is_dirty(&self) -> bool {
+        self.name.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::AutonomousSystem(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_autonomous_system(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<AutonomousSystem> for AutonomousSystemUpdate {
+    fn changeset(&mut self, existing: &AutonomousSystem) {
+        Self::clear_if_equal(&mut self.name, &existing.name);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "name", &self.name);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autonomous_system_invalid() {
+        let r = InsertAutonomousSystem {
+            value: -1,
+            name: None,
+            confidence: None,
+            verified: None,
+            extra: None,
+        }.try_into_new();
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_autonomous_system_invalid_confidence() {
+        let r = InsertAutonomousSystem {
+            value: 1234,
+            name: None,
+            confidence: Some(101),
+            verified: None,
+            extra: None,
+        }.try_into_new();
+        assert!(r.is_err());
+    }
+
+    fn existing(confidence: i32, verified: bool) -> AutonomousSystem {
+        AutonomousSystem {
+            id: 1,
+            value: 1234,
+            unscoped: false,
+            name: None,
+            first_seen: None,
+            last_seen: None,
+            confidence,
+            verified,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_autonomous_system_upsert_confidence() {
+        let new = InsertAutonomousSystem {
+            value: 1234,
+            name: None,
+            confidence: Some(80),
+            verified: None,
+            extra: None,
+        }.try_into_new().unwrap();
+
+        let update = new.clone().upsert(&existing(50, false));
+        assert_eq!(update.confidence, Some(80));
+        assert_eq!(update.verified, None);
+
+        let update = new.upsert(&existing(90, true));
+        assert!(!update.is_dirty());
+    }
+    #[test]
+    fn test_autonomous_system_update_invalid_confidence() {
+        let update = AutonomousSystemUpdate {
+            id: 1,
+            name: None,
+            confidence: Some(101),
+            verified: None,
+            extra: None,
+        };
+        assert!(update.validate().is_err());
+        assert!(Update::AutonomousSystem(update).validate().is_err());
+
+        let update = AutonomousSystemUpdate {
+            id: 1,
+            name: None,
+            confidence: Some(80),
+            verified: None,
+            extra: None,
+        };
+        assert!(update.validate().is_ok());
+    }
+}
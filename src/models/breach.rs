@@ -3,6 +3,7 @@ use crate::fmt::colors::*;
 use diesel;
 use diesel::prelude::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 
 
 #[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
@@ -11,11 +12,21 @@ pub struct Breach {
     pub id: i32,
     pub value: String,
     pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Breach {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Breach)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -23,7 +34,7 @@ impl Model for Breach {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::breaches::dsl::*;
 
-        let results = breaches.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| breaches.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -32,7 +43,7 @@ impl Model for Breach {
         use crate::schema::breaches::dsl::*;
 
         let query = breaches.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -40,16 +51,16 @@ impl Model for Breach {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::breaches::dsl::*;
 
-        diesel::delete(breaches.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(breaches.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::breaches::dsl::*;
 
-        diesel::delete(breaches.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(breaches.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -64,8 +75,8 @@ impl Model for Breach {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::breaches::dsl::*;
 
-        let domain = breaches.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| breaches.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -73,8 +84,8 @@ impl Model for Breach {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::breaches::dsl::*;
 
-        let breach = breaches.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let breach = with_conn!(db.db(), |conn| breaches.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(breach)
     }
@@ -82,8 +93,8 @@ impl Model for Breach {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::breaches::dsl::*;
 
-        let breach = breaches.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let breach = with_conn!(db.db(), |conn| breaches.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(breach)
@@ -98,35 +109,53 @@ impl Scopable for Breach {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::breaches::dsl::*;
 
-        diesel::update(breaches.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(breaches.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::breaches::dsl::*;
 
-        diesel::update(breaches.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(breaches.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Breach {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::breaches::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(breaches.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(breaches.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Breach {
     fn emails(&self, db: &Database) -> Result<Vec<(Email, Option<String>)>> {
         use std::result;
 
-        let email_id_pws = BreachEmail::belonging_to(self)
+        let email_id_pws = with_conn!(db.db(), |conn| BreachEmail::belonging_to(self)
             .select((breach_emails::email_id, breach_emails::password))
-            .load::<(i32, Option<String>)>(db.db())?;
+            .load::<(i32, Option<String>)>(conn))?;
 
         email_id_pws.into_iter()
             .map(|(email_id, password)| {
-                emails::table
+                with_conn!(db.db(), |conn| emails::table
                     .filter(emails::id.eq(email_id))
-                    .first::<Email>(db.db())
+                    .first::<Email>(conn))
                     .map(|email| (email, password))
             })
             .collect::<result::Result<Vec<_>, _>>()
@@ -172,6 +201,9 @@ pub struct DetailedBreach {
     value: String,
     emails: Vec<EmailWithPassword>,
     unscoped: bool,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedBreach {
@@ -184,6 +216,8 @@ impl DisplayableDetailed for DetailedBreach {
     fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
         w.id(self.id)?;
         w.debug::<Green, _>(&self.value)?;
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
         Ok(())
     }
 
@@ -214,6 +248,9 @@ impl Detailed for Breach {
             value: self.value.to_string(),
             emails,
             unscoped: self.unscoped,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -223,6 +260,10 @@ impl Detailed for Breach {
 pub struct NewBreach {
     pub value: String,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Breach> for NewBreach {
@@ -234,20 +275,21 @@ impl InsertableStruct<Breach> for NewBreach {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(breaches::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), breaches::table, self)?;
+        Ok(n > 0)
     }
 }
 
 impl Upsertable<Breach> for NewBreach {
-    type Update = NullUpdate;
+    type Update = BreachUpdate;
 
     fn upsert(self, existing: &Breach) -> Self::Update {
         Self::Update {
             id: existing.id,
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -263,6 +305,10 @@ impl Printable<PrintableBreach> for NewBreach {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InsertBreach {
     pub value: String,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertBreach {
@@ -273,6 +319,54 @@ impl InsertToNew for InsertBreach {
             value: self.value,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="breaches"]
+pub struct BreachUpdate {
+    pub id: i32,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for BreachUpdate {
+    fn is_dirty(&self) -> bool {
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::Breach(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_breach(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<Breach> for BreachUpdate {
+    fn changeset(&mut self, existing: &Breach) {
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
@@ -25,7 +25,7 @@ impl Model for BreachEmail {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::breach_emails::dsl::*;
 
-        let results = breach_emails.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| breach_emails.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -34,7 +34,7 @@ impl Model for BreachEmail {
         use crate::schema::breach_emails::dsl::*;
 
         let query = breach_emails.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -42,16 +42,16 @@ impl Model for BreachEmail {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::breach_emails::dsl::*;
 
-        diesel::delete(breach_emails.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(breach_emails.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::breach_emails::dsl::*;
 
-        diesel::delete(breach_emails.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(breach_emails.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -62,8 +62,8 @@ impl Model for BreachEmail {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::breach_emails::dsl::*;
 
-        let breach_email = breach_emails.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let breach_email = with_conn!(db.db(), |conn| breach_emails.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(breach_email)
     }
@@ -76,12 +76,12 @@ impl Model for BreachEmail {
         let query = breach_emails.filter(breach_id.eq(my_breach_id))
                                  .filter(email_id.eq(my_email_id));
         let breach_email = if let Some(my_password) = my_password {
-           query
+           with_conn!(db.db(), |conn| query
                .filter(password.is_null().or(password.eq(my_password)))
-               .first::<Self>(db.db())?
+               .first::<Self>(conn))?
         } else {
-           query
-               .first::<Self>(db.db())?
+           with_conn!(db.db(), |conn| query
+               .first::<Self>(conn))?
         };
 
         Ok(breach_email)
@@ -95,13 +95,13 @@ impl Model for BreachEmail {
         let query = breach_emails.filter(breach_id.eq(my_breach_id))
                                  .filter(email_id.eq(my_email_id));
         let breach_email = if let Some(my_password) = my_password {
-           query
+           with_conn!(db.db(), |conn| query
                .filter(password.is_null().or(password.eq(my_password)))
-               .first::<Self>(db.db())
+               .first::<Self>(conn))
                .optional()?
         } else {
-           query
-               .first::<Self>(db.db())
+           with_conn!(db.db(), |conn| query
+               .first::<Self>(conn))
                .optional()?
         };
 
@@ -0,0 +1,422 @@
+use crate::errors::*;
+use crate::fmt::colors::*;
+use crate::models::*;
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::prelude::*;
+use sn0int_std::crt::CertificateDetails;
+use std::result;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="certificates"]
+pub struct Certificate {
+    pub id: i32,
+    pub value: String,
+    pub serial: String,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: NaiveDateTime,
+    pub not_after: NaiveDateTime,
+    pub names: String,
+    pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Model for Certificate {
+    type ID = str;
+
+    fn family() -> Option<Family> {
+        Some(Family::Certificate)
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_owned()
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::certificates::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| certificates.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::certificates::dsl::*;
+
+        let query = certificates.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(certificates.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(certificates.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn value(&self) -> &Self::ID {
+        &self.value
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::certificates::dsl::*;
+
+        let certificate = with_conn!(db.db(), |conn| certificates.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(certificate)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::certificates::dsl::*;
+
+        let certificate = with_conn!(db.db(), |conn| certificates.filter(value.eq(query))
+            .first::<Self>(conn))?;
+
+        Ok(certificate)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::certificates::dsl::*;
+
+        let certificate = with_conn!(db.db(), |conn| certificates.filter(value.eq(query))
+            .first::<Self>(conn))
+            .optional()?;
+
+        Ok(certificate)
+    }
+}
+
+impl Scopable for Certificate {
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    fn scope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(certificates.filter(filter.sql()))
+            .set(unscoped.eq(false))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(certificates.filter(filter.sql()))
+            .set(unscoped.eq(true))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+}
+
+impl Seen for Certificate {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::certificates::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(certificates.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(certificates.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
+impl Certificate {
+    fn subdomains(&self, db: &Database) -> Result<Vec<Subdomain>> {
+        let subdomain_ids = with_conn!(db.db(), |conn| SubdomainCertificate::belonging_to(self)
+            .select(subdomain_certificates::subdomain_id)
+            .load::<i32>(conn))?;
+
+        subdomain_ids.into_iter()
+            .map(|subdomain_id| with_conn!(db.db(), |conn| subdomains::table
+                .filter(subdomains::id.eq(subdomain_id))
+                .first::<Subdomain>(conn))
+            )
+            .collect::<result::Result<_, _>>()
+            .map_err(Error::from)
+    }
+
+    fn ports(&self, db: &Database) -> Result<Vec<Port>> {
+        let port_ids = with_conn!(db.db(), |conn| PortCertificate::belonging_to(self)
+            .select(port_certificates::port_id)
+            .load::<i32>(conn))?;
+
+        port_ids.into_iter()
+            .map(|port_id| with_conn!(db.db(), |conn| ports::table
+                .filter(ports::id.eq(port_id))
+                .first::<Port>(conn))
+            )
+            .collect::<result::Result<_, _>>()
+            .map_err(Error::from)
+    }
+}
+
+pub struct PrintableCertificate {
+    value: String,
+}
+
+impl fmt::Display for PrintableCertificate {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?}", self.value)
+    }
+}
+
+impl Printable<PrintableCertificate> for Certificate {
+    fn printable(&self, _db: &Database) -> Result<PrintableCertificate> {
+        Ok(PrintableCertificate {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+pub struct DetailedCertificate {
+    id: i32,
+    value: String,
+    subject: String,
+    issuer: String,
+    not_after: NaiveDateTime,
+    names: String,
+    subdomains: Vec<PrintableSubdomain>,
+    ports: Vec<PrintablePort>,
+    unscoped: bool,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
+}
+
+impl DisplayableDetailed for DetailedCertificate {
+    #[inline]
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        w.id(self.id)?;
+        w.debug::<Green, _>(&self.value)?;
+
+        w.start_group();
+        w.display_label::<Yellow, _>("subject", &self.subject)?;
+        w.display_label::<Yellow, _>("issuer", &self.issuer)?;
+        if self.not_after < Utc::now().naive_utc() {
+            w.display_label::<Red, _>("expired", &self.not_after)?;
+        } else {
+            w.display_label::<Green, _>("expires", &self.not_after)?;
+        }
+        if !self.names.is_empty() {
+            w.display_label::<Yellow, _>("names", &self.names)?;
+        }
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        for subdomain in &self.subdomains {
+            w.child(subdomain)?;
+        }
+        for port in &self.ports {
+            w.child(port)?;
+        }
+        Ok(())
+    }
+}
+
+display_detailed!(DetailedCertificate);
+
+impl Detailed for Certificate {
+    type T = DetailedCertificate;
+
+    fn detailed(&self, db: &Database) -> Result<Self::T> {
+        let subdomains = self.subdomains(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let ports = self.ports(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        Ok(DetailedCertificate {
+            id: self.id,
+            value: self.value.to_string(),
+            subject: self.subject.to_string(),
+            issuer: self.issuer.to_string(),
+            not_after: self.not_after,
+            names: self.names.to_string(),
+            subdomains,
+            ports,
+            unscoped: self.unscoped,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="certificates"]
+pub struct NewCertificate {
+    pub value: String,
+    pub serial: String,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: NaiveDateTime,
+    pub not_after: NaiveDateTime,
+    pub names: String,
+    pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertableStruct<Certificate> for NewCertificate {
+    fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn set_scoped(&mut self, scoped: bool) {
+        self.unscoped = !scoped;
+    }
+
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), certificates::table, self)?;
+        Ok(n > 0)
+    }
+}
+
+impl Upsertable<Certificate> for NewCertificate {
+    type Update = CertificateUpdate;
+
+    fn upsert(self, existing: &Certificate) -> Self::Update {
+        Self::Update {
+            id: existing.id,
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
+        }
+    }
+}
+
+impl Printable<PrintableCertificate> for NewCertificate {
+    fn printable(&self, _db: &Database) -> Result<PrintableCertificate> {
+        Ok(PrintableCertificate {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertCertificate {
+    pub pem: String,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertToNew for InsertCertificate {
+    type Target = NewCertificate;
+
+    fn try_into_new(self) -> Result<NewCertificate> {
+        let crt = CertificateDetails::parse_pem(&self.pem)?;
+
+        let mut names = crt.names.valid_names;
+        names.extend(crt.names.valid_ipaddrs.into_iter()
+            .map(|ip| ip.to_string()));
+        names.sort();
+
+        Ok(NewCertificate {
+            value: crt.fingerprint,
+            serial: crt.serial,
+            subject: crt.subject,
+            issuer: crt.issuer,
+            not_before: crt.not_before,
+            not_after: crt.not_after,
+            names: names.join(","),
+            unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
+    }
+}
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="certificates"]
+pub struct CertificateUpdate {
+    pub id: i32,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for CertificateUpdate {
+    fn is_dirty(&self) -> bool {
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::Certificate(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_certificate(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<Certificate> for CertificateUpdate {
+    fn changeset(&mut self, existing: &Certificate) {
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
@@ -19,11 +19,20 @@ pub struct CryptoAddr {
     pub last_withdrawal: Option<NaiveDateTime>,
     pub unscoped: bool,
     pub description: Option<String>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for CryptoAddr {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Cryptoaddr)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -31,7 +40,7 @@ impl Model for CryptoAddr {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        let results = cryptoaddrs.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| cryptoaddrs.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -40,7 +49,7 @@ impl Model for CryptoAddr {
         use crate::schema::cryptoaddrs::dsl::*;
 
         let query = cryptoaddrs.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -51,7 +60,7 @@ impl Model for CryptoAddr {
         let query = cryptoaddrs
             .filter(currency.eq(param))
             .filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -59,16 +68,16 @@ impl Model for CryptoAddr {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        diesel::delete(cryptoaddrs.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(cryptoaddrs.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        diesel::delete(cryptoaddrs.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(cryptoaddrs.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -83,8 +92,8 @@ impl Model for CryptoAddr {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        let domain = cryptoaddrs.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| cryptoaddrs.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -92,8 +101,8 @@ impl Model for CryptoAddr {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        let cryptoaddr = cryptoaddrs.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let cryptoaddr = with_conn!(db.db(), |conn| cryptoaddrs.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(cryptoaddr)
     }
@@ -101,8 +110,8 @@ impl Model for CryptoAddr {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        let cryptoaddr = cryptoaddrs.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let cryptoaddr = with_conn!(db.db(), |conn| cryptoaddrs.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(cryptoaddr)
@@ -117,22 +126,40 @@ impl Scopable for CryptoAddr {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        diesel::update(cryptoaddrs.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(cryptoaddrs.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::cryptoaddrs::dsl::*;
 
-        diesel::update(cryptoaddrs.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(cryptoaddrs.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for CryptoAddr {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::cryptoaddrs::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(cryptoaddrs.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(cryptoaddrs.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 pub struct PrintableCryptoAddr {
     value: String,
 }
@@ -162,6 +189,9 @@ pub struct DetailedCryptoAddr {
     last_withdrawal: Option<NaiveDateTime>,
     unscoped: bool,
     description: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 #[inline]
@@ -201,6 +231,9 @@ impl DisplayableDetailed for DetailedCryptoAddr {
         w.opt_debug_label::<Yellow, _>("description", &self.description)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -227,6 +260,9 @@ impl Detailed for CryptoAddr {
             last_withdrawal: self.last_withdrawal.clone(),
             unscoped: self.unscoped,
             description: self.description.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -243,6 +279,10 @@ pub struct NewCryptoAddr {
     pub last_withdrawal: Option<NaiveDateTime>,
     pub unscoped: bool,
     pub description: Option<String>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<CryptoAddr> for NewCryptoAddr {
@@ -254,11 +294,9 @@ impl InsertableStruct<CryptoAddr> for NewCryptoAddr {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(cryptoaddrs::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), cryptoaddrs::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -275,6 +313,9 @@ impl Upsertable<CryptoAddr> for NewCryptoAddr {
             first_seen: Self::upsert_opt(self.first_seen, &existing.first_seen),
             last_withdrawal: Self::upsert_opt(self.last_withdrawal, &existing.last_withdrawal),
             description: Self::upsert_opt(self.description, &existing.description),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -287,14 +328,41 @@ impl Printable<PrintableCryptoAddr> for NewCryptoAddr {
     }
 }
 
-pub type InsertCryptoAddr = NewCryptoAddr;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertCryptoAddr {
+    pub value: String,
+    pub currency: Option<String>,
+    pub denominator: Option<i32>,
+    pub balance: Option<i64>,
+    pub received: Option<i64>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_withdrawal: Option<NaiveDateTime>,
+    pub unscoped: bool,
+    pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
 
 impl InsertToNew for InsertCryptoAddr {
     type Target = NewCryptoAddr;
 
-    #[inline]
     fn try_into_new(self) -> Result<NewCryptoAddr> {
-        Ok(self)
+        Ok(NewCryptoAddr {
+            value: self.value,
+            currency: self.currency,
+            denominator: self.denominator,
+            balance: self.balance,
+            received: self.received,
+            first_seen: self.first_seen,
+            last_withdrawal: self.last_withdrawal,
+            unscoped: self.unscoped,
+            description: self.description,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
     }
 }
 
@@ -309,6 +377,10 @@ pub struct CryptoAddrUpdate {
     pub first_seen: Option<NaiveDateTime>,
     pub last_withdrawal: Option<NaiveDateTime>,
     pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for CryptoAddrUpdate {
@@ -319,7 +391,10 @@ impl Upsert for CryptoAddrUpdate {
         self.received.is_some() ||
         self.first_seen.is_some() ||
         self.last_withdrawal.is_some() ||
-        self.description.is_some()
+        self.description.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -329,6 +404,11 @@ impl Upsert for CryptoAddrUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_cryptoaddr(&self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<CryptoAddr> for CryptoAddrUpdate {
@@ -340,6 +420,9 @@ impl Updateable<CryptoAddr> for CryptoAddrUpdate {
         Self::clear_if_greater_or_equal(&mut self.first_seen, &existing.first_seen);
         Self::clear_if_lower_or_equal(&mut self.last_withdrawal, &existing.last_withdrawal);
         Self::clear_if_equal(&mut self.description, &existing.description);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -350,6 +433,9 @@ impl Updateable<CryptoAddr> for CryptoAddrUpdate {
         Self::push_value(updates, "first_seen", &self.first_seen);
         Self::push_value(updates, "last_withdrawal", &self.last_withdrawal);
         Self::push_value(updates, "description", &self.description);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
 
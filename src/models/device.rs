@@ -16,11 +16,20 @@ pub struct Device {
     pub vendor: Option<String>,
     pub unscoped: bool,
     pub last_seen: Option<NaiveDateTime>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Device {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Device)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -28,7 +37,7 @@ impl Model for Device {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::devices::dsl::*;
 
-        let results = devices.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| devices.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -37,7 +46,7 @@ impl Model for Device {
         use crate::schema::devices::dsl::*;
 
         let query = devices.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -45,16 +54,16 @@ impl Model for Device {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::devices::dsl::*;
 
-        diesel::delete(devices.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(devices.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::devices::dsl::*;
 
-        diesel::delete(devices.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(devices.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -69,8 +78,8 @@ impl Model for Device {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::devices::dsl::*;
 
-        let domain = devices.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| devices.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -78,8 +87,8 @@ impl Model for Device {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::devices::dsl::*;
 
-        let domain = devices.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| devices.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -87,8 +96,8 @@ impl Model for Device {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::devices::dsl::*;
 
-        let domain = devices.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let domain = with_conn!(db.db(), |conn| devices.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(domain)
@@ -103,27 +112,45 @@ impl Scopable for Device {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::devices::dsl::*;
 
-        diesel::update(devices.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(devices.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::devices::dsl::*;
 
-        diesel::update(devices.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(devices.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Device {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::devices::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(devices.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(devices.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Device {
     fn network_device(&self, db: &Database) -> Result<Option<NetworkDevice>> {
-        NetworkDevice::belonging_to(self)
+        with_conn!(db.db(), |conn| NetworkDevice::belonging_to(self)
             .order(network_devices::last_seen.desc())
-            .first::<NetworkDevice>(db.db())
+            .first::<NetworkDevice>(conn))
             .optional()
             .map_err(Error::from)
     }
@@ -157,6 +184,9 @@ pub struct DetailedDevice {
     network: Option<String>,
     unscoped: bool,
     last_seen: Option<NaiveDateTime>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedDevice {
@@ -182,6 +212,9 @@ impl DisplayableDetailed for DetailedDevice {
         w.opt_debug_label::<Yellow, _>("ipaddr", &self.ipaddr)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -217,6 +250,9 @@ impl Detailed for Device {
             network,
             unscoped: self.unscoped,
             last_seen: self.last_seen.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -230,6 +266,10 @@ pub struct NewDevice {
     pub vendor: Option<String>,
     pub last_seen: Option<NaiveDateTime>,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Device> for NewDevice {
@@ -241,11 +281,9 @@ impl InsertableStruct<Device> for NewDevice {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(devices::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), devices::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -259,6 +297,9 @@ impl Upsertable<Device> for NewDevice {
             hostname: Self::upsert_opt(self.hostname, &existing.hostname),
             vendor: Self::upsert_opt(self.vendor, &existing.vendor),
             last_seen: Self::upsert_opt(self.last_seen, &existing.last_seen),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -278,6 +319,10 @@ pub struct InsertDevice {
     pub hostname: Option<String>,
     pub vendor: Option<String>,
     pub last_seen: Option<NaiveDateTime>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertDevice {
@@ -293,6 +338,9 @@ impl InsertToNew for InsertDevice {
             last_seen: self.last_seen,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -305,6 +353,10 @@ pub struct DeviceUpdate {
     pub hostname: Option<String>,
     pub vendor: Option<String>,
     pub last_seen: Option<NaiveDateTime>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for DeviceUpdate {
@@ -312,7 +364,10 @@ impl Upsert for DeviceUpdate {
         self.name.is_some() ||
         self.hostname.is_some() ||
         self.vendor.is_some() ||
-        self.last_seen.is_some()
+        self.last_seen.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -322,6 +377,11 @@ impl Upsert for DeviceUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_device(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Device> for DeviceUpdate {
@@ -330,6 +390,9 @@ impl Updateable<Device> for DeviceUpdate {
         Self::clear_if_equal(&mut self.hostname, &existing.hostname);
         Self::clear_if_equal(&mut self.vendor, &existing.vendor);
         Self::clear_if_equal(&mut self.last_seen, &existing.last_seen);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -337,5 +400,8 @@ impl Updateable<Device> for DeviceUpdate {
         Self::push_value(updates, "hostname", &self.hostname);
         Self::push_value(updates, "vendor", &self.vendor);
         Self::push_value(updates, "last_seen", &self.last_seen);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
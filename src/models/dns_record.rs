@@ -0,0 +1,426 @@
+use crate::errors::*;
+use crate::fmt::Write;
+use crate::fmt::colors::*;
+use crate::models::*;
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Subdomain)]
+#[table_name="dns_records"]
+pub struct DnsRecord {
+    pub id: i32,
+    pub subdomain_id: i32,
+    pub value: String,
+    pub name: String,
+    pub record_type: String,
+    pub data: String,
+    pub ttl: Option<i32>,
+    pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Model for DnsRecord {
+    type ID = str;
+
+    fn family() -> Option<Family> {
+        Some(Family::DnsRecord)
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_owned()
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::dns_records::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| dns_records.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::dns_records::dsl::*;
+
+        let query = dns_records.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter_with_param(db: &Database, filter: &Filter, param: &str) -> Result<Vec<Self>> {
+        use crate::schema::dns_records::dsl::*;
+
+        let query = dns_records
+            .filter(record_type.eq(param.to_uppercase()))
+            .filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::dns_records::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(dns_records.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::dns_records::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(dns_records.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn value(&self) -> &Self::ID {
+        &self.value
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::dns_records::dsl::*;
+
+        let record = with_conn!(db.db(), |conn| dns_records.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(record)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::dns_records::dsl::*;
+
+        let record = with_conn!(db.db(), |conn| dns_records.filter(value.eq(query))
+            .first::<Self>(conn))?;
+
+        Ok(record)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::dns_records::dsl::*;
+
+        let record = with_conn!(db.db(), |conn| dns_records.filter(value.eq(query))
+            .first::<Self>(conn))
+            .optional()?;
+
+        Ok(record)
+    }
+}
+
+impl Scopable for DnsRecord {
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    fn scope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::dns_records::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(dns_records.filter(filter.sql()))
+            .set(unscoped.eq(false))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::dns_records::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(dns_records.filter(filter.sql()))
+            .set(unscoped.eq(true))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+}
+
+impl Seen for DnsRecord {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::dns_records::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(dns_records.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(dns_records.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
+pub struct PrintableDnsRecord {
+    value: String,
+}
+
+impl fmt::Display for PrintableDnsRecord {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?}", self.value)
+    }
+}
+
+impl Printable<PrintableDnsRecord> for DnsRecord {
+    fn printable(&self, _db: &Database) -> Result<PrintableDnsRecord> {
+        Ok(PrintableDnsRecord {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+pub struct DetailedDnsRecord {
+    id: i32,
+    name: String,
+    record_type: String,
+    data: String,
+    ttl: Option<i32>,
+    unscoped: bool,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
+}
+
+impl DisplayableDetailed for DetailedDnsRecord {
+    #[inline]
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        w.id(self.id)?;
+        w.debug::<Green, _>(&self.name)?;
+        write!(w, " ")?;
+        w.display::<Yellow, _>(&self.record_type)?;
+        write!(w, " ")?;
+        w.debug::<Green, _>(&self.data)?;
+
+        w.start_group();
+        w.opt_debug_label::<Yellow, _>("ttl", &self.ttl)?;
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn children(&self, _w: &mut fmt::DetailFormatter) -> fmt::Result {
+        Ok(())
+    }
+}
+
+display_detailed!(DetailedDnsRecord);
+
+impl Detailed for DnsRecord {
+    type T = DetailedDnsRecord;
+
+    fn detailed(&self, _db: &Database) -> Result<Self::T> {
+        Ok(DetailedDnsRecord {
+            id: self.id,
+            name: self.name.clone(),
+            record_type: self.record_type.clone(),
+            data: self.data.clone(),
+            ttl: self.ttl,
+            unscoped: self.unscoped,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="dns_records"]
+pub struct NewDnsRecord {
+    pub subdomain_id: i32,
+    pub value: String,
+    pub name: String,
+    pub record_type: String,
+    pub data: String,
+    pub ttl: Option<i32>,
+    pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertableStruct<DnsRecord> for NewDnsRecord {
+    fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn set_scoped(&mut self, scoped: bool) {
+        self.unscoped = !scoped;
+    }
+
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), dns_records::table, self)?;
+        Ok(n > 0)
+    }
+}
+
+impl Upsertable<DnsRecord> for NewDnsRecord {
+    type Update = DnsRecordUpdate;
+
+    fn upsert(self, existing: &DnsRecord) -> Self::Update {
+        Self::Update {
+            id: existing.id,
+            ttl: Self::upsert_opt(self.ttl, &existing.ttl),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
+        }
+    }
+}
+
+impl Printable<PrintableDnsRecord> for NewDnsRecord {
+    fn printable(&self, _db: &Database) -> Result<PrintableDnsRecord> {
+        Ok(PrintableDnsRecord {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertDnsRecord {
+    pub subdomain_id: i32,
+    pub name: String,
+    pub record_type: String,
+    pub data: String,
+    pub ttl: Option<i32>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertToNew for InsertDnsRecord {
+    type Target = NewDnsRecord;
+
+    fn try_into_new(self) -> Result<NewDnsRecord> {
+        let name = self.name.trim_end_matches('.').to_lowercase();
+        if name.is_empty() {
+            bail!("dns record name can't be empty");
+        }
+
+        let record_type = self.record_type.to_uppercase();
+        if record_type.is_empty() || !record_type.chars().all(|c| c.is_ascii_alphanumeric()) {
+            bail!("invalid dns record type: {:?}", self.record_type);
+        }
+
+        let value = format!("{} {} {}", name, record_type, self.data);
+
+        Ok(NewDnsRecord {
+            subdomain_id: self.subdomain_id,
+            value,
+            name,
+            record_type,
+            data: self.data,
+            ttl: self.ttl,
+            unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
+    }
+}
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="dns_records"]
+pub struct DnsRecordUpdate {
+    pub id: i32,
+    pub ttl: Option<i32>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for DnsRecordUpdate {
+    fn is_dirty(&self) -> bool {
+        self.ttl.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::DnsRecord(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_dns_record(&self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<DnsRecord> for DnsRecordUpdate {
+    fn changeset(&mut self, existing: &DnsRecord) {
+        Self::clear_if_equal(&mut self.ttl, &existing.ttl);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "ttl", &self.ttl);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_record_value() {
+        let record = InsertDnsRecord {
+            subdomain_id: 1,
+            name: "Example.com.".to_string(),
+            record_type: "mx".to_string(),
+            data: "10 mail.example.com".to_string(),
+            ttl: Some(300),
+            confidence: None,
+            verified: None,
+            extra: None,
+        }.try_into_new().unwrap();
+        assert_eq!(record.name, "example.com");
+        assert_eq!(record.record_type, "MX");
+        assert_eq!(record.value, "example.com MX 10 mail.example.com");
+    }
+
+    #[test]
+    fn test_dns_record_invalid_type() {
+        let r = InsertDnsRecord {
+            subdomain_id: 1,
+            name: "example.com".to_string(),
+            record_type: "M X".to_string(),
+            data: "10 mail.example.com".to_string(),
+            ttl: None,
+            confidence: None,
+            verified: None,
+            extra: None,
+        }.try_into_new();
+        assert!(r.is_err());
+    }
+}
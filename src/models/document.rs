@@ -0,0 +1,508 @@
+use crate::errors::*;
+use crate::fmt::colors::*;
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+use std::sync::Arc;
+use crate::engine::ctx::State;
+
+
+#[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="documents"]
+pub struct Document {
+    pub id: i32,
+    pub value: String,
+
+    pub filename: Option<String>,
+    pub mime: Option<String>,
+    pub size: Option<i32>,
+
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub creator: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub modified: Option<NaiveDateTime>,
+    pub text: Option<String>,
+
+    pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Model for Document {
+    type ID = str;
+
+    fn family() -> Option<Family> {
+        Some(Family::Document)
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_owned()
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::documents::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| documents.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::documents::dsl::*;
+
+        let query = documents.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::documents::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(documents.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::documents::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(documents.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn value(&self) -> &Self::ID {
+        &self.value
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::documents::dsl::*;
+
+        let document = with_conn!(db.db(), |conn| documents.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(document)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::documents::dsl::*;
+
+        let document = with_conn!(db.db(), |conn| documents.filter(value.eq(query))
+            .first::<Self>(conn))?;
+
+        Ok(document)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::documents::dsl::*;
+
+        let document = with_conn!(db.db(), |conn| documents.filter(value.eq(query))
+            .first::<Self>(conn))
+            .optional()?;
+
+        Ok(document)
+    }
+
+    fn blob(&self) -> Option<&str> {
+        Some(&self.value)
+    }
+}
+
+impl Scopable for Document {
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    fn scope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::documents::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(documents.filter(filter.sql()))
+            .set(unscoped.eq(false))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::documents::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(documents.filter(filter.sql()))
+            .set(unscoped.eq(true))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+}
+
+impl Seen for Document {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::documents::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(documents.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(documents.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
+impl Document {
+    fn urls(&self, db: &Database) -> Result<Vec<Url>> {
+        let url_ids = UrlDocument::belonging_to(self).select(url_documents::url_id);
+
+        with_conn!(db.db(), |conn| urls::table
+            .filter(urls::id.eq_any(url_ids))
+            .load::<Url>(conn))
+            .map_err(Error::from)
+    }
+}
+
+pub struct PrintableDocument {
+    value: String,
+    filename: Option<String>,
+    mime: Option<String>,
+}
+
+impl fmt::Display for PrintableDocument {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?}", self.value)?;
+
+        match (&self.filename, &self.mime) {
+            (Some(filename), Some(mime)) => write!(w, " ({:?}, {})", filename, mime),
+            (Some(filename), None) => write!(w, " ({:?})", filename),
+            (None, Some(mime)) => write!(w, " ({})", mime),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl Printable<PrintableDocument> for Document {
+    fn printable(&self, _db: &Database) -> Result<PrintableDocument> {
+        Ok(PrintableDocument {
+            value: self.value.to_string(),
+            filename: self.filename.clone(),
+            mime: self.mime.clone(),
+        })
+    }
+}
+
+pub struct DetailedDocument {
+    id: i32,
+    value: String,
+
+    filename: Option<String>,
+    mime: Option<String>,
+    size: Option<i32>,
+
+    title: Option<String>,
+    author: Option<String>,
+    creator: Option<String>,
+    created: Option<NaiveDateTime>,
+    modified: Option<NaiveDateTime>,
+
+    unscoped: bool,
+    urls: Vec<PrintableUrl>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
+}
+
+impl DisplayableDetailed for DetailedDocument {
+    #[inline]
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        w.id(self.id)?;
+        w.debug::<Green, _>(&self.value)?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.filename)?;
+        w.opt_debug::<Yellow, _>(&self.mime)?;
+        w.opt_debug::<Yellow, _>(&self.size)?;
+        w.end_group()?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.title)?;
+        w.opt_debug::<Yellow, _>(&self.author)?;
+        w.opt_debug::<Yellow, _>(&self.creator)?;
+        w.opt_debug::<Yellow, _>(&self.created)?;
+        w.opt_debug::<Yellow, _>(&self.modified)?;
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        for url in &self.urls {
+            w.child(url)?;
+        }
+        Ok(())
+    }
+}
+
+display_detailed!(DetailedDocument);
+
+impl Detailed for Document {
+    type T = DetailedDocument;
+
+    fn detailed(&self, db: &Database) -> Result<Self::T> {
+        let urls = self.urls(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        Ok(DetailedDocument {
+            id: self.id,
+            value: self.value.to_string(),
+
+            filename: self.filename.clone(),
+            mime: self.mime.clone(),
+            size: self.size,
+
+            title: self.title.clone(),
+            author: self.author.clone(),
+            creator: self.creator.clone(),
+            created: self.created,
+            modified: self.modified,
+
+            unscoped: self.unscoped,
+            urls,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="documents"]
+pub struct NewDocument {
+    pub value: String,
+
+    pub filename: Option<String>,
+    pub mime: Option<String>,
+    pub size: Option<i32>,
+
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub creator: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub modified: Option<NaiveDateTime>,
+    pub text: Option<String>,
+
+    pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertableStruct<Document> for NewDocument {
+    fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn set_scoped(&mut self, scoped: bool) {
+        self.unscoped = !scoped;
+    }
+
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), documents::table, self)?;
+        Ok(n > 0)
+    }
+}
+
+impl Upsertable<Document> for NewDocument {
+    type Update = DocumentUpdate;
+
+    fn upsert(self, existing: &Document) -> Self::Update {
+        Self::Update {
+            id: existing.id,
+
+            filename: Self::upsert_opt(self.filename, &existing.filename),
+            mime: Self::upsert_opt(self.mime, &existing.mime),
+            size: Self::upsert_opt(self.size, &existing.size),
+
+            title: Self::upsert_opt(self.title, &existing.title),
+            author: Self::upsert_opt(self.author, &existing.author),
+            creator: Self::upsert_opt(self.creator, &existing.creator),
+            created: Self::upsert_opt(self.created, &existing.created),
+            modified: Self::upsert_opt(self.modified, &existing.modified),
+            text: Self::upsert_opt(self.text, &existing.text),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
+        }
+    }
+}
+
+impl Printable<PrintableDocument> for NewDocument {
+    fn printable(&self, _db: &Database) -> Result<PrintableDocument> {
+        Ok(PrintableDocument {
+            value: self.value.to_string(),
+            filename: self.filename.clone(),
+            mime: self.mime.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertDocument {
+    pub value: String,
+
+    pub filename: Option<String>,
+    pub mime: Option<String>,
+    pub size: Option<i32>,
+
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub creator: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub modified: Option<NaiveDateTime>,
+    pub text: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl LuaInsertToNew for InsertDocument {
+    type Target = NewDocument;
+
+    fn lua_try_into_new(self, state: &Arc<dyn State>) -> Result<NewDocument> {
+        if let Some(filename) = &self.filename {
+            if filename.contains('/') {
+                bail!("filename can't contains slashes");
+            }
+        }
+
+        state.persist_blob(&self.value)?;
+
+        Ok(NewDocument {
+            value: self.value,
+
+            filename: self.filename,
+            mime: self.mime,
+            size: self.size,
+
+            title: self.title,
+            author: self.author,
+            creator: self.creator,
+            created: self.created,
+            modified: self.modified,
+            text: self.text,
+
+            unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
+    }
+}
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="documents"]
+pub struct DocumentUpdate {
+    pub id: i32,
+
+    pub filename: Option<String>,
+    pub mime: Option<String>,
+    pub size: Option<i32>,
+
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub creator: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub modified: Option<NaiveDateTime>,
+    pub text: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for DocumentUpdate {
+    fn is_dirty(&self) -> bool {
+        self.filename.is_some() ||
+        self.mime.is_some() ||
+        self.size.is_some() ||
+
+        self.title.is_some() ||
+        self.author.is_some() ||
+        self.creator.is_some() ||
+        self.created.is_some() ||
+        self.modified.is_some() ||
+        self.text.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::Document(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_document(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<Document> for DocumentUpdate {
+    fn changeset(&mut self, existing: &Document) {
+        Self::clear_if_equal(&mut self.filename, &existing.filename);
+        Self::clear_if_equal(&mut self.mime, &existing.mime);
+        Self::clear_if_equal(&mut self.size, &existing.size);
+
+        Self::clear_if_equal(&mut self.title, &existing.title);
+        Self::clear_if_equal(&mut self.author, &existing.author);
+        Self::clear_if_equal(&mut self.creator, &existing.creator);
+        Self::clear_if_equal(&mut self.created, &existing.created);
+        Self::clear_if_equal(&mut self.modified, &existing.modified);
+        Self::clear_if_equal(&mut self.text, &existing.text);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "filename", &self.filename);
+        Self::push_value(updates, "mime", &self.mime);
+        Self::push_value(updates, "size", &self.size);
+
+        Self::push_value(updates, "title", &self.title);
+        Self::push_value(updates, "author", &self.author);
+        Self::push_value(updates, "creator", &self.creator);
+        Self::push_value(updates, "created", &self.created);
+        Self::push_value(updates, "modified", &self.modified);
+        Self::push_value(updates, "text", &self.text);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
@@ -3,6 +3,7 @@ use crate::fmt::colors::*;
 use diesel;
 use diesel::prelude::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 
 
 #[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
@@ -11,11 +12,21 @@ pub struct Domain {
     pub id: i32,
     pub value: String,
     pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Domain {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Domain)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -23,7 +34,7 @@ impl Model for Domain {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::domains::dsl::*;
 
-        let results = domains.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| domains.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -32,7 +43,7 @@ impl Model for Domain {
         use crate::schema::domains::dsl::*;
 
         let query = domains.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -40,16 +51,16 @@ impl Model for Domain {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::domains::dsl::*;
 
-        diesel::delete(domains.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(domains.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::domains::dsl::*;
 
-        diesel::delete(domains.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(domains.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -64,8 +75,8 @@ impl Model for Domain {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::domains::dsl::*;
 
-        let domain = domains.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| domains.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -73,8 +84,8 @@ impl Model for Domain {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::domains::dsl::*;
 
-        let domain = domains.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| domains.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -82,8 +93,8 @@ impl Model for Domain {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::domains::dsl::*;
 
-        let domain = domains.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let domain = with_conn!(db.db(), |conn| domains.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(domain)
@@ -98,26 +109,44 @@ impl Scopable for Domain {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::domains::dsl::*;
 
-        diesel::update(domains.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(domains.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::domains::dsl::*;
 
-        diesel::update(domains.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(domains.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Domain {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::domains::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(domains.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(domains.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Domain {
     fn subdomains(&self, db: &Database) -> Result<Vec<Subdomain>> {
-        Subdomain::belonging_to(self)
-            .load(db.db())
+        with_conn!(db.db(), |conn| Subdomain::belonging_to(self)
+            .load(conn))
             .map_err(Error::from)
     }
 }
@@ -145,6 +174,9 @@ pub struct DetailedDomain {
     value: String,
     subdomains: Vec<PrintableSubdomain>,
     unscoped: bool,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedDomain {
@@ -157,6 +189,8 @@ impl DisplayableDetailed for DetailedDomain {
     fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
         w.id(self.id)?;
         w.debug::<Green, _>(&self.value)?;
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
         Ok(())
     }
 
@@ -184,6 +218,9 @@ impl Detailed for Domain {
             value: self.value.to_string(),
             subdomains,
             unscoped: self.unscoped,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -193,6 +230,10 @@ impl Detailed for Domain {
 pub struct NewDomain {
     pub value: String,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Domain> for NewDomain {
@@ -204,20 +245,21 @@ impl InsertableStruct<Domain> for NewDomain {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(domains::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), domains::table, self)?;
+        Ok(n > 0)
     }
 }
 
 impl Upsertable<Domain> for NewDomain {
-    type Update = NullUpdate;
+    type Update = DomainUpdate;
 
     fn upsert(self, existing: &Domain) -> Self::Update {
         Self::Update {
             id: existing.id,
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -233,6 +275,10 @@ impl Printable<PrintableDomain> for NewDomain {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InsertDomain {
     pub value: String,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertDomain {
@@ -243,6 +289,54 @@ impl InsertToNew for InsertDomain {
         Ok(NewDomain {
             value,
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="domains"]
+pub struct DomainUpdate {
+    pub id: i32,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for DomainUpdate {
+    fn is_dirty(&self) -> bool {
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::Domain(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_domain(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<Domain> for DomainUpdate {
+    fn changeset(&mut self, existing: &Domain) {
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
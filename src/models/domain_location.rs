@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Domain)]
+#[belongs_to(Location)]
+#[table_name="domain_locations"]
+pub struct DomainLocation {
+    pub id: i32,
+    pub domain_id: i32,
+    pub location_id: i32,
+}
+
+impl Model for DomainLocation {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("DomainLocation can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::domain_locations::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| domain_locations.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::domain_locations::dsl::*;
+
+        let query = domain_locations.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::domain_locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(domain_locations.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::domain_locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(domain_locations.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::domain_locations::dsl::*;
+
+        let domain_location = with_conn!(db.db(), |conn| domain_locations.filter(id.eq(my_id))
+                                              .first::<Self>(conn))?;
+
+        Ok(domain_location)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::domain_locations::dsl::*;
+
+        let (my_domain_id, my_location_id) = query;
+        let domain_location = with_conn!(db.db(), |conn| domain_locations.filter(domain_id.eq(my_domain_id))
+                                              .filter(location_id.eq(my_location_id))
+                                              .first::<Self>(conn))?;
+
+        Ok(domain_location)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::domain_locations::dsl::*;
+
+        let (my_domain_id, my_location_id) = query;
+        let domain_location = with_conn!(db.db(), |conn| domain_locations.filter(domain_id.eq(my_domain_id))
+                                              .filter(location_id.eq(my_location_id))
+                                              .first::<Self>(conn))
+                                              .optional()?;
+
+        Ok(domain_location)
+    }
+}
+
+pub struct PrintableDomainLocation {
+    domain: String,
+    location: String,
+}
+
+impl fmt::Display for PrintableDomainLocation {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.domain, self.location)
+    }
+}
+
+impl Printable<PrintableDomainLocation> for DomainLocation {
+    fn printable(&self, db: &Database) -> Result<PrintableDomainLocation> {
+        let domain = Domain::by_id(db, self.domain_id)?;
+        let location = Location::by_id(db, self.location_id)?;
+        Ok(PrintableDomainLocation {
+            domain: domain.value.to_string(),
+            location: location.value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="domain_locations"]
+pub struct NewDomainLocation {
+    pub domain_id: i32,
+    pub location_id: i32,
+}
+
+impl Printable<PrintableDomainLocation> for NewDomainLocation {
+    fn printable(&self, db: &Database) -> Result<PrintableDomainLocation> {
+        let domain = Domain::by_id(db, self.domain_id)?;
+        let location = Location::by_id(db, self.location_id)?;
+        Ok(PrintableDomainLocation {
+            domain: domain.value.to_string(),
+            location: location.value,
+        })
+    }
+}
+
+pub type InsertDomainLocation = NewDomainLocation;
+
+impl InsertToNew for InsertDomainLocation {
+    type Target = NewDomainLocation;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewDomainLocation> {
+        Ok(self)
+    }
+}
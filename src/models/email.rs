@@ -3,6 +3,7 @@ use crate::fmt::colors::*;
 use diesel;
 use diesel::prelude::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 
 
 #[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
@@ -13,11 +14,21 @@ pub struct Email {
     pub unscoped: bool,
     pub valid: Option<bool>,
     pub displayname: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Email {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Email)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -25,7 +36,7 @@ impl Model for Email {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::emails::dsl::*;
 
-        let results = emails.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| emails.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -34,7 +45,7 @@ impl Model for Email {
         use crate::schema::emails::dsl::*;
 
         let query = emails.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -42,16 +53,16 @@ impl Model for Email {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::emails::dsl::*;
 
-        diesel::delete(emails.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(emails.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::emails::dsl::*;
 
-        diesel::delete(emails.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(emails.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -66,8 +77,8 @@ impl Model for Email {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::emails::dsl::*;
 
-        let domain = emails.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| emails.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -75,8 +86,8 @@ impl Model for Email {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::emails::dsl::*;
 
-        let email = emails.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let email = with_conn!(db.db(), |conn| emails.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(email)
     }
@@ -84,8 +95,8 @@ impl Model for Email {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::emails::dsl::*;
 
-        let email = emails.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let email = with_conn!(db.db(), |conn| emails.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(email)
@@ -100,35 +111,53 @@ impl Scopable for Email {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::emails::dsl::*;
 
-        diesel::update(emails.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(emails.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::emails::dsl::*;
 
-        diesel::update(emails.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(emails.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Email {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::emails::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(emails.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(emails.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Email {
     fn breaches(&self, db: &Database) -> Result<Vec<(Breach, Option<String>)>> {
         use std::result;
 
-        let breach_id_pws = BreachEmail::belonging_to(self)
+        let breach_id_pws = with_conn!(db.db(), |conn| BreachEmail::belonging_to(self)
             .select((breach_emails::breach_id, breach_emails::password))
-            .load::<(i32, Option<String>)>(db.db())?;
+            .load::<(i32, Option<String>)>(conn))?;
 
         breach_id_pws.into_iter()
             .map(|(breach_id, password)| {
-                breaches::table
+                with_conn!(db.db(), |conn| breaches::table
                     .filter(breaches::id.eq(breach_id))
-                    .first::<Breach>(db.db())
+                    .first::<Breach>(conn))
                     .map(|breach| (breach, password))
             })
             .collect::<result::Result<Vec<_>, _>>()
@@ -176,6 +205,9 @@ pub struct DetailedEmail {
     breaches: Vec<BreachWithPassword>,
     unscoped: bool,
     valid: Option<bool>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedEmail {
@@ -202,6 +234,9 @@ impl DisplayableDetailed for DetailedEmail {
 
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -234,6 +269,9 @@ impl Detailed for Email {
             breaches,
             unscoped: self.unscoped,
             valid: self.valid,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -245,6 +283,10 @@ pub struct NewEmail {
     pub displayname: Option<String>,
     pub valid: Option<bool>,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Email> for NewEmail {
@@ -256,11 +298,9 @@ impl InsertableStruct<Email> for NewEmail {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(emails::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), emails::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -272,6 +312,9 @@ impl Upsertable<Email> for NewEmail {
             id: existing.id,
             displayname: Self::upsert_opt(self.displayname, &existing.displayname),
             valid: Self::upsert_opt(self.valid, &existing.valid),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -289,6 +332,10 @@ pub struct InsertEmail {
     pub value: String,
     pub displayname: Option<String>,
     pub valid: Option<bool>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertEmail {
@@ -302,6 +349,9 @@ impl InsertToNew for InsertEmail {
             valid: self.valid,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -312,12 +362,19 @@ pub struct EmailUpdate {
     pub id: i32,
     pub displayname: Option<String>,
     pub valid: Option<bool>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for EmailUpdate {
     fn is_dirty(&self) -> bool {
         self.displayname.is_some() ||
-            self.valid.is_some()
+            self.valid.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -327,17 +384,28 @@ impl Upsert for EmailUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_email(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Email> for EmailUpdate {
     fn changeset(&mut self, existing: &Email) {
         Self::clear_if_equal(&mut self.displayname, &existing.displayname);
         Self::clear_if_equal(&mut self.valid, &existing.valid);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
         Self::push_value(updates, "displayname", &self.displayname);
         Self::push_value(updates, "valid", &self.valid);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
 
@@ -351,12 +419,18 @@ mod tests {
             value: "Foo.Bar@Example.com".to_string(),
             displayname: None,
             valid: None,
+            confidence: None,
+            verified: None,
+            extra: None,
         };
         assert_eq!(email.try_into_new().unwrap(), NewEmail {
             value: "foo.bar@example.com".to_string(),
             displayname: None,
             valid: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         });
     }
 }
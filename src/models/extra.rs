@@ -0,0 +1,99 @@
+//! Custom attributes that don't fit into the schema of an entity
+//!
+//! The attributes are stored as a json object in the `extra` column but
+//! exposed as a table to scripts and as an object in exports.
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error;
+use serde_json::{Map, Value};
+
+
+pub fn serialize<S: Serializer>(extra: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match extra {
+        Some(extra) => match serde_json::from_str::<Value>(extra) {
+            Ok(value) => value.serialize(serializer),
+            Err(_) => serializer.serialize_str(extra),
+        },
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        // empty lua tables can't be told apart from empty arrays
+        Value::Array(ref x) if x.is_empty() => Ok(None),
+        Value::Object(ref x) if x.is_empty() => Ok(None),
+        Value::Object(x) => Ok(Some(Value::Object(x).to_string())),
+        _ => Err(D::Error::custom("extra attributes must be a table")),
+    }
+}
+
+/// Parse the attributes, corrupted attributes are treated as empty
+pub fn parse(extra: &Option<String>) -> Map<String, Value> {
+    extra.as_ref()
+        .and_then(|extra| serde_json::from_str(extra).ok())
+        .unwrap_or_default()
+}
+
+/// Add new attributes to the existing ones, returns `None` if nothing changed
+pub fn merge(update: Option<String>, existing: &Option<String>) -> Option<String> {
+    let update = parse(&update);
+    let existing = parse(existing);
+
+    let mut merged = existing.clone();
+    merged.extend(update);
+
+    if merged != existing {
+        Some(Value::Object(merged).to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entity {
+        #[serde(default, with="super")]
+        extra: Option<String>,
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let entity = serde_json::from_str::<Entity>(r#"{"extra":{"server":"nginx"}}"#).unwrap();
+        assert_eq!(entity, Entity {
+            extra: Some(r#"{"server":"nginx"}"#.to_string()),
+        });
+        assert_eq!(serde_json::to_string(&entity).unwrap(), r#"{"extra":{"server":"nginx"}}"#);
+    }
+
+    #[test]
+    fn test_empty() {
+        let entity = serde_json::from_str::<Entity>(r#"{"extra":[]}"#).unwrap();
+        assert_eq!(entity, Entity { extra: None });
+        let entity = serde_json::from_str::<Entity>(r#"{}"#).unwrap();
+        assert_eq!(entity, Entity { extra: None });
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(serde_json::from_str::<Entity>(r#"{"extra":"nginx"}"#).is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let existing = Some(r#"{"followers":3,"server":"nginx"}"#.to_string());
+        let merged = merge(Some(r#"{"server":"apache"}"#.to_string()), &existing);
+        assert_eq!(merged, Some(r#"{"followers":3,"server":"apache"}"#.to_string()));
+    }
+
+    #[test]
+    fn test_merge_unchanged() {
+        let existing = Some(r#"{"followers":3,"server":"nginx"}"#.to_string());
+        assert_eq!(merge(Some(r#"{"server":"nginx"}"#.to_string()), &existing), None);
+        assert_eq!(merge(None, &existing), None);
+    }
+}
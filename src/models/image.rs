@@ -29,11 +29,21 @@ pub struct Image {
     pub phash: Option<String>,
 
     pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Image {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Image)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -41,7 +51,7 @@ impl Model for Image {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::images::dsl::*;
 
-        let results = images.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| images.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -50,7 +60,7 @@ impl Model for Image {
         use crate::schema::images::dsl::*;
 
         let query = images.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -58,16 +68,16 @@ impl Model for Image {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::images::dsl::*;
 
-        diesel::delete(images.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(images.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::images::dsl::*;
 
-        diesel::delete(images.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(images.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -82,8 +92,8 @@ impl Model for Image {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::images::dsl::*;
 
-        let domain = images.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| images.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -91,8 +101,8 @@ impl Model for Image {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::images::dsl::*;
 
-        let email = images.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let email = with_conn!(db.db(), |conn| images.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(email)
     }
@@ -100,8 +110,8 @@ impl Model for Image {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::images::dsl::*;
 
-        let email = images.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let email = with_conn!(db.db(), |conn| images.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(email)
@@ -120,22 +130,40 @@ impl Scopable for Image {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::images::dsl::*;
 
-        diesel::update(images.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(images.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::images::dsl::*;
 
-        diesel::update(images.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(images.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Image {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::images::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(images.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(images.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 pub struct PrintableImage {
     value: String,
     filename: Option<String>,
@@ -199,6 +227,9 @@ pub struct DetailedImage {
     phash: Option<String>,
 
     unscoped: bool,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedImage {
@@ -235,6 +266,9 @@ impl DisplayableDetailed for DetailedImage {
         w.opt_debug::<Yellow, _>(&self.phash)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -269,6 +303,9 @@ impl Detailed for Image {
             phash: self.phash.clone(),
 
             unscoped: self.unscoped,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -293,6 +330,10 @@ pub struct NewImage {
     pub phash: Option<String>,
 
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Image> for NewImage {
@@ -304,11 +345,9 @@ impl InsertableStruct<Image> for NewImage {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(images::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), images::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -332,6 +371,9 @@ impl Upsertable<Image> for NewImage {
             ahash: Self::upsert_opt(self.ahash, &existing.ahash),
             dhash: Self::upsert_opt(self.dhash, &existing.dhash),
             phash: Self::upsert_opt(self.phash, &existing.phash),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -364,6 +406,10 @@ pub struct InsertImage {
     pub ahash: Option<String>,
     pub dhash: Option<String>,
     pub phash: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl LuaInsertToNew for InsertImage {
@@ -397,6 +443,9 @@ impl LuaInsertToNew for InsertImage {
             phash: self.phash,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -419,6 +468,10 @@ pub struct ImageUpdate {
     pub ahash: Option<String>,
     pub dhash: Option<String>,
     pub phash: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for ImageUpdate {
@@ -435,7 +488,10 @@ impl Upsert for ImageUpdate {
         self.nudity.is_some() ||
         self.ahash.is_some() ||
         self.dhash.is_some() ||
-        self.phash.is_some()
+        self.phash.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -445,6 +501,11 @@ impl Upsert for ImageUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_image(&self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Image> for ImageUpdate {
@@ -462,6 +523,9 @@ impl Updateable<Image> for ImageUpdate {
         Self::clear_if_equal(&mut self.ahash, &existing.ahash);
         Self::clear_if_equal(&mut self.dhash, &existing.dhash);
         Self::clear_if_equal(&mut self.phash, &existing.phash);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -478,5 +542,8 @@ impl Updateable<Image> for ImageUpdate {
         Self::push_value(updates, "ahash", &self.ahash);
         Self::push_value(updates, "dhash", &self.dhash);
         Self::push_value(updates, "phash", &self.phash);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
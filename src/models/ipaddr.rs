@@ -2,8 +2,11 @@ use crate::errors::*;
 use crate::fmt::Write;
 use crate::fmt::colors::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 use diesel;
 use diesel::prelude::*;
+use diesel::sql_types::Text;
+use crate::db::Backend;
 use std::net;
 use std::result;
 
@@ -26,11 +29,24 @@ pub struct IpAddr {
     pub as_org: Option<String>,
     pub description: Option<String>,
     pub reverse_dns: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+    pub netblock_id: Option<i32>,
+    #[serde(skip)]
+    pub range_key: Option<String>,
 }
 
 impl Model for IpAddr {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Ipaddr)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -38,7 +54,7 @@ impl Model for IpAddr {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::ipaddrs::dsl::*;
 
-        let results = ipaddrs.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| ipaddrs.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -47,7 +63,7 @@ impl Model for IpAddr {
         use crate::schema::ipaddrs::dsl::*;
 
         let query = ipaddrs.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -55,16 +71,16 @@ impl Model for IpAddr {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::ipaddrs::dsl::*;
 
-        diesel::delete(ipaddrs.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(ipaddrs.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::ipaddrs::dsl::*;
 
-        diesel::delete(ipaddrs.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(ipaddrs.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -79,8 +95,8 @@ impl Model for IpAddr {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::ipaddrs::dsl::*;
 
-        let ipaddr = ipaddrs.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let ipaddr = with_conn!(db.db(), |conn| ipaddrs.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(ipaddr)
     }
@@ -88,8 +104,8 @@ impl Model for IpAddr {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::ipaddrs::dsl::*;
 
-        let ipaddr = ipaddrs.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let ipaddr = with_conn!(db.db(), |conn| ipaddrs.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(ipaddr)
     }
@@ -97,8 +113,8 @@ impl Model for IpAddr {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::ipaddrs::dsl::*;
 
-        let ipaddr = ipaddrs.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let ipaddr = with_conn!(db.db(), |conn| ipaddrs.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(ipaddr)
@@ -113,42 +129,130 @@ impl Scopable for IpAddr {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::ipaddrs::dsl::*;
 
-        diesel::update(ipaddrs.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(ipaddrs.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::ipaddrs::dsl::*;
 
-        diesel::update(ipaddrs.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(ipaddrs.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for IpAddr {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::ipaddrs::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(ipaddrs.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(ipaddrs.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl IpAddr {
     fn subdomains(&self, db: &Database) -> Result<Vec<Subdomain>> {
-        let subdomain_ids = SubdomainIpAddr::belonging_to(self)
+        let subdomain_ids = with_conn!(db.db(), |conn| SubdomainIpAddr::belonging_to(self)
             .select(subdomain_ipaddrs::subdomain_id)
-            .load::<i32>(db.db())?;
+            .load::<i32>(conn))?;
 
         subdomain_ids.into_iter()
-            .map(|subdomain_id| subdomains::table
+            .map(|subdomain_id| with_conn!(db.db(), |conn| subdomains::table
                 .filter(subdomains::id.eq(subdomain_id))
-                .first::<Subdomain>(db.db())
+                .first::<Subdomain>(conn))
             )
             .collect::<result::Result<_, _>>()
             .map_err(Error::from)
     }
 
     fn ports(&self, db: &Database) -> Result<Vec<Port>> {
-        Port::belonging_to(self)
-            .load(db.db())
+        with_conn!(db.db(), |conn| Port::belonging_to(self)
+            .load(conn))
             .map_err(Error::from)
     }
+
+    pub fn netblock(&self, db: &Database) -> Result<Option<Netblock>> {
+        match self.netblock_id {
+            Some(netblock_id) => Netblock::by_id(db, netblock_id).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Set the range key of a new ipaddr and link it to the most specific
+    /// netblock that contains it
+    pub fn link_netblock(&self, db: &Database) -> Result<()> {
+        let key = self.update_range_key(db)?;
+        Self::link_netblocks(db, &key, &key)?;
+        Ok(())
+    }
+
+    fn update_range_key(&self, db: &Database) -> Result<String> {
+        let key = range_key(self.value.parse()?);
+        with_conn!(db.db(), |conn| diesel::update(ipaddrs::table.filter(ipaddrs::id.eq(self.id)))
+            .set(ipaddrs::range_key.eq(&key))
+            .execute(conn))?;
+        Ok(key)
+    }
+
+    /// Set the range key of ipaddrs that have been added before range keys
+    /// were tracked
+    pub fn update_missing_range_keys(db: &Database) -> Result<()> {
+        let missing = with_conn!(db.db(), |conn| ipaddrs::table
+            .filter(ipaddrs::range_key.is_null())
+            .load::<Self>(conn))?;
+        for ipaddr in missing {
+            ipaddr.update_range_key(db)?;
+        }
+        Ok(())
+    }
+
+    /// Link the ipaddrs within a range to the most specific netblock that
+    /// contains them
+    pub fn link_netblocks(db: &Database, start: &str, end: &str) -> Result<usize> {
+        let (start_param, end_param) = match db.db().backend() {
+            Backend::Sqlite => ("?", "?"),
+            #[cfg(feature="postgres")]
+            Backend::Postgres => ("$1", "$2"),
+        };
+        let query = link_query(&format!("range_key BETWEEN {} AND {}", start_param, end_param));
+        let n = with_conn!(db.db(), |conn| diesel::sql_query(query)
+            .bind::<Text, _>(start)
+            .bind::<Text, _>(end)
+            .execute(conn))?;
+        Ok(n)
+    }
+
+    /// Link all ipaddrs to the most specific netblock that contains them
+    pub fn link_all_netblocks(db: &Database) -> Result<usize> {
+        let query = link_query("range_key IS NOT NULL");
+        let n = with_conn!(db.db(), |conn| diesel::sql_query(query)
+            .execute(conn))?;
+        Ok(n)
+    }
+}
+
+/// Update the netblock of all ipaddrs matching the condition that are linked
+/// to the wrong netblock
+fn link_query(condition: &str) -> String {
+    let netblock = "(SELECT n.id FROM netblocks n
+        WHERE n.range_start <= ipaddrs.range_key AND n.range_end >= ipaddrs.range_key
+        ORDER BY n.range_start DESC, n.range_end ASC LIMIT 1)";
+    format!("UPDATE ipaddrs SET netblock_id = {0}
+        WHERE {1} AND COALESCE(netblock_id, 0) != COALESCE({0}, 0)",
+        netblock, condition)
 }
 
 pub struct PrintableIpAddr {
@@ -174,6 +278,7 @@ pub struct DetailedIpAddr {
     value: net::IpAddr,
     subdomains: Vec<PrintableSubdomain>,
     ports: Vec<PrintablePort>,
+    netblock: Option<PrintableNetblock>,
     unscoped: bool,
     continent: Option<String>,
     country: Option<String>,
@@ -182,6 +287,9 @@ pub struct DetailedIpAddr {
     as_org: Option<String>,
     description: Option<String>,
     reverse_dns: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedIpAddr {
@@ -224,6 +332,15 @@ impl DisplayableDetailed for DetailedIpAddr {
             write!(w, "]")?;
         }
 
+        w.start_group();
+        if let Some(ref netblock) = self.netblock {
+            w.display_label::<Yellow, _>("netblock", netblock)?;
+        }
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -253,11 +370,17 @@ impl Detailed for IpAddr {
             .map(|x| x.printable(db))
             .collect::<Result<_>>()?;
 
+        let netblock = match self.netblock(db)? {
+            Some(netblock) => Some(netblock.printable(db)?),
+            None => None,
+        };
+
         Ok(DetailedIpAddr {
             id: self.id,
             value: self.value.parse()?,
             subdomains,
             ports,
+            netblock,
             unscoped: self.unscoped,
             continent: self.continent.clone(),
             country: self.country.clone(),
@@ -266,6 +389,9 @@ impl Detailed for IpAddr {
             as_org: self.as_org.clone(),
             description: self.description.clone(),
             reverse_dns: self.reverse_dns.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -288,6 +414,10 @@ pub struct NewIpAddr {
     pub reverse_dns: Option<String>,
 
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<IpAddr> for NewIpAddr {
@@ -299,11 +429,9 @@ impl InsertableStruct<IpAddr> for NewIpAddr {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(ipaddrs::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), ipaddrs::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -324,6 +452,9 @@ impl Upsertable<IpAddr> for NewIpAddr {
             as_org: Self::upsert_opt(self.as_org, &existing.as_org),
             description: Self::upsert_opt(self.description, &existing.description),
             reverse_dns: Self::upsert_opt(self.reverse_dns, &existing.reverse_dns),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -353,6 +484,10 @@ pub struct InsertIpAddr {
     pub as_org: Option<String>,
     pub description: Option<String>,
     pub reverse_dns: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertIpAddr {
@@ -384,6 +519,9 @@ impl InsertToNew for InsertIpAddr {
             reverse_dns: self.reverse_dns,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -403,6 +541,10 @@ pub struct IpAddrUpdate {
     pub as_org: Option<String>,
     pub description: Option<String>,
     pub reverse_dns: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for IpAddrUpdate {
@@ -417,7 +559,10 @@ impl Upsert for IpAddrUpdate {
         self.asn.is_some() ||
         self.as_org.is_some() ||
         self.description.is_some() ||
-        self.reverse_dns.is_some()
+        self.reverse_dns.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -427,6 +572,11 @@ impl Upsert for IpAddrUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_ipaddr(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<IpAddr> for IpAddrUpdate {
@@ -442,6 +592,9 @@ impl Updateable<IpAddr> for IpAddrUpdate {
         Self::clear_if_equal(&mut self.as_org, &existing.as_org);
         Self::clear_if_equal(&mut self.description, &existing.description);
         Self::clear_if_equal(&mut self.reverse_dns, &existing.reverse_dns);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -456,5 +609,8 @@ impl Updateable<IpAddr> for IpAddrUpdate {
         Self::push_value(updates, "as_org", &self.as_org);
         Self::push_value(updates, "description", &self.description);
         Self::push_value(updates, "reverse_dns", &self.reverse_dns);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
@@ -0,0 +1,605 @@
+use crate::errors::*;
+use crate::fmt::colors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+use chrono::NaiveDateTime;
+use sn0int_std::geo::{Point, Polygon};
+
+
+#[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="locations"]
+pub struct Location {
+    pub id: i32,
+    pub value: String,
+
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub postcode: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    pub radius: Option<f32>,
+
+    pub source: Option<String>,
+
+    pub unscoped: bool,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Model for Location {
+    type ID = str;
+
+    fn family() -> Option<Family> {
+        Some(Family::Location)
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_owned()
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::locations::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| locations.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::locations::dsl::*;
+
+        let query = locations.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(locations.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(locations.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn value(&self) -> &Self::ID {
+        &self.value
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::locations::dsl::*;
+
+        let location = with_conn!(db.db(), |conn| locations.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(location)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::locations::dsl::*;
+
+        let location = with_conn!(db.db(), |conn| locations.filter(value.eq(query))
+            .first::<Self>(conn))?;
+
+        Ok(location)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::locations::dsl::*;
+
+        let location = with_conn!(db.db(), |conn| locations.filter(value.eq(query))
+            .first::<Self>(conn))
+            .optional()?;
+
+        Ok(location)
+    }
+}
+
+impl Scopable for Location {
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    fn scope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(locations.filter(filter.sql()))
+            .set(unscoped.eq(false))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(locations.filter(filter.sql()))
+            .set(unscoped.eq(true))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+}
+
+impl Seen for Location {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::locations::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(locations.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(locations.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
+impl Location {
+    /// Check if the coordinates of this location are inside of the polygon
+    pub fn within(&self, polygon: &Polygon) -> bool {
+        match (self.latitude, self.longitude) {
+            (Some(lat), Some(lon)) => polygon.contains(&Point::new(lat.into(), lon.into())),
+            _ => false,
+        }
+    }
+
+    fn domains(&self, db: &Database) -> Result<Vec<Domain>> {
+        let domain_ids = DomainLocation::belonging_to(self).select(domain_locations::domain_id);
+
+        with_conn!(db.db(), |conn| domains::table
+            .filter(domains::id.eq_any(domain_ids))
+            .load::<Domain>(conn))
+            .map_err(Error::from)
+    }
+
+    fn phonenumbers(&self, db: &Database) -> Result<Vec<PhoneNumber>> {
+        let phonenumber_ids = PhoneNumberLocation::belonging_to(self).select(phonenumber_locations::phone_number_id);
+
+        with_conn!(db.db(), |conn| phonenumbers::table
+            .filter(phonenumbers::id.eq_any(phonenumber_ids))
+            .load::<PhoneNumber>(conn))
+            .map_err(Error::from)
+    }
+
+    fn accounts(&self, db: &Database) -> Result<Vec<Account>> {
+        let account_ids = AccountLocation::belonging_to(self).select(account_locations::account_id);
+
+        with_conn!(db.db(), |conn| accounts::table
+            .filter(accounts::id.eq_any(account_ids))
+            .load::<Account>(conn))
+            .map_err(Error::from)
+    }
+}
+
+pub struct PrintableLocation {
+    value: String,
+}
+
+impl fmt::Display for PrintableLocation {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?}", self.value)
+    }
+}
+
+impl Printable<PrintableLocation> for Location {
+    fn printable(&self, _db: &Database) -> Result<PrintableLocation> {
+        Ok(PrintableLocation {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+pub struct DetailedLocation {
+    id: i32,
+    value: String,
+    unscoped: bool,
+
+    street: Option<String>,
+    city: Option<String>,
+    postcode: Option<String>,
+    region: Option<String>,
+    country: Option<String>,
+
+    latitude: Option<f32>,
+    longitude: Option<f32>,
+    radius: Option<f32>,
+
+    source: Option<String>,
+
+    domains: Vec<PrintableDomain>,
+    phonenumbers: Vec<PrintablePhoneNumber>,
+    accounts: Vec<PrintableAccount>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
+}
+
+impl DisplayableDetailed for DetailedLocation {
+    #[inline]
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        w.id(self.id)?;
+        w.debug::<Green, _>(&self.value)?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.street)?;
+        w.opt_debug::<Yellow, _>(&self.city)?;
+        w.opt_debug::<Yellow, _>(&self.postcode)?;
+        w.opt_debug::<Yellow, _>(&self.region)?;
+        w.opt_debug::<Yellow, _>(&self.country)?;
+        w.end_group()?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.latitude)?;
+        w.opt_debug::<Yellow, _>(&self.longitude)?;
+        w.opt_debug::<Yellow, _>(&self.radius)?;
+        w.end_group()?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.source)?;
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        for domain in &self.domains {
+            w.child(domain)?;
+        }
+        for phonenumber in &self.phonenumbers {
+            w.child(phonenumber)?;
+        }
+        for account in &self.accounts {
+            w.child(account)?;
+        }
+        Ok(())
+    }
+}
+
+display_detailed!(DetailedLocation);
+
+impl Detailed for Location {
+    type T = DetailedLocation;
+
+    fn detailed(&self, db: &Database) -> Result<Self::T> {
+        let domains = self.domains(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let phonenumbers = self.phonenumbers(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let accounts = self.accounts(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        Ok(DetailedLocation {
+            id: self.id,
+            value: self.value.to_string(),
+            unscoped: self.unscoped,
+
+            street: self.street.clone(),
+            city: self.city.clone(),
+            postcode: self.postcode.clone(),
+            region: self.region.clone(),
+            country: self.country.clone(),
+
+            latitude: self.latitude,
+            longitude: self.longitude,
+            radius: self.radius,
+
+            source: self.source.clone(),
+
+            domains,
+            phonenumbers,
+            accounts,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="locations"]
+pub struct NewLocation {
+    pub value: String,
+
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub postcode: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    pub radius: Option<f32>,
+
+    pub source: Option<String>,
+
+    pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertableStruct<Location> for NewLocation {
+    fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn set_scoped(&mut self, scoped: bool) {
+        self.unscoped = !scoped;
+    }
+
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), locations::table, self)?;
+        Ok(n > 0)
+    }
+}
+
+impl Upsertable<Location> for NewLocation {
+    type Update = LocationUpdate;
+
+    fn upsert(self, existing: &Location) -> Self::Update {
+        Self::Update {
+            id: existing.id,
+
+            street: Self::upsert_opt(self.street, &existing.street),
+            city: Self::upsert_opt(self.city, &existing.city),
+            postcode: Self::upsert_opt(self.postcode, &existing.postcode),
+            region: Self::upsert_opt(self.region, &existing.region),
+            country: Self::upsert_opt(self.country, &existing.country),
+
+            latitude: Self::upsert_opt(self.latitude, &existing.latitude),
+            longitude: Self::upsert_opt(self.longitude, &existing.longitude),
+            radius: Self::upsert_opt(self.radius, &existing.radius),
+
+            source: Self::upsert_opt(self.source, &existing.source),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
+        }
+    }
+}
+
+impl Printable<PrintableLocation> for NewLocation {
+    fn printable(&self, _db: &Database) -> Result<PrintableLocation> {
+        Ok(PrintableLocation {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertLocation {
+    pub value: String,
+
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub postcode: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    pub radius: Option<f32>,
+
+    pub source: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertToNew for InsertLocation {
+    type Target = NewLocation;
+
+    fn try_into_new(self) -> Result<NewLocation> {
+        if let Some(radius) = self.radius {
+            if radius < 0.0 {
+                bail!("Radius can't be negative: {}", radius);
+            }
+        }
+
+        Ok(NewLocation {
+            value: self.value,
+
+            street: self.street,
+            city: self.city,
+            postcode: self.postcode,
+            region: self.region,
+            country: self.country,
+
+            latitude: self.latitude,
+            longitude: self.longitude,
+            radius: self.radius,
+
+            source: self.source,
+
+            unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
+    }
+}
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="locations"]
+pub struct LocationUpdate {
+    pub id: i32,
+
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub postcode: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    pub radius: Option<f32>,
+
+    pub source: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for LocationUpdate {
+    fn is_dirty(&self) -> bool {
+        self.street.is_some() ||
+        self.city.is_some() ||
+        self.postcode.is_some() ||
+        self.region.is_some() ||
+        self.country.is_some() ||
+
+        self.latitude.is_some() ||
+        self.longitude.is_some() ||
+        self.radius.is_some() ||
+
+        self.source.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::Location(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_location(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<Location> for LocationUpdate {
+    fn changeset(&mut self, existing: &Location) {
+        Self::clear_if_equal(&mut self.street, &existing.street);
+        Self::clear_if_equal(&mut self.city, &existing.city);
+        Self::clear_if_equal(&mut self.postcode, &existing.postcode);
+        Self::clear_if_equal(&mut self.region, &existing.region);
+        Self::clear_if_equal(&mut self.country, &existing.country);
+
+        Self::clear_if_equal(&mut self.latitude, &existing.latitude);
+        Self::clear_if_equal(&mut self.longitude, &existing.longitude);
+        Self::clear_if_equal(&mut self.radius, &existing.radius);
+
+        Self::clear_if_equal(&mut self.source, &existing.source);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "street", &self.street);
+        Self::push_value(updates, "city", &self.city);
+        Self::push_value(updates, "postcode", &self.postcode);
+        Self::push_value(updates, "region", &self.region);
+        Self::push_value(updates, "country", &self.country);
+
+        Self::push_value(updates, "latitude", &self.latitude);
+        Self::push_value(updates, "longitude", &self.longitude);
+        Self::push_value(updates, "radius", &self.radius);
+
+        Self::push_value(updates, "source", &self.source);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(latitude: Option<f32>, longitude: Option<f32>) -> Location {
+        Location {
+            id: 1,
+            value: "Office".to_string(),
+            street: None,
+            city: None,
+            postcode: None,
+            region: None,
+            country: None,
+            latitude,
+            longitude,
+            radius: None,
+            source: None,
+            unscoped: false,
+            first_seen: None,
+            last_seen: None,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_location_within() {
+        let hamburg = "53.63,9.73 53.63,10.3 53.4,10.3 53.4,9.73".parse::<Polygon>().unwrap();
+        assert!(location(Some(53.55), Some(9.99)).within(&hamburg));
+        assert!(!location(Some(52.52), Some(13.40)).within(&hamburg));
+        assert!(!location(None, None).within(&hamburg));
+        assert!(!location(Some(53.55), None).within(&hamburg));
+    }
+
+    #[test]
+    fn test_location_negative_radius() {
+        let r = InsertLocation {
+            value: "Office".to_string(),
+            street: None,
+            city: Some("Hamburg".to_string()),
+            postcode: None,
+            region: None,
+            country: Some("Germany".to_string()),
+            latitude: Some(53.551086),
+            longitude: Some(9.993682),
+            radius: Some(-1.0),
+            source: None,
+            confidence: None,
+            verified: None,
+            extra: None,
+        }.try_into_new();
+        assert!(r.is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use crate::errors::*;
-use crate::db::{Database, Table, Filter};
+use crate::db::{Database, Family, Table, Filter};
 use crate::fmt;
 use crate::schema::*;
 use std::sync::Arc;
+use chrono::NaiveDateTime;
 use crate::engine::ctx::State;
 
 
@@ -25,6 +26,29 @@ pub enum Insert {
     Port(NewPort),
     Netblock(NewNetblock),
     CryptoAddr(NewCryptoAddr),
+    DnsRecord(NewDnsRecord),
+    Certificate(NewCertificate),
+    SubdomainCertificate(NewSubdomainCertificate),
+    PortCertificate(NewPortCertificate),
+    Organization(NewOrganization),
+    Person(NewPerson),
+    OrganizationDomain(NewOrganizationDomain),
+    OrganizationEmail(NewOrganizationEmail),
+    OrganizationPhoneNumber(NewOrganizationPhoneNumber),
+    OrganizationAccount(NewOrganizationAccount),
+    OrganizationNetblock(NewOrganizationNetblock),
+    PersonDomain(NewPersonDomain),
+    PersonEmail(NewPersonEmail),
+    PersonPhoneNumber(NewPersonPhoneNumber),
+    PersonAccount(NewPersonAccount),
+    PersonNetblock(NewPersonNetblock),
+    AutonomousSystem(NewAutonomousSystem),
+    Document(NewDocument),
+    UrlDocument(NewUrlDocument),
+    Location(NewLocation),
+    DomainLocation(NewDomainLocation),
+    PhoneNumberLocation(NewPhoneNumberLocation),
+    AccountLocation(NewAccountLocation),
 }
 
 impl Insert {
@@ -59,6 +83,93 @@ impl Insert {
             Insert::Port(x) => format!("{:?}", x.value),
             Insert::Netblock(x) => format!("{:?}", x.value),
             Insert::CryptoAddr(x) => format!("{:?}", x.value),
+            Insert::DnsRecord(x) => format!("{:?}", x.value),
+            Insert::Certificate(x) => format!("{:?}", x.value),
+            Insert::SubdomainCertificate(x) => {
+                let subdomain = Subdomain::by_id(db, x.subdomain_id)?;
+                let certificate = Certificate::by_id(db, x.certificate_id)?;
+                format!("{:?}+{:?}", subdomain.value, certificate.value)
+            },
+            Insert::PortCertificate(x) => {
+                let port = Port::by_id(db, x.port_id)?;
+                let certificate = Certificate::by_id(db, x.certificate_id)?;
+                format!("{:?}+{:?}", port.value, certificate.value)
+            },
+            Insert::Organization(x) => format!("{:?}", x.value),
+            Insert::Person(x) => format!("{:?}", x.value),
+            Insert::OrganizationDomain(x) => {
+                let organization = Organization::by_id(db, x.organization_id)?;
+                let domain = Domain::by_id(db, x.domain_id)?;
+                format!("{:?}+{:?}", organization.value, domain.value)
+            },
+            Insert::OrganizationEmail(x) => {
+                let organization = Organization::by_id(db, x.organization_id)?;
+                let email = Email::by_id(db, x.email_id)?;
+                format!("{:?}+{:?}", organization.value, email.value)
+            },
+            Insert::OrganizationPhoneNumber(x) => {
+                let organization = Organization::by_id(db, x.organization_id)?;
+                let phonenumber = PhoneNumber::by_id(db, x.phone_number_id)?;
+                format!("{:?}+{:?}", organization.value, phonenumber.value)
+            },
+            Insert::OrganizationAccount(x) => {
+                let organization = Organization::by_id(db, x.organization_id)?;
+                let account = Account::by_id(db, x.account_id)?;
+                format!("{:?}+{:?}", organization.value, account.value)
+            },
+            Insert::OrganizationNetblock(x) => {
+                let organization = Organization::by_id(db, x.organization_id)?;
+                let netblock = Netblock::by_id(db, x.netblock_id)?;
+                format!("{:?}+{:?}", organization.value, netblock.value)
+            },
+            Insert::PersonDomain(x) => {
+                let person = Person::by_id(db, x.person_id)?;
+                let domain = Domain::by_id(db, x.domain_id)?;
+                format!("{:?}+{:?}", person.value, domain.value)
+            },
+            Insert::PersonEmail(x) => {
+                let person = Person::by_id(db, x.person_id)?;
+                let email = Email::by_id(db, x.email_id)?;
+                format!("{:?}+{:?}", person.value, email.value)
+            },
+            Insert::PersonPhoneNumber(x) => {
+                let person = Person::by_id(db, x.person_id)?;
+                let phonenumber = PhoneNumber::by_id(db, x.phone_number_id)?;
+                format!("{:?}+{:?}", person.value, phonenumber.value)
+            },
+            Insert::PersonAccount(x) => {
+                let person = Person::by_id(db, x.person_id)?;
+                let account = Account::by_id(db, x.account_id)?;
+                format!("{:?}+{:?}", person.value, account.value)
+            },
+            Insert::PersonNetblock(x) => {
+                let person = Person::by_id(db, x.person_id)?;
+                let netblock = Netblock::by_id(db, x.netblock_id)?;
+                format!("{:?}+{:?}", person.value, netblock.value)
+            },
+            Insert::AutonomousSystem(x) => format!("{:?}", x.value),
+            Insert::Document(x) => format!("{:?}", x.value),
+            Insert::UrlDocument(x) => {
+                let url = Url::by_id(db, x.url_id)?;
+                let document = Document::by_id(db, x.document_id)?;
+                format!("{:?}+{:?}", url.value, document.value)
+            },
+            Insert::Location(x) => format!("{:?}", x.value),
+            Insert::DomainLocation(x) => {
+                let domain = Domain::by_id(db, x.domain_id)?;
+                let location = Location::by_id(db, x.location_id)?;
+                format!("{:?}+{:?}", domain.value, location.value)
+            },
+            Insert::PhoneNumberLocation(x) => {
+                let phonenumber = PhoneNumber::by_id(db, x.phone_number_id)?;
+                let location = Location::by_id(db, x.location_id)?;
+                format!("{:?}+{:?}", phonenumber.value, location.value)
+            },
+            Insert::AccountLocation(x) => {
+                let account = Account::by_id(db, x.account_id)?;
+                let location = Location::by_id(db, x.location_id)?;
+                format!("{:?}+{:?}", account.value, location.value)
+            },
         };
         Ok(label)
     }
@@ -87,6 +198,29 @@ impl Insert {
             Insert::Port(x) => format!("Port: {}", x.printable(db)?),
             Insert::Netblock(x) => format!("Netblock: {}", x.printable(db)?),
             Insert::CryptoAddr(x) => format!("CryptoAddr: {}", x.printable(db)?),
+            Insert::DnsRecord(x) => format!("DnsRecord: {}", x.printable(db)?),
+            Insert::Certificate(x) => format!("Certificate: {}", x.printable(db)?),
+            Insert::SubdomainCertificate(x) => x.printable(db)?.to_string(),
+            Insert::PortCertificate(x) => x.printable(db)?.to_string(),
+            Insert::Organization(x) => format!("Organization: {}", x.printable(db)?),
+            Insert::Person(x) => format!("Person: {}", x.printable(db)?),
+            Insert::OrganizationDomain(x) => x.printable(db)?.to_string(),
+            Insert::OrganizationEmail(x) => x.printable(db)?.to_string(),
+            Insert::OrganizationPhoneNumber(x) => x.printable(db)?.to_string(),
+            Insert::OrganizationAccount(x) => x.printable(db)?.to_string(),
+            Insert::OrganizationNetblock(x) => x.printable(db)?.to_string(),
+            Insert::PersonDomain(x) => x.printable(db)?.to_string(),
+            Insert::PersonEmail(x) => x.printable(db)?.to_string(),
+            Insert::PersonPhoneNumber(x) => x.printable(db)?.to_string(),
+            Insert::PersonAccount(x) => x.printable(db)?.to_string(),
+            Insert::PersonNetblock(x) => x.printable(db)?.to_string(),
+            Insert::AutonomousSystem(x) => format!("AutonomousSystem: {}", x.printable(db)?),
+            Insert::Document(x) => format!("Document: {}", x.printable(db)?),
+            Insert::UrlDocument(x) => x.printable(db)?.to_string(),
+            Insert::Location(x) => format!("Location: {}", x.printable(db)?),
+            Insert::DomainLocation(x) => x.printable(db)?.to_string(),
+            Insert::PhoneNumberLocation(x) => x.printable(db)?.to_string(),
+            Insert::AccountLocation(x) => x.printable(db)?.to_string(),
         })
     }
 }
@@ -111,12 +245,36 @@ impl From<&Insert> for Table {
             Insert::Port(_) => Table::Ports,
             Insert::Netblock(_) => Table::Netblocks,
             Insert::CryptoAddr(_) => Table::Cryptoaddrs,
+            Insert::DnsRecord(_) => Table::DnsRecords,
+            Insert::Certificate(_) => Table::Certificates,
+            Insert::SubdomainCertificate(_) => Table::SubdomainCertificates,
+            Insert::PortCertificate(_) => Table::PortCertificates,
+            Insert::Organization(_) => Table::Organizations,
+            Insert::Person(_) => Table::Persons,
+            Insert::OrganizationDomain(_) => Table::OrganizationDomains,
+            Insert::OrganizationEmail(_) => Table::OrganizationEmails,
+            Insert::OrganizationPhoneNumber(_) => Table::OrganizationPhonenumbers,
+            Insert::OrganizationAccount(_) => Table::OrganizationAccounts,
+            Insert::OrganizationNetblock(_) => Table::OrganizationNetblocks,
+            Insert::PersonDomain(_) => Table::PersonDomains,
+            Insert::PersonEmail(_) => Table::PersonEmails,
+            Insert::PersonPhoneNumber(_) => Table::PersonPhonenumbers,
+            Insert::PersonAccount(_) => Table::PersonAccounts,
+            Insert::PersonNetblock(_) => Table::PersonNetblocks,
+            Insert::AutonomousSystem(_) => Table::AutonomousSystems,
+            Insert::Document(_) => Table::Documents,
+            Insert::UrlDocument(_) => Table::UrlDocuments,
+            Insert::Location(_) => Table::Locations,
+            Insert::DomainLocation(_) => Table::DomainLocations,
+            Insert::PhoneNumberLocation(_) => Table::PhonenumberLocations,
+            Insert::AccountLocation(_) => Table::AccountLocations,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Update {
+    Domain(DomainUpdate),
     Subdomain(SubdomainUpdate),
     IpAddr(IpAddrUpdate),
     Url(UrlUpdate),
@@ -131,11 +289,20 @@ pub enum Update {
     Port(PortUpdate),
     Netblock(NetblockUpdate),
     CryptoAddr(CryptoAddrUpdate),
+    DnsRecord(DnsRecordUpdate),
+    Organization(OrganizationUpdate),
+    Person(PersonUpdate),
+    AutonomousSystem(AutonomousSystemUpdate),
+    Document(DocumentUpdate),
+    Location(LocationUpdate),
+    Breach(BreachUpdate),
+    Certificate(CertificateUpdate),
 }
 
 impl Update {
     pub fn is_dirty(&self) -> bool {
         match self {
+            Update::Domain(update)        => update.is_dirty(),
             Update::Subdomain(update)     => update.is_dirty(),
             Update::IpAddr(update)        => update.is_dirty(),
             Update::Url(update)           => update.is_dirty(),
@@ -150,6 +317,42 @@ impl Update {
             Update::Port(update)          => update.is_dirty(),
             Update::Netblock(update)      => update.is_dirty(),
             Update::CryptoAddr(update)    => update.is_dirty(),
+            Update::DnsRecord(update)     => update.is_dirty(),
+            Update::Organization(update)  => update.is_dirty(),
+            Update::Person(update)        => update.is_dirty(),
+            Update::AutonomousSystem(update) => update.is_dirty(),
+            Update::Document(update)      => update.is_dirty(),
+            Update::Location(update)      => update.is_dirty(),
+            Update::Breach(update)        => update.is_dirty(),
+            Update::Certificate(update)   => update.is_dirty(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Update::Domain(update)        => update.validate(),
+            Update::Subdomain(update)     => update.validate(),
+            Update::IpAddr(update)        => update.validate(),
+            Update::Url(update)           => update.validate(),
+            Update::Email(update)         => update.validate(),
+            Update::PhoneNumber(update)   => update.validate(),
+            Update::Device(update)        => update.validate(),
+            Update::Network(update)       => update.validate(),
+            Update::NetworkDevice(update) => update.validate(),
+            Update::Account(update)       => update.validate(),
+            Update::BreachEmail(update)   => update.validate(),
+            Update::Image(update)         => update.validate(),
+            Update::Port(update)          => update.validate(),
+            Update::Netblock(update)      => update.validate(),
+            Update::CryptoAddr(update)    => update.validate(),
+            Update::DnsRecord(update)     => update.validate(),
+            Update::Organization(update)  => update.validate(),
+            Update::Person(update)        => update.validate(),
+            Update::AutonomousSystem(update) => update.validate(),
+            Update::Document(update)      => update.validate(),
+            Update::Location(update)      => update.validate(),
+            Update::Breach(update)        => update.validate(),
+            Update::Certificate(update)   => update.validate(),
         }
     }
 }
@@ -157,6 +360,7 @@ impl Update {
 impl fmt::Display for Update {
     fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Update::Domain(update)        => write!(w, "{}", update.to_string()),
             Update::Subdomain(update)     => write!(w, "{}", update.to_string()),
             Update::IpAddr(update)        => write!(w, "{}", update.to_string()),
             Update::Url(update)           => write!(w, "{}", update.to_string()),
@@ -171,6 +375,14 @@ impl fmt::Display for Update {
             Update::Port(update)          => write!(w, "{}", update.to_string()),
             Update::Netblock(update)      => write!(w, "{}", update.to_string()),
             Update::CryptoAddr(update)    => write!(w, "{}", update.to_string()),
+            Update::DnsRecord(update)     => write!(w, "{}", update.to_string()),
+            Update::Organization(update)  => write!(w, "{}", update.to_string()),
+            Update::Person(update)        => write!(w, "{}", update.to_string()),
+            Update::AutonomousSystem(update) => write!(w, "{}", update.to_string()),
+            Update::Document(update)      => write!(w, "{}", update.to_string()),
+            Update::Location(update)      => write!(w, "{}", update.to_string()),
+            Update::Breach(update)        => write!(w, "{}", update.to_string()),
+            Update::Certificate(update)   => write!(w, "{}", update.to_string()),
         }
     }
 }
@@ -178,6 +390,11 @@ impl fmt::Display for Update {
 pub trait Model: Sized {
     type ID: ?Sized;
 
+    /// The family that is used to attach tags to this model
+    fn family() -> Option<Family> {
+        None
+    }
+
     fn to_string(&self) -> String;
 
     fn list(db: &Database) -> Result<Vec<Self>>;
@@ -220,6 +437,17 @@ pub trait Model: Sized {
     }
 }
 
+/// Confidence of an entity if the module didn't provide one
+pub const DEFAULT_CONFIDENCE: i32 = 100;
+
+pub fn validate_confidence(confidence: Option<i32>) -> Result<i32> {
+    match confidence {
+        Some(confidence) if confidence < 0 || confidence > 100 => bail!("Confidence must be between 0 and 100: {}", confidence),
+        Some(confidence) => Ok(confidence),
+        None => Ok(DEFAULT_CONFIDENCE),
+    }
+}
+
 pub trait Scopable: Model {
     fn scoped(&self) -> bool;
 
@@ -228,12 +456,18 @@ pub trait Scopable: Model {
     fn noscope(db: &Database, filter: &Filter) -> Result<usize>;
 }
 
+pub trait Seen: Model {
+    /// Bump last_seen, and first_seen if the entity has just been inserted
+    fn seen(db: &Database, id: i32, now: NaiveDateTime, inserted: bool) -> Result<()>;
+}
+
 pub trait InsertableStruct<T: Model>: Upsertable<T> {
     fn value(&self) -> &T::ID;
 
     fn set_scoped(&mut self, scoped: bool);
 
-    fn insert(&self, db: &Database) -> Result<()>;
+    /// Returns false if the entity already exists
+    fn insert(&self, db: &Database) -> Result<bool>;
 }
 
 pub trait Upsertable<M> {
@@ -244,33 +478,24 @@ pub trait Upsertable<M> {
         if insert != *existing { insert } else { None }
     }
 
+    #[inline]
+    fn upsert_max<T: PartialOrd>(insert: T, existing: T) -> Option<T> {
+        if insert > existing { Some(insert) } else { None }
+    }
+
     fn upsert(self, existing: &M) -> Self::Update;
 }
 
 pub trait Upsert {
     fn is_dirty(&self) -> bool;
 
-    fn generic(self) -> Update;
-
-    fn apply(&self, db: &Database) -> Result<i32>;
-}
-
-pub struct NullUpdate {
-    pub id: i32,
-}
-
-impl Upsert for NullUpdate {
-    fn is_dirty(&self) -> bool {
-        false
+    fn validate(&self) -> Result<()> {
+        Ok(())
     }
 
-    fn generic(self) -> Update {
-        unreachable!("Object doesn't have any immutable fields")
-    }
+    fn generic(self) -> Update;
 
-    fn apply(&self, _db: &Database) -> Result<i32> {
-        Ok(self.id)
-    }
+    fn apply(&self, db: &Database) -> Result<i32>;
 }
 
 pub trait Updateable<M> {
@@ -325,9 +550,18 @@ pub trait Printable<T: Sized> {
 }
 
 pub trait Detailed: Scopable {
-    type T: fmt::Display;
+    type T: fmt::Display + DisplayableDetailed;
 
     fn detailed(&self, db: &Database) -> Result<Self::T>;
+
+    fn annotated(&self, db: &Database) -> Result<Annotated<Self::T>> {
+        let detailed = self.detailed(db)?;
+        let notes = match Self::family() {
+            Some(family) => Note::list_for(db, &family, self.id())?,
+            None => Vec::new(),
+        };
+        Ok(Annotated::new(detailed, notes))
+    }
 }
 
 pub trait DisplayableDetailed {
@@ -425,5 +659,82 @@ pub use self::port::*;
 mod cryptoaddr;
 pub use self::cryptoaddr::*;
 
+mod dns_record;
+pub use self::dns_record::*;
+
+mod certificate;
+pub use self::certificate::*;
+
+mod subdomain_certificate;
+pub use self::subdomain_certificate::*;
+
+mod port_certificate;
+pub use self::port_certificate::*;
+
+mod organization;
+pub use self::organization::*;
+
+mod person;
+pub use self::person::*;
+
+mod organization_domain;
+pub use self::organization_domain::*;
+
+mod organization_email;
+pub use self::organization_email::*;
+
+mod organization_phonenumber;
+pub use self::organization_phonenumber::*;
+
+mod organization_account;
+pub use self::organization_account::*;
+
+mod organization_netblock;
+pub use self::organization_netblock::*;
+
+mod person_domain;
+pub use self::person_domain::*;
+
+mod person_email;
+pub use self::person_email::*;
+
+mod person_phonenumber;
+pub use self::person_phonenumber::*;
+
+mod person_account;
+pub use self::person_account::*;
+
+mod person_netblock;
+pub use self::person_netblock::*;
+
+mod autonomous_system;
+pub use self::autonomous_system::*;
+
+mod document;
+pub use self::document::*;
+
+mod url_document;
+pub use self::url_document::*;
+
+mod location;
+pub use self::location::*;
+
+mod domain_location;
+pub use self::domain_location::*;
+
+mod phonenumber_location;
+pub use self::phonenumber_location::*;
+
+mod account_location;
+pub use self::account_location::*;
+
 mod activity;
 pub use self::activity::*;
+
+mod tag;
+pub use self::tag::*;
+
+mod note;
+pub use self::note::*;
+
+pub mod extra;
@@ -3,7 +3,9 @@ use crate::fmt::colors::*;
 use diesel;
 use diesel::prelude::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 use ipnetwork;
+use std::net;
 
 
 #[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
@@ -16,11 +18,25 @@ pub struct Netblock {
     pub asn: Option<i32>,
     pub as_org: Option<String>,
     pub description: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+    #[serde(skip)]
+    pub range_start: Option<String>,
+    #[serde(skip)]
+    pub range_end: Option<String>,
 }
 
 impl Model for Netblock {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Netblock)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -28,7 +44,7 @@ impl Model for Netblock {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::netblocks::dsl::*;
 
-        let results = netblocks.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| netblocks.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -37,7 +53,7 @@ impl Model for Netblock {
         use crate::schema::netblocks::dsl::*;
 
         let query = netblocks.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -45,17 +61,30 @@ impl Model for Netblock {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::netblocks::dsl::*;
 
-        diesel::delete(netblocks.filter(filter.sql()))
-            .execute(db.db())
-            .map_err(Error::from)
+        let deleted = Self::filter(db, filter)?;
+        let n = with_conn!(db.db(), |conn| diesel::delete(netblocks.filter(filter.sql()))
+            .execute(conn))?;
+        // ipaddrs of deleted netblocks move to the next netblock that contains them
+        for netblock in deleted {
+            if let Some((start, end)) = netblock.range() {
+                IpAddr::link_netblocks(db, start, end)?;
+            }
+        }
+        Ok(n)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::netblocks::dsl::*;
 
-        diesel::delete(netblocks.filter(id.eq(my_id)))
-            .execute(db.db())
-            .map_err(Error::from)
+        let deleted = with_conn!(db.db(), |conn| netblocks.filter(id.eq(my_id))
+            .first::<Self>(conn))
+            .optional()?;
+        let n = with_conn!(db.db(), |conn| diesel::delete(netblocks.filter(id.eq(my_id)))
+            .execute(conn))?;
+        if let Some((start, end)) = deleted.as_ref().and_then(Netblock::range) {
+            IpAddr::link_netblocks(db, start, end)?;
+        }
+        Ok(n)
     }
 
     fn id(&self) -> i32 {
@@ -69,8 +98,8 @@ impl Model for Netblock {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::netblocks::dsl::*;
 
-        let domain = netblocks.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| netblocks.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -78,8 +107,8 @@ impl Model for Netblock {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::netblocks::dsl::*;
 
-        let netblock = netblocks.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let netblock = with_conn!(db.db(), |conn| netblocks.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(netblock)
     }
@@ -87,8 +116,8 @@ impl Model for Netblock {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::netblocks::dsl::*;
 
-        let netblock = netblocks.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let netblock = with_conn!(db.db(), |conn| netblocks.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(netblock)
@@ -103,24 +132,133 @@ impl Scopable for Netblock {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::netblocks::dsl::*;
 
-        diesel::update(netblocks.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(netblocks.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::netblocks::dsl::*;
 
-        diesel::update(netblocks.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(netblocks.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Netblock {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::netblocks::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(netblocks.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(netblocks.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Netblock {
-    // TODO: ips and subnets?
+    /// Find the most specific netblock that contains this ipaddr
+    pub fn containing(db: &Database, addr: net::IpAddr) -> Result<Option<Self>> {
+        use crate::schema::netblocks::dsl::*;
+
+        let key = range_key(addr);
+        // netblocks are either nested or don't overlap, the most specific one starts last
+        with_conn!(db.db(), |conn| netblocks
+            .filter(range_start.le(&key))
+            .filter(range_end.ge(&key))
+            .order_by((range_start.desc(), range_end.asc()))
+            .first::<Self>(conn))
+            .optional()
+            .map_err(Error::from)
+    }
+
+    /// The first and last ipaddr of this netblock, encoded with `range_key`
+    pub fn range(&self) -> Option<(&str, &str)> {
+        match (&self.range_start, &self.range_end) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    /// Link the ipaddrs within this netblock to the most specific netblock
+    /// that contains them
+    pub fn link_ipaddrs(&self, db: &Database) -> Result<usize> {
+        match self.range() {
+            Some((start, end)) => IpAddr::link_netblocks(db, start, end),
+            None => match self.update_range(db)? {
+                Some((start, end)) => IpAddr::link_netblocks(db, &start, &end),
+                None => Ok(0),
+            },
+        }
+    }
+
+    fn update_range(&self, db: &Database) -> Result<Option<(String, String)>> {
+        use crate::schema::netblocks::dsl::*;
+
+        // netblocks that fail to parse don't contain any ipaddrs
+        let network = match self.value.parse::<ipnetwork::IpNetwork>() {
+            Ok(network) => network,
+            Err(_) => return Ok(None),
+        };
+        let (start, end) = network_range(&network);
+
+        with_conn!(db.db(), |conn| diesel::update(netblocks.filter(id.eq(self.id)))
+            .set((range_start.eq(&start), range_end.eq(&end)))
+            .execute(conn))?;
+        Ok(Some((start, end)))
+    }
+
+    /// Set the range of netblocks that have been added before ranges were tracked
+    pub fn update_missing_ranges(db: &Database) -> Result<()> {
+        use crate::schema::netblocks::dsl::*;
+
+        let missing = with_conn!(db.db(), |conn| netblocks
+            .filter(range_start.is_null())
+            .load::<Self>(conn))?;
+        for netblock in missing {
+            netblock.update_range(db)?;
+        }
+        Ok(())
+    }
+
+    fn ipaddrs(&self, db: &Database) -> Result<Vec<IpAddr>> {
+        use crate::schema::ipaddrs::dsl::*;
+
+        with_conn!(db.db(), |conn| ipaddrs
+            .filter(netblock_id.eq(self.id))
+            .order_by(id.asc())
+            .load::<IpAddr>(conn))
+            .map_err(Error::from)
+    }
+}
+
+/// Encode an ipaddr so ipaddrs can be compared as text, ipv4 and ipv6 are
+/// kept apart by their prefix
+pub fn range_key(addr: net::IpAddr) -> String {
+    let (prefix, octets) = match addr {
+        net::IpAddr::V4(addr) => ("4:", addr.octets().to_vec()),
+        net::IpAddr::V6(addr) => ("6:", addr.octets().to_vec()),
+    };
+    octets.iter()
+        .fold(String::from(prefix), |mut key, octet| {
+            key.push_str(&format!("{:02x}", octet));
+            key
+        })
+}
+
+/// The first and last ipaddr of a network, encoded with `range_key`
+pub fn network_range(network: &ipnetwork::IpNetwork) -> (String, String) {
+    (range_key(network.network()), range_key(network.broadcast()))
 }
 
 pub struct PrintableNetblock {
@@ -144,10 +282,14 @@ impl Printable<PrintableNetblock> for Netblock {
 pub struct DetailedNetblock {
     id: i32,
     value: ipnetwork::IpNetwork,
+    ipaddrs: Vec<PrintableIpAddr>,
     unscoped: bool,
     asn: Option<i32>,
     as_org: Option<String>,
     description: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedNetblock {
@@ -167,12 +309,17 @@ impl DisplayableDetailed for DetailedNetblock {
         w.opt_debug::<Yellow, _>(&self.description)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
     #[inline]
-    fn children(&self, _w: &mut fmt::DetailFormatter) -> fmt::Result {
-        // TODO: ips, subnets
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        for ipaddr in &self.ipaddrs {
+            w.child(ipaddr)?;
+        }
         Ok(())
     }
 }
@@ -182,15 +329,22 @@ display_detailed!(DetailedNetblock);
 impl Detailed for Netblock {
     type T = DetailedNetblock;
 
-    fn detailed(&self, _db: &Database) -> Result<Self::T> {
-        // TODO: ips, subnets
+    fn detailed(&self, db: &Database) -> Result<Self::T> {
+        let ipaddrs = self.ipaddrs(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
         Ok(DetailedNetblock {
             id: self.id,
             value: self.value.parse()?,
+            ipaddrs,
             unscoped: self.unscoped,
             asn: self.asn,
             as_org: self.as_org.clone(),
             description: self.description.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -204,6 +358,10 @@ pub struct NewNetblock {
     pub as_org: Option<String>,
     pub description: Option<String>,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Netblock> for NewNetblock {
@@ -215,11 +373,9 @@ impl InsertableStruct<Netblock> for NewNetblock {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(netblocks::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), netblocks::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -232,6 +388,9 @@ impl Upsertable<Netblock> for NewNetblock {
             asn: Self::upsert_opt(self.asn, &existing.asn),
             as_org: Self::upsert_opt(self.as_org, &existing.as_org),
             description: Self::upsert_opt(self.description, &existing.description),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -250,6 +409,10 @@ pub struct InsertNetblock {
     pub asn: Option<i32>,
     pub as_org: Option<String>,
     pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertNetblock {
@@ -271,6 +434,9 @@ impl InsertToNew for InsertNetblock {
             as_org: self.as_org,
             description: self.description,
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -282,13 +448,20 @@ pub struct NetblockUpdate {
     pub asn: Option<i32>,
     pub as_org: Option<String>,
     pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for NetblockUpdate {
     fn is_dirty(&self) -> bool {
         self.asn.is_some() ||
         self.as_org.is_some() ||
-        self.description.is_some()
+        self.description.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -298,6 +471,11 @@ impl Upsert for NetblockUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_netblock(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Netblock> for NetblockUpdate {
@@ -305,11 +483,71 @@ impl Updateable<Netblock> for NetblockUpdate {
         Self::clear_if_equal(&mut self.asn, &existing.asn);
         Self::clear_if_equal(&mut self.as_org, &existing.as_org);
         Self::clear_if_equal(&mut self.description, &existing.description);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
         Self::push_value(updates, "asn", &self.asn);
         Self::push_value(updates, "as_org", &self.as_org);
         Self::push_value(updates, "description", &self.description);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn netblocks() -> Database {
+        let db = Database::in_memory().unwrap();
+        for &(value, family) in &[("10.0.0.0/8", "4"), ("10.1.0.0/16", "4"), ("192.168.0.0/24", "4"), ("2001:db8::/32", "6")] {
+            db.insert_netblock_struct(NewNetblock {
+                family: family.to_string(),
+                value: value.to_string(),
+                unscoped: false,
+                asn: None,
+                as_org: None,
+                description: None,
+                confidence: 100,
+                verified: false,
+                extra: None,
+            }, true).unwrap();
+        }
+        db
+    }
+
+    fn containing(db: &Database, addr: &str) -> Option<i32> {
+        Netblock::containing(db, addr.parse().unwrap()).unwrap()
+            .map(|netblock| netblock.id)
+    }
+
+    #[test]
+    fn test_range_key() {
+        assert_eq!(range_key("192.0.2.1".parse().unwrap()), "4:c0000201");
+        assert_eq!(range_key("2001:db8::1".parse().unwrap()), "6:20010db8000000000000000000000001");
+        assert_eq!(network_range(&"10.1.0.0/16".parse().unwrap()),
+            ("4:0a010000".to_string(), "4:0a01ffff".to_string()));
+    }
+
+    #[test]
+    fn test_most_specific_netblock() {
+        let db = netblocks();
+        assert_eq!(containing(&db, "10.1.2.3"), Some(2));
+        assert_eq!(containing(&db, "10.2.3.4"), Some(1));
+        assert_eq!(containing(&db, "2001:db8::1"), Some(4));
+    }
+
+    #[test]
+    fn test_most_specific_netblock_none() {
+        let db = netblocks();
+        assert_eq!(containing(&db, "192.168.1.1"), None);
+        // ipv4 mapped into ipv6 isn't matched by ipv4 netblocks
+        assert_eq!(containing(&db, "::ffff:10.1.2.3"), None);
+        let db = Database::in_memory().unwrap();
+        assert_eq!(containing(&db, "10.1.2.3"), None);
     }
 }
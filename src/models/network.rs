@@ -3,6 +3,7 @@ use crate::fmt::colors::*;
 use diesel;
 use diesel::prelude::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 
 
 #[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
@@ -14,11 +15,21 @@ pub struct Network {
     pub latitude: Option<f32>,
     pub longitude: Option<f32>,
     pub description: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Network {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Network)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -26,7 +37,7 @@ impl Model for Network {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::networks::dsl::*;
 
-        let results = networks.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| networks.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -35,7 +46,7 @@ impl Model for Network {
         use crate::schema::networks::dsl::*;
 
         let query = networks.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -43,16 +54,16 @@ impl Model for Network {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::networks::dsl::*;
 
-        diesel::delete(networks.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(networks.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::networks::dsl::*;
 
-        diesel::delete(networks.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(networks.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -67,8 +78,8 @@ impl Model for Network {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::networks::dsl::*;
 
-        let domain = networks.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| networks.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -76,8 +87,8 @@ impl Model for Network {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::networks::dsl::*;
 
-        let domain = networks.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| networks.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -85,8 +96,8 @@ impl Model for Network {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::networks::dsl::*;
 
-        let domain = networks.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let domain = with_conn!(db.db(), |conn| networks.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(domain)
@@ -101,29 +112,47 @@ impl Scopable for Network {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::networks::dsl::*;
 
-        diesel::update(networks.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(networks.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::networks::dsl::*;
 
-        diesel::update(networks.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(networks.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Network {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::networks::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(networks.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(networks.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Network {
     fn devices(&self, db: &Database) -> Result<Vec<Device>> {
         let device_ids = NetworkDevice::belonging_to(self).select(network_devices::device_id);
 
-        devices::table
+        with_conn!(db.db(), |conn| devices::table
             .filter(devices::id.eq_any(device_ids))
-            .load::<Device>(db.db())
+            .load::<Device>(conn))
             .map_err(Error::from)
     }
 }
@@ -154,6 +183,9 @@ pub struct DetailedNetwork {
     longitude: Option<f32>,
     description: Option<String>,
     devices: Vec<PrintableDevice>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedNetwork {
@@ -173,6 +205,9 @@ impl DisplayableDetailed for DetailedNetwork {
         w.opt_debug::<Yellow, _>(&self.description)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -203,6 +238,9 @@ impl Detailed for Network {
             longitude: self.longitude.clone(),
             description: self.description.clone(),
             devices,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -215,6 +253,10 @@ pub struct NewNetwork {
     pub longitude: Option<f32>,
     pub description: Option<String>,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Network> for NewNetwork {
@@ -226,11 +268,9 @@ impl InsertableStruct<Network> for NewNetwork {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(networks::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), networks::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -243,6 +283,9 @@ impl Upsertable<Network> for NewNetwork {
             latitude: Self::upsert_opt(self.latitude, &existing.latitude),
             longitude: Self::upsert_opt(self.longitude, &existing.longitude),
             description: Self::upsert_opt(self.description, &existing.description),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -261,6 +304,10 @@ pub struct InsertNetwork {
     pub latitude: Option<f32>,
     pub longitude: Option<f32>,
     pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertNetwork {
@@ -274,6 +321,9 @@ impl InsertToNew for InsertNetwork {
             description: self.description,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -285,13 +335,20 @@ pub struct NetworkUpdate {
     pub latitude: Option<f32>,
     pub longitude: Option<f32>,
     pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for NetworkUpdate {
     fn is_dirty(&self) -> bool {
         self.latitude.is_some() ||
         self.longitude.is_some() ||
-        self.description.is_some()
+        self.description.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -301,6 +358,11 @@ impl Upsert for NetworkUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_network(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Network> for NetworkUpdate {
@@ -308,11 +370,17 @@ impl Updateable<Network> for NetworkUpdate {
         Self::clear_if_equal(&mut self.latitude, &existing.latitude);
         Self::clear_if_equal(&mut self.longitude, &existing.longitude);
         Self::clear_if_equal(&mut self.description, &existing.description);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
         Self::push_value(updates, "latitude", &self.latitude);
         Self::push_value(updates, "longitude", &self.longitude);
         Self::push_value(updates, "description", &self.description);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
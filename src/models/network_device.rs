@@ -27,7 +27,7 @@ impl Model for NetworkDevice {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::network_devices::dsl::*;
 
-        let results = network_devices.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| network_devices.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -36,7 +36,7 @@ impl Model for NetworkDevice {
         use crate::schema::network_devices::dsl::*;
 
         let query = network_devices.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -44,16 +44,16 @@ impl Model for NetworkDevice {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::network_devices::dsl::*;
 
-        diesel::delete(network_devices.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(network_devices.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::network_devices::dsl::*;
 
-        diesel::delete(network_devices.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(network_devices.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -64,8 +64,8 @@ impl Model for NetworkDevice {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::network_devices::dsl::*;
 
-        let network_device = network_devices.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let network_device = with_conn!(db.db(), |conn| network_devices.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(network_device)
     }
@@ -74,9 +74,9 @@ impl Model for NetworkDevice {
         use crate::schema::network_devices::dsl::*;
 
         let (my_network_id, my_device_id) = query;
-        let network_device = network_devices.filter(network_id.eq(my_network_id))
+        let network_device = with_conn!(db.db(), |conn| network_devices.filter(network_id.eq(my_network_id))
                                                    .filter(device_id.eq(my_device_id))
-                                                   .first::<Self>(db.db())?;
+                                                   .first::<Self>(conn))?;
 
         Ok(network_device)
     }
@@ -85,9 +85,9 @@ impl Model for NetworkDevice {
         use crate::schema::network_devices::dsl::*;
 
         let (my_network_id, my_device_id) = query;
-        let network_device = network_devices.filter(network_id.eq(my_network_id))
+        let network_device = with_conn!(db.db(), |conn| network_devices.filter(network_id.eq(my_network_id))
                                                    .filter(device_id.eq(my_device_id))
-                                                   .first::<Self>(db.db())
+                                                   .first::<Self>(conn))
                                                    .optional()?;
 
         Ok(network_device)
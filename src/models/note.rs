@@ -0,0 +1,146 @@
+use crate::errors::*;
+use crate::db::Family;
+use crate::schema::notes;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+use chrono::NaiveDateTime;
+
+
+#[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="notes"]
+pub struct Note {
+    pub id: i32,
+    pub family: String,
+    pub entity_id: i32,
+    pub author: String,
+    pub time: NaiveDateTime,
+    pub content: String,
+}
+
+impl Note {
+    pub fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::notes::dsl::*;
+
+        with_conn!(db.db(), |conn| notes.load::<Self>(conn))
+            .map_err(Error::from)
+    }
+
+    pub fn list_for(db: &Database, my_family: &Family, my_entity_id: i32) -> Result<Vec<Self>> {
+        use crate::schema::notes::dsl::*;
+
+        with_conn!(db.db(), |conn| notes
+            .filter(family.eq(my_family.as_str()))
+            .filter(entity_id.eq(my_entity_id))
+            .order_by((time.asc(), id.asc()))
+            .load::<Self>(conn))
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Insertable)]
+#[table_name="notes"]
+pub struct NewNote<'a> {
+    pub family: &'a str,
+    pub entity_id: i32,
+    pub author: &'a str,
+    pub time: NaiveDateTime,
+    pub content: &'a str,
+}
+
+impl<'a> NewNote<'a> {
+    pub fn insert(&self, db: &Database) -> Result<()> {
+        if self.content.trim().is_empty() {
+            bail!("Note can't be empty");
+        }
+
+        with_conn!(db.db(), |conn| diesel::insert_into(notes::table)
+            .values(self)
+            .execute(conn))?;
+        Ok(())
+    }
+}
+
+pub struct Annotated<T> {
+    detailed: T,
+    notes: Vec<Note>,
+}
+
+impl<T> Annotated<T> {
+    pub fn new(detailed: T, notes: Vec<Note>) -> Annotated<T> {
+        Annotated {
+            detailed,
+            notes,
+        }
+    }
+}
+
+impl<T: DisplayableDetailed> DisplayableDetailed for Annotated<T> {
+    #[inline]
+    fn scoped(&self) -> bool {
+        self.detailed.scoped()
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        self.detailed.print(w)
+    }
+
+    #[inline]
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        self.detailed.children(w)?;
+        for note in &self.notes {
+            w.note(&note.author, note.time, &note.content)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: DisplayableDetailed> fmt::Display for Annotated<T> {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        let mut w = fmt::DetailFormatter::new(w, self.scoped());
+        w.start()?;
+        self.print(&mut w)?;
+        w.end()?;
+        self.children(&mut w)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fmt::colors::*;
+    use chrono::NaiveDate;
+
+    struct Dummy;
+
+    impl DisplayableDetailed for Dummy {
+        fn scoped(&self) -> bool {
+            false
+        }
+
+        fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+            w.id(1)?;
+            w.debug::<Green, _>("example.com")
+        }
+
+        fn children(&self, _w: &mut fmt::DetailFormatter) -> fmt::Result {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_annotated_display() {
+        let notes = vec![Note {
+            id: 1,
+            family: String::from("domain"),
+            entity_id: 1,
+            author: String::from("alice"),
+            time: NaiveDate::from_ymd(2020, 3, 9).and_hms(15, 12, 7),
+            content: String::from("owned by marketing"),
+        }];
+        let out = Annotated::new(Dummy, notes).to_string();
+        assert_eq!(out, "\x1b[90m#1, \"example.com\"\x1b[0m\n\t\x1b[90m# alice @ 2020-03-09 15:12:07: \"owned by marketing\"\x1b[0m");
+    }
+}
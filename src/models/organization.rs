@@ -0,0 +1,434 @@
+use crate::errors::*;
+use crate::fmt::colors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+use chrono::NaiveDateTime;
+
+
+#[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="organizations"]
+pub struct Organization {
+    pub id: i32,
+    pub value: String,
+    pub unscoped: bool,
+    pub description: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Model for Organization {
+    type ID = str;
+
+    fn family() -> Option<Family> {
+        Some(Family::Organization)
+    }
+
+    fn to_string(&self) -> String {
+        self.value.to_owned()
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::organizations::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| organizations.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::organizations::dsl::*;
+
+        let query = organizations.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organizations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organizations.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::organizations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organizations.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn value(&self) -> &Self::ID {
+        &self.value
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::organizations::dsl::*;
+
+        let organization = with_conn!(db.db(), |conn| organizations.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(organization)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::organizations::dsl::*;
+
+        let organization = with_conn!(db.db(), |conn| organizations.filter(value.eq(query))
+            .first::<Self>(conn))?;
+
+        Ok(organization)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::organizations::dsl::*;
+
+        let organization = with_conn!(db.db(), |conn| organizations.filter(value.eq(query))
+            .first::<Self>(conn))
+            .optional()?;
+
+        Ok(organization)
+    }
+}
+
+impl Scopable for Organization {
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    fn scope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organizations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(organizations.filter(filter.sql()))
+            .set(unscoped.eq(false))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organizations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::update(organizations.filter(filter.sql()))
+            .set(unscoped.eq(true))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+}
+
+impl Seen for Organization {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::organizations::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(organizations.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(organizations.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
+impl Organization {
+    fn domains(&self, db: &Database) -> Result<Vec<Domain>> {
+        let domain_ids = OrganizationDomain::belonging_to(self).select(organization_domains::domain_id);
+
+        with_conn!(db.db(), |conn| domains::table
+            .filter(domains::id.eq_any(domain_ids))
+            .load::<Domain>(conn))
+            .map_err(Error::from)
+    }
+
+    fn emails(&self, db: &Database) -> Result<Vec<Email>> {
+        let email_ids = OrganizationEmail::belonging_to(self).select(organization_emails::email_id);
+
+        with_conn!(db.db(), |conn| emails::table
+            .filter(emails::id.eq_any(email_ids))
+            .load::<Email>(conn))
+            .map_err(Error::from)
+    }
+
+    fn phonenumbers(&self, db: &Database) -> Result<Vec<PhoneNumber>> {
+        let phonenumber_ids = OrganizationPhoneNumber::belonging_to(self).select(organization_phonenumbers::phone_number_id);
+
+        with_conn!(db.db(), |conn| phonenumbers::table
+            .filter(phonenumbers::id.eq_any(phonenumber_ids))
+            .load::<PhoneNumber>(conn))
+            .map_err(Error::from)
+    }
+
+    fn accounts(&self, db: &Database) -> Result<Vec<Account>> {
+        let account_ids = OrganizationAccount::belonging_to(self).select(organization_accounts::account_id);
+
+        with_conn!(db.db(), |conn| accounts::table
+            .filter(accounts::id.eq_any(account_ids))
+            .load::<Account>(conn))
+            .map_err(Error::from)
+    }
+
+    fn netblocks(&self, db: &Database) -> Result<Vec<Netblock>> {
+        let netblock_ids = OrganizationNetblock::belonging_to(self).select(organization_netblocks::netblock_id);
+
+        with_conn!(db.db(), |conn| netblocks::table
+            .filter(netblocks::id.eq_any(netblock_ids))
+            .load::<Netblock>(conn))
+            .map_err(Error::from)
+    }
+}
+
+pub struct PrintableOrganization {
+    value: String,
+}
+
+impl fmt::Display for PrintableOrganization {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?}", self.value)
+    }
+}
+
+impl Printable<PrintableOrganization> for Organization {
+    fn printable(&self, _db: &Database) -> Result<PrintableOrganization> {
+        Ok(PrintableOrganization {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+pub struct DetailedOrganization {
+    id: i32,
+    value: String,
+    unscoped: bool,
+    description: Option<String>,
+    domains: Vec<PrintableDomain>,
+    emails: Vec<PrintableEmail>,
+    phonenumbers: Vec<PrintablePhoneNumber>,
+    accounts: Vec<PrintableAccount>,
+    netblocks: Vec<PrintableNetblock>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
+}
+
+impl DisplayableDetailed for DetailedOrganization {
+    #[inline]
+    fn scoped(&self) -> bool {
+        !self.unscoped
+    }
+
+    #[inline]
+    fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        w.id(self.id)?;
+        w.debug::<Green, _>(&self.value)?;
+
+        w.start_group();
+        w.opt_debug::<Yellow, _>(&self.description)?;
+        w.end_group()?;
+
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn children(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
+        for domain in &self.domains {
+            w.child(domain)?;
+        }
+        for email in &self.emails {
+            w.child(email)?;
+        }
+        for phonenumber in &self.phonenumbers {
+            w.child(phonenumber)?;
+        }
+        for account in &self.accounts {
+            w.child(account)?;
+        }
+        for netblock in &self.netblocks {
+            w.child(netblock)?;
+        }
+        Ok(())
+    }
+}
+
+display_detailed!(DetailedOrganization);
+
+impl Detailed for Organization {
+    type T = DetailedOrganization;
+
+    fn detailed(&self, db: &Database) -> Result<Self::T> {
+        let domains = self.domains(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let emails = self.emails(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let phonenumbers = self.phonenumbers(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let accounts = self.accounts(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        let netblocks = self.netblocks(db)?.into_iter()
+            .map(|x| x.printable(db))
+            .collect::<Result<_>>()?;
+
+        Ok(DetailedOrganization {
+            id: self.id,
+            value: self.value.to_string(),
+            unscoped: self.unscoped,
+            description: self.description.clone(),
+            domains,
+            emails,
+            phonenumbers,
+            accounts,
+            netblocks,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="organizations"]
+pub struct NewOrganization {
+    pub value: String,
+    pub description: Option<String>,
+    pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertableStruct<Organization> for NewOrganization {
+    fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn set_scoped(&mut self, scoped: bool) {
+        self.unscoped = !scoped;
+    }
+
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), organizations::table, self)?;
+        Ok(n > 0)
+    }
+}
+
+impl Upsertable<Organization> for NewOrganization {
+    type Update = OrganizationUpdate;
+
+    fn upsert(self, existing: &Organization) -> Self::Update {
+        Self::Update {
+            id: existing.id,
+            description: Self::upsert_opt(self.description, &existing.description),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
+        }
+    }
+}
+
+impl Printable<PrintableOrganization> for NewOrganization {
+    fn printable(&self, _db: &Database) -> Result<PrintableOrganization> {
+        Ok(PrintableOrganization {
+            value: self.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InsertOrganization {
+    pub value: String,
+    pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl InsertToNew for InsertOrganization {
+    type Target = NewOrganization;
+
+    fn try_into_new(self) -> Result<NewOrganization> {
+        Ok(NewOrganization {
+            value: self.value,
+            description: self.description,
+
+            unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
+        })
+    }
+}
+
+#[derive(Identifiable, AsChangeset, Serialize, Deserialize, Debug)]
+#[table_name="organizations"]
+pub struct OrganizationUpdate {
+    pub id: i32,
+    pub description: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
+}
+
+impl Upsert for OrganizationUpdate {
+    fn is_dirty(&self) -> bool {
+        self.description.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
+    }
+
+    fn generic(self) -> Update {
+        Update::Organization(self)
+    }
+
+    fn apply(&self, db: &Database) -> Result<i32> {
+        db.update_organization(self)
+    }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
+}
+
+impl Updateable<Organization> for OrganizationUpdate {
+    fn changeset(&mut self, existing: &Organization) {
+        Self::clear_if_equal(&mut self.description, &existing.description);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
+    }
+
+    fn fmt(&self, updates: &mut Vec<String>) {
+        Self::push_value(updates, "description", &self.description);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
+    }
+}
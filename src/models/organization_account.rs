@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[belongs_to(Account)]
+#[table_name="organization_accounts"]
+pub struct OrganizationAccount {
+    pub id: i32,
+    pub organization_id: i32,
+    pub account_id: i32,
+}
+
+impl Model for OrganizationAccount {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("OrganizationAccount can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| organization_accounts.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        let query = organization_accounts.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_accounts.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_accounts.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        let organization_account = with_conn!(db.db(), |conn| organization_accounts.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(organization_account)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        let (my_organization_id, my_account_id) = query;
+        let organization_account = with_conn!(db.db(), |conn| organization_accounts.filter(organization_id.eq(my_organization_id))
+                                                   .filter(account_id.eq(my_account_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(organization_account)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::organization_accounts::dsl::*;
+
+        let (my_organization_id, my_account_id) = query;
+        let organization_account = with_conn!(db.db(), |conn| organization_accounts.filter(organization_id.eq(my_organization_id))
+                                                   .filter(account_id.eq(my_account_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(organization_account)
+    }
+}
+
+pub struct PrintableOrganizationAccount {
+    organization: String,
+    account: String,
+}
+
+impl fmt::Display for PrintableOrganizationAccount {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.organization, self.account)
+    }
+}
+
+impl Printable<PrintableOrganizationAccount> for OrganizationAccount {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationAccount> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let account = Account::by_id(db, self.account_id)?;
+        Ok(PrintableOrganizationAccount {
+            organization: organization.value.to_string(),
+            account: account.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="organization_accounts"]
+pub struct NewOrganizationAccount {
+    pub organization_id: i32,
+    pub account_id: i32,
+}
+
+impl Printable<PrintableOrganizationAccount> for NewOrganizationAccount {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationAccount> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let account = Account::by_id(db, self.account_id)?;
+        Ok(PrintableOrganizationAccount {
+            organization: organization.value.to_string(),
+            account: account.value.to_string(),
+        })
+    }
+}
+
+pub type InsertOrganizationAccount = NewOrganizationAccount;
+
+impl InsertToNew for InsertOrganizationAccount {
+    type Target = NewOrganizationAccount;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewOrganizationAccount> {
+        Ok(self)
+    }
+}
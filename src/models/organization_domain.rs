@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[belongs_to(Domain)]
+#[table_name="organization_domains"]
+pub struct OrganizationDomain {
+    pub id: i32,
+    pub organization_id: i32,
+    pub domain_id: i32,
+}
+
+impl Model for OrganizationDomain {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("OrganizationDomain can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::organization_domains::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| organization_domains.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::organization_domains::dsl::*;
+
+        let query = organization_domains.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organization_domains::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_domains.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::organization_domains::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_domains.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::organization_domains::dsl::*;
+
+        let organization_domain = with_conn!(db.db(), |conn| organization_domains.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(organization_domain)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::organization_domains::dsl::*;
+
+        let (my_organization_id, my_domain_id) = query;
+        let organization_domain = with_conn!(db.db(), |conn| organization_domains.filter(organization_id.eq(my_organization_id))
+                                                   .filter(domain_id.eq(my_domain_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(organization_domain)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::organization_domains::dsl::*;
+
+        let (my_organization_id, my_domain_id) = query;
+        let organization_domain = with_conn!(db.db(), |conn| organization_domains.filter(organization_id.eq(my_organization_id))
+                                                   .filter(domain_id.eq(my_domain_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(organization_domain)
+    }
+}
+
+pub struct PrintableOrganizationDomain {
+    organization: String,
+    domain: String,
+}
+
+impl fmt::Display for PrintableOrganizationDomain {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.organization, self.domain)
+    }
+}
+
+impl Printable<PrintableOrganizationDomain> for OrganizationDomain {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationDomain> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let domain = Domain::by_id(db, self.domain_id)?;
+        Ok(PrintableOrganizationDomain {
+            organization: organization.value.to_string(),
+            domain: domain.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="organization_domains"]
+pub struct NewOrganizationDomain {
+    pub organization_id: i32,
+    pub domain_id: i32,
+}
+
+impl Printable<PrintableOrganizationDomain> for NewOrganizationDomain {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationDomain> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let domain = Domain::by_id(db, self.domain_id)?;
+        Ok(PrintableOrganizationDomain {
+            organization: organization.value.to_string(),
+            domain: domain.value.to_string(),
+        })
+    }
+}
+
+pub type InsertOrganizationDomain = NewOrganizationDomain;
+
+impl InsertToNew for InsertOrganizationDomain {
+    type Target = NewOrganizationDomain;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewOrganizationDomain> {
+        Ok(self)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[belongs_to(Email)]
+#[table_name="organization_emails"]
+pub struct OrganizationEmail {
+    pub id: i32,
+    pub organization_id: i32,
+    pub email_id: i32,
+}
+
+impl Model for OrganizationEmail {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("OrganizationEmail can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::organization_emails::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| organization_emails.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::organization_emails::dsl::*;
+
+        let query = organization_emails.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organization_emails::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_emails.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::organization_emails::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_emails.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::organization_emails::dsl::*;
+
+        let organization_email = with_conn!(db.db(), |conn| organization_emails.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(organization_email)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::organization_emails::dsl::*;
+
+        let (my_organization_id, my_email_id) = query;
+        let organization_email = with_conn!(db.db(), |conn| organization_emails.filter(organization_id.eq(my_organization_id))
+                                                   .filter(email_id.eq(my_email_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(organization_email)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::organization_emails::dsl::*;
+
+        let (my_organization_id, my_email_id) = query;
+        let organization_email = with_conn!(db.db(), |conn| organization_emails.filter(organization_id.eq(my_organization_id))
+                                                   .filter(email_id.eq(my_email_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(organization_email)
+    }
+}
+
+pub struct PrintableOrganizationEmail {
+    organization: String,
+    email: String,
+}
+
+impl fmt::Display for PrintableOrganizationEmail {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.organization, self.email)
+    }
+}
+
+impl Printable<PrintableOrganizationEmail> for OrganizationEmail {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationEmail> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let email = Email::by_id(db, self.email_id)?;
+        Ok(PrintableOrganizationEmail {
+            organization: organization.value.to_string(),
+            email: email.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="organization_emails"]
+pub struct NewOrganizationEmail {
+    pub organization_id: i32,
+    pub email_id: i32,
+}
+
+impl Printable<PrintableOrganizationEmail> for NewOrganizationEmail {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationEmail> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let email = Email::by_id(db, self.email_id)?;
+        Ok(PrintableOrganizationEmail {
+            organization: organization.value.to_string(),
+            email: email.value.to_string(),
+        })
+    }
+}
+
+pub type InsertOrganizationEmail = NewOrganizationEmail;
+
+impl InsertToNew for InsertOrganizationEmail {
+    type Target = NewOrganizationEmail;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewOrganizationEmail> {
+        Ok(self)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[belongs_to(Netblock)]
+#[table_name="organization_netblocks"]
+pub struct OrganizationNetblock {
+    pub id: i32,
+    pub organization_id: i32,
+    pub netblock_id: i32,
+}
+
+impl Model for OrganizationNetblock {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("OrganizationNetblock can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| organization_netblocks.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        let query = organization_netblocks.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_netblocks.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_netblocks.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        let organization_netblock = with_conn!(db.db(), |conn| organization_netblocks.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(organization_netblock)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        let (my_organization_id, my_netblock_id) = query;
+        let organization_netblock = with_conn!(db.db(), |conn| organization_netblocks.filter(organization_id.eq(my_organization_id))
+                                                   .filter(netblock_id.eq(my_netblock_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(organization_netblock)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::organization_netblocks::dsl::*;
+
+        let (my_organization_id, my_netblock_id) = query;
+        let organization_netblock = with_conn!(db.db(), |conn| organization_netblocks.filter(organization_id.eq(my_organization_id))
+                                                   .filter(netblock_id.eq(my_netblock_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(organization_netblock)
+    }
+}
+
+pub struct PrintableOrganizationNetblock {
+    organization: String,
+    netblock: String,
+}
+
+impl fmt::Display for PrintableOrganizationNetblock {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.organization, self.netblock)
+    }
+}
+
+impl Printable<PrintableOrganizationNetblock> for OrganizationNetblock {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationNetblock> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let netblock = Netblock::by_id(db, self.netblock_id)?;
+        Ok(PrintableOrganizationNetblock {
+            organization: organization.value.to_string(),
+            netblock: netblock.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="organization_netblocks"]
+pub struct NewOrganizationNetblock {
+    pub organization_id: i32,
+    pub netblock_id: i32,
+}
+
+impl Printable<PrintableOrganizationNetblock> for NewOrganizationNetblock {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationNetblock> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let netblock = Netblock::by_id(db, self.netblock_id)?;
+        Ok(PrintableOrganizationNetblock {
+            organization: organization.value.to_string(),
+            netblock: netblock.value.to_string(),
+        })
+    }
+}
+
+pub type InsertOrganizationNetblock = NewOrganizationNetblock;
+
+impl InsertToNew for InsertOrganizationNetblock {
+    type Target = NewOrganizationNetblock;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewOrganizationNetblock> {
+        Ok(self)
+    }
+}
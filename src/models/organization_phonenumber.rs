@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Organization)]
+#[belongs_to(PhoneNumber)]
+#[table_name="organization_phonenumbers"]
+pub struct OrganizationPhoneNumber {
+    pub id: i32,
+    pub organization_id: i32,
+    pub phone_number_id: i32,
+}
+
+impl Model for OrganizationPhoneNumber {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("OrganizationPhoneNumber can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| organization_phonenumbers.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        let query = organization_phonenumbers.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_phonenumbers.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(organization_phonenumbers.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        let organization_phonenumber = with_conn!(db.db(), |conn| organization_phonenumbers.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(organization_phonenumber)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        let (my_organization_id, my_phone_number_id) = query;
+        let organization_phonenumber = with_conn!(db.db(), |conn| organization_phonenumbers.filter(organization_id.eq(my_organization_id))
+                                                   .filter(phone_number_id.eq(my_phone_number_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(organization_phonenumber)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::organization_phonenumbers::dsl::*;
+
+        let (my_organization_id, my_phone_number_id) = query;
+        let organization_phonenumber = with_conn!(db.db(), |conn| organization_phonenumbers.filter(organization_id.eq(my_organization_id))
+                                                   .filter(phone_number_id.eq(my_phone_number_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(organization_phonenumber)
+    }
+}
+
+pub struct PrintableOrganizationPhoneNumber {
+    organization: String,
+    phonenumber: String,
+}
+
+impl fmt::Display for PrintableOrganizationPhoneNumber {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.organization, self.phonenumber)
+    }
+}
+
+impl Printable<PrintableOrganizationPhoneNumber> for OrganizationPhoneNumber {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationPhoneNumber> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let phonenumber = PhoneNumber::by_id(db, self.phone_number_id)?;
+        Ok(PrintableOrganizationPhoneNumber {
+            organization: organization.value.to_string(),
+            phonenumber: phonenumber.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="organization_phonenumbers"]
+pub struct NewOrganizationPhoneNumber {
+    pub organization_id: i32,
+    pub phone_number_id: i32,
+}
+
+impl Printable<PrintableOrganizationPhoneNumber> for NewOrganizationPhoneNumber {
+    fn printable(&self, db: &Database) -> Result<PrintableOrganizationPhoneNumber> {
+        let organization = Organization::by_id(db, self.organization_id)?;
+        let phonenumber = PhoneNumber::by_id(db, self.phone_number_id)?;
+        Ok(PrintableOrganizationPhoneNumber {
+            organization: organization.value.to_string(),
+            phonenumber: phonenumber.value.to_string(),
+        })
+    }
+}
+
+pub type InsertOrganizationPhoneNumber = NewOrganizationPhoneNumber;
+
+impl InsertToNew for InsertOrganizationPhoneNumber {
+    type Target = NewOrganizationPhoneNumber;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewOrganizationPhoneNumber> {
+        Ok(self)
+    }
+}
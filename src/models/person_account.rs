@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Person)]
+#[belongs_to(Account)]
+#[table_name="person_accounts"]
+pub struct PersonAccount {
+    pub id: i32,
+    pub person_id: i32,
+    pub account_id: i32,
+}
+
+impl Model for PersonAccount {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("PersonAccount can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::person_accounts::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| person_accounts.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::person_accounts::dsl::*;
+
+        let query = person_accounts.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::person_accounts::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_accounts.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::person_accounts::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_accounts.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::person_accounts::dsl::*;
+
+        let person_account = with_conn!(db.db(), |conn| person_accounts.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(person_account)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::person_accounts::dsl::*;
+
+        let (my_person_id, my_account_id) = query;
+        let person_account = with_conn!(db.db(), |conn| person_accounts.filter(person_id.eq(my_person_id))
+                                                   .filter(account_id.eq(my_account_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(person_account)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::person_accounts::dsl::*;
+
+        let (my_person_id, my_account_id) = query;
+        let person_account = with_conn!(db.db(), |conn| person_accounts.filter(person_id.eq(my_person_id))
+                                                   .filter(account_id.eq(my_account_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(person_account)
+    }
+}
+
+pub struct PrintablePersonAccount {
+    person: String,
+    account: String,
+}
+
+impl fmt::Display for PrintablePersonAccount {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.person, self.account)
+    }
+}
+
+impl Printable<PrintablePersonAccount> for PersonAccount {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonAccount> {
+        let person = Person::by_id(db, self.person_id)?;
+        let account = Account::by_id(db, self.account_id)?;
+        Ok(PrintablePersonAccount {
+            person: person.value.to_string(),
+            account: account.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="person_accounts"]
+pub struct NewPersonAccount {
+    pub person_id: i32,
+    pub account_id: i32,
+}
+
+impl Printable<PrintablePersonAccount> for NewPersonAccount {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonAccount> {
+        let person = Person::by_id(db, self.person_id)?;
+        let account = Account::by_id(db, self.account_id)?;
+        Ok(PrintablePersonAccount {
+            person: person.value.to_string(),
+            account: account.value.to_string(),
+        })
+    }
+}
+
+pub type InsertPersonAccount = NewPersonAccount;
+
+impl InsertToNew for InsertPersonAccount {
+    type Target = NewPersonAccount;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewPersonAccount> {
+        Ok(self)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Person)]
+#[belongs_to(Domain)]
+#[table_name="person_domains"]
+pub struct PersonDomain {
+    pub id: i32,
+    pub person_id: i32,
+    pub domain_id: i32,
+}
+
+impl Model for PersonDomain {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("PersonDomain can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::person_domains::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| person_domains.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::person_domains::dsl::*;
+
+        let query = person_domains.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::person_domains::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_domains.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::person_domains::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_domains.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::person_domains::dsl::*;
+
+        let person_domain = with_conn!(db.db(), |conn| person_domains.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(person_domain)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::person_domains::dsl::*;
+
+        let (my_person_id, my_domain_id) = query;
+        let person_domain = with_conn!(db.db(), |conn| person_domains.filter(person_id.eq(my_person_id))
+                                                   .filter(domain_id.eq(my_domain_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(person_domain)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::person_domains::dsl::*;
+
+        let (my_person_id, my_domain_id) = query;
+        let person_domain = with_conn!(db.db(), |conn| person_domains.filter(person_id.eq(my_person_id))
+                                                   .filter(domain_id.eq(my_domain_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(person_domain)
+    }
+}
+
+pub struct PrintablePersonDomain {
+    person: String,
+    domain: String,
+}
+
+impl fmt::Display for PrintablePersonDomain {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.person, self.domain)
+    }
+}
+
+impl Printable<PrintablePersonDomain> for PersonDomain {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonDomain> {
+        let person = Person::by_id(db, self.person_id)?;
+        let domain = Domain::by_id(db, self.domain_id)?;
+        Ok(PrintablePersonDomain {
+            person: person.value.to_string(),
+            domain: domain.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="person_domains"]
+pub struct NewPersonDomain {
+    pub person_id: i32,
+    pub domain_id: i32,
+}
+
+impl Printable<PrintablePersonDomain> for NewPersonDomain {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonDomain> {
+        let person = Person::by_id(db, self.person_id)?;
+        let domain = Domain::by_id(db, self.domain_id)?;
+        Ok(PrintablePersonDomain {
+            person: person.value.to_string(),
+            domain: domain.value.to_string(),
+        })
+    }
+}
+
+pub type InsertPersonDomain = NewPersonDomain;
+
+impl InsertToNew for InsertPersonDomain {
+    type Target = NewPersonDomain;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewPersonDomain> {
+        Ok(self)
+    }
+}
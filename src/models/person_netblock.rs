@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Person)]
+#[belongs_to(Netblock)]
+#[table_name="person_netblocks"]
+pub struct PersonNetblock {
+    pub id: i32,
+    pub person_id: i32,
+    pub netblock_id: i32,
+}
+
+impl Model for PersonNetblock {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("PersonNetblock can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| person_netblocks.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        let query = person_netblocks.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_netblocks.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_netblocks.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        let person_netblock = with_conn!(db.db(), |conn| person_netblocks.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(person_netblock)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        let (my_person_id, my_netblock_id) = query;
+        let person_netblock = with_conn!(db.db(), |conn| person_netblocks.filter(person_id.eq(my_person_id))
+                                                   .filter(netblock_id.eq(my_netblock_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(person_netblock)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::person_netblocks::dsl::*;
+
+        let (my_person_id, my_netblock_id) = query;
+        let person_netblock = with_conn!(db.db(), |conn| person_netblocks.filter(person_id.eq(my_person_id))
+                                                   .filter(netblock_id.eq(my_netblock_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(person_netblock)
+    }
+}
+
+pub struct PrintablePersonNetblock {
+    person: String,
+    netblock: String,
+}
+
+impl fmt::Display for PrintablePersonNetblock {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.person, self.netblock)
+    }
+}
+
+impl Printable<PrintablePersonNetblock> for PersonNetblock {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonNetblock> {
+        let person = Person::by_id(db, self.person_id)?;
+        let netblock = Netblock::by_id(db, self.netblock_id)?;
+        Ok(PrintablePersonNetblock {
+            person: person.value.to_string(),
+            netblock: netblock.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="person_netblocks"]
+pub struct NewPersonNetblock {
+    pub person_id: i32,
+    pub netblock_id: i32,
+}
+
+impl Printable<PrintablePersonNetblock> for NewPersonNetblock {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonNetblock> {
+        let person = Person::by_id(db, self.person_id)?;
+        let netblock = Netblock::by_id(db, self.netblock_id)?;
+        Ok(PrintablePersonNetblock {
+            person: person.value.to_string(),
+            netblock: netblock.value.to_string(),
+        })
+    }
+}
+
+pub type InsertPersonNetblock = NewPersonNetblock;
+
+impl InsertToNew for InsertPersonNetblock {
+    type Target = NewPersonNetblock;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewPersonNetblock> {
+        Ok(self)
+    }
+}
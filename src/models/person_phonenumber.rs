@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Person)]
+#[belongs_to(PhoneNumber)]
+#[table_name="person_phonenumbers"]
+pub struct PersonPhoneNumber {
+    pub id: i32,
+    pub person_id: i32,
+    pub phone_number_id: i32,
+}
+
+impl Model for PersonPhoneNumber {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("PersonPhoneNumber can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| person_phonenumbers.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        let query = person_phonenumbers.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_phonenumbers.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(person_phonenumbers.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        let person_phonenumber = with_conn!(db.db(), |conn| person_phonenumbers.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(person_phonenumber)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        let (my_person_id, my_phone_number_id) = query;
+        let person_phonenumber = with_conn!(db.db(), |conn| person_phonenumbers.filter(person_id.eq(my_person_id))
+                                                   .filter(phone_number_id.eq(my_phone_number_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(person_phonenumber)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::person_phonenumbers::dsl::*;
+
+        let (my_person_id, my_phone_number_id) = query;
+        let person_phonenumber = with_conn!(db.db(), |conn| person_phonenumbers.filter(person_id.eq(my_person_id))
+                                                   .filter(phone_number_id.eq(my_phone_number_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(person_phonenumber)
+    }
+}
+
+pub struct PrintablePersonPhoneNumber {
+    person: String,
+    phonenumber: String,
+}
+
+impl fmt::Display for PrintablePersonPhoneNumber {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.person, self.phonenumber)
+    }
+}
+
+impl Printable<PrintablePersonPhoneNumber> for PersonPhoneNumber {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonPhoneNumber> {
+        let person = Person::by_id(db, self.person_id)?;
+        let phonenumber = PhoneNumber::by_id(db, self.phone_number_id)?;
+        Ok(PrintablePersonPhoneNumber {
+            person: person.value.to_string(),
+            phonenumber: phonenumber.value.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="person_phonenumbers"]
+pub struct NewPersonPhoneNumber {
+    pub person_id: i32,
+    pub phone_number_id: i32,
+}
+
+impl Printable<PrintablePersonPhoneNumber> for NewPersonPhoneNumber {
+    fn printable(&self, db: &Database) -> Result<PrintablePersonPhoneNumber> {
+        let person = Person::by_id(db, self.person_id)?;
+        let phonenumber = PhoneNumber::by_id(db, self.phone_number_id)?;
+        Ok(PrintablePersonPhoneNumber {
+            person: person.value.to_string(),
+            phonenumber: phonenumber.value.to_string(),
+        })
+    }
+}
+
+pub type InsertPersonPhoneNumber = NewPersonPhoneNumber;
+
+impl InsertToNew for InsertPersonPhoneNumber {
+    type Target = NewPersonPhoneNumber;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewPersonPhoneNumber> {
+        Ok(self)
+    }
+}
@@ -22,11 +22,21 @@ pub struct PhoneNumber {
     pub last_ported: Option<NaiveDateTime>,
     pub caller_name: Option<String>,
     pub caller_type: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for PhoneNumber {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Phonenumber)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -34,7 +44,7 @@ impl Model for PhoneNumber {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::phonenumbers::dsl::*;
 
-        let results = phonenumbers.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| phonenumbers.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -43,7 +53,7 @@ impl Model for PhoneNumber {
         use crate::schema::phonenumbers::dsl::*;
 
         let query = phonenumbers.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -51,16 +61,16 @@ impl Model for PhoneNumber {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::phonenumbers::dsl::*;
 
-        diesel::delete(phonenumbers.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(phonenumbers.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::phonenumbers::dsl::*;
 
-        diesel::delete(phonenumbers.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(phonenumbers.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -75,8 +85,8 @@ impl Model for PhoneNumber {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::phonenumbers::dsl::*;
 
-        let domain = phonenumbers.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let domain = with_conn!(db.db(), |conn| phonenumbers.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(domain)
     }
@@ -84,8 +94,8 @@ impl Model for PhoneNumber {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::phonenumbers::dsl::*;
 
-        let phonenumber = phonenumbers.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let phonenumber = with_conn!(db.db(), |conn| phonenumbers.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(phonenumber)
     }
@@ -93,8 +103,8 @@ impl Model for PhoneNumber {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::phonenumbers::dsl::*;
 
-        let phonenumber = phonenumbers.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let phonenumber = with_conn!(db.db(), |conn| phonenumbers.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(phonenumber)
@@ -109,22 +119,40 @@ impl Scopable for PhoneNumber {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::phonenumbers::dsl::*;
 
-        diesel::update(phonenumbers.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(phonenumbers.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::phonenumbers::dsl::*;
 
-        diesel::update(phonenumbers.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(phonenumbers.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for PhoneNumber {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::phonenumbers::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(phonenumbers.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(phonenumbers.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 pub struct PrintablePhoneNumber {
     value: String,
 }
@@ -154,6 +182,9 @@ pub struct DetailedPhoneNumber {
     line: Option<String>,
     caller_name: Option<String>,
     caller_type: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedPhoneNumber {
@@ -185,6 +216,9 @@ impl DisplayableDetailed for DetailedPhoneNumber {
         w.opt_debug::<Yellow, _>(&self.caller_type)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -211,6 +245,9 @@ impl Detailed for PhoneNumber {
             line: self.line.clone(),
             caller_name: self.caller_name.clone(),
             caller_type: self.caller_type.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -231,6 +268,10 @@ pub struct NewPhoneNumber {
     pub caller_type: Option<String>,
 
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<PhoneNumber> for NewPhoneNumber {
@@ -242,11 +283,9 @@ impl InsertableStruct<PhoneNumber> for NewPhoneNumber {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(phonenumbers::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), phonenumbers::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -266,6 +305,9 @@ impl Upsertable<PhoneNumber> for NewPhoneNumber {
             last_ported: Self::upsert_opt(self.last_ported, &existing.last_ported),
             caller_name: Self::upsert_opt(self.caller_name, &existing.caller_name),
             caller_type: Self::upsert_opt(self.caller_type, &existing.caller_type),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -291,6 +333,10 @@ pub struct InsertPhoneNumber {
     pub last_ported: Option<NaiveDateTime>,
     pub caller_name: Option<String>,
     pub caller_type: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 // TODO: enforce valid E.164 number?
@@ -320,6 +366,9 @@ impl InsertToNew for InsertPhoneNumber {
             caller_type: self.caller_type,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -338,6 +387,10 @@ pub struct PhoneNumberUpdate {
     pub last_ported: Option<NaiveDateTime>,
     pub caller_name: Option<String>,
     pub caller_type: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for PhoneNumberUpdate {
@@ -351,7 +404,10 @@ impl Upsert for PhoneNumberUpdate {
         self.is_ported.is_some() ||
         self.last_ported.is_some() ||
         self.caller_name.is_some() ||
-        self.caller_type.is_some()
+        self.caller_type.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -361,6 +417,11 @@ impl Upsert for PhoneNumberUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_phonenumber(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<PhoneNumber> for PhoneNumberUpdate {
@@ -375,6 +436,9 @@ impl Updateable<PhoneNumber> for PhoneNumberUpdate {
         Self::clear_if_equal(&mut self.last_ported, &existing.last_ported);
         Self::clear_if_equal(&mut self.caller_name, &existing.caller_name);
         Self::clear_if_equal(&mut self.caller_type, &existing.caller_type);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -388,5 +452,8 @@ impl Updateable<PhoneNumber> for PhoneNumberUpdate {
         Self::push_value(updates, "last_ported", &self.last_ported);
         Self::push_value(updates, "caller_name", &self.caller_name);
         Self::push_value(updates, "caller_type", &self.caller_type);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
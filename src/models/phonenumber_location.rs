@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(PhoneNumber)]
+#[belongs_to(Location)]
+#[table_name="phonenumber_locations"]
+pub struct PhoneNumberLocation {
+    pub id: i32,
+    pub phone_number_id: i32,
+    pub location_id: i32,
+}
+
+impl Model for PhoneNumberLocation {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("PhoneNumberLocation can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| phonenumber_locations.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        let query = phonenumber_locations.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(phonenumber_locations.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(phonenumber_locations.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        let phonenumber_location = with_conn!(db.db(), |conn| phonenumber_locations.filter(id.eq(my_id))
+                                                        .first::<Self>(conn))?;
+
+        Ok(phonenumber_location)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        let (my_phone_number_id, my_location_id) = query;
+        let phonenumber_location = with_conn!(db.db(), |conn| phonenumber_locations.filter(phone_number_id.eq(my_phone_number_id))
+                                                        .filter(location_id.eq(my_location_id))
+                                                        .first::<Self>(conn))?;
+
+        Ok(phonenumber_location)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::phonenumber_locations::dsl::*;
+
+        let (my_phone_number_id, my_location_id) = query;
+        let phonenumber_location = with_conn!(db.db(), |conn| phonenumber_locations.filter(phone_number_id.eq(my_phone_number_id))
+                                                        .filter(location_id.eq(my_location_id))
+                                                        .first::<Self>(conn))
+                                                        .optional()?;
+
+        Ok(phonenumber_location)
+    }
+}
+
+pub struct PrintablePhoneNumberLocation {
+    phonenumber: String,
+    location: String,
+}
+
+impl fmt::Display for PrintablePhoneNumberLocation {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.phonenumber, self.location)
+    }
+}
+
+impl Printable<PrintablePhoneNumberLocation> for PhoneNumberLocation {
+    fn printable(&self, db: &Database) -> Result<PrintablePhoneNumberLocation> {
+        let phonenumber = PhoneNumber::by_id(db, self.phone_number_id)?;
+        let location = Location::by_id(db, self.location_id)?;
+        Ok(PrintablePhoneNumberLocation {
+            phonenumber: phonenumber.value.to_string(),
+            location: location.value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="phonenumber_locations"]
+pub struct NewPhoneNumberLocation {
+    pub phone_number_id: i32,
+    pub location_id: i32,
+}
+
+impl Printable<PrintablePhoneNumberLocation> for NewPhoneNumberLocation {
+    fn printable(&self, db: &Database) -> Result<PrintablePhoneNumberLocation> {
+        let phonenumber = PhoneNumber::by_id(db, self.phone_number_id)?;
+        let location = Location::by_id(db, self.location_id)?;
+        Ok(PrintablePhoneNumberLocation {
+            phonenumber: phonenumber.value.to_string(),
+            location: location.value,
+        })
+    }
+}
+
+pub type InsertPhoneNumberLocation = NewPhoneNumberLocation;
+
+impl InsertToNew for InsertPhoneNumberLocation {
+    type Target = NewPhoneNumberLocation;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewPhoneNumberLocation> {
+        Ok(self)
+    }
+}
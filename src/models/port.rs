@@ -2,6 +2,7 @@ use crate::errors::*;
 use crate::fmt::Write;
 use crate::fmt::colors::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 use diesel;
 use diesel::prelude::*;
 use std::net::{self, SocketAddr};
@@ -23,11 +24,21 @@ pub struct Port {
     pub banner: Option<String>,
     pub service: Option<String>,
     pub version: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Port {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Port)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -35,7 +46,7 @@ impl Model for Port {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::ports::dsl::*;
 
-        let results = ports.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| ports.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -44,7 +55,7 @@ impl Model for Port {
         use crate::schema::ports::dsl::*;
 
         let query = ports.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -52,16 +63,16 @@ impl Model for Port {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::ports::dsl::*;
 
-        diesel::delete(ports.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(ports.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::ports::dsl::*;
 
-        diesel::delete(ports.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(ports.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -76,8 +87,8 @@ impl Model for Port {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::ports::dsl::*;
 
-        let url = ports.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let url = with_conn!(db.db(), |conn| ports.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(url)
     }
@@ -85,8 +96,8 @@ impl Model for Port {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::ports::dsl::*;
 
-        let url = ports.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let url = with_conn!(db.db(), |conn| ports.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(url)
     }
@@ -94,8 +105,8 @@ impl Model for Port {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::ports::dsl::*;
 
-        let url = ports.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let url = with_conn!(db.db(), |conn| ports.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(url)
@@ -110,22 +121,40 @@ impl Scopable for Port {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::ports::dsl::*;
 
-        diesel::update(ports.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(ports.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::ports::dsl::*;
 
-        diesel::update(ports.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(ports.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Port {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::ports::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(ports.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(ports.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 pub struct PrintablePort {
     value: String,
 }
@@ -153,6 +182,9 @@ pub struct DetailedPort {
     banner: Option<String>,
     service: Option<String>,
     version: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedPort {
@@ -174,6 +206,9 @@ impl DisplayableDetailed for DetailedPort {
         w.opt_debug::<Yellow, _>(&self.version)?;
         w.end_group()?;
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -198,6 +233,9 @@ impl Detailed for Port {
             banner: self.banner.clone(),
             service: self.service.clone(),
             version: self.version.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -217,6 +255,10 @@ pub struct NewPort {
     pub version: Option<String>,
 
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Port> for NewPort {
@@ -228,11 +270,9 @@ impl InsertableStruct<Port> for NewPort {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(ports::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), ports::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -246,6 +286,9 @@ impl Upsertable<Port> for NewPort {
             banner: Self::upsert_opt(self.banner, &existing.banner),
             service: Self::upsert_opt(self.service, &existing.service),
             version: Self::upsert_opt(self.version, &existing.version),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -269,6 +312,10 @@ pub struct InsertPort {
     pub banner: Option<String>,
     pub service: Option<String>,
     pub version: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertPort {
@@ -298,6 +345,9 @@ impl InsertToNew for InsertPort {
             version: self.version,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -310,6 +360,10 @@ pub struct PortUpdate {
     pub banner: Option<String>,
     pub service: Option<String>,
     pub version: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for PortUpdate {
@@ -317,7 +371,10 @@ impl Upsert for PortUpdate {
         self.status.is_some() ||
         self.banner.is_some() ||
         self.service.is_some() ||
-        self.version.is_some()
+        self.version.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -327,6 +384,11 @@ impl Upsert for PortUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_port(&self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Port> for PortUpdate {
@@ -335,6 +397,9 @@ impl Updateable<Port> for PortUpdate {
         Self::clear_if_equal(&mut self.banner, &existing.banner);
         Self::clear_if_equal(&mut self.service, &existing.service);
         Self::clear_if_equal(&mut self.version, &existing.version);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -342,5 +407,8 @@ impl Updateable<Port> for PortUpdate {
         Self::push_value(updates, "banner", &self.banner);
         Self::push_value(updates, "service", &self.service);
         Self::push_value(updates, "version", &self.version);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Port)]
+#[belongs_to(Certificate)]
+#[table_name="port_certificates"]
+pub struct PortCertificate {
+    pub id: i32,
+    pub port_id: i32,
+    pub certificate_id: i32,
+}
+
+impl Model for PortCertificate {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("PortCertificate can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::port_certificates::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| port_certificates.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::port_certificates::dsl::*;
+
+        let query = port_certificates.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::port_certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(port_certificates.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::port_certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(port_certificates.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::port_certificates::dsl::*;
+
+        let port_certificate = with_conn!(db.db(), |conn| port_certificates.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(port_certificate)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::port_certificates::dsl::*;
+
+        let (my_port_id, my_certificate_id) = query;
+        let port_certificate = with_conn!(db.db(), |conn| port_certificates.filter(port_id.eq(my_port_id))
+                                                   .filter(certificate_id.eq(my_certificate_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(port_certificate)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::port_certificates::dsl::*;
+
+        let (my_port_id, my_certificate_id) = query;
+        let port_certificate = with_conn!(db.db(), |conn| port_certificates.filter(port_id.eq(my_port_id))
+                                                   .filter(certificate_id.eq(my_certificate_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(port_certificate)
+    }
+}
+
+pub struct PrintablePortCertificate {
+    port: String,
+    certificate: String,
+}
+
+impl fmt::Display for PrintablePortCertificate {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{} -> {:?}", self.port, self.certificate)
+    }
+}
+
+impl Printable<PrintablePortCertificate> for PortCertificate {
+    fn printable(&self, db: &Database) -> Result<PrintablePortCertificate> {
+        let port = Port::by_id(db, self.port_id)?;
+        let certificate = Certificate::by_id(db, self.certificate_id)?;
+        Ok(PrintablePortCertificate {
+            port: port.value.to_string(),
+            certificate: certificate.value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="port_certificates"]
+pub struct NewPortCertificate {
+    pub port_id: i32,
+    pub certificate_id: i32,
+}
+
+impl Printable<PrintablePortCertificate> for NewPortCertificate {
+    fn printable(&self, db: &Database) -> Result<PrintablePortCertificate> {
+        let port = Port::by_id(db, self.port_id)?;
+        let certificate = Certificate::by_id(db, self.certificate_id)?;
+        Ok(PrintablePortCertificate {
+            port: port.value.to_string(),
+            certificate: certificate.value,
+        })
+    }
+}
+
+pub type InsertPortCertificate = NewPortCertificate;
+
+impl InsertToNew for InsertPortCertificate {
+    type Target = NewPortCertificate;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewPortCertificate> {
+        Ok(self)
+    }
+}
@@ -3,6 +3,7 @@ use crate::fmt::colors::*;
 use diesel;
 use diesel::prelude::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 use std::result;
 
 
@@ -15,11 +16,21 @@ pub struct Subdomain {
     pub value: String,
     pub unscoped: bool,
     pub resolvable: Option<bool>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Subdomain {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Subdomain)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -27,7 +38,7 @@ impl Model for Subdomain {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::subdomains::dsl::*;
 
-        let results = subdomains.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| subdomains.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -36,7 +47,7 @@ impl Model for Subdomain {
         use crate::schema::subdomains::dsl::*;
 
         let query = subdomains.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -44,16 +55,16 @@ impl Model for Subdomain {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::subdomains::dsl::*;
 
-        diesel::delete(subdomains.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(subdomains.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::subdomains::dsl::*;
 
-        diesel::delete(subdomains.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(subdomains.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -68,8 +79,8 @@ impl Model for Subdomain {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::subdomains::dsl::*;
 
-        let subdomain = subdomains.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let subdomain = with_conn!(db.db(), |conn| subdomains.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(subdomain)
     }
@@ -77,8 +88,8 @@ impl Model for Subdomain {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::subdomains::dsl::*;
 
-        let subdomain = subdomains.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let subdomain = with_conn!(db.db(), |conn| subdomains.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(subdomain)
     }
@@ -86,8 +97,8 @@ impl Model for Subdomain {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::subdomains::dsl::*;
 
-        let subdomain = subdomains.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let subdomain = with_conn!(db.db(), |conn| subdomains.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(subdomain)
@@ -102,32 +113,50 @@ impl Scopable for Subdomain {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::subdomains::dsl::*;
 
-        diesel::update(subdomains.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(subdomains.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::subdomains::dsl::*;
 
-        diesel::update(subdomains.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(subdomains.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Subdomain {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::subdomains::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(subdomains.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(subdomains.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 impl Subdomain {
     fn ip_addrs(&self, db: &Database) -> Result<Vec<IpAddr>> {
-        let ipaddr_ids = SubdomainIpAddr::belonging_to(self)
+        let ipaddr_ids = with_conn!(db.db(), |conn| SubdomainIpAddr::belonging_to(self)
             .select(subdomain_ipaddrs::ip_addr_id)
-            .load::<i32>(db.db())?;
+            .load::<i32>(conn))?;
 
         ipaddr_ids.into_iter()
-            .map(|ipaddr_id| ipaddrs::table
+            .map(|ipaddr_id| with_conn!(db.db(), |conn| ipaddrs::table
                 .filter(ipaddrs::id.eq(ipaddr_id))
-                .first::<IpAddr>(db.db())
+                .first::<IpAddr>(conn))
             )
             .collect::<result::Result<_, _>>()
             .map_err(Error::from)
@@ -157,6 +186,9 @@ pub struct DetailedSubdomain {
     value: String,
     ipaddrs: Vec<PrintableIpAddr>,
     unscoped: bool,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 // TODO: maybe print urls as well
@@ -170,6 +202,8 @@ impl DisplayableDetailed for DetailedSubdomain {
     fn print(&self, w: &mut fmt::DetailFormatter) -> fmt::Result {
         w.id(self.id)?;
         w.debug::<Green, _>(&self.value)?;
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
         Ok(())
     }
 
@@ -197,6 +231,9 @@ impl Detailed for Subdomain {
             value: self.value.to_string(),
             ipaddrs,
             unscoped: self.unscoped,
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -208,6 +245,10 @@ pub struct NewSubdomain {
     pub value: String,
     pub resolvable: Option<bool>,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Subdomain> for NewSubdomain {
@@ -219,11 +260,9 @@ impl InsertableStruct<Subdomain> for NewSubdomain {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(subdomains::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), subdomains::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -234,6 +273,9 @@ impl Upsertable<Subdomain> for NewSubdomain {
         Self::Update {
             id: existing.id,
             resolvable: Self::upsert_opt(self.resolvable, &existing.resolvable),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -251,6 +293,10 @@ pub struct InsertSubdomain {
     pub domain_id: i32,
     pub value: String,
     pub resolvable: Option<bool>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertSubdomain {
@@ -264,6 +310,9 @@ impl InsertToNew for InsertSubdomain {
             resolvable: self.resolvable,
 
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -273,11 +322,18 @@ impl InsertToNew for InsertSubdomain {
 pub struct SubdomainUpdate {
     pub id: i32,
     pub resolvable: Option<bool>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for SubdomainUpdate {
     fn is_dirty(&self) -> bool {
-        self.resolvable.is_some()
+        self.resolvable.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -287,14 +343,25 @@ impl Upsert for SubdomainUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_subdomain(self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Subdomain> for SubdomainUpdate {
     fn changeset(&mut self, existing: &Subdomain) {
         Self::clear_if_equal(&mut self.resolvable, &existing.resolvable);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
         Self::push_value(updates, "resolvable", &self.resolvable);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Subdomain)]
+#[belongs_to(Certificate)]
+#[table_name="subdomain_certificates"]
+pub struct SubdomainCertificate {
+    pub id: i32,
+    pub subdomain_id: i32,
+    pub certificate_id: i32,
+}
+
+impl Model for SubdomainCertificate {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("SubdomainCertificate can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| subdomain_certificates.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        let query = subdomain_certificates.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(subdomain_certificates.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(subdomain_certificates.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        let subdomain_certificate = with_conn!(db.db(), |conn| subdomain_certificates.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
+
+        Ok(subdomain_certificate)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        let (my_subdomain_id, my_certificate_id) = query;
+        let subdomain_certificate = with_conn!(db.db(), |conn| subdomain_certificates.filter(subdomain_id.eq(my_subdomain_id))
+                                                   .filter(certificate_id.eq(my_certificate_id))
+                                                   .first::<Self>(conn))?;
+
+        Ok(subdomain_certificate)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::subdomain_certificates::dsl::*;
+
+        let (my_subdomain_id, my_certificate_id) = query;
+        let subdomain_certificate = with_conn!(db.db(), |conn| subdomain_certificates.filter(subdomain_id.eq(my_subdomain_id))
+                                                   .filter(certificate_id.eq(my_certificate_id))
+                                                   .first::<Self>(conn))
+                                                   .optional()?;
+
+        Ok(subdomain_certificate)
+    }
+}
+
+pub struct PrintableSubdomainCertificate {
+    subdomain: String,
+    certificate: String,
+}
+
+impl fmt::Display for PrintableSubdomainCertificate {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.subdomain, self.certificate)
+    }
+}
+
+impl Printable<PrintableSubdomainCertificate> for SubdomainCertificate {
+    fn printable(&self, db: &Database) -> Result<PrintableSubdomainCertificate> {
+        let subdomain = Subdomain::by_id(db, self.subdomain_id)?;
+        let certificate = Certificate::by_id(db, self.certificate_id)?;
+        Ok(PrintableSubdomainCertificate {
+            subdomain: subdomain.value.to_string(),
+            certificate: certificate.value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="subdomain_certificates"]
+pub struct NewSubdomainCertificate {
+    pub subdomain_id: i32,
+    pub certificate_id: i32,
+}
+
+impl Printable<PrintableSubdomainCertificate> for NewSubdomainCertificate {
+    fn printable(&self, db: &Database) -> Result<PrintableSubdomainCertificate> {
+        let subdomain = Subdomain::by_id(db, self.subdomain_id)?;
+        let certificate = Certificate::by_id(db, self.certificate_id)?;
+        Ok(PrintableSubdomainCertificate {
+            subdomain: subdomain.value.to_string(),
+            certificate: certificate.value,
+        })
+    }
+}
+
+pub type InsertSubdomainCertificate = NewSubdomainCertificate;
+
+impl InsertToNew for InsertSubdomainCertificate {
+    type Target = NewSubdomainCertificate;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewSubdomainCertificate> {
+        Ok(self)
+    }
+}
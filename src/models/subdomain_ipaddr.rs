@@ -25,7 +25,7 @@ impl Model for SubdomainIpAddr {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
-        let results = subdomain_ipaddrs.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| subdomain_ipaddrs.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -34,7 +34,7 @@ impl Model for SubdomainIpAddr {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
         let query = subdomain_ipaddrs.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -42,16 +42,16 @@ impl Model for SubdomainIpAddr {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
-        diesel::delete(subdomain_ipaddrs.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(subdomain_ipaddrs.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
-        diesel::delete(subdomain_ipaddrs.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(subdomain_ipaddrs.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -62,8 +62,8 @@ impl Model for SubdomainIpAddr {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
-        let subdomain_ipaddr = subdomain_ipaddrs.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let subdomain_ipaddr = with_conn!(db.db(), |conn| subdomain_ipaddrs.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(subdomain_ipaddr)
     }
@@ -72,9 +72,9 @@ impl Model for SubdomainIpAddr {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
         let (my_subdomain_id, my_ip_addr_id) = query;
-        let subdomain_ipaddr = subdomain_ipaddrs.filter(subdomain_id.eq(my_subdomain_id))
+        let subdomain_ipaddr = with_conn!(db.db(), |conn| subdomain_ipaddrs.filter(subdomain_id.eq(my_subdomain_id))
                                                    .filter(ip_addr_id.eq(my_ip_addr_id))
-                                                   .first::<Self>(db.db())?;
+                                                   .first::<Self>(conn))?;
 
         Ok(subdomain_ipaddr)
     }
@@ -83,9 +83,9 @@ impl Model for SubdomainIpAddr {
         use crate::schema::subdomain_ipaddrs::dsl::*;
 
         let (my_subdomain_id, my_ip_addr_id) = query;
-        let subdomain_ipaddr = subdomain_ipaddrs.filter(subdomain_id.eq(my_subdomain_id))
+        let subdomain_ipaddr = with_conn!(db.db(), |conn| subdomain_ipaddrs.filter(subdomain_id.eq(my_subdomain_id))
                                                    .filter(ip_addr_id.eq(my_ip_addr_id))
-                                                   .first::<Self>(db.db())
+                                                   .first::<Self>(conn))
                                                    .optional()?;
 
         Ok(subdomain_ipaddr)
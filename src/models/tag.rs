@@ -0,0 +1,100 @@
+use crate::errors::*;
+use crate::db::{DatabaseSock, Family};
+use crate::schema::tags;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name="tags"]
+pub struct Tag {
+    pub id: i32,
+    pub family: String,
+    pub entity_id: i32,
+    pub value: String,
+}
+
+#[derive(Insertable)]
+#[table_name="tags"]
+pub struct NewTag<'a> {
+    pub family: &'a str,
+    pub entity_id: i32,
+    pub value: &'a str,
+}
+
+impl Tag {
+    pub fn validate(value: &str) -> Result<()> {
+        if value.is_empty() {
+            bail!("Tag can't be empty");
+        }
+        if value.chars().any(char::is_whitespace) {
+            bail!("Tag can't contain whitespace: {:?}", value);
+        }
+        Ok(())
+    }
+
+    pub fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::tags::dsl::*;
+
+        with_conn!(db.db(), |conn| tags.load::<Self>(conn))
+            .map_err(Error::from)
+    }
+
+    /// Returns true if the entity didn't have this tag yet
+    pub fn add(db: &Database, family: &Family, entity_id: i32, value: &str) -> Result<bool> {
+        Self::validate(value)?;
+
+        let tag = NewTag {
+            family: family.as_str(),
+            entity_id,
+            value,
+        };
+
+        let n = match db.db() {
+            DatabaseSock::Sqlite(conn) => diesel::insert_or_ignore_into(tags::table)
+                .values(&tag)
+                .execute(conn)?,
+            #[cfg(feature="postgres")]
+            DatabaseSock::Postgres(conn) => diesel::insert_into(tags::table)
+                .values(&tag)
+                .on_conflict_do_nothing()
+                .execute(conn)?,
+        };
+
+        Ok(n > 0)
+    }
+
+    /// Returns true if the tag was removed from the entity
+    pub fn remove(db: &Database, my_family: &Family, my_entity_id: i32, my_value: &str) -> Result<bool> {
+        use crate::schema::tags::dsl::*;
+
+        let n = with_conn!(db.db(), |conn| diesel::delete(tags
+                .filter(family.eq(my_family.as_str()))
+                .filter(entity_id.eq(my_entity_id))
+                .filter(value.eq(my_value)))
+            .execute(conn))?;
+
+        Ok(n > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_valid() {
+        Tag::validate("interesting").unwrap();
+    }
+
+    #[test]
+    fn test_tag_empty() {
+        assert!(Tag::validate("").is_err());
+    }
+
+    #[test]
+    fn test_tag_whitespace() {
+        assert!(Tag::validate("foo bar").is_err());
+    }
+}
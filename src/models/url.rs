@@ -2,6 +2,7 @@ use crate::errors::*;
 use crate::fmt::Write;
 use crate::fmt::colors::*;
 use crate::models::*;
+use chrono::NaiveDateTime;
 use diesel;
 use diesel::prelude::*;
 use crate::ser;
@@ -22,11 +23,21 @@ pub struct Url {
     pub online: Option<bool>,
     pub title: Option<String>,
     pub redirect: Option<String>,
+    pub first_seen: Option<NaiveDateTime>,
+    pub last_seen: Option<NaiveDateTime>,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Model for Url {
     type ID = str;
 
+    fn family() -> Option<Family> {
+        Some(Family::Url)
+    }
+
     fn to_string(&self) -> String {
         self.value.to_owned()
     }
@@ -34,7 +45,7 @@ impl Model for Url {
     fn list(db: &Database) -> Result<Vec<Self>> {
         use crate::schema::urls::dsl::*;
 
-        let results = urls.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| urls.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -43,7 +54,7 @@ impl Model for Url {
         use crate::schema::urls::dsl::*;
 
         let query = urls.filter(filter.sql());
-        let results = query.load::<Self>(db.db())?;
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
 
         Ok(results)
     }
@@ -51,16 +62,16 @@ impl Model for Url {
     fn delete(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::urls::dsl::*;
 
-        diesel::delete(urls.filter(filter.sql()))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(urls.filter(filter.sql()))
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
         use crate::schema::urls::dsl::*;
 
-        diesel::delete(urls.filter(id.eq(my_id)))
-            .execute(db.db())
+        with_conn!(db.db(), |conn| diesel::delete(urls.filter(id.eq(my_id)))
+            .execute(conn))
             .map_err(Error::from)
     }
 
@@ -75,8 +86,8 @@ impl Model for Url {
     fn by_id(db: &Database, my_id: i32) -> Result<Self> {
         use crate::schema::urls::dsl::*;
 
-        let url = urls.filter(id.eq(my_id))
-            .first::<Self>(db.db())?;
+        let url = with_conn!(db.db(), |conn| urls.filter(id.eq(my_id))
+            .first::<Self>(conn))?;
 
         Ok(url)
     }
@@ -84,8 +95,8 @@ impl Model for Url {
     fn get(db: &Database, query: &Self::ID) -> Result<Self> {
         use crate::schema::urls::dsl::*;
 
-        let url = urls.filter(value.eq(query))
-            .first::<Self>(db.db())?;
+        let url = with_conn!(db.db(), |conn| urls.filter(value.eq(query))
+            .first::<Self>(conn))?;
 
         Ok(url)
     }
@@ -93,8 +104,8 @@ impl Model for Url {
     fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
         use crate::schema::urls::dsl::*;
 
-        let url = urls.filter(value.eq(query))
-            .first::<Self>(db.db())
+        let url = with_conn!(db.db(), |conn| urls.filter(value.eq(query))
+            .first::<Self>(conn))
             .optional()?;
 
         Ok(url)
@@ -109,22 +120,40 @@ impl Scopable for Url {
     fn scope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::urls::dsl::*;
 
-        diesel::update(urls.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(urls.filter(filter.sql()))
             .set(unscoped.eq(false))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 
     fn noscope(db: &Database, filter: &Filter) -> Result<usize> {
         use crate::schema::urls::dsl::*;
 
-        diesel::update(urls.filter(filter.sql()))
+        with_conn!(db.db(), |conn| diesel::update(urls.filter(filter.sql()))
             .set(unscoped.eq(true))
-            .execute(db.db())
+            .execute(conn))
             .map_err(Error::from)
     }
 }
 
+impl Seen for Url {
+    fn seen(db: &Database, my_id: i32, now: NaiveDateTime, inserted: bool) -> Result<()> {
+        use crate::schema::urls::dsl::*;
+
+        if inserted {
+            with_conn!(db.db(), |conn| diesel::update(urls.filter(id.eq(my_id)).filter(first_seen.is_null()))
+                .set(first_seen.eq(now))
+                .execute(conn))?;
+        }
+
+        with_conn!(db.db(), |conn| diesel::update(urls.filter(id.eq(my_id)))
+            .set(last_seen.eq(now))
+            .execute(conn))?;
+
+        Ok(())
+    }
+}
+
 pub struct PrintableUrl {
     value: String,
     status: Option<u16>,
@@ -166,6 +195,9 @@ pub struct DetailedUrl {
     unscoped: bool,
     title: Option<String>,
     redirect: Option<String>,
+    confidence: i32,
+    verified: bool,
+    extra: Option<String>,
 }
 
 impl DisplayableDetailed for DetailedUrl {
@@ -195,6 +227,9 @@ impl DisplayableDetailed for DetailedUrl {
             write!(w, " {:?}", title)?;
         }
 
+        w.confidence(self.confidence, self.verified)?;
+        w.extra(&self.extra)?;
+
         Ok(())
     }
 
@@ -217,6 +252,9 @@ impl Detailed for Url {
             unscoped: self.unscoped,
             title: self.title.clone(),
             redirect: self.redirect.clone(),
+            confidence: self.confidence,
+            verified: self.verified,
+            extra: self.extra.clone(),
         })
     }
 }
@@ -234,6 +272,10 @@ pub struct NewUrl {
     pub title: Option<String>,
     pub redirect: Option<String>,
     pub unscoped: bool,
+    pub confidence: i32,
+    pub verified: bool,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertableStruct<Url> for NewUrl {
@@ -245,11 +287,9 @@ impl InsertableStruct<Url> for NewUrl {
         self.unscoped = !scoped;
     }
 
-    fn insert(&self, db: &Database) -> Result<()> {
-        diesel::insert_into(urls::table)
-            .values(self)
-            .execute(db.db())?;
-        Ok(())
+    fn insert(&self, db: &Database) -> Result<bool> {
+        let n = insert_ignore!(db.db(), urls::table, self)?;
+        Ok(n > 0)
     }
 }
 
@@ -264,6 +304,9 @@ impl Upsertable<Url> for NewUrl {
             online: Self::upsert_opt(self.online, &existing.online),
             title: Self::upsert_opt(self.title, &existing.title),
             redirect: Self::upsert_opt(self.redirect, &existing.redirect),
+            confidence: Self::upsert_max(self.confidence, existing.confidence),
+            verified: Self::upsert_max(self.verified, existing.verified),
+            extra: extra::merge(self.extra, &existing.extra),
         }
     }
 }
@@ -288,6 +331,10 @@ pub struct InsertUrl {
     pub online: Option<bool>,
     pub title: Option<String>,
     pub redirect: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl InsertToNew for InsertUrl {
@@ -314,6 +361,9 @@ impl InsertToNew for InsertUrl {
             title: self.title,
             redirect,
             unscoped: false,
+            confidence: validate_confidence(self.confidence)?,
+            verified: self.verified.unwrap_or(false),
+            extra: self.extra,
         })
     }
 }
@@ -327,6 +377,10 @@ pub struct UrlUpdate {
     pub online: Option<bool>,
     pub title: Option<String>,
     pub redirect: Option<String>,
+    pub confidence: Option<i32>,
+    pub verified: Option<bool>,
+    #[serde(default, with="extra")]
+    pub extra: Option<String>,
 }
 
 impl Upsert for UrlUpdate {
@@ -335,7 +389,10 @@ impl Upsert for UrlUpdate {
         self.body.is_some() ||
         self.online.is_some() ||
         self.title.is_some() ||
-        self.redirect.is_some()
+        self.redirect.is_some() ||
+        self.confidence.is_some() ||
+        self.verified.is_some() ||
+        self.extra.is_some()
     }
 
     fn generic(self) -> Update {
@@ -345,6 +402,11 @@ impl Upsert for UrlUpdate {
     fn apply(&self, db: &Database) -> Result<i32> {
         db.update_url(&self)
     }
+
+    fn validate(&self) -> Result<()> {
+        validate_confidence(self.confidence)?;
+        Ok(())
+    }
 }
 
 impl Updateable<Url> for UrlUpdate {
@@ -354,6 +416,9 @@ impl Updateable<Url> for UrlUpdate {
         Self::clear_if_equal(&mut self.body, &existing.body);
         Self::clear_if_equal(&mut self.title, &existing.title);
         Self::clear_if_equal(&mut self.redirect, &existing.redirect);
+        Self::clear_if_equal(&mut self.confidence, &Some(existing.confidence));
+        Self::clear_if_equal(&mut self.verified, &Some(existing.verified));
+        self.extra = extra::merge(self.extra.take(), &existing.extra);
     }
 
     fn fmt(&self, updates: &mut Vec<String>) {
@@ -362,6 +427,9 @@ impl Updateable<Url> for UrlUpdate {
         Self::push_raw(updates, "body", self.body.as_ref().map(|x| format!("[{} bytes]", x.len())));
         Self::push_value(updates, "title", &self.title);
         Self::push_value(updates, "redirect", &self.redirect);
+        Self::push_value(updates, "confidence", &self.confidence);
+        Self::push_value(updates, "verified", &self.verified);
+        Self::push_raw(updates, "extra", self.extra.as_ref());
     }
 }
 
@@ -379,6 +447,9 @@ mod tests {
             online: None,
             title: None,
             redirect: None,
+            confidence: None,
+            verified: None,
+            extra: None,
         };
         assert_eq!(url.try_into_new().unwrap(), NewUrl {
             subdomain_id: 1234,
@@ -390,6 +461,9 @@ mod tests {
             title: None,
             redirect: None,
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         });
     }
 
@@ -403,6 +477,9 @@ mod tests {
             online: None,
             title: None,
             redirect: None,
+            confidence: None,
+            verified: None,
+            extra: None,
         };
         assert!(url.try_into_new().is_err());
     }
@@ -417,6 +494,9 @@ mod tests {
             online: None,
             title: None,
             redirect: Some("https://github.com/robots.txt".to_string()),
+            confidence: None,
+            verified: None,
+            extra: None,
         };
         assert_eq!(url.try_into_new().unwrap(), NewUrl {
             subdomain_id: 1234,
@@ -428,6 +508,9 @@ mod tests {
             title: None,
             redirect: Some("https://github.com/robots.txt".to_string()),
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         });
     }
 
@@ -441,6 +524,9 @@ mod tests {
             online: None,
             title: None,
             redirect: Some("/".to_string()),
+            confidence: None,
+            verified: None,
+            extra: None,
         };
         assert_eq!(url.try_into_new().unwrap(), NewUrl {
             subdomain_id: 1234,
@@ -452,6 +538,9 @@ mod tests {
             title: None,
             redirect: Some("https://example.com/".to_string()),
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         });
     }
 
@@ -465,6 +554,9 @@ mod tests {
             online: None,
             title: None,
             redirect: Some("//github.com/robots.txt".to_string()),
+            confidence: None,
+            verified: None,
+            extra: None,
         };
         assert_eq!(url.try_into_new().unwrap(), NewUrl {
             subdomain_id: 1234,
@@ -476,6 +568,9 @@ mod tests {
             title: None,
             redirect: Some("https://github.com/robots.txt".to_string()),
             unscoped: false,
+            confidence: DEFAULT_CONFIDENCE,
+            verified: false,
+            extra: None,
         });
     }
 }
@@ -0,0 +1,143 @@
+use crate::errors::*;
+use diesel;
+use diesel::prelude::*;
+use crate::models::*;
+
+
+#[derive(Identifiable, Queryable, Associations, Serialize, Deserialize)]
+#[belongs_to(Url)]
+#[belongs_to(Document)]
+#[table_name="url_documents"]
+pub struct UrlDocument {
+    pub id: i32,
+    pub url_id: i32,
+    pub document_id: i32,
+}
+
+impl Model for UrlDocument {
+    type ID = (i32, i32);
+
+    fn to_string(&self) -> String {
+        unimplemented!("UrlDocument can not be printed")
+    }
+
+    fn list(db: &Database) -> Result<Vec<Self>> {
+        use crate::schema::url_documents::dsl::*;
+
+        let results = with_conn!(db.db(), |conn| url_documents.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn filter(db: &Database, filter: &Filter) -> Result<Vec<Self>> {
+        use crate::schema::url_documents::dsl::*;
+
+        let query = url_documents.filter(filter.sql());
+        let results = with_conn!(db.db(), |conn| query.load::<Self>(conn))?;
+
+        Ok(results)
+    }
+
+    fn delete(db: &Database, filter: &Filter) -> Result<usize> {
+        use crate::schema::url_documents::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(url_documents.filter(filter.sql()))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn delete_id(db: &Database, my_id: i32) -> Result<usize> {
+        use crate::schema::url_documents::dsl::*;
+
+        with_conn!(db.db(), |conn| diesel::delete(url_documents.filter(id.eq(my_id)))
+            .execute(conn))
+            .map_err(Error::from)
+    }
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn by_id(db: &Database, my_id: i32) -> Result<Self> {
+        use crate::schema::url_documents::dsl::*;
+
+        let url_document = with_conn!(db.db(), |conn| url_documents.filter(id.eq(my_id))
+                                        .first::<Self>(conn))?;
+
+        Ok(url_document)
+    }
+
+    fn get(db: &Database, query: &Self::ID) -> Result<Self> {
+        use crate::schema::url_documents::dsl::*;
+
+        let (my_url_id, my_document_id) = query;
+        let url_document = with_conn!(db.db(), |conn| url_documents.filter(url_id.eq(my_url_id))
+                                        .filter(document_id.eq(my_document_id))
+                                        .first::<Self>(conn))?;
+
+        Ok(url_document)
+    }
+
+    fn get_opt(db: &Database, query: &Self::ID) -> Result<Option<Self>> {
+        use crate::schema::url_documents::dsl::*;
+
+        let (my_url_id, my_document_id) = query;
+        let url_document = with_conn!(db.db(), |conn| url_documents.filter(url_id.eq(my_url_id))
+                                        .filter(document_id.eq(my_document_id))
+                                        .first::<Self>(conn))
+                                        .optional()?;
+
+        Ok(url_document)
+    }
+}
+
+pub struct PrintableUrlDocument {
+    url: String,
+    document: String,
+}
+
+impl fmt::Display for PrintableUrlDocument {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{:?} -> {:?}", self.url, self.document)
+    }
+}
+
+impl Printable<PrintableUrlDocument> for UrlDocument {
+    fn printable(&self, db: &Database) -> Result<PrintableUrlDocument> {
+        let url = Url::by_id(db, self.url_id)?;
+        let document = Document::by_id(db, self.document_id)?;
+        Ok(PrintableUrlDocument {
+            url: url.value.to_string(),
+            document: document.value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
+#[table_name="url_documents"]
+pub struct NewUrlDocument {
+    pub url_id: i32,
+    pub document_id: i32,
+}
+
+impl Printable<PrintableUrlDocument> for NewUrlDocument {
+    fn printable(&self, db: &Database) -> Result<PrintableUrlDocument> {
+        let url = Url::by_id(db, self.url_id)?;
+        let document = Document::by_id(db, self.document_id)?;
+        Ok(PrintableUrlDocument {
+            url: url.value.to_string(),
+            document: document.value,
+        })
+    }
+}
+
+pub type InsertUrlDocument = NewUrlDocument;
+
+impl InsertToNew for InsertUrlDocument {
+    type Target = NewUrlDocument;
+
+    #[inline]
+    fn try_into_new(self) -> Result<NewUrlDocument> {
+        Ok(self)
+    }
+}
@@ -38,10 +38,16 @@ pub fn data_dir() -> Result<PathBuf> {
     Ok(path)
 }
 
-pub fn workspace_dir(workspace: &Workspace) -> Result<PathBuf> {
+/// The folder of a workspace, unlike `workspace_dir` it's not created
+pub fn workspace_path(workspace: &Workspace) -> Result<PathBuf> {
     let path = sn0int_dir()?
         .join("data")
         .join(workspace.as_str());
+    Ok(path)
+}
+
+pub fn workspace_dir(workspace: &Workspace) -> Result<PathBuf> {
+    let path = workspace_path(workspace)?;
     fs::create_dir_all(&path)
         .context("Failed to create module directory")?;
     Ok(path)
@@ -55,6 +61,14 @@ pub fn blobs_dir(workspace: &Workspace) -> Result<PathBuf> {
     Ok(path)
 }
 
+pub fn cookies_dir(workspace: &Workspace) -> Result<PathBuf> {
+    let path = workspace_dir(workspace)?
+        .join("cookies");
+    fs::create_dir_all(&path)
+        .context("Failed to create cookies directory")?;
+    Ok(path)
+}
+
 pub fn cache_dir() -> Result<PathBuf> {
     let path = dirs::cache_dir()
         .ok_or_else(|| format_err!("Failed to find cache directory"))?;
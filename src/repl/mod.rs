@@ -83,7 +83,7 @@ impl<'a> Repl<'a> {
 pub fn run(config: &Config) -> Result<()> {
     let keyring = Vec::new();
     let dns_config = Resolver::from_system()?;
-    let proxy = config.network.proxy;
+    let proxy = config.network.proxy_pool().for_sandbox();
 
     let cache_dir = paths::cache_dir()?;
     let psl = PslReader::open(&cache_dir)?;
@@ -97,6 +97,7 @@ pub fn run(config: &Config) -> Result<()> {
         proxy,
         options: HashMap::new(),
         blobs: Vec::new(),
+        cookie_jars: HashMap::new(),
         psl,
         geoip,
         asn,
@@ -73,6 +73,75 @@ fn into_insert(family: Family, object: LuaJsonValue, state: &Arc<dyn State>) ->
         Family::Cryptoaddr => {
             Insert::CryptoAddr(try_into_new::<InsertCryptoAddr>(object, state)?)
         },
+        Family::DnsRecord => {
+            Insert::DnsRecord(try_into_new::<InsertDnsRecord>(object, state)?)
+        },
+        Family::Certificate => {
+            Insert::Certificate(try_into_new::<InsertCertificate>(object, state)?)
+        },
+        Family::SubdomainCertificate => {
+            Insert::SubdomainCertificate(try_into_new::<InsertSubdomainCertificate>(object, state)?)
+        },
+        Family::PortCertificate => {
+            Insert::PortCertificate(try_into_new::<InsertPortCertificate>(object, state)?)
+        },
+        Family::Organization => {
+            Insert::Organization(try_into_new::<InsertOrganization>(object, state)?)
+        },
+        Family::Person => {
+            Insert::Person(try_into_new::<InsertPerson>(object, state)?)
+        },
+        Family::OrganizationDomain => {
+            Insert::OrganizationDomain(try_into_new::<InsertOrganizationDomain>(object, state)?)
+        },
+        Family::OrganizationEmail => {
+            Insert::OrganizationEmail(try_into_new::<InsertOrganizationEmail>(object, state)?)
+        },
+        Family::OrganizationPhonenumber => {
+            Insert::OrganizationPhoneNumber(try_into_new::<InsertOrganizationPhoneNumber>(object, state)?)
+        },
+        Family::OrganizationAccount => {
+            Insert::OrganizationAccount(try_into_new::<InsertOrganizationAccount>(object, state)?)
+        },
+        Family::OrganizationNetblock => {
+            Insert::OrganizationNetblock(try_into_new::<InsertOrganizationNetblock>(object, state)?)
+        },
+        Family::PersonDomain => {
+            Insert::PersonDomain(try_into_new::<InsertPersonDomain>(object, state)?)
+        },
+        Family::PersonEmail => {
+            Insert::PersonEmail(try_into_new::<InsertPersonEmail>(object, state)?)
+        },
+        Family::PersonPhonenumber => {
+            Insert::PersonPhoneNumber(try_into_new::<InsertPersonPhoneNumber>(object, state)?)
+        },
+        Family::PersonAccount => {
+            Insert::PersonAccount(try_into_new::<InsertPersonAccount>(object, state)?)
+        },
+        Family::PersonNetblock => {
+            Insert::PersonNetblock(try_into_new::<InsertPersonNetblock>(object, state)?)
+        },
+        Family::AutonomousSystem => {
+            Insert::AutonomousSystem(try_into_new::<InsertAutonomousSystem>(object, state)?)
+        },
+        Family::Document => {
+            Insert::Document(try_into_new::<InsertDocument>(object, state)?)
+        },
+        Family::UrlDocument => {
+            Insert::UrlDocument(try_into_new::<InsertUrlDocument>(object, state)?)
+        },
+        Family::Location => {
+            Insert::Location(try_into_new::<InsertLocation>(object, state)?)
+        },
+        Family::DomainLocation => {
+            Insert::DomainLocation(try_into_new::<InsertDomainLocation>(object, state)?)
+        },
+        Family::PhonenumberLocation => {
+            Insert::PhoneNumberLocation(try_into_new::<InsertPhoneNumberLocation>(object, state)?)
+        },
+        Family::AccountLocation => {
+            Insert::AccountLocation(try_into_new::<InsertAccountLocation>(object, state)?)
+        },
     };
     Ok(obj)
 }
@@ -157,6 +226,30 @@ pub fn db_select(lua: &mut hlua::Lua, state: Arc<dyn State>) {
     }))
 }
 
+pub fn db_tag(lua: &mut hlua::Lua, state: Arc<dyn State>) {
+    lua.set("db_tag", hlua::function3(move |family: String, id: i32, tag: String| -> Result<()> {
+        let family = Family::from_str(&family)
+            .map_err(|e| state.set_error(e.into()))?;
+        Tag::validate(&tag)
+            .map_err(|e| state.set_error(e))?;
+
+        state.db_tag(family, id, tag)
+            .map_err(|e| state.set_error(e))?;
+        Ok(())
+    }))
+}
+
+pub fn db_untag(lua: &mut hlua::Lua, state: Arc<dyn State>) {
+    lua.set("db_untag", hlua::function3(move |family: String, id: i32, tag: String| -> Result<()> {
+        let family = Family::from_str(&family)
+            .map_err(|e| state.set_error(e.into()))?;
+
+        state.db_untag(family, id, tag)
+            .map_err(|e| state.set_error(e))?;
+        Ok(())
+    }))
+}
+
 fn gen_changeset<T: Model, U: Updateable<T>>(object: LuaJsonValue, mut update: LuaJsonValue) -> Result<(i32, String, U)>
     where
         for<'de> T: serde::Deserialize<'de>,
@@ -185,7 +278,8 @@ pub fn db_update(lua: &mut hlua::Lua, state: Arc<dyn State>) {
         let update = LuaJsonValue::from(update);
 
         let update = match family {
-            Family::Domain => bail!("Domain doesn't have mutable fields"),
+            Family::Domain => gen_changeset::<Domain, DomainUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Domain(u))),
             Family::Subdomain => gen_changeset::<Subdomain, SubdomainUpdate>(object, update)
                 .map(|(id, v, u)| (id, v, Update::Subdomain(u))),
             Family::Ipaddr => gen_changeset::<IpAddr, IpAddrUpdate>(object, update)
@@ -205,7 +299,8 @@ pub fn db_update(lua: &mut hlua::Lua, state: Arc<dyn State>) {
                 .map(|(id, v, u)| (id, v, Update::NetworkDevice(u))),
             Family::Account => gen_changeset::<Account, AccountUpdate>(object, update)
                 .map(|(id, v, u)| (id, v, Update::Account(u))),
-            Family::Breach => bail!("Breach doesn't have mutable fields"),
+            Family::Breach => gen_changeset::<Breach, BreachUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Breach(u))),
             Family::BreachEmail => gen_changeset::<BreachEmail, BreachEmailUpdate>(object, update)
                 .map(|(id, v, u)| (id, v, Update::BreachEmail(u))),
             Family::Image => gen_changeset::<Image, ImageUpdate>(object, update)
@@ -216,6 +311,36 @@ pub fn db_update(lua: &mut hlua::Lua, state: Arc<dyn State>) {
                 .map(|(id, v, u)| (id, v, Update::Netblock(u))),
             Family::Cryptoaddr => gen_changeset::<CryptoAddr, CryptoAddrUpdate>(object, update)
                 .map(|(id, v, u)| (id, v, Update::CryptoAddr(u))),
+            Family::DnsRecord => gen_changeset::<DnsRecord, DnsRecordUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::DnsRecord(u))),
+            Family::Certificate => gen_changeset::<Certificate, CertificateUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Certificate(u))),
+            Family::SubdomainCertificate => bail!("Subdomain-Certificate doesn't have mutable fields"),
+            Family::PortCertificate => bail!("Port-Certificate doesn't have mutable fields"),
+            Family::Organization => gen_changeset::<Organization, OrganizationUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Organization(u))),
+            Family::Person => gen_changeset::<Person, PersonUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Person(u))),
+            Family::OrganizationDomain => bail!("Organization-Domain doesn't have mutable fields"),
+            Family::OrganizationEmail => bail!("Organization-Email doesn't have mutable fields"),
+            Family::OrganizationPhonenumber => bail!("Organization-PhoneNumber doesn't have mutable fields"),
+            Family::OrganizationAccount => bail!("Organization-Account doesn't have mutable fields"),
+            Family::OrganizationNetblock => bail!("Organization-Netblock doesn't have mutable fields"),
+            Family::PersonDomain => bail!("Person-Domain doesn't have mutable fields"),
+            Family::PersonEmail => bail!("Person-Email doesn't have mutable fields"),
+            Family::PersonPhonenumber => bail!("Person-PhoneNumber doesn't have mutable fields"),
+            Family::PersonAccount => bail!("Person-Account doesn't have mutable fields"),
+            Family::PersonNetblock => bail!("Person-Netblock doesn't have mutable fields"),
+            Family::AutonomousSystem => gen_changeset::<AutonomousSystem, AutonomousSystemUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::AutonomousSystem(u))),
+            Family::Document => gen_changeset::<Document, DocumentUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Document(u))),
+            Family::UrlDocument => bail!("Url-Document doesn't have mutable fields"),
+            Family::Location => gen_changeset::<Location, LocationUpdate>(object, update)
+                .map(|(id, v, u)| (id, v, Update::Location(u))),
+            Family::DomainLocation => bail!("Domain-Location doesn't have mutable fields"),
+            Family::PhonenumberLocation => bail!("PhoneNumber-Location doesn't have mutable fields"),
+            Family::AccountLocation => bail!("Account-Location doesn't have mutable fields"),
         };
 
         let (id, value, update) = update
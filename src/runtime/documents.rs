@@ -0,0 +1,68 @@
+use crate::errors::*;
+
+use crate::documents;
+use crate::engine::ctx::State;
+use crate::json::LuaJsonValue;
+use crate::hlua::{self, AnyLuaValue};
+use std::sync::Arc;
+
+
+pub fn office_metadata(lua: &mut hlua::Lua, state: Arc<dyn State>) {
+    lua.set("office_metadata", hlua::function1(move |blob: String| -> Result<AnyLuaValue> {
+        let doc = state.get_blob(&blob)
+            .map_err(|err| state.set_error(err))?;
+
+        let metadata = documents::office(&doc.bytes)
+            .map_err(|err| state.set_error(err))?;
+
+        let metadata = serde_json::to_value(metadata)
+            .map_err(|e| state.set_error(e.into()))?;
+
+        Ok(LuaJsonValue::from(metadata).into())
+    }))
+}
+
+pub fn pdf_metadata(lua: &mut hlua::Lua, state: Arc<dyn State>) {
+    lua.set("pdf_metadata", hlua::function1(move |blob: String| -> Result<AnyLuaValue> {
+        let doc = state.get_blob(&blob)
+            .map_err(|err| state.set_error(err))?;
+
+        let metadata = documents::pdf(&doc.bytes)
+            .map_err(|err| state.set_error(err))?;
+
+        let metadata = serde_json::to_value(metadata)
+            .map_err(|e| state.set_error(e.into()))?;
+
+        Ok(LuaJsonValue::from(metadata).into())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::ctx::Script;
+
+    #[test]
+    fn verify_pdf_metadata() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            blob = create_blob('%PDF-1.4\n1 0 obj\n<< /Title (Quarterly report) /Author (alice) /ModDate (D:20200101120000Z) >>\nendobj\n%%EOF\n')
+            meta = pdf_metadata(blob)
+            if last_err() then return end
+
+            if meta['mime'] ~= 'application/pdf' then
+                return 'mime incorrect: ' .. meta['mime']
+            end
+            if meta['title'] ~= 'Quarterly report' then
+                return 'title incorrect: ' .. meta['title']
+            end
+            if meta['author'] ~= 'alice' then
+                return 'author incorrect: ' .. meta['author']
+            end
+            if meta['modified'] ~= '2020-01-01T12:00:00' then
+                return 'modified incorrect: ' .. meta['modified']
+            end
+        end
+        "#).expect("failed to load script");
+        script.test().expect("Script failed");
+    }
+}
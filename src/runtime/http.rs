@@ -18,12 +18,27 @@ pub fn http_mksession(lua: &mut hlua::Lua, state: Arc<dyn State>) {
 
 pub fn http_request(lua: &mut hlua::Lua, state: Arc<dyn State>) {
     lua.set("http_request", hlua::function4(move |session: String, method: String, url: String, options: AnyLuaValue| -> Result<AnyLuaValue> {
-        RequestOptions::try_from(options)
+        let options = RequestOptions::try_from(options)
             .context("invalid request options")
-            .map_err(|err| state.set_error(Error::from(err)))
-            .map(|options| {
-                state.http_request(&session, method, url, options).into()
-            })
+            .map_err(|err| state.set_error(Error::from(err)))?;
+
+        state.http_request(&session, method, url, options)
+            .map_err(|err| state.set_error(err))
+            .map(|req| req.into())
+    }))
+}
+
+pub fn http_jar_restore(lua: &mut hlua::Lua, state: Arc<dyn State>) {
+    lua.set("http_jar_restore", hlua::function2(move |session: String, name: String| -> Result<bool> {
+        state.http_jar_restore(&session, &name)
+            .map_err(|err| state.set_error(err))
+    }))
+}
+
+pub fn http_jar_persist(lua: &mut hlua::Lua, state: Arc<dyn State>) {
+    lua.set("http_jar_persist", hlua::function2(move |session: String, name: String| -> Result<()> {
+        state.http_jar_persist(&session, &name)
+            .map_err(|err| state.set_error(err))
     }))
 }
 
@@ -38,7 +53,7 @@ pub fn http_send<S>(lua: &mut hlua::Lua, state: Arc<S>)
         let resp = req.send(state.as_ref())
             .map_err(|err| state.set_error(err))?;
 
-        req.response_to_lua(state.as_ref(), resp)
+        req.response_to_lua(resp)
             .map_err(|err| state.set_error(err))
             .map(|resp| resp.into())
     }))
@@ -59,7 +74,7 @@ pub fn http_fetch<S>(lua: &mut hlua::Lua, state: Arc<S>)
             return Err(state.set_error(format_err!("http status error: {}", resp.status)));
         }
 
-        req.response_to_lua(state.as_ref(), resp)
+        req.response_to_lua(resp)
             .map_err(|err| state.set_error(err))
             .map(|resp| resp.into())
     }))
@@ -91,6 +106,19 @@ mod tests {
     use crate::engine::ctx::Script;
     use std::time::{Instant, Duration};
 
+    #[test]
+    fn verify_jar_restore_missing() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            session = http_mksession()
+            if http_jar_restore(session, "login") then
+                return "restored jar that doesn't exist"
+            end
+        end
+        "#).expect("failed to load script");
+        script.test().expect("Script failed");
+    }
+
     #[test]
     #[ignore]
     fn verify_request() {
@@ -163,6 +191,111 @@ mod tests {
         script.test().expect("Script failed");
     }
 
+    #[test]
+    #[ignore]
+    fn verify_multipart() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            session = http_mksession()
+
+            blob = create_blob("ohai")
+            req = http_request(session, "POST", "https://httpbin.org/anything", {
+                multipart={
+                    {name="foo", text="bar"},
+                    {name="file", blob=blob, filename="hello.txt", content_type="text/plain"},
+                }
+            })
+            x = http_send(req)
+            if last_err() then return end
+            print(x)
+
+            o = json_decode(x['text'])
+            if last_err() then return end
+
+            if o['form']['foo'] ~= 'bar' or o['files']['file'] ~= 'ohai' then
+                return "reply didn't contain all fields"
+            end
+        end
+        "#).expect("failed to load script");
+        script.test().expect("Script failed");
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_max_size() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            session = http_mksession()
+            req = http_request(session, "GET", "https://httpbin.org/bytes/4096", {
+                into_blob=true,
+                max_size=1024,
+            })
+            x = http_send(req)
+        end
+        "#).expect("failed to load script");
+        script.test().err().expect("Script should have failed");
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_basic_auth() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            session = http_mksession()
+            req = http_request(session, "GET", "https://httpbin.org/basic-auth/foo/bar", {
+                auth={type="basic", user="foo", password="bar"}
+            })
+            x = http_fetch_json(req)
+            if last_err() then return end
+
+            if not x['authenticated'] then
+                return "not authenticated"
+            end
+        end
+        "#).expect("failed to load script");
+        script.test().expect("Script failed");
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_digest_auth() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            session = http_mksession()
+            req = http_request(session, "GET", "https://httpbin.org/digest-auth/auth/foo/bar", {
+                auth={type="digest", user="foo", password="bar"}
+            })
+            x = http_fetch_json(req)
+            if last_err() then return end
+
+            if not x['authenticated'] then
+                return "not authenticated"
+            end
+        end
+        "#).expect("failed to load script");
+        script.test().expect("Script failed");
+    }
+
+    #[test]
+    #[ignore]
+    fn verify_bearer_auth() {
+        let script = Script::load_unchecked(r#"
+        function run()
+            session = http_mksession()
+            req = http_request(session, "GET", "https://httpbin.org/bearer", {
+                auth={type="bearer", token="ohai"}
+            })
+            x = http_fetch_json(req)
+            if last_err() then return end
+
+            if x['token'] ~= 'ohai' then
+                return "wrong token"
+            end
+        end
+        "#).expect("failed to load script");
+        script.test().expect("Script failed");
+    }
+
     #[test]
     #[ignore]
     fn verify_cookies() {
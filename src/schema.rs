@@ -12,6 +12,18 @@ table! {
         phonenumber -> Nullable<Text>,
         profile_pic -> Nullable<Text>,
         birthday -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    account_locations (id) {
+        id -> Integer,
+        account_id -> Integer,
+        location_id -> Integer,
     }
 }
 
@@ -37,6 +49,20 @@ table! {
     }
 }
 
+table! {
+    autonomous_systems (id) {
+        id -> Integer,
+        value -> Integer,
+        unscoped -> Bool,
+        name -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
 table! {
     breach_emails (id) {
         id -> Integer,
@@ -51,6 +77,30 @@ table! {
         id -> Integer,
         value -> Text,
         unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    certificates (id) {
+        id -> Integer,
+        value -> Text,
+        serial -> Text,
+        subject -> Text,
+        issuer -> Text,
+        not_before -> Timestamp,
+        not_after -> Timestamp,
+        names -> Text,
+        unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
@@ -66,6 +116,10 @@ table! {
         last_withdrawal -> Nullable<Timestamp>,
         unscoped -> Bool,
         description -> Nullable<Text>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
@@ -78,6 +132,58 @@ table! {
         vendor -> Nullable<Text>,
         unscoped -> Bool,
         last_seen -> Nullable<Timestamp>,
+        first_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    dns_records (id) {
+        id -> Integer,
+        subdomain_id -> Integer,
+        value -> Text,
+        name -> Text,
+        record_type -> Text,
+        data -> Text,
+        ttl -> Nullable<Integer>,
+        unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    documents (id) {
+        id -> Integer,
+        value -> Text,
+        filename -> Nullable<Text>,
+        mime -> Nullable<Text>,
+        size -> Nullable<Integer>,
+        title -> Nullable<Text>,
+        author -> Nullable<Text>,
+        creator -> Nullable<Text>,
+        created -> Nullable<Timestamp>,
+        modified -> Nullable<Timestamp>,
+        text -> Nullable<Text>,
+        unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    domain_locations (id) {
+        id -> Integer,
+        domain_id -> Integer,
+        location_id -> Integer,
     }
 }
 
@@ -86,6 +192,11 @@ table! {
         id -> Integer,
         value -> Text,
         unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
@@ -96,6 +207,11 @@ table! {
         unscoped -> Bool,
         valid -> Nullable<Bool>,
         displayname -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
@@ -115,6 +231,11 @@ table! {
         dhash -> Nullable<Text>,
         phash -> Nullable<Text>,
         unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
@@ -135,6 +256,35 @@ table! {
         as_org -> Nullable<Text>,
         description -> Nullable<Text>,
         reverse_dns -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+        netblock_id -> Nullable<Integer>,
+        range_key -> Nullable<Text>,
+    }
+}
+
+table! {
+    locations (id) {
+        id -> Integer,
+        value -> Text,
+        street -> Nullable<Text>,
+        city -> Nullable<Text>,
+        postcode -> Nullable<Text>,
+        region -> Nullable<Text>,
+        country -> Nullable<Text>,
+        latitude -> Nullable<Float>,
+        longitude -> Nullable<Float>,
+        radius -> Nullable<Float>,
+        source -> Nullable<Text>,
+        unscoped -> Bool,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
@@ -147,6 +297,13 @@ table! {
         asn -> Nullable<Integer>,
         as_org -> Nullable<Text>,
         description -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+        range_start -> Nullable<Text>,
+        range_end -> Nullable<Text>,
     }
 }
 
@@ -168,6 +325,138 @@ table! {
         latitude -> Nullable<Float>,
         longitude -> Nullable<Float>,
         description -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    notes (id) {
+        id -> Integer,
+        family -> Text,
+        entity_id -> Integer,
+        author -> Text,
+        time -> Timestamp,
+        content -> Text,
+    }
+}
+
+table! {
+    organization_accounts (id) {
+        id -> Integer,
+        organization_id -> Integer,
+        account_id -> Integer,
+    }
+}
+
+table! {
+    organization_domains (id) {
+        id -> Integer,
+        organization_id -> Integer,
+        domain_id -> Integer,
+    }
+}
+
+table! {
+    organization_emails (id) {
+        id -> Integer,
+        organization_id -> Integer,
+        email_id -> Integer,
+    }
+}
+
+table! {
+    organization_netblocks (id) {
+        id -> Integer,
+        organization_id -> Integer,
+        netblock_id -> Integer,
+    }
+}
+
+table! {
+    organization_phonenumbers (id) {
+        id -> Integer,
+        organization_id -> Integer,
+        phone_number_id -> Integer,
+    }
+}
+
+table! {
+    organizations (id) {
+        id -> Integer,
+        value -> Text,
+        unscoped -> Bool,
+        description -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    person_accounts (id) {
+        id -> Integer,
+        person_id -> Integer,
+        account_id -> Integer,
+    }
+}
+
+table! {
+    person_domains (id) {
+        id -> Integer,
+        person_id -> Integer,
+        domain_id -> Integer,
+    }
+}
+
+table! {
+    person_emails (id) {
+        id -> Integer,
+        person_id -> Integer,
+        email_id -> Integer,
+    }
+}
+
+table! {
+    person_netblocks (id) {
+        id -> Integer,
+        person_id -> Integer,
+        netblock_id -> Integer,
+    }
+}
+
+table! {
+    person_phonenumbers (id) {
+        id -> Integer,
+        person_id -> Integer,
+        phone_number_id -> Integer,
+    }
+}
+
+table! {
+    persons (id) {
+        id -> Integer,
+        value -> Text,
+        unscoped -> Bool,
+        description -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    phonenumber_locations (id) {
+        id -> Integer,
+        phone_number_id -> Integer,
+        location_id -> Integer,
     }
 }
 
@@ -186,6 +475,19 @@ table! {
         last_ported -> Nullable<Timestamp>,
         caller_name -> Nullable<Text>,
         caller_type -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    port_certificates (id) {
+        id -> Integer,
+        port_id -> Integer,
+        certificate_id -> Integer,
     }
 }
 
@@ -202,6 +504,19 @@ table! {
         banner -> Nullable<Text>,
         service -> Nullable<Text>,
         version -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    subdomain_certificates (id) {
+        id -> Integer,
+        subdomain_id -> Integer,
+        certificate_id -> Integer,
     }
 }
 
@@ -220,6 +535,39 @@ table! {
         value -> Text,
         unscoped -> Bool,
         resolvable -> Nullable<Bool>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
+    }
+}
+
+table! {
+    tags (id) {
+        id -> Integer,
+        family -> Text,
+        entity_id -> Integer,
+        value -> Text,
+    }
+}
+
+table! {
+    trash (id) {
+        id -> Integer,
+        time -> Timestamp,
+        description -> Text,
+    }
+}
+
+table! {
+    trash_rows (id) {
+        id -> Integer,
+        trash_id -> Integer,
+        family -> Text,
+        key -> Integer,
+        data -> Text,
+        reference -> Bool,
     }
 }
 
@@ -232,6 +580,14 @@ table! {
     }
 }
 
+table! {
+    url_documents (id) {
+        id -> Integer,
+        url_id -> Integer,
+        document_id -> Integer,
+    }
+}
+
 table! {
     urls (id) {
         id -> Integer,
@@ -244,38 +600,104 @@ table! {
         online -> Nullable<Bool>,
         title -> Nullable<Text>,
         redirect -> Nullable<Text>,
+        first_seen -> Nullable<Timestamp>,
+        last_seen -> Nullable<Timestamp>,
+        confidence -> Integer,
+        verified -> Bool,
+        extra -> Nullable<Text>,
     }
 }
 
+joinable!(account_locations -> accounts (account_id));
+joinable!(account_locations -> locations (location_id));
 joinable!(breach_emails -> breaches (breach_id));
 joinable!(breach_emails -> emails (email_id));
+joinable!(dns_records -> subdomains (subdomain_id));
+joinable!(domain_locations -> domains (domain_id));
+joinable!(domain_locations -> locations (location_id));
 joinable!(network_devices -> devices (device_id));
 joinable!(network_devices -> networks (network_id));
+joinable!(organization_accounts -> accounts (account_id));
+joinable!(organization_accounts -> organizations (organization_id));
+joinable!(organization_domains -> domains (domain_id));
+joinable!(organization_domains -> organizations (organization_id));
+joinable!(organization_emails -> emails (email_id));
+joinable!(organization_emails -> organizations (organization_id));
+joinable!(organization_netblocks -> netblocks (netblock_id));
+joinable!(organization_netblocks -> organizations (organization_id));
+joinable!(organization_phonenumbers -> organizations (organization_id));
+joinable!(organization_phonenumbers -> phonenumbers (phone_number_id));
+joinable!(person_accounts -> accounts (account_id));
+joinable!(person_accounts -> persons (person_id));
+joinable!(person_domains -> domains (domain_id));
+joinable!(person_domains -> persons (person_id));
+joinable!(person_emails -> emails (email_id));
+joinable!(person_emails -> persons (person_id));
+joinable!(person_netblocks -> netblocks (netblock_id));
+joinable!(person_netblocks -> persons (person_id));
+joinable!(person_phonenumbers -> persons (person_id));
+joinable!(person_phonenumbers -> phonenumbers (phone_number_id));
+joinable!(phonenumber_locations -> locations (location_id));
+joinable!(phonenumber_locations -> phonenumbers (phone_number_id));
+joinable!(port_certificates -> certificates (certificate_id));
+joinable!(port_certificates -> ports (port_id));
 joinable!(ports -> ipaddrs (ip_addr_id));
+joinable!(subdomain_certificates -> certificates (certificate_id));
+joinable!(subdomain_certificates -> subdomains (subdomain_id));
 joinable!(subdomain_ipaddrs -> ipaddrs (ip_addr_id));
 joinable!(subdomain_ipaddrs -> subdomains (subdomain_id));
 joinable!(subdomains -> domains (domain_id));
+joinable!(trash_rows -> trash (trash_id));
+joinable!(url_documents -> documents (document_id));
+joinable!(url_documents -> urls (url_id));
 joinable!(urls -> subdomains (subdomain_id));
 
 allow_tables_to_appear_in_same_query!(
+    account_locations,
     accounts,
     activity,
+    autonomous_systems,
     autonoscope,
     breach_emails,
     breaches,
+    certificates,
     cryptoaddrs,
     devices,
+    dns_records,
+    documents,
+    domain_locations,
     domains,
     emails,
     images,
     ipaddrs,
+    locations,
     netblocks,
     network_devices,
     networks,
+    notes,
+    organization_accounts,
+    organization_domains,
+    organization_emails,
+    organization_netblocks,
+    organization_phonenumbers,
+    organizations,
+    person_accounts,
+    person_domains,
+    person_emails,
+    person_netblocks,
+    person_phonenumbers,
+    persons,
+    phonenumber_locations,
     phonenumbers,
+    port_certificates,
     ports,
+    subdomain_certificates,
     subdomain_ipaddrs,
     subdomains,
+    tags,
+    trash,
+    trash_rows,
     ttls,
+    url_documents,
     urls,
 );
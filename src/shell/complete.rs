@@ -1,5 +1,6 @@
 use crate::args::{Args, Completions};
 use crate::autonoscope::RuleType;
+use crate::cmd::dedupe_cmd;
 use crate::errors::*;
 use rustyline::{self, Context};
 use rustyline::completion::Completer;
@@ -41,9 +42,25 @@ impl CmdCompleter {
                 "ports",
                 "netblocks",
                 "cryptoaddrs",
+                "dns-records",
+                "certificates",
+                "organizations",
+                "persons",
+                "autonomous-systems",
+                "documents",
+                "locations",
             ], &args[1]))
         }
     }
+
+    pub fn filter_after_arg(&self, args: &[String]) -> rustyline::Result<(usize, Vec<String>)> {
+        // we can only complete the 3rd argument
+        if args.len() != 3 {
+            Ok((0, vec![]))
+        } else {
+            self.filter(&format!("{} {}", args[0], args[1]), &args[1..])
+        }
+    }
 }
 
 fn filter_options(prefix: &str, options: &[&str], arg: &str) -> (usize, Vec<String>) {
@@ -54,6 +71,16 @@ fn filter_options(prefix: &str, options: &[&str], arg: &str) -> (usize, Vec<Stri
     (0, results)
 }
 
+fn filter_workspaces(prefix: &str, arg: &str) -> Vec<String> {
+    match workspaces::list() {
+        Ok(workspaces) => workspaces.iter()
+            .filter(|x| x.starts_with(arg))
+            .map(|x| format!("{} {} ", prefix, x.as_str()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 impl Completer for CmdCompleter {
     type Candidate = String;
 
@@ -206,6 +233,16 @@ impl Completer for CmdCompleter {
                     }
                 },
                 Command::Noscope => self.filter("noscope", &cmd),
+                Command::Dedupe => {
+                    // we can only complete the 2nd argument
+                    if args != 2 {
+                        Ok((0, vec![]))
+                    } else {
+                        Ok(filter_options("dedupe", dedupe_cmd::FAMILIES, &cmd[1]))
+                    }
+                },
+                Command::Merge => self.filter("merge", &cmd),
+                Command::Note => self.filter_after_arg(&cmd),
                 Command::Use => {
                     // we can only complete the 2nd argument
                     if args != 2 {
@@ -221,23 +258,44 @@ impl Completer for CmdCompleter {
                     }
                 },
                 Command::Scope => self.filter("scope", &cmd),
+                Command::Search => Ok((0, vec![])),
                 Command::Select => self.filter("select", &cmd),
-                Command::Workspace => {
+                Command::Tag => self.filter_after_arg(&cmd),
+                Command::Trash => {
                     // we can only complete the 2nd argument
                     if args != 2 {
                         Ok((0, vec![]))
                     } else {
-                        let arg = &cmd[1];
-
-                        let results: Vec<String> = match workspaces::list() {
-                            Ok(workspaces) => workspaces.iter()
-                                .filter(|x| x.starts_with(arg))
-                                .map(|x| format!("workspace {} ", x.as_str()))
-                                .collect(),
-                            _ => Vec::new(),
-                        };
-
+                        Ok(filter_options("trash", &[
+                            "list",
+                            "restore",
+                            "purge",
+                        ], &cmd[1]))
+                    }
+                },
+                Command::Untag => self.filter_after_arg(&cmd),
+                Command::Workspace => {
+                    if args == 2 {
+                        // either a subcommand or a workspace to switch to
+                        let (_, mut results) = filter_options("workspace", &[
+                            "switch",
+                            "list",
+                            "delete",
+                            "usage",
+                            "merge",
+                            "clone",
+                            "archive",
+                            "restore",
+                            "config",
+                        ], &cmd[1]);
+                        results.extend(filter_workspaces("workspace", &cmd[1]));
                         Ok((0, results))
+                    } else if args == 3 && cmd[1] != "list" && cmd[1] != "restore" {
+                        // complete the workspace name after the subcommand
+                        let prefix = format!("workspace {}", cmd[1]);
+                        Ok((0, filter_workspaces(&prefix, &cmd[2])))
+                    } else {
+                        Ok((0, vec![]))
                     }
                 },
                 _ => Ok((0, vec![])),
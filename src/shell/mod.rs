@@ -34,17 +34,24 @@ pub enum Command {
     Autonoscope,
     Autoscope,
     Back,
+    Dedupe,
     Delete,
     Help,
     Keyring,
+    Merge,
     Mod,
     Noscope,
+    Note,
     Pkg,
     Run,
     Scope,
+    Search,
     Set,
     Select,
+    Tag,
     Target,
+    Trash,
+    Untag,
     Use,
     Quickstart,
     Workspace,
@@ -63,18 +70,25 @@ impl Command {
             Command::Autonoscope => "autonoscope",
             Command::Autoscope => "autoscope",
             Command::Back => "back",
+            Command::Dedupe => "dedupe",
             Command::Delete => "delete",
             Command::Exit => "exit",
             Command::Help => "help",
             Command::Keyring => "keyring",
+            Command::Merge => "merge",
             Command::Mod => "mod",
             Command::Noscope => "noscope",
+            Command::Note => "note",
             Command::Pkg => "pkg",
             Command::Run => "run",
             Command::Scope => "scope",
+            Command::Search => "search",
             Command::Set => "set",
             Command::Select => "select",
+            Command::Tag => "tag",
             Command::Target => "target",
+            Command::Trash => "trash",
+            Command::Untag => "untag",
             Command::Use => "use",
             Command::Quickstart => "quickstart",
             Command::Quit => "quit",
@@ -91,18 +105,25 @@ impl Command {
                 Command::Autonoscope.as_str(),
                 Command::Autoscope.as_str(),
                 Command::Back.as_str(),
+                Command::Dedupe.as_str(),
                 Command::Delete.as_str(),
                 Command::Help.as_str(),
                 Command::Keyring.as_str(),
+                Command::Merge.as_str(),
                 Command::Mod.as_str(),
                 Command::Noscope.as_str(),
+                Command::Note.as_str(),
                 Command::Pkg.as_str(),
                 Command::Run.as_str(),
                 Command::Scope.as_str(),
+                Command::Search.as_str(),
                 Command::Set.as_str(),
                 Command::Select.as_str(),
                 Command::Workspace.as_str(),
+                Command::Tag.as_str(),
                 Command::Target.as_str(),
+                Command::Trash.as_str(),
+                Command::Untag.as_str(),
                 Command::Use.as_str(),
                 Command::Quickstart.as_str(),
             ];
@@ -122,18 +143,25 @@ impl FromStr for Command {
             "autonoscope" => Ok(Command::Autonoscope),
             "autoscope" => Ok(Command::Autoscope),
             "back" => Ok(Command::Back),
+            "dedupe" => Ok(Command::Dedupe),
             "delete" => Ok(Command::Delete),
             "exit" => Ok(Command::Exit),
             "help" => Ok(Command::Help),
             "keyring" => Ok(Command::Keyring),
+            "merge" => Ok(Command::Merge),
             "mod" => Ok(Command::Mod),
             "noscope" => Ok(Command::Noscope),
+            "note" => Ok(Command::Note),
             "pkg"  => Ok(Command::Pkg),
             "run"  => Ok(Command::Run),
             "scope"  => Ok(Command::Scope),
+            "search" => Ok(Command::Search),
             "set"  => Ok(Command::Set),
             "select" => Ok(Command::Select),
+            "tag" => Ok(Command::Tag),
             "target"  => Ok(Command::Target),
+            "trash" => Ok(Command::Trash),
+            "untag" => Ok(Command::Untag),
             "use"  => Ok(Command::Use),
             "quickstart"  => Ok(Command::Quickstart),
             "quit" => Ok(Command::Quit),
@@ -149,7 +177,8 @@ pub struct Shell<'a> {
     db: Database,
     blobs: BlobStorage,
     psl: Lazy<PslReader, Arc<Psl>>,
-    config: &'a Config,
+    global_config: &'a Config,
+    config: Config,
     library: Library<'a>,
     keyring: KeyRing,
     // autonoscope: RuleSet,
@@ -158,7 +187,7 @@ pub struct Shell<'a> {
 }
 
 impl<'a> Shell<'a> {
-    pub fn new(config: &'a Config, db: Database, blobs: BlobStorage, psl: PslReader, library: Library<'a>, keyring: KeyRing) -> Shell<'a> {
+    pub fn new(global_config: &'a Config, config: Config, db: Database, blobs: BlobStorage, psl: PslReader, library: Library<'a>, keyring: KeyRing) -> Shell<'a> {
         let h = CmdCompleter::default();
         let rl = Readline::with(h);
 
@@ -170,6 +199,7 @@ impl<'a> Shell<'a> {
             db,
             blobs,
             psl: Lazy::from(psl),
+            global_config,
             config,
             library,
             keyring,
@@ -220,7 +250,7 @@ impl<'a> Shell<'a> {
     pub fn scoped_targets(&self) -> db::Filter {
         match &self.prompt.target {
             Some(filter) => filter.and_scoped(),
-            _ => db::Filter::new("unscoped=0"),
+            _ => db::Filter::new("NOT unscoped"),
         }
     }
 
@@ -255,11 +285,22 @@ impl<'a> Shell<'a> {
         Ok(self.psl.get()?)
     }
 
+    /// The config with the settings of the current workspace applied
     #[inline(always)]
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    #[inline(always)]
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    #[inline(always)]
+    pub fn global_config(&self) -> &'a Config {
+        self.global_config
+    }
+
     #[inline(always)]
     pub fn library(&self) -> &Library {
         &self.library
@@ -458,17 +499,24 @@ pub fn run_once(rl: &mut Shell) -> Result<bool> {
         Some((Command::Back, _)) => if rl.take_module().is_none() {
             return Ok(true);
         },
+        Some((Command::Dedupe, args)) => cmd::<dedupe_cmd::Args>(rl, &args)?,
         Some((Command::Delete, args)) => delete_cmd::run(rl, &args)?,
         Some((Command::Help, args)) => help_cmd::run(rl, &args)?,
         Some((Command::Keyring, args)) => keyring_cmd::run(rl, &args)?,
+        Some((Command::Merge, args)) => cmd::<merge_cmd::Args>(rl, &args)?,
         Some((Command::Mod, args)) => cmd::<pkg_cmd::ArgsInteractive>(rl, &args)?,
         Some((Command::Noscope, args)) => noscope_cmd::run(rl, &args)?,
+        Some((Command::Note, args)) => cmd::<note_cmd::Args>(rl, &args)?,
         Some((Command::Pkg, args)) => cmd::<pkg_cmd::ArgsInteractive>(rl, &args)?,
         Some((Command::Run, args)) => cmd::<run_cmd::Args>(rl, &args)?,
         Some((Command::Scope, args)) => scope_cmd::run(rl, &args)?,
+        Some((Command::Search, args)) => cmd::<search_cmd::Args>(rl, &args)?,
         Some((Command::Set, args)) => set_cmd::run(rl, &args)?,
         Some((Command::Select, args)) => cmd::<select_cmd::Args>(rl, &args)?,
+        Some((Command::Tag, args)) => cmd::<tag_cmd::Args>(rl, &args)?,
         Some((Command::Target, args)) => target_cmd::run(rl, &args)?,
+        Some((Command::Trash, args)) => trash_cmd::run(rl, &args)?,
+        Some((Command::Untag, args)) => cmd::<untag_cmd::Args>(rl, &args)?,
         Some((Command::Use, args)) => use_cmd::run(rl, &args)?,
         Some((Command::Quickstart, args)) => quickstart_cmd::run(rl, &args)?,
         Some((Command::Workspace, args)) => cmd::<workspace_cmd::Args>(rl, &args)?,
@@ -491,10 +539,11 @@ pub fn init<'a>(args: &Args, config: &'a Config, verbose_init: bool) -> Result<S
     workspace.migrate()?;
 
     let blobs = BlobStorage::workspace(&workspace)?;
+    let workspace_config = config.for_workspace(&workspace)?;
     let db = if verbose_init {
-        Database::establish(workspace)?
+        Database::establish(workspace, config)?
     } else {
-        Database::establish_quiet(workspace)?
+        Database::establish_quiet(workspace, config)?
     };
     ttl::reap_expired(&db)?;
 
@@ -515,7 +564,7 @@ pub fn init<'a>(args: &Args, config: &'a Config, verbose_init: bool) -> Result<S
     }
     autoupdate.check_background(&config, library.list());
 
-    let rl = Shell::new(&config, db, blobs, psl, library, keyring);
+    let rl = Shell::new(&config, workspace_config, db, blobs, psl, library, keyring);
 
     Ok(rl)
 }
@@ -1,6 +1,7 @@
 use crate::errors::*;
 
-use crate::blobs::Blob;
+use crate::blobs::{Blob, BlobStreamEvent};
+use crate::cookies::{CookieJar, CookieStorage, PersistCookieJar};
 use crate::channel;
 use crate::cmd::run_cmd::Params;
 use crate::db::{Database, DbChange, Family};
@@ -12,21 +13,28 @@ use crate::models::*;
 use serde_json;
 use crate::ratelimits::{Ratelimiter, RatelimitResponse};
 use crate::shell::Shell;
+use sn0int_std::proxy::ProxyPool;
 use sn0int_std::ratelimits::RatelimitSender;
 use std::collections::HashMap;
+use std::fmt;
 use std::result;
 use std::sync::{mpsc, Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::thread;
 use std::io::{Stdin, Read, BufRead, BufReader};
-use std::net::SocketAddr;
-use crate::term::{Spinner, StackedSpinners, SpinLogger};
+use crate::term::{self, PrefixedLogger, Spinner, StackedSpinners, SpinLogger};
 use threadpool::ThreadPool;
 
 
-type DbSender = mpsc::Sender<result::Result<DatabaseResponse, String>>;
+type DbResult = result::Result<DatabaseResponse, String>;
+type DbSender = mpsc::Sender<DbResult>;
 pub type VoidSender = mpsc::Sender<result::Result<(), String>>;
 
+/// Maximum number of database events that are applied in one transaction
+const MAX_BATCH_SIZE: usize = 128;
+/// Maximum time a transaction is kept open for more database events
+const BATCH_WINDOW: Duration = Duration::from_millis(50);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum DatabaseResponse {
     Inserted(i32),
@@ -43,6 +51,8 @@ pub enum Event {
     Stdio(StdioEvent),
     Ratelimit(RatelimitEvent),
     Blob(Blob),
+    BlobStream(BlobStreamEvent),
+    CookieJar(PersistCookieJar),
     Exit(ExitEvent),
 }
 
@@ -53,6 +63,7 @@ pub enum Event2 {
     Database((DatabaseEvent, DbSender)),
     Ratelimit((RatelimitEvent, RatelimitSender)),
     Blob((Blob, VoidSender)),
+    CookieJar((PersistCookieJar, VoidSender)),
     Exit(ExitEvent),
 }
 
@@ -157,6 +168,8 @@ pub enum DatabaseEvent {
     Activity(NewActivity),
     Select((Family, String)),
     Update((String, Update)),
+    Tag((Family, i32, String)),
+    Untag((Family, i32, String)),
 }
 
 impl EventWithCallback for DatabaseEvent {
@@ -168,7 +181,7 @@ impl EventWithCallback for DatabaseEvent {
 }
 
 impl DatabaseEvent {
-    pub fn insert<T: SpinLogger>(object: Insert, ttl: Option<i32>, tx: DbSender, spinner: &mut T, db: &Database, verbose: u64) {
+    pub fn insert<T: SpinLogger>(object: Insert, ttl: Option<i32>, spinner: &mut T, db: &Database, verbose: u64) -> DbResult {
         if verbose >= 1 {
             spinner.debug(&format!("Inserting: {:?}", object));
         }
@@ -176,7 +189,7 @@ impl DatabaseEvent {
         let result = db.insert_generic(object.clone());
         debug!("{:?} => {:?}", object, result);
 
-        let result = match result {
+        match result {
             Ok(Some((DbChange::Insert, id))) => {
                 if let Some(ttl) = ttl {
                     if let Err(err) = Ttl::create(&object, id, ttl, db) {
@@ -226,16 +239,14 @@ impl DatabaseEvent {
                 spinner.error(&err);
                 Err(err)
             },
-        };
-
-        tx.send(result).expect("Failed to send db result to channel");
+        }
     }
 
-    pub fn activity<T: SpinLogger>(object: NewActivity, tx: DbSender, spinner: &mut T, db: &Database, verbose: u64) {
+    pub fn activity<T: SpinLogger>(object: NewActivity, spinner: &mut T, db: &Database, verbose: u64) -> DbResult {
         let result = db.insert_activity(object.clone());
         debug!("{:?} => {:?}", object, result);
 
-        let result = match result {
+        match result {
             Ok(true) => {
                 let mut log = format!("{:?} ", object.topic);
                 if let Some(uniq) = &object.uniq {
@@ -260,24 +271,56 @@ impl DatabaseEvent {
                 spinner.error(&err);
                 Err(err)
             },
-        };
+        }
+    }
+
+    pub fn tag<T: SpinLogger>(family: Family, id: i32, tag: String, spinner: &mut T, db: &Database) -> DbResult {
+        let result = Tag::add(db, &family, id, &tag);
+        debug!("{:?} #{} += {:?} => {:?}", family, id, tag, result);
+
+        match result {
+            Ok(true) => {
+                spinner.log(&format!("Tagging {} #{} with {:?}", family.as_str(), id, tag));
+                Ok(DatabaseResponse::Inserted(id))
+            },
+            Ok(false) => Ok(DatabaseResponse::NoChange(id)),
+            Err(err) => {
+                let err = err.to_string();
+                spinner.error(&err);
+                Err(err)
+            },
+        }
+    }
+
+    pub fn untag<T: SpinLogger>(family: Family, id: i32, tag: String, spinner: &mut T, db: &Database) -> DbResult {
+        let result = Tag::remove(db, &family, id, &tag);
+        debug!("{:?} #{} -= {:?} => {:?}", family, id, tag, result);
 
-        tx.send(result).expect("Failed to send db result to channel");
+        match result {
+            Ok(true) => {
+                spinner.log(&format!("Removing tag {:?} from {} #{}", tag, family.as_str(), id));
+                Ok(DatabaseResponse::Updated(id))
+            },
+            Ok(false) => Ok(DatabaseResponse::NoChange(id)),
+            Err(err) => {
+                let err = err.to_string();
+                spinner.error(&err);
+                Err(err)
+            },
+        }
     }
 
-    pub fn apply<T: SpinLogger>(self, tx: DbSender, spinner: &mut T, db: &Database, verbose: u64) {
+    pub fn apply<T: SpinLogger>(self, spinner: &mut T, db: &Database, verbose: u64) -> DbResult {
         match self {
-            DatabaseEvent::Insert(object) => Self::insert(object, None, tx, spinner, db, verbose),
-            DatabaseEvent::InsertTtl((object, ttl)) => Self::insert(object, Some(ttl), tx, spinner, db, verbose),
-            DatabaseEvent::Activity(object) => Self::activity(object, tx, spinner, db, verbose),
+            DatabaseEvent::Insert(object) => Self::insert(object, None, spinner, db, verbose),
+            DatabaseEvent::InsertTtl((object, ttl)) => Self::insert(object, Some(ttl), spinner, db, verbose),
+            DatabaseEvent::Activity(object) => Self::activity(object, spinner, db, verbose),
             DatabaseEvent::Select((family, value)) => {
-                let result = match db.get_opt(&family, &value) {
+                match db.get_opt(&family, &value) {
                     Ok(Some(id)) => Ok(DatabaseResponse::Found(id)),
                     Ok(None) => Ok(DatabaseResponse::None),
                     Err(e) => Err(e.to_string()),
-                };
-
-                tx.send(result).expect("Failed to send db result to channel");
+                }
             },
             DatabaseEvent::Update((object, update)) => {
                 if verbose >= 1 {
@@ -297,12 +340,217 @@ impl DatabaseEvent {
                     spinner.log(&format!("Updating {:?} ({})", object, update));
                 }
 
-                tx.send(result).expect("Failed to send db result to channel");
+                result
             },
+            DatabaseEvent::Tag((family, id, tag)) => Self::tag(family, id, tag, spinner, db),
+            DatabaseEvent::Untag((family, id, tag)) => Self::untag(family, id, tag, spinner, db),
+        }
+    }
+}
+
+/// Database events that have been applied in a transaction that is still open
+///
+/// Every event gets its own savepoint so a failing event doesn't roll back the
+/// others. The responses and everything that is logged about the events are
+/// held back until the transaction has been committed, so the ids reported to
+/// the scripts are final.
+struct DatabaseBatch {
+    started: Instant,
+    elapsed: Duration,
+    events: usize,
+    responses: Vec<(DbSender, DbResult)>,
+    logs: Vec<(String, LogEvent)>,
+}
+
+impl DatabaseBatch {
+    fn begin(db: &Database) -> Result<DatabaseBatch> {
+        let start = Instant::now();
+        db.db().begin()?;
+        Ok(DatabaseBatch {
+            started: start,
+            elapsed: start.elapsed(),
+            events: 0,
+            responses: Vec::new(),
+            logs: Vec::new(),
+        })
+    }
+
+    /// Apply an event in the open transaction, or start a new one
+    fn add(batch: &mut Option<DatabaseBatch>, name: String, event: DatabaseEvent, tx: DbSender, stack: &mut StackedSpinners, db: &Database, verbose: u64) {
+        if batch.is_none() {
+            match DatabaseBatch::begin(db) {
+                Ok(b) => *batch = Some(b),
+                Err(err) => {
+                    let err = format!("Failed to start database transaction: {}", err);
+                    stack.prefixed(name).error(&err);
+                    tx.send(Err(err)).expect("Failed to send db result to channel");
+                    return;
+                },
+            }
+        }
+        if let Some(batch) = batch {
+            batch.apply(name, event, tx, db, verbose);
+        }
+    }
+
+    /// Commit the open transaction, if there is one
+    fn finish<T: SpinLogger>(batch: &mut Option<DatabaseBatch>, spinner: &mut T, db: &Database, metrics: &mut DatabaseMetrics) {
+        if let Some(batch) = batch.take() {
+            batch.commit(spinner, db, metrics);
+        }
+    }
+
+    fn apply(&mut self, name: String, event: DatabaseEvent, tx: DbSender, db: &Database, verbose: u64) {
+        let start = Instant::now();
+        let mut logger = BufferedLogger::new(&name, &mut self.logs);
+
+        let mut response = Ok(DatabaseResponse::None);
+        let savepoint = db.db().transaction::<_, Error, _>(|| {
+            response = event.apply(&mut logger, db, verbose);
+            match &response {
+                Ok(_) => Ok(()),
+                Err(err) => bail!("{}", err),
+            }
+        });
+        if let (Err(err), Ok(_)) = (savepoint, &response) {
+            response = Err(err.to_string());
+        }
+
+        self.responses.push((tx, response));
+        self.events += 1;
+        self.elapsed += start.elapsed();
+    }
+
+    /// The transaction is committed after a short time, if it grew too large
+    /// or if every running script is waiting for a response
+    fn is_due(&self, running: usize) -> bool {
+        self.events >= MAX_BATCH_SIZE
+            || self.responses.len() >= running
+            || self.started.elapsed() >= BATCH_WINDOW
+    }
+
+    /// Time until the transaction is due
+    fn remaining(&self) -> Duration {
+        BATCH_WINDOW.checked_sub(self.started.elapsed())
+            .unwrap_or_default()
+    }
+
+    fn commit<T: SpinLogger>(self, spinner: &mut T, db: &Database, metrics: &mut DatabaseMetrics) {
+        let start = Instant::now();
+        if let Err(err) = db.db().commit() {
+            if let Err(err) = db.db().rollback() {
+                error!("Failed to rollback database transaction: {}", err);
+            }
+            let err = format!("Failed to commit database transaction: {}", err);
+            spinner.error(&err);
+            for (tx, _) in self.responses {
+                tx.send(Err(err.clone())).expect("Failed to send db result to channel");
+            }
+        } else {
+            for (name, log) in self.logs {
+                log.apply(&mut PrefixedLogger::new(spinner, name));
+            }
+            for (tx, response) in self.responses {
+                tx.send(response).expect("Failed to send db result to channel");
+            }
+        }
+        metrics.record(self.events, self.elapsed + start.elapsed());
+    }
+}
+
+/// Keeps the log output of database events until their transaction has been
+/// committed
+struct BufferedLogger<'a> {
+    name: &'a str,
+    logs: &'a mut Vec<(String, LogEvent)>,
+}
+
+impl<'a> BufferedLogger<'a> {
+    fn new(name: &'a str, logs: &'a mut Vec<(String, LogEvent)>) -> BufferedLogger<'a> {
+        BufferedLogger {
+            name,
+            logs,
+        }
+    }
+
+    #[inline]
+    fn push(&mut self, log: LogEvent) {
+        self.logs.push((self.name.to_string(), log));
+    }
+}
+
+impl<'a> SpinLogger for BufferedLogger<'a> {
+    fn log(&mut self, line: &str) {
+        self.push(LogEvent::Info(line.to_string()));
+    }
+
+    fn debug(&mut self, line: &str) {
+        self.push(LogEvent::Debug(line.to_string()));
+    }
+
+    fn success(&mut self, line: &str) {
+        self.push(LogEvent::Success(line.to_string()));
+    }
+
+    fn error(&mut self, line: &str) {
+        self.push(LogEvent::Error(line.to_string()));
+    }
+
+    fn warn(&mut self, line: &str) {
+        self.push(LogEvent::Warn(line.to_string()));
+    }
+
+    fn warn_once(&mut self, line: &str) {
+        self.push(LogEvent::WarnOnce(line.to_string()));
+    }
+
+    fn status(&mut self, status: String) {
+        self.push(LogEvent::Status(status));
+    }
+
+    fn stacked_status(&mut self, name: &String, status: String) {
+        self.logs.push((name.to_string(), LogEvent::Status(status)));
+    }
+}
+
+/// Throughput of the database events of a run
+#[derive(Debug, Default)]
+pub struct DatabaseMetrics {
+    events: usize,
+    transactions: usize,
+    largest_transaction: usize,
+    elapsed: Duration,
+}
+
+impl DatabaseMetrics {
+    fn record(&mut self, events: usize, elapsed: Duration) {
+        self.events += events;
+        self.transactions += 1;
+        self.largest_transaction = self.largest_transaction.max(events);
+        self.elapsed += elapsed;
+    }
+
+    pub fn events_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.events as f64 / secs
+        } else {
+            0.0
         }
     }
 }
 
+impl fmt::Display for DatabaseMetrics {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{} database events in {} transactions (up to {} per transaction), {:.2}s, {:.0} events/s",
+            self.events,
+            self.transactions,
+            self.largest_transaction,
+            self.elapsed.as_secs_f64(),
+            self.events_per_second())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum StdioEvent {
     Readline,
@@ -377,7 +625,7 @@ impl RatelimitEvent {
     }
 }
 
-pub fn spawn(rl: &mut Shell, module: &Module, args: Vec<(serde_json::Value, Option<String>, Vec<Blob>)>, params: &Params, proxy: Option<SocketAddr>, options: HashMap<String, String>) -> usize {
+pub fn spawn(rl: &mut Shell, module: &Module, args: Vec<(serde_json::Value, Option<String>, Vec<Blob>)>, params: &Params, proxy: &ProxyPool, cookies: &CookieStorage, options: HashMap<String, String>) -> usize {
     // This function hangs if args is empty, so return early if that's the case
     if args.is_empty() {
         return 0;
@@ -389,8 +637,10 @@ pub fn spawn(rl: &mut Shell, module: &Module, args: Vec<(serde_json::Value, Opti
 
     let mut stack = StackedSpinners::new();
 
-    let (tx, rx) = channel::bounded(1);
-    let pool = ThreadPool::new(params.threads);
+    // every worker can queue an event without waiting for the others
+    let threads = params.threads(rl.config());
+    let (tx, rx) = channel::bounded(threads.max(1));
+    let pool = ThreadPool::new(threads);
 
     let mut expected = 0;
     for (arg, pretty_arg, blobs) in args {
@@ -403,6 +653,9 @@ pub fn spawn(rl: &mut Shell, module: &Module, args: Vec<(serde_json::Value, Opti
         let module = module.clone();
         let keyring = keyring.clone();
         let options = options.clone();
+        let proxy = proxy.for_sandbox();
+        let cookies = cookies.clone();
+        let storage = rl.blobs().clone();
         let signal_register = rl.signal_register().clone();
         pool.execute(move || {
             let tx = EventSender::new(name, tx);
@@ -413,7 +666,10 @@ pub fn spawn(rl: &mut Shell, module: &Module, args: Vec<(serde_json::Value, Opti
             }
 
             tx.send(Event2::Start);
-            let event = match ipc::parent::run(module, &tx, arg, keyring, verbose, has_stdin, proxy, options, blobs) {
+            // load the jars as late as possible to pick up jars persisted by earlier scripts
+            let event = match load_cookie_jars(&cookies, &module)
+                .and_then(|cookie_jars| ipc::parent::run(module, &tx, arg, keyring, verbose, has_stdin, proxy, options, blobs, storage, cookie_jars))
+            {
                 Ok(exit) => exit,
                 Err(err) => ExitEvent::SetupFailed(err.to_string()),
             };
@@ -423,63 +679,108 @@ pub fn spawn(rl: &mut Shell, module: &Module, args: Vec<(serde_json::Value, Opti
     }
 
     let mut ratelimit = Ratelimiter::new();
+    let mut metrics = DatabaseMetrics::default();
 
     let mut errors = 0;
     let mut failed = Vec::new();
-    let timeout = Duration::from_millis(100);
+    let mut running: usize = 0;
+    let mut batch: Option<DatabaseBatch> = None;
     loop {
+        // wake up in time to commit the open transaction
+        let timeout = batch.as_ref()
+            .map(DatabaseBatch::remaining)
+            .unwrap_or_else(|| Duration::from_millis(100));
+
         select! {
             recv(rx) -> msg => match msg.ok() {
                 Some(event) => {
-                    let (name, event) = (event.name, event.event);
-
-                    match event {
-                        Event2::Start => {
-                            let label = format!("Investigating {}", name);
-                            stack.add(name, label);
-                        },
-                        Event2::Log(log) => log.apply(&mut stack.prefixed(name)),
-                        Event2::Database((db, tx)) => db.apply(tx, &mut stack.prefixed(name), rl.db(), verbose),
-                        Event2::Ratelimit((req, tx)) => ratelimit.pass(tx, &req.key, req.passes, req.time),
-                        Event2::Blob((blob, tx)) => rl.store_blob(tx, &blob),
-                        Event2::Exit(event) => {
-                            debug!("Received exit: {:?} -> {:?}", name, event);
-                            stack.remove(&name);
-
-                            if ExitEvent::Ok != event {
-                                trace!("bumping error counter");
-                                errors += 1;
-                            }
+                    // take everything that is already queued
+                    let mut events = vec![event];
+                    while events.len() < MAX_BATCH_SIZE {
+                        match rx.try_recv() {
+                            Ok(event) => events.push(event),
+                            Err(_) => break,
+                        }
+                    }
 
-                            if let ExitEvent::SetupFailed(error) = event {
-                                failed.push((name, error));
-                            }
+                    let mut done = false;
+                    for event in events {
+                        let (name, event) = (event.name, event.event);
+
+                        match event {
+                            Event2::Start => {
+                                let label = format!("Investigating {}", name);
+                                stack.add(name, label);
+                                running += 1;
+                            },
+                            Event2::Log(log) => log.apply(&mut stack.prefixed(name)),
+                            Event2::Database((event, tx)) => DatabaseBatch::add(&mut batch, name, event, tx, &mut stack, rl.db(), verbose),
+                            Event2::Ratelimit((req, tx)) => ratelimit.pass(tx, &req.key, req.passes, req.time),
+                            Event2::Blob((blob, tx)) => rl.store_blob(tx, &blob),
+                            Event2::CookieJar((jar, tx)) => cookies.store(tx, &module.id(), &jar),
+                            Event2::Exit(event) => {
+                                debug!("Received exit: {:?} -> {:?}", name, event);
+                                stack.remove(&name);
+                                // sandboxes that are skipped after ctrl-c exit without a start
+                                running = running.saturating_sub(1);
+
+                                if ExitEvent::Ok != event {
+                                    trace!("bumping error counter");
+                                    errors += 1;
+                                }
+
+                                if let ExitEvent::SetupFailed(error) = event {
+                                    failed.push((name, error));
+                                }
+
+                                // if every task reported back, exit
+                                expected -= 1;
+                                info!("spawn_all is expecting {} more results", expected);
+                                if expected == 0 {
+                                    done = true;
+                                }
+                            },
+                        }
+                    }
 
-                            // if every task reported back, exit
-                            expected -= 1;
-                            info!("spawn_all is expecting {} more results", expected);
-                            if expected == 0 {
-                                break;
-                            }
-                        },
+                    if done {
+                        break;
                     }
                 },
                 None => break, // channel closed
             },
             default(timeout) => (),
         }
+
+        if batch.as_ref().map(|batch| batch.is_due(running)).unwrap_or(false) {
+            DatabaseBatch::finish(&mut batch, &mut stack, rl.db(), &mut metrics);
+        }
         stack.tick();
     }
 
+    DatabaseBatch::finish(&mut batch, &mut stack, rl.db(), &mut metrics);
+
     for (name, fail) in &failed {
         stack.error(&format!("Failed {}: {}", name, fail));
     }
 
     stack.clear();
 
+    info!("{}", metrics);
+    if verbose > 0 && metrics.events > 0 {
+        term::debug(&metrics.to_string());
+    }
+
     errors
 }
 
+#[inline]
+fn load_cookie_jars(cookies: &CookieStorage, module: &Module) -> Result<HashMap<String, CookieJar>> {
+    cookies.load_all(&module.id())
+        .context("Failed to load cookie jars")
+        .map_err(Error::from)
+}
+
 pub fn spawn_fn<F, T>(label: &str, f: F, clear: bool) -> Result<T>
         where F: FnOnce() -> Result<T> {
     let (tx, rx) = channel::bounded(1);
@@ -498,6 +799,8 @@ pub fn spawn_fn<F, T>(label: &str, f: F, clear: bool) -> Result<T>
                     Some(Event::Stdio(_)) => (),
                     Some(Event::Ratelimit(_)) => (),
                     Some(Event::Blob(_)) => (),
+                    Some(Event::BlobStream(_)) => (),
+                    Some(Event::CookieJar(_)) => (),
                     // TODO: refactor
                     Some(Event::Exit(ExitEvent::Ok)) => break,
                     Some(Event::Exit(ExitEvent::Err(error))) => spinner.error(&error),
@@ -581,6 +884,7 @@ pub fn spawn_multi<T: Task, F>(tasks: Vec<T>, mut done_fn: F, threads: usize) ->
                         Event2::Database(_) => (),
                         Event2::Ratelimit(_) => (),
                         Event2::Blob(_) => (),
+                        Event2::CookieJar(_) => (),
                         Event2::Exit(event) => {
                             debug!("Received exit: {:?} -> {:?}", name, event);
                             stack.remove(&name);
@@ -611,3 +915,158 @@ pub fn spawn_multi<T: Task, F>(tasks: Vec<T>, mut done_fn: F, threads: usize) ->
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_metrics() {
+        let mut metrics = DatabaseMetrics::default();
+        metrics.record(3, Duration::from_millis(250));
+        metrics.record(5, Duration::from_millis(250));
+        metrics.record(2, Duration::from_millis(500));
+        assert_eq!(metrics.to_string(), "10 database events in 3 transactions (up to 5 per transaction), 1.00s, 10 events/s");
+    }
+
+    #[test]
+    fn test_database_metrics_empty() {
+        let metrics = DatabaseMetrics::default();
+        assert_eq!(metrics.to_string(), "0 database events in 0 transactions (up to 0 per transaction), 0.00s, 0 events/s");
+    }
+
+    #[derive(Default)]
+    struct Lines(Vec<String>);
+
+    impl SpinLogger for Lines {
+        fn log(&mut self, line: &str) { self.0.push(format!("* {}", line)) }
+        fn debug(&mut self, line: &str) { self.0.push(format!("# {}", line)) }
+        fn success(&mut self, line: &str) { self.0.push(format!("+ {}", line)) }
+        fn error(&mut self, line: &str) { self.0.push(format!("- {}", line)) }
+        fn warn(&mut self, line: &str) { self.0.push(format!("! {}", line)) }
+        fn warn_once(&mut self, line: &str) { self.0.push(format!("! {}", line)) }
+        fn status(&mut self, _status: String) { }
+        fn stacked_status(&mut self, _name: &String, _status: String) { }
+    }
+
+    fn domain(value: &str) -> DatabaseEvent {
+        DatabaseEvent::Insert(Insert::Domain(NewDomain {
+            value: value.to_string(),
+            unscoped: false,
+            confidence: 100,
+            verified: false,
+            extra: None,
+        }))
+    }
+
+    fn subdomain(domain_id: i32, value: &str) -> DatabaseEvent {
+        DatabaseEvent::Insert(Insert::Subdomain(NewSubdomain {
+            domain_id,
+            value: value.to_string(),
+            resolvable: None,
+            unscoped: false,
+            confidence: 100,
+            verified: false,
+            extra: None,
+        }))
+    }
+
+    fn apply(batch: &mut DatabaseBatch, db: &Database, name: &str, event: DatabaseEvent) -> mpsc::Receiver<DbResult> {
+        let (tx, rx) = mpsc::channel();
+        batch.apply(name.to_string(), event, tx, db, 0);
+        rx
+    }
+
+    #[test]
+    fn test_database_batch_responses() {
+        let db = Database::in_memory().unwrap();
+        let mut batch = DatabaseBatch::begin(&db).unwrap();
+
+        let r1 = apply(&mut batch, &db, "a", domain("example.com"));
+        let r2 = apply(&mut batch, &db, "b", domain("example.org"));
+        let r3 = apply(&mut batch, &db, "a", domain("example.com"));
+        let r4 = apply(&mut batch, &db, "b", subdomain(2, "www.example.org"));
+        // responses are held back until the commit
+        assert!(r1.try_recv().is_err());
+
+        let mut lines = Lines::default();
+        let mut metrics = DatabaseMetrics::default();
+        batch.commit(&mut lines, &db, &mut metrics);
+        assert_eq!(metrics.events, 4);
+        assert_eq!(metrics.transactions, 1);
+
+        assert!(matches!(r1.recv().unwrap(), Ok(DatabaseResponse::Inserted(1))));
+        assert!(matches!(r2.recv().unwrap(), Ok(DatabaseResponse::Inserted(2))));
+        assert!(matches!(r3.recv().unwrap(), Ok(DatabaseResponse::NoChange(1))));
+        assert!(matches!(r4.recv().unwrap(), Ok(DatabaseResponse::Inserted(1))));
+
+        let domains = db.list::<Domain>().unwrap();
+        assert_eq!(domains.len(), 2);
+        let subdomain = Subdomain::by_id(&db, 1).unwrap();
+        assert_eq!(subdomain.domain_id, 2);
+    }
+
+    #[test]
+    fn test_database_batch_failed_event() {
+        let db = Database::in_memory().unwrap();
+        let mut batch = DatabaseBatch::begin(&db).unwrap();
+
+        let r1 = apply(&mut batch, &db, "a", domain("example.com"));
+        // the domain doesn't exist, only this event is rolled back
+        let r2 = apply(&mut batch, &db, "b", subdomain(1337, "www.example.org"));
+        let r3 = apply(&mut batch, &db, "a", subdomain(1, "www.example.com"));
+
+        let mut lines = Lines::default();
+        let mut metrics = DatabaseMetrics::default();
+        batch.commit(&mut lines, &db, &mut metrics);
+
+        assert!(matches!(r1.recv().unwrap(), Ok(DatabaseResponse::Inserted(1))));
+        assert!(r2.recv().unwrap().is_err());
+        assert!(matches!(r3.recv().unwrap(), Ok(DatabaseResponse::Inserted(_))));
+
+        assert_eq!(db.list::<Domain>().unwrap().len(), 1);
+        let subdomains = db.list::<Subdomain>().unwrap();
+        assert_eq!(subdomains.len(), 1);
+        assert_eq!(subdomains[0].value, "www.example.com");
+        assert_eq!(lines.0.iter().filter(|line| line.starts_with('-')).count(), 1);
+    }
+
+    #[test]
+    fn test_database_batch_failed_commit() {
+        let db = Database::in_memory().unwrap();
+        let mut batch = DatabaseBatch::begin(&db).unwrap();
+        let r1 = apply(&mut batch, &db, "a", domain("example.com"));
+        let r2 = apply(&mut batch, &db, "b", domain("example.org"));
+
+        // end the transaction behind the batch's back so the commit fails
+        db.db().rollback().unwrap();
+
+        let mut lines = Lines::default();
+        let mut metrics = DatabaseMetrics::default();
+        batch.commit(&mut lines, &db, &mut metrics);
+
+        assert!(r1.recv().unwrap().is_err());
+        assert!(r2.recv().unwrap().is_err());
+        assert!(db.list::<Domain>().unwrap().is_empty());
+        assert_eq!(lines.0.len(), 1);
+        assert!(lines.0[0].starts_with('-'));
+    }
+
+    #[test]
+    fn test_database_batch_logs_after_commit() {
+        let db = Database::in_memory().unwrap();
+        let mut batch = DatabaseBatch::begin(&db).unwrap();
+        let r1 = apply(&mut batch, &db, "a", domain("example.com"));
+        let r2 = apply(&mut batch, &db, "b", domain("example.org"));
+
+        let mut lines = Lines::default();
+        let mut metrics = DatabaseMetrics::default();
+        batch.commit(&mut lines, &db, &mut metrics);
+        r1.recv().unwrap().unwrap();
+        r2.recv().unwrap().unwrap();
+        assert_eq!(lines.0, vec![
+            format!("* {:50}: {}", "a", "Domain: \"example.com\""),
+            format!("* {:50}: {}", "b", "Domain: \"example.org\""),
+        ]);
+    }
+}
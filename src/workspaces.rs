@@ -22,6 +22,11 @@ impl Workspace {
             .join("db.sqlite"))
     }
 
+    #[inline]
+    pub fn exists(&self) -> Result<bool> {
+        Ok(list()?.contains(self))
+    }
+
     #[inline]
     pub fn usage_human(&self) -> Result<String> {
         let usage = self.usage()?;
@@ -31,7 +36,13 @@ impl Workspace {
     pub fn usage(&self) -> Result<u64> {
         let blobs = BlobStorage::workspace(self)?;
 
-        let mut sum = fs::metadata(self.db_path()?)?.len();
+        // workspaces that are stored in postgres only have blobs locally
+        let db_path = self.db_path()?;
+        let mut sum = if db_path.exists() {
+            fs::metadata(db_path)?.len()
+        } else {
+            0
+        };
         for entry in fs::read_dir(blobs.path())? {
             sum += fs::metadata(entry?.path())?.len();
         }